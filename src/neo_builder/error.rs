@@ -34,10 +34,24 @@ pub enum BuilderError {
 	InvalidConfiguration(String),
 	#[error("Too many signers: {0}")]
 	TooManySigners(String),
+	#[error("Too many attributes: {count} including signers, the maximum is {max}")]
+	TooManyAttributes { count: usize, max: usize },
+	#[error("Too many witnesses: {count}, the maximum is {max}")]
+	TooManyWitnesses { count: usize, max: usize },
+	#[error("Script too large: {size} bytes, the maximum is {max}")]
+	ScriptTooLarge { size: usize, max: usize },
+	#[error("Transaction too large: {size} bytes, the maximum is {max}")]
+	TransactionTooLarge { size: usize, max: usize },
+	#[error("Not enough signatures: {count} provided, {threshold} required")]
+	NotEnoughSignatures { count: usize, threshold: usize },
+	#[error("Invalid signature: {0}")]
+	InvalidSignature(String),
 	#[error("Illegal state: {0}")]
 	IllegalState(String),
 	#[error("Illegal argument: {0}")]
 	IllegalArgument(String),
+	#[error("Unsupported transaction version: {0}")]
+	UnsupportedVersion(u8),
 	#[error("Invalid public key: {0}")]
 	CodecError(#[from] CodecError),
 	#[error("Crypto error: {0}")]
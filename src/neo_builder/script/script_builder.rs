@@ -139,7 +139,7 @@ impl ScriptBuilder {
 		if params.is_empty() {
 			self.op_code(&[OpCode::NewArray0]);
 		} else {
-			self.push_params(params);
+			self.push_params(params)?;
 		}
 
 		Ok(self
@@ -174,7 +174,8 @@ impl ScriptBuilder {
 		self.push_opcode_bytes(OpCode::Syscall, operation.hash().from_hex().unwrap())
 	}
 
-	/// Pushes an array of contract parameters to the script.
+	/// Pushes an array of contract parameters to the script, in reverse so that `PACK` builds the
+	/// array in their original order.
 	///
 	/// # Arguments
 	///
@@ -182,7 +183,8 @@ impl ScriptBuilder {
 	///
 	/// # Returns
 	///
-	/// A mutable reference to the `ScriptBuilder` for method chaining.
+	/// A `Result` containing a mutable reference to the `ScriptBuilder` for method chaining,
+	/// or a `BuilderError` if any of the parameters cannot be pushed.
 	///
 	/// # Examples
 	///
@@ -194,14 +196,14 @@ impl ScriptBuilder {
 	///     ContractParameter::from("param1"),
 	///     ContractParameter::from(42),
 	///     ContractParameter::from(true)
-	/// ]);
+	/// ]).unwrap();
 	/// ```
-	pub fn push_params(&mut self, params: &[ContractParameter]) -> &mut Self {
-		for param in params {
-			self.push_param(param).expect("Its expected to be a successful push.");
+	pub fn push_params(&mut self, params: &[ContractParameter]) -> Result<&mut Self, BuilderError> {
+		for param in params.iter().rev() {
+			self.push_param(param)?;
 		}
 
-		self.push_integer(BigInt::from(params.len())).op_code(&[OpCode::Pack])
+		Ok(self.push_integer(BigInt::from(params.len())).op_code(&[OpCode::Pack]))
 	}
 
 	/// Pushes a single contract parameter to the script.
@@ -228,17 +230,27 @@ impl ScriptBuilder {
 			self.op_code(&[OpCode::PushNull]);
 			return Ok(self);
 		}
-		match &param.value.clone().unwrap() {
+		let value = param.value.as_ref().ok_or_else(|| {
+			BuilderError::IllegalArgument(format!(
+				"Parameter of type {:?} has no value",
+				param.get_type()
+			))
+		})?;
+		match value {
 			ParameterValue::Boolean(b) => self.push_bool(*b),
 			ParameterValue::Integer(i) => self.push_integer(BigInt::from(i.clone())),
+			ParameterValue::BigInteger(i) =>
+				self.push_integer(i.parse::<BigInt>().map_err(|e| {
+					BuilderError::IllegalArgument(format!("Invalid integer {i}: {e}"))
+				})?),
 			ParameterValue::ByteArray(b)
 			| ParameterValue::Signature(b)
 			| ParameterValue::PublicKey(b) => self.push_data(b.as_bytes().to_vec()),
 			ParameterValue::H160(h) => self.push_data(h.as_bytes().to_vec()),
 			ParameterValue::H256(h) => self.push_data(h.as_bytes().to_vec()),
 			ParameterValue::String(s) => self.push_data(s.as_bytes().to_vec()),
-			ParameterValue::Array(arr) => self.push_array(arr).unwrap(),
-			ParameterValue::Map(map) => self.push_map(&map.0).unwrap(),
+			ParameterValue::Array(arr) => self.push_array(arr)?,
+			ParameterValue::Map(map) => self.push_map(&map.0)?,
 			_ =>
 				return Err(BuilderError::IllegalArgument("Unsupported parameter type".to_string())),
 		};
@@ -412,6 +424,10 @@ impl ScriptBuilder {
 
 	/// Pushes an array of contract parameters to the script.
 	///
+	/// The elements are pushed in reverse, so that `PACK`, which pops the top of the stack first,
+	/// builds the array in their original order. Nested arrays and maps are pushed recursively,
+	/// each packed with its own `PACK` or `PACKMAP`, and an empty array is pushed with `NEWARRAY0`.
+	///
 	/// # Arguments
 	///
 	/// * `arr` - A slice of `ContractParameter` values to push to the script.
@@ -419,13 +435,16 @@ impl ScriptBuilder {
 	/// # Returns
 	///
 	/// A `Result` containing a mutable reference to the `ScriptBuilder` for method chaining,
-	/// or a `BuilderError` if an error occurs.
+	/// or a `BuilderError` if any element, at any depth, cannot be pushed.
 	///
 	pub fn push_array(&mut self, arr: &[ContractParameter]) -> Result<&mut Self, BuilderError> {
 		if arr.is_empty() {
 			self.op_code(&[OpCode::NewArray0]);
 		} else {
-			self.push_params(arr);
+			for param in arr.iter().rev() {
+				self.push_param(param)?;
+			}
+			self.push_integer(BigInt::from(arr.len())).pack();
 		};
 		Ok(self)
 	}
@@ -447,8 +466,8 @@ impl ScriptBuilder {
 		for (k, v) in map {
 			let kk: ContractParameter = k.clone().into();
 			let vv: ContractParameter = v.clone().into();
-			self.push_param(&vv).unwrap();
-			self.push_param(&kk).unwrap();
+			self.push_param(&vv)?;
+			self.push_param(&kk)?;
 		}
 
 		Ok(self.push_integer(BigInt::from(map.len())).op_code(&[OpCode::PackMap]))
@@ -559,7 +578,7 @@ impl ScriptBuilder {
 		let mut sb = Self::new();
 		sb.push_integer(BigInt::from(max_items));
 
-		sb.contract_call(contract_hash, method, params, call_flags).unwrap();
+		sb.contract_call(contract_hash, method, params, call_flags)?;
 
 		sb.op_code(&[OpCode::NewArray]);
 
@@ -601,6 +620,58 @@ impl ScriptBuilder {
 		Ok(script)
 	}
 
+	/// Builds a script that calls the NEP-24 `royaltyInfo` method of the NFT contract `nft`, which
+	/// returns the royalties due when selling the token `token_id` for `sale_price` of
+	/// `royalty_token`.
+	///
+	/// # Returns
+	///
+	/// A `Result` containing the script, or a `BuilderError` if an error occurs.
+	pub fn build_royalty_info_call(
+		nft: &H160,
+		token_id: &[u8],
+		royalty_token: &H160,
+		sale_price: i64,
+	) -> Result<Bytes, BuilderError> {
+		let mut sb = Self::new();
+		sb.contract_call(
+			nft,
+			"royaltyInfo",
+			&[token_id.into(), royalty_token.into(), ContractParameter::integer(sale_price)],
+			Some(CallFlags::ReadOnly),
+		)?;
+		Ok(sb.to_bytes())
+	}
+
+	/// Builds a single script that performs several contract calls, e.g. to batch read calls into
+	/// one `invokescript`.
+	///
+	/// # Arguments
+	///
+	/// * `calls` - The contract hash, method and parameters of each call.
+	///
+	/// # Returns
+	///
+	/// A `Result` containing the script and, for each call, the index of its result in the stack
+	/// returned by `invokescript`, or a `BuilderError` if an error occurs. Calls to `Void`
+	/// methods leave nothing on the stack, so the script pushes `Any` in their place and every
+	/// call keeps its own stack slot.
+	pub fn build_multi_call(
+		calls: &[(H160, &str, &[ContractParameter])],
+	) -> Result<(Bytes, Vec<usize>), BuilderError> {
+		let mut sb = Self::new();
+		let mut slots = Vec::with_capacity(calls.len());
+		for (slot, (contract_hash, method, params)) in calls.iter().enumerate() {
+			sb.contract_call(contract_hash, method, params, None)?;
+			// Skips the PUSHNULL if the call left its result above the results of the calls
+			// before it
+			sb.op_code(&[OpCode::Depth]).push_integer(BigInt::from(slot));
+			sb.op_code_with_arg(OpCode::JmpGt, vec![3]).op_code(&[OpCode::PushNull]);
+			slots.push(slot);
+		}
+		Ok((sb.to_bytes(), slots))
+	}
+
 	/// Returns the length of the script in bytes.
 	pub fn len(&self) -> usize {
 		self.script().size()
@@ -754,6 +825,42 @@ mod tests {
 		// assert_eq!(script, expected);
 	}
 
+	#[test]
+	fn test_push_nested_array() {
+		let nested = ContractParameter::array(vec![
+			ContractParameter::from(1),
+			ContractParameter::array(vec![ContractParameter::from(2), ContractParameter::from(3)]),
+			ContractParameter::array(vec![]),
+		]);
+
+		let mut builder = ScriptBuilder::new();
+		builder.push_param(&nested).unwrap();
+
+		// PACK pops the top of the stack first, so the elements are pushed last to first
+		let expected = ScriptBuilder::new()
+			.op_code(&[OpCode::NewArray0])
+			.push_integer(BigInt::from(3))
+			.push_integer(BigInt::from(2))
+			.push_integer(BigInt::from(2))
+			.op_code(&[OpCode::Pack])
+			.push_integer(BigInt::from(1))
+			.push_integer(BigInt::from(3))
+			.op_code(&[OpCode::Pack])
+			.to_bytes();
+		assert_eq!(builder.to_bytes(), expected);
+	}
+
+	#[test]
+	fn test_push_nested_array_without_value_fails() {
+		let nested =
+			ContractParameter::array(vec![ContractParameter::array(vec![ContractParameter::new(
+				ContractParameterType::Integer,
+			)])]);
+
+		let result = ScriptBuilder::new().push_param(&nested).map(|_| ());
+		assert!(matches!(result, Err(BuilderError::IllegalArgument(_))));
+	}
+
 	#[test]
 	fn test_map() {
 		let mut map: HashMap<ContractParameter, ContractParameter> = HashMap::new();
@@ -827,6 +934,142 @@ mod tests {
 		assert!(expected == expected_one || expected == expected_two);
 	}
 
+	#[test]
+	fn test_build_multi_call() {
+		let neo = H160::repeat_byte(0x01);
+		let gas = H160::repeat_byte(0x02);
+		let account = ContractParameter::h160(&H160::repeat_byte(0x03));
+		let balance_params = [account];
+
+		let calls: [(H160, &str, &[ContractParameter]); 3] =
+			[(neo, "symbol", &[]), (neo, "balanceOf", &balance_params), (gas, "decimals", &[])];
+
+		let (script, slots) = ScriptBuilder::build_multi_call(&calls).unwrap();
+
+		let mut expected = ScriptBuilder::new();
+		for (slot, (hash, method, params)) in calls.iter().enumerate() {
+			expected.contract_call(hash, method, params, None).unwrap();
+			expected.op_code(&[OpCode::Depth]).push_integer(BigInt::from(slot));
+			expected.op_code_with_arg(OpCode::JmpGt, vec![3]).op_code(&[OpCode::PushNull]);
+		}
+
+		assert_eq!(script, expected.to_bytes());
+		assert_eq!(slots, vec![0, 1, 2]);
+	}
+
+	#[test]
+	fn test_build_multi_call_with_void_calls() {
+		let contract = H160::repeat_byte(0x01);
+		let (script, slots) = ScriptBuilder::build_multi_call(&[
+			(contract, "balanceOf", &[]),
+			(contract, "update", &[]),
+			(contract, "decimals", &[]),
+		])
+		.unwrap();
+
+		// The second call is to a Void method and leaves nothing on the stack
+		let stack = run_multi_call(&script, &[Some(7), None, Some(8)]);
+		assert_eq!(slots, vec![0, 1, 2]);
+		assert_eq!(
+			slots.iter().map(|slot| stack[*slot]).collect::<Vec<_>>(),
+			[Some(7), None, Some(8)]
+		);
+	}
+
+	/// Runs the stack operations of a script built by `build_multi_call`, where each contract
+	/// call returns the next of `results`, or nothing if it is `None`. Values other than call
+	/// results and integers are `None` on the stack.
+	fn run_multi_call(script: &[u8], results: &[Option<i64>]) -> Vec<Option<i64>> {
+		let mut results = results.iter();
+		let mut stack: Vec<Option<i64>> = vec![];
+		let mut position = 0;
+		while position < script.len() {
+			let op_code = OpCode::try_from(script[position]).unwrap();
+			let operand_len = match op_code.operand_size() {
+				Some(size) if *size.prefix_size() > 0 => script[position + 1] as usize + 1,
+				Some(size) => *size.size() as usize,
+				None => 0,
+			};
+			let operand = &script[position + 1..position + 1 + operand_len];
+			let mut next = position + 1 + operand_len;
+			match op_code {
+				OpCode::Push0 | OpCode::Push1 | OpCode::Push2 | OpCode::Push15 =>
+					stack.push(Some(op_code as i64 - OpCode::Push0 as i64)),
+				OpCode::PushData1 | OpCode::PushNull | OpCode::NewArray0 => stack.push(None),
+				OpCode::Depth => stack.push(Some(stack.len() as i64)),
+				OpCode::JmpGt => {
+					let (b, a) = (stack.pop().unwrap(), stack.pop().unwrap());
+					if a > b {
+						next = position + operand[0] as usize;
+					}
+				},
+				OpCode::Syscall => {
+					stack.truncate(stack.len() - 4);
+					if let Some(result) = results.next().unwrap() {
+						stack.push(Some(*result));
+					}
+				},
+				_ => panic!("Unexpected op code {op_code:?}"),
+			}
+			position = next;
+		}
+		stack
+	}
+
+	#[test]
+	fn test_push_params_without_value_fails() {
+		let invalid = ContractParameter::new(ContractParameterType::Integer);
+
+		let mut builder = ScriptBuilder::new();
+		assert!(builder.push_params(&[ContractParameter::from(1), invalid.clone()]).is_err());
+		let mut builder = ScriptBuilder::new();
+		assert!(builder.contract_call(&H160::zero(), "method", &[invalid], None).is_err());
+	}
+
+	#[test]
+	fn test_push_array() {
+		let mut builder = ScriptBuilder::new();
+		builder
+			.push_array(&[
+				ContractParameter::from(1),
+				ContractParameter::array(vec![]),
+				ContractParameter::array(vec![ContractParameter::from(true)]),
+			])
+			.unwrap();
+
+		assert_builder(
+			&builder,
+			&[
+				OpCode::PushTrue as u8,
+				OpCode::Push1 as u8,
+				OpCode::Pack as u8,
+				OpCode::NewArray0 as u8,
+				OpCode::Push1 as u8,
+				OpCode::Push3 as u8,
+				OpCode::Pack as u8,
+			],
+		);
+	}
+
+	#[test]
+	fn test_build_royalty_info_call() {
+		let nft = H160::repeat_byte(1);
+		let royalty_token = H160::repeat_byte(2);
+		let script =
+			ScriptBuilder::build_royalty_info_call(&nft, &[7], &royalty_token, 1000).unwrap();
+
+		let mut expected = ScriptBuilder::new();
+		expected.push_integer(BigInt::from(1000));
+		expected.push_data(royalty_token.to_vec());
+		expected.push_data(vec![7]);
+		expected.push_integer(BigInt::from(3)).pack();
+		expected.push_integer(BigInt::from(CallFlags::ReadOnly.value()));
+		expected.push_data(b"royaltyInfo".to_vec());
+		expected.push_data(nft.to_vec());
+		expected.sys_call(InteropService::SystemContractCall);
+		assert_eq!(script, expected.to_bytes());
+	}
+
 	fn assert_builder(builder: &ScriptBuilder, expected: &[u8]) {
 		assert_eq!(builder.to_bytes(), expected);
 	}
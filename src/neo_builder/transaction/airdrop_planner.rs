@@ -0,0 +1,168 @@
+use primitive_types::H160;
+
+use neo::prelude::{
+	Account, AccountSigner, BuilderError, Bytes, ContractParameter, JsonRpcProvider, OpCode,
+	RpcClient, ScriptBuilder, ScriptHash, Transaction, TransactionBuilder, TransactionLimits,
+};
+
+/// A generous upper bound for everything in a single-signer transaction besides its script: the
+/// header, the signer, the script's length prefix and the witness.
+const SINGLE_SIGNER_TX_OVERHEAD: usize = 256;
+
+/// The transactions of an airdrop, as planned by [`AirdropPlanner::plan`].
+#[derive(Debug)]
+pub struct AirdropPlan<'a, P: JsonRpcProvider + 'static> {
+	/// The unsigned transactions, in the order they should be sent.
+	pub transactions: Vec<Transaction<'a, P>>,
+}
+
+impl<'a, P: JsonRpcProvider + 'static> AirdropPlan<'a, P> {
+	/// The estimated system and network fees of all transactions in the plan, in GAS fractions.
+	pub fn total_fee(&self) -> i64 {
+		self.transactions.iter().map(|tx| tx.sys_fee + tx.net_fee).sum()
+	}
+}
+
+/// Splits a token transfer to many recipients into as few transactions as the protocol limits
+/// allow.
+pub struct AirdropPlanner;
+
+impl AirdropPlanner {
+	/// Packs one `transfer` call of `token` from `from` per output into transactions that stay
+	/// within `limits`, and returns them unsigned with their fees calculated, ready to be signed
+	/// by `from` and sent.
+	///
+	/// Each transfer is followed by an `ASSERT`, so a transaction aborts if any of its transfers
+	/// fails.
+	pub async fn plan<'a, P: JsonRpcProvider + 'static>(
+		client: &'a RpcClient<P>,
+		token: &ScriptHash,
+		from: &Account,
+		outputs: &[(H160, i64)],
+		limits: TransactionLimits,
+	) -> Result<AirdropPlan<'a, P>, BuilderError> {
+		let sender = from.get_script_hash();
+		let scripts = Self::pack(token, &sender, outputs, &limits)?;
+
+		let mut transactions = Vec::with_capacity(scripts.len());
+		for script in scripts {
+			let mut builder = TransactionBuilder::with_client(client);
+			builder
+				.set_limits(limits)
+				.set_script(Some(script))
+				.set_signers(vec![AccountSigner::called_by_entry(from)?.into()])?;
+			transactions.push(builder.get_unsigned_tx().await?);
+		}
+		Ok(AirdropPlan { transactions })
+	}
+
+	/// Packs the transfers into as few scripts as possible, each within the script and
+	/// transaction size of `limits`.
+	pub fn pack(
+		token: &ScriptHash,
+		from: &ScriptHash,
+		outputs: &[(H160, i64)],
+		limits: &TransactionLimits,
+	) -> Result<Vec<Bytes>, BuilderError> {
+		let max_size = limits
+			.max_script_size
+			.min(limits.max_transaction_size.saturating_sub(SINGLE_SIGNER_TX_OVERHEAD));
+
+		let mut scripts = Vec::new();
+		let mut current = Bytes::new();
+		for (to, amount) in outputs {
+			let transfer = ScriptBuilder::new()
+				.contract_call(
+					token,
+					"transfer",
+					&[
+						from.into(),
+						to.into(),
+						ContractParameter::integer(*amount),
+						ContractParameter::any(),
+					],
+					None,
+				)?
+				.op_code(&[OpCode::Assert])
+				.to_bytes();
+			if transfer.len() > max_size {
+				return Err(BuilderError::ScriptTooLarge { size: transfer.len(), max: max_size });
+			}
+			if current.len() + transfer.len() > max_size {
+				scripts.push(std::mem::take(&mut current));
+			}
+			current.extend(transfer);
+		}
+		if !current.is_empty() {
+			scripts.push(current);
+		}
+		Ok(scripts)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{ops::Deref, sync::Arc};
+
+	use lazy_static::lazy_static;
+	use primitive_types::H160;
+	use tokio::sync::Mutex;
+
+	use crate::neo_clients::MockClient;
+	use neo::prelude::{Account, AccountTrait, AirdropPlanner, TransactionLimits, GAS_TOKEN_HASH};
+
+	lazy_static! {
+		static ref SENDER: Account = Account::create().unwrap();
+	}
+
+	#[tokio::test]
+	async fn test_plan_splits_outputs_by_script_size() {
+		let mock_provider = Arc::new(Mutex::new(MockClient::new().await));
+		{
+			let mut mock_provider_guard = mock_provider.lock().await;
+			let mut mock_provider_guard = mock_provider_guard
+				.mock_response_with_file_ignore_param(
+					"invokescript",
+					"invokescript_necessary_mock.json",
+				)
+				.await;
+			let mut mock_provider_guard = mock_provider_guard
+				.mock_response_with_file_ignore_param(
+					"calculatenetworkfee",
+					"calculatenetworkfee.json",
+				)
+				.await;
+			mock_provider_guard
+				.mock_response_with_file_ignore_param("getblockcount", "getblockcount_1000.json")
+				.await;
+			mock_provider_guard.mount_mocks().await;
+		}
+		let client = {
+			let mock_provider = mock_provider.lock().await;
+			mock_provider.into_client()
+		};
+
+		let outputs: Vec<(H160, i64)> =
+			(0..500u64).map(|i| (H160::from_low_u64_be(i + 1), 100)).collect();
+		let transfer_size = AirdropPlanner::pack(
+			&GAS_TOKEN_HASH,
+			&SENDER.get_script_hash(),
+			&outputs[..1],
+			&TransactionLimits::default(),
+		)
+		.unwrap()[0]
+			.len();
+		let limits =
+			TransactionLimits { max_script_size: transfer_size * 12, ..Default::default() };
+
+		let plan = AirdropPlanner::plan(&client, &GAS_TOKEN_HASH, SENDER.deref(), &outputs, limits)
+			.await
+			.unwrap();
+
+		// 500 transfers at 12 per transaction
+		assert_eq!(plan.transactions.len(), 42);
+		assert!(plan.transactions.iter().all(|tx| tx.script.len() <= transfer_size * 12));
+		assert_eq!(plan.transactions.last().unwrap().script.len(), transfer_size * 8);
+		assert_eq!(plan.total_fee(), 42 * (30 + 1230610));
+	}
+}
@@ -1,3 +1,4 @@
+pub use airdrop_planner::*;
 pub use call_flags::*;
 pub use contract_parameters_context::*;
 pub use invocation_script::*;
@@ -7,12 +8,14 @@ pub use transaction::*;
 pub use transaction_attribute::*;
 pub use transaction_builder::*;
 pub use transaction_error::*;
+pub use transaction_limits::*;
 pub use transaction_send_token::*;
 pub use verification_script::*;
 pub use witness::*;
 pub use witness_rule::*;
 pub use witness_scope::*;
 
+mod airdrop_planner;
 mod call_flags;
 mod contract_parameters_context;
 mod invocation_script;
@@ -23,6 +26,7 @@ mod transaction_attribute;
 mod transaction_builder;
 mod transaction_builder_tests;
 mod transaction_error;
+mod transaction_limits;
 mod transaction_send_token;
 mod verification_script;
 mod witness;
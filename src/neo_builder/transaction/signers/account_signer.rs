@@ -115,13 +115,13 @@ impl NeoSerializable for AccountSigner {
 	where
 		Self: Sized,
 	{
-		let signer_hash = reader.read_serializable::<H160>().unwrap();
-		let scopes = WitnessScope::split(reader.read_u8());
+		let signer_hash = reader.read_serializable::<H160>()?;
+		let scopes = WitnessScope::split(reader.read_bytes(1)?[0]);
 		let mut allowed_contracts = vec![];
 		let mut allowed_groups = vec![];
 		let mut rules = vec![];
 		if scopes.contains(&WitnessScope::CustomContracts) {
-			allowed_contracts = reader.read_serializable_list::<H160>().unwrap();
+			allowed_contracts = reader.read_serializable_list::<H160>()?;
 			if allowed_contracts.len() > NeoConstants::MAX_SIGNER_SUBITEMS as usize {
 				return Err(BuilderError::SignerConfiguration(format!(
                     "A signer's scope can only contain {} allowed contracts. The input data contained {} contracts.",
@@ -132,7 +132,7 @@ impl NeoSerializable for AccountSigner {
 			}
 		}
 		if scopes.contains(&WitnessScope::CustomGroups) {
-			allowed_groups = reader.read_serializable_list::<Secp256r1PublicKey>().unwrap();
+			allowed_groups = reader.read_serializable_list::<Secp256r1PublicKey>()?;
 			if allowed_groups.len() > NeoConstants::MAX_SIGNER_SUBITEMS as usize {
 				return Err(BuilderError::SignerConfiguration(format!(
                     "A signer's scope can only contain {} allowed contract groups. The input data contained {} groups.",
@@ -143,7 +143,7 @@ impl NeoSerializable for AccountSigner {
 			}
 		}
 		if scopes.contains(&WitnessScope::WitnessRules) {
-			rules = reader.read_serializable_list::<WitnessRule>().unwrap();
+			rules = reader.read_serializable_list::<WitnessRule>()?;
 			if rules.len() > NeoConstants::MAX_SIGNER_SUBITEMS as usize {
 				return Err(BuilderError::SignerConfiguration(format!(
                     "A signer's scope can only contain {} rules. The input data contained {} rules.",
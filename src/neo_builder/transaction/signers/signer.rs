@@ -555,7 +555,8 @@ mod tests {
 		prelude::{
 			Account, AccountSigner, AccountTrait, BuilderError, ContractSigner, Encoder,
 			NeoConstants, NeoSerializable, ScriptHash, ScriptHashExtension, Secp256r1PublicKey,
-			SignerTrait, WitnessAction, WitnessCondition, WitnessRule, WitnessScope,
+			SignerTrait, TransactionSigner, WitnessAction, WitnessCondition, WitnessRule,
+			WitnessScope,
 		},
 	};
 
@@ -616,6 +617,29 @@ mod tests {
 		assert!(signer.get_allowed_groups().is_empty());
 	}
 
+	#[test]
+	fn test_combined_scopes_encode_as_or_of_flags() {
+		let mut signer = AccountSigner::called_by_entry(&SCRIPT_HASH.deref().into()).unwrap();
+		signer.set_allowed_contracts(vec![*SCRIPT_HASH1]).unwrap();
+
+		let mut encoder = Encoder::new();
+		signer.encode(&mut encoder);
+		let bytes = encoder.to_bytes();
+		assert_eq!(
+			bytes[20],
+			WitnessScope::CalledByEntry.byte_repr() | WitnessScope::CustomContracts.byte_repr()
+		);
+
+		let signer = TransactionSigner::new(*SCRIPT_HASH, signer.get_scopes().clone());
+		let json = serde_json::to_value(&signer).unwrap();
+		assert_eq!(json["scopes"], "CalledByEntry,CustomContracts");
+		let decoded: TransactionSigner = serde_json::from_value(json).unwrap();
+		assert_eq!(
+			decoded.scopes,
+			vec![WitnessScope::CalledByEntry, WitnessScope::CustomContracts]
+		);
+	}
+
 	#[test]
 	fn test_build_valid_signer2() {
 		let mut signer = AccountSigner::none(&SCRIPT_HASH.deref().into()).unwrap();
@@ -1,8 +1,12 @@
-use std::hash::{Hash, Hasher};
+use std::{
+	collections::HashSet,
+	hash::{Hash, Hasher},
+};
 
+use base64::{engine::general_purpose, Engine};
 use futures_util::TryFutureExt;
 use getset::{CopyGetters, Getters, MutGetters, Setters};
-use primitive_types::U256;
+use primitive_types::{H160, U256};
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
 use serde_with::__private__::DeError;
@@ -14,9 +18,10 @@ use crate::{
 };
 use neo::{
 	prelude::{
-		APITrait, ApplicationLog, Bytes, Decoder, Encoder, HashableForVec, NameOrAddress,
-		NeoSerializable, RpcClient, Signer, TransactionAttribute, TransactionError, VarSizeTrait,
-		Witness,
+		APITrait, AccountSigner, ApplicationLog, Bytes, CodecError, Decoder, Encoder,
+		HashableForVec, NameOrAddress, NeoSerializable, ProviderError, RpcClient, ScriptHash,
+		Signer, SignerTrait, TransactionAttribute, TransactionBroadcaster, TransactionError,
+		VarSizeTrait, Witness,
 	},
 	types::ContractParameterType::H256,
 };
@@ -72,6 +77,31 @@ pub struct Transaction<'a, P: JsonRpcProvider + 'static> {
 	// pub block_time: Option<i32>,
 	#[serde(skip)]
 	pub(crate) block_count_when_sent: Option<u32>,
+
+	/// Skips the check in `send_tx` that rejects transactions whose `valid_until_block` has
+	/// already passed, so the transaction can be sent with `send_tx_with` without a client. The
+	/// block count is still recorded if the transaction has a client.
+	#[serde(skip)]
+	#[getset(get_copy = "pub", set = "pub")]
+	pub(crate) skip_expiry_check: bool,
+}
+
+/// The network fee of a transaction split into the part paid for its size and the part paid for
+/// verifying each signer's witness.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkFeeBreakdown {
+	/// The fee for the serialized size of the transaction.
+	pub size_fee: u64,
+	/// The verification fee of each signer whose verification script has a known cost, in
+	/// signer order.
+	pub verification_fee_per_signer: Vec<(ScriptHash, u64)>,
+}
+
+impl NetworkFeeBreakdown {
+	/// The sum of the size fee and all verification fees.
+	pub fn total(&self) -> u64 {
+		self.size_fee + self.verification_fee_per_signer.iter().map(|(_, fee)| fee).sum::<u64>()
+	}
 }
 
 impl<'a, P: JsonRpcProvider + 'static> Default for Transaction<'a, P> {
@@ -90,6 +120,7 @@ impl<'a, P: JsonRpcProvider + 'static> Default for Transaction<'a, P> {
 			witnesses: Default::default(),
 			// block_time: Default::default(),
 			block_count_when_sent: None,
+			skip_expiry_check: false,
 		}
 	}
 }
@@ -144,6 +175,7 @@ impl<'de, 'a, P: JsonRpcProvider + 'static> Deserialize<'de> for Transaction<'a,
 			// block_time,
 			// Fill in other fields as necessary
 			block_count_when_sent: None,
+			skip_expiry_check: false,
 		})
 	}
 }
@@ -171,6 +203,92 @@ impl<'a, T: JsonRpcProvider + 'static> Transaction<'a, T> {
 		self.witnesses.push(witness);
 	}
 
+	/// Decodes a signed transaction, e.g. one returned by `getrawtransaction` or stored in a
+	/// block.
+	///
+	/// Fails if the data is larger than a transaction may be, is not a complete transaction,
+	/// has bytes left after it, or does not have a witness for every signer.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, TransactionError> {
+		if bytes.len() > NeoConstants::MAX_TRANSACTION_SIZE as usize {
+			return Err(TransactionError::TxTooLarge);
+		}
+		let mut reader = Decoder::new(bytes);
+		let transaction = Self::decode(&mut reader)?;
+		if reader.available() > 0 {
+			return Err(TransactionError::TransactionConfiguration(format!(
+				"{} bytes left after the transaction",
+				reader.available()
+			)));
+		}
+		if transaction.witnesses.len() != transaction.signers.len() {
+			return Err(TransactionError::TransactionConfiguration(
+				"The transaction does not have the same number of signers and witnesses."
+					.to_string(),
+			));
+		}
+		Ok(transaction)
+	}
+
+	/// Decodes a signed transaction from its hex form, with or without a `0x` prefix. See
+	/// [`Transaction::from_bytes`].
+	pub fn from_hex(encoded: &str) -> Result<Self, TransactionError> {
+		let bytes = hex::decode(encoded.trim_start_matches("0x"))
+			.map_err(|e| CodecError::InvalidEncoding(e.to_string()))?;
+		Self::from_bytes(&bytes)
+	}
+
+	/// Decodes a signed transaction from its base64 form, which is how `getrawtransaction`
+	/// returns it when not verbose. See [`Transaction::from_bytes`].
+	pub fn from_base64(encoded: &str) -> Result<Self, TransactionError> {
+		let bytes = general_purpose::STANDARD
+			.decode(encoded)
+			.map_err(|e| CodecError::InvalidEncoding(e.to_string()))?;
+		Self::from_bytes(&bytes)
+	}
+
+	/// Returns the signer with the given script hash, if the transaction has one.
+	pub fn signer_for(&self, script_hash: &H160) -> Option<&Signer> {
+		self.signers.iter().find(|signer| signer.get_signer_hash() == script_hash)
+	}
+
+	/// Splits the network fee of this transaction into its size and verification parts.
+	///
+	/// `fee_per_byte` and `exec_fee_factor` are the current values of the Policy contract. The
+	/// size fee is based on the current serialized size, so the witnesses should already be
+	/// attached. A signer's verification script is taken from its witness, or from its account if
+	/// the witness is missing. Signers without a standard single-sig or multi-sig script, such as
+	/// contract signers, are left out.
+	pub fn network_fee_breakdown(
+		&self,
+		fee_per_byte: u64,
+		exec_fee_factor: u64,
+	) -> NetworkFeeBreakdown {
+		let verification_fee_per_signer = self
+			.signers
+			.iter()
+			.enumerate()
+			.filter_map(|(index, signer)| {
+				let verification_script = self
+					.witnesses
+					.get(index)
+					.map(|witness| witness.verification.clone())
+					.filter(|script| !script.script().is_empty())
+					.or_else(|| {
+						signer
+							.as_account_signer()
+							.and_then(|signer| signer.account().verification_script().clone())
+					})?;
+				let cost = verification_script.execution_cost()?;
+				Some((*signer.get_signer_hash(), cost * exec_fee_factor))
+			})
+			.collect();
+
+		NetworkFeeBreakdown {
+			size_fee: NeoSerializable::size(self) as u64 * fee_per_byte,
+			verification_fee_per_signer,
+		}
+	}
+
 	pub async fn get_hash_data(&self) -> Result<Bytes, TransactionError> {
 		if self.network.is_none() {
 			panic!("Transaction network magic is not set");
@@ -178,12 +296,13 @@ impl<'a, T: JsonRpcProvider + 'static> Transaction<'a, T> {
 		let mut encoder = Encoder::new();
 		self.serialize_without_witnesses(&mut encoder);
 		let mut data = encoder.to_bytes().hash256();
-		data.splice(0..0, self.network.as_ref().unwrap().network().await.to_be_bytes());
+		let magic = self.network.as_ref().unwrap().network_magic().await;
+		data.splice(0..0, magic.as_u32().to_be_bytes());
 
 		Ok(data)
 	}
 
-	fn get_tx_id(&self) -> Result<primitive_types::H256, TransactionError> {
+	pub(crate) fn get_tx_id(&self) -> Result<primitive_types::H256, TransactionError> {
 		let mut encoder = Encoder::new();
 		self.serialize_without_witnesses(&mut encoder);
 		let data = encoder.to_bytes().hash256();
@@ -191,6 +310,13 @@ impl<'a, T: JsonRpcProvider + 'static> Transaction<'a, T> {
 		Ok(primitive_types::H256::from_slice(&reversed_data))
 	}
 
+	/// The transaction serialized without its witnesses, which is what a signature covers.
+	pub(crate) fn unsigned_bytes(&self) -> Bytes {
+		let mut encoder = Encoder::new();
+		self.serialize_without_witnesses(&mut encoder);
+		encoder.to_bytes()
+	}
+
 	fn serialize_without_witnesses(&self, writer: &mut Encoder) {
 		writer.write_u8(self.version);
 		writer.write_u32(self.nonce);
@@ -202,10 +328,21 @@ impl<'a, T: JsonRpcProvider + 'static> Transaction<'a, T> {
 		writer.write_var_bytes(&self.script);
 	}
 
-	pub async fn send_tx(&mut self) -> Result<RawTransaction, TransactionError>
-// where
-	// 	P: APITrait,
-	{
+	/// Sends the transaction to the node it was built with, using `sendrawtransaction`.
+	pub async fn send_tx(&mut self) -> Result<RawTransaction, TransactionError> {
+		let client = self.network.ok_or(TransactionError::IllegalState(
+			"The transaction has no client to send it with".to_string(),
+		))?;
+		self.send_tx_with(client).await
+	}
+
+	/// Sends the transaction through `broadcaster`, e.g. a relayer. Unless `skip_expiry_check` is
+	/// set, the expiry check queries the block count from the transaction's client, and fails if
+	/// the transaction has none.
+	pub async fn send_tx_with(
+		&mut self,
+		broadcaster: &(impl TransactionBroadcaster + ?Sized),
+	) -> Result<RawTransaction, TransactionError> {
 		if self.signers.len() != self.witnesses.len() {
 			return Err(TransactionError::TransactionConfiguration(
 				"The transaction does not have the same number of signers and witnesses."
@@ -217,14 +354,32 @@ impl<'a, T: JsonRpcProvider + 'static> Transaction<'a, T> {
 				"The transaction exceeds the maximum transaction size.".to_string(),
 			));
 		}
-		let hex = hex::encode(self.to_array());
+		let signed_tx = self.to_array();
 		// self.throw()?;
-		self.block_count_when_sent = Some(self.network().unwrap().get_block_count().await?);
-		self.network()
-			.unwrap()
-			.send_raw_transaction(hex)
-			.await
-			.map_err(|e| TransactionError::IllegalState(e.to_string()))
+		let block_count = match (self.skip_expiry_check, self.network) {
+			(false, None) =>
+				return Err(TransactionError::IllegalState(
+					"The transaction has no client to check its expiry with".to_string(),
+				)),
+			(false, Some(client)) => {
+				let block_count = client.get_block_count().await?;
+				let current = block_count.saturating_sub(1);
+				if self.valid_until_block <= current {
+					return Err(ProviderError::TransactionExpired {
+						valid_until: self.valid_until_block,
+						current,
+					}
+					.into());
+				}
+				Some(block_count)
+			},
+			// The block count is only recorded, for `get_application_log`
+			(true, Some(client)) => Some(client.get_block_count().await?),
+			(true, None) => None,
+		};
+		let response = broadcaster.broadcast(signed_tx).await.map(RawTransaction::new)?;
+		self.block_count_when_sent = block_count;
+		Ok(response)
 	}
 
 	// TODO: implement this
@@ -328,24 +483,58 @@ impl<'a, P: JsonRpcProvider + 'static> NeoSerializable for Transaction<'a, P> {
 	where
 		Self: Sized,
 	{
+		let start = *reader.pointer();
+		if reader.available() < Transaction::<HttpProvider>::HEADER_SIZE {
+			return Err(TransactionError::InvalidTransaction);
+		}
 		let version = reader.read_u8();
+		if version != 0 {
+			return Err(TransactionError::TransactionConfiguration(format!(
+				"Unsupported transaction version {version}"
+			)));
+		}
 		let nonce = reader.read_u32();
 		let system_fee = reader.read_i64();
 		let network_fee = reader.read_i64();
+		if system_fee < 0 || network_fee < 0 {
+			return Err(TransactionError::TransactionConfiguration(
+				"The transaction has a negative fee".to_string(),
+			));
+		}
 		let valid_until_block = reader.read_u32();
 
-		// Read signers
-		let signers: Vec<Signer> = reader.read_serializable_list::<Signer>().unwrap();
+		// Signers are serialized without the signer type, so they are read as account signers
+		let signers: Vec<Signer> = reader
+			.read_serializable_list::<AccountSigner>()?
+			.into_iter()
+			.map(Signer::AccountSigner)
+			.collect();
+		if signers.is_empty() {
+			return Err(TransactionError::NoSigners);
+		}
+		let mut signer_hashes = HashSet::new();
+		if !signers.iter().all(|signer| signer_hashes.insert(*signer.get_signer_hash())) {
+			return Err(TransactionError::DuplicateSigner);
+		}
 
-		// Read attributes
 		let attributes: Vec<TransactionAttribute> =
-			reader.read_serializable_list::<TransactionAttribute>().unwrap();
+			reader.read_serializable_list::<TransactionAttribute>()?;
+		// Signers count towards the attribute limit
+		if signers.len() + attributes.len() > NeoConstants::MAX_TRANSACTION_ATTRIBUTES as usize {
+			return Err(TransactionError::TransactionConfiguration(format!(
+				"A transaction can have at most {} signers and attributes together",
+				NeoConstants::MAX_TRANSACTION_ATTRIBUTES
+			)));
+		}
 
-		let script = reader.read_var_bytes().unwrap().to_vec();
+		let script = reader.read_var_bytes()?;
+		if script.is_empty() {
+			return Err(TransactionError::EmptyScript);
+		}
 
 		let mut witnesses = vec![];
 		if reader.available() > 0 {
-			witnesses.append(&mut reader.read_serializable_list::<Witness>().unwrap());
+			witnesses.append(&mut reader.read_serializable_list::<Witness>()?);
 		}
 
 		Ok(Self {
@@ -353,7 +542,7 @@ impl<'a, P: JsonRpcProvider + 'static> NeoSerializable for Transaction<'a, P> {
 			version,
 			nonce,
 			valid_until_block,
-			size: 0,
+			size: (*reader.pointer() - start) as i32,
 			sys_fee: system_fee,
 			net_fee: network_fee,
 			signers,
@@ -362,6 +551,7 @@ impl<'a, P: JsonRpcProvider + 'static> NeoSerializable for Transaction<'a, P> {
 			witnesses,
 			// block_time: None,
 			block_count_when_sent: None,
+			skip_expiry_check: false,
 		})
 	}
 
@@ -4,7 +4,7 @@ use ethereum_types::H256;
 use rustc_serialize::base64::FromBase64;
 use serde::{Deserialize, Serialize};
 
-use neo::prelude::{Decoder, Encoder, NeoSerializable, TransactionError};
+use neo::prelude::{Decoder, Encoder, NeoSerializable, TransactionError, VarSizeTrait};
 
 use crate::prelude::Base64Encode;
 
@@ -31,13 +31,17 @@ pub enum TransactionAttribute {
 
 #[derive(Serialize, Deserialize, PartialEq, Hash, Debug, Clone)]
 struct OracleResponse {
-	pub(crate) id: u32,
+	pub(crate) id: u64,
 	pub(crate) response_code: OracleResponseCode,
 	pub(crate) result: String,
 }
 
 impl TransactionAttribute {
 	pub const MAX_RESULT_SIZE: usize = 0xffff;
+	const HIGH_PRIORITY_BYTE: u8 = 0x01;
+	const ORACLE_RESPONSE_BYTE: u8 = 0x11;
+	const NOT_VALID_BEFORE_BYTE: u8 = 0x20;
+	const CONFLICTS_BYTE: u8 = 0x21;
 
 	pub fn to_bytes(&self) -> Vec<u8> {
 		let mut bytes = vec![];
@@ -74,7 +78,7 @@ impl TransactionAttribute {
 					String::from_utf8(bytes[10..].to_vec()).map_err(|_| "Invalid UTF-8").unwrap();
 
 				Ok(TransactionAttribute::OracleResponse(OracleResponse {
-					id: id as u32,
+					id,
 					response_code,
 					result,
 				}))
@@ -114,44 +118,62 @@ impl NeoSerializable for TransactionAttribute {
 				id: _,
 				response_code: _,
 				result,
-			}) => 1 + 9 + result.len(),
-			TransactionAttribute::NotValidBefore { height: _ } => 1,
-			// TODO: check the size of the conflicts attribute
-			TransactionAttribute::Conflicts { hash: _ } => 1,
+			}) => 1 + 9 + result.from_base64().unwrap_or_default().var_size(),
+			TransactionAttribute::NotValidBefore { height: _ } => 1 + 4,
+			TransactionAttribute::Conflicts { hash: _ } => 1 + 32,
 		}
 	}
 
 	fn encode(&self, writer: &mut Encoder) {
 		match self {
 			TransactionAttribute::HighPriority => {
-				writer.write_u8(0x01);
+				writer.write_u8(Self::HIGH_PRIORITY_BYTE);
 			},
 			TransactionAttribute::OracleResponse(OracleResponse { id, response_code, result }) => {
-				writer.write_u8(0x11);
-				let mut v = id.to_be_bytes();
-				v.reverse();
-				writer.write(&v);
+				writer.write_u8(Self::ORACLE_RESPONSE_BYTE);
+				writer.write_bytes(&id.to_le_bytes());
 				writer.write_u8(response_code.clone() as u8);
 				writer.write_var_bytes(result.from_base64().unwrap().as_slice());
 			},
-			_ => {},
+			TransactionAttribute::NotValidBefore { height } => {
+				writer.write_u8(Self::NOT_VALID_BEFORE_BYTE);
+				writer.write_u32(*height);
+			},
+			TransactionAttribute::Conflicts { hash } => {
+				writer.write_u8(Self::CONFLICTS_BYTE);
+				// Transaction hashes are held in display order, but serialized little-endian
+				writer.write_bytes(&hash.as_bytes().iter().rev().copied().collect::<Vec<u8>>());
+			},
 		}
 	}
 
 	fn decode(reader: &mut Decoder) -> Result<Self, Self::Error> {
-		match reader.read_u8() {
-			0x01 => Ok(TransactionAttribute::HighPriority),
-			0x11 => {
-				let id = reader.read_u32();
-				let response_code = OracleResponseCode::try_from(reader.read_u8()).unwrap();
-				let result = reader.read_var_bytes().unwrap().to_base64();
+		match reader.read_bytes(1)?[0] {
+			Self::HIGH_PRIORITY_BYTE => Ok(TransactionAttribute::HighPriority),
+			Self::ORACLE_RESPONSE_BYTE => {
+				let id = u64::from_le_bytes(reader.read_bytes(8)?.try_into().unwrap());
+				let response_code = OracleResponseCode::try_from(reader.read_bytes(1)?[0])
+					.map_err(|_| TransactionError::InvalidTransaction)?;
+				let result = reader.read_var_bytes()?;
+				if result.len() > Self::MAX_RESULT_SIZE {
+					return Err(TransactionError::InvalidTransaction);
+				}
 
 				Ok(TransactionAttribute::OracleResponse(OracleResponse {
 					id,
 					response_code,
-					result,
+					result: result.to_base64(),
 				}))
 			},
+			Self::NOT_VALID_BEFORE_BYTE => {
+				let height = u32::from_le_bytes(reader.read_bytes(4)?.try_into().unwrap());
+				Ok(TransactionAttribute::NotValidBefore { height })
+			},
+			Self::CONFLICTS_BYTE => {
+				let mut hash = reader.read_bytes(32)?;
+				hash.reverse();
+				Ok(TransactionAttribute::Conflicts { hash: H256::from_slice(&hash) })
+			},
 			_ => Err(TransactionError::InvalidTransaction),
 		}
 	}
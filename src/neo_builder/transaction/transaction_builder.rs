@@ -18,6 +18,7 @@ use ethereum_types::H256;
 /// - `script`: The transaction script.
 /// - `fee_consumer`: An optional closure for fee-related operations.
 /// - `fee_error`: An optional error related to fee calculations.
+/// - `limits`: The protocol limits the transaction is checked against before it is built.
 ///
 /// # Example
 ///
@@ -25,7 +26,7 @@ use ethereum_types::H256;
 /// use NeoRust::prelude::TransactionBuilder;
 ///
 /// let mut tx_builder = TransactionBuilder::new();
-/// tx_builder.version(0)
+/// tx_builder.version(0).unwrap()
 ///           .nonce(1)
 ///           .valid_until_block(100)
 ///           .extend_script(vec![0x01, 0x02, 0x03]);
@@ -52,6 +53,7 @@ use getset::{CopyGetters, Getters, MutGetters, Setters};
 use once_cell::sync::Lazy;
 use primitive_types::H160;
 use rustc_serialize::hex::ToHex;
+use tracing::warn;
 
 use neo::{neo_types::ScriptHashExtension, prelude::*};
 
@@ -70,10 +72,19 @@ pub struct TransactionBuilder<'a, P: JsonRpcProvider + 'static> {
 	additional_system_fee: u64,
 	#[getset(get = "pub")]
 	attributes: Vec<TransactionAttribute>,
-	#[getset(get = "pub", set = "pub")]
+	#[getset(get = "pub")]
 	script: Option<Bytes>,
 	fee_consumer: Option<Box<dyn Fn(i64, i64)>>,
 	fee_error: Option<TransactionError>,
+	#[getset(get = "pub")]
+	limits: TransactionLimits,
+	/// The network fee of the last calculation, or `None` if it has to be recalculated because a
+	/// setter changed the transaction since
+	network_fee: Option<i64>,
+	/// Lower bound for the network fee, set when replacing a transaction
+	min_network_fee: i64,
+	/// Cosigner signatures of multi-sig signers, by the script hash of the multi-sig account
+	multisig_signatures: Vec<(ScriptHash, Vec<(Secp256r1PublicKey, Secp256r1Signature)>)>,
 }
 
 impl<'a, P: JsonRpcProvider + 'static> Debug for TransactionBuilder<'a, P> {
@@ -89,6 +100,9 @@ impl<'a, P: JsonRpcProvider + 'static> Debug for TransactionBuilder<'a, P> {
 			.field("script", &self.script)
 			// .field("fee_consumer", &self.fee_consumer)
 			.field("fee_error", &self.fee_error)
+			.field("limits", &self.limits)
+			.field("multisig_signatures", &self.multisig_signatures)
+			.field("min_network_fee", &self.min_network_fee)
 			.finish()
 	}
 }
@@ -108,6 +122,10 @@ impl<'a, P: JsonRpcProvider + 'static> Clone for TransactionBuilder<'a, P> {
 			// fee_consumer: self.fee_consumer.clone(),
 			fee_consumer: None,
 			fee_error: None,
+			limits: self.limits,
+			network_fee: self.network_fee,
+			multisig_signatures: self.multisig_signatures.clone(),
+			min_network_fee: self.min_network_fee,
 		}
 	}
 }
@@ -124,6 +142,9 @@ impl<'a, P: JsonRpcProvider + 'static> PartialEq for TransactionBuilder<'a, P> {
 			&& self.additional_system_fee == other.additional_system_fee
 			&& self.attributes == other.attributes
 			&& self.script == other.script
+			&& self.limits == other.limits
+			&& self.multisig_signatures == other.multisig_signatures
+			&& self.min_network_fee == other.min_network_fee
 	}
 }
 
@@ -137,6 +158,9 @@ impl<'a, P: JsonRpcProvider + 'static> Hash for TransactionBuilder<'a, P> {
 		self.additional_system_fee.hash(state);
 		self.attributes.hash(state);
 		self.script.hash(state);
+		self.limits.hash(state);
+		self.multisig_signatures.hash(state);
+		self.min_network_fee.hash(state);
 	}
 }
 
@@ -145,6 +169,8 @@ pub static GAS_TOKEN_HASH: Lazy<ScriptHash> =
 
 impl<'a, P: JsonRpcProvider + 'static> TransactionBuilder<'a, P> {
 	// const GAS_TOKEN_HASH: ScriptHash = ScriptHash::from_str("d2a4cff31913016155e38e474a2c06d08be276cf").unwrap();
+	/// The transaction versions accepted by the network.
+	pub const SUPPORTED_VERSIONS: &'static [u8] = &[NeoConstants::CURRENT_TX_VERSION];
 	pub const BALANCE_OF_FUNCTION: &'static str = "balanceOf";
 	pub const DUMMY_PUB_KEY: &'static str =
 		"02ec143f00b88524caf36a0121c2de09eef0519ddbe1c710a00f0e2663201ee4c0";
@@ -163,6 +189,10 @@ impl<'a, P: JsonRpcProvider + 'static> TransactionBuilder<'a, P> {
 			script: None,
 			fee_consumer: None,
 			fee_error: None,
+			limits: TransactionLimits::default(),
+			network_fee: None,
+			multisig_signatures: Vec::new(),
+			min_network_fee: 0,
 		}
 	}
 
@@ -179,13 +209,60 @@ impl<'a, P: JsonRpcProvider + 'static> TransactionBuilder<'a, P> {
 			script: None,
 			fee_consumer: None,
 			fee_error: None,
+			limits: TransactionLimits::default(),
+			network_fee: None,
+			multisig_signatures: Vec::new(),
+			min_network_fee: 0,
 		}
 	}
 
+	/// Creates a builder for a replacement of `original_tx` that pays `increment` more network fee,
+	/// e.g. because the original is stuck in the mempool. The script, signers, attributes, nonce
+	/// and `valid_until_block` are taken over, and a `Conflicts` attribute makes the network drop
+	/// the original once the replacement is accepted, so only one of them can be executed.
+	///
+	/// Logs a warning if the original has already expired, as the replacement expires with it.
+	pub async fn bump_fee(
+		original_tx: &Transaction<'a, P>,
+		increment: u64,
+	) -> Result<Self, TransactionError> {
+		let client = original_tx.network.ok_or_else(|| {
+			TransactionError::IllegalState(
+				"The transaction has no client to build its replacement with".to_string(),
+			)
+		})?;
+		let block_count = client.get_block_count().await?;
+		if original_tx.valid_until_block < block_count {
+			warn!(
+				"Replacing a transaction that expired at block {}, the current block count is {}",
+				original_tx.valid_until_block, block_count
+			);
+		}
+
+		let mut builder = Self::with_client(client);
+		builder.version = original_tx.version;
+		builder.nonce = original_tx.nonce;
+		builder.valid_until_block = Some(original_tx.valid_until_block);
+		builder.signers = original_tx.signers.clone();
+		builder.attributes = original_tx.attributes.clone();
+		builder.script = Some(original_tx.script.clone());
+		builder.min_network_fee = original_tx.net_fee + increment as i64;
+		let hash = original_tx.get_tx_id()?;
+		builder.add_attributes(vec![TransactionAttribute::Conflicts { hash }])?;
+		Ok(builder)
+	}
+
 	// Configuration
-	pub fn version(&mut self, version: u8) -> &mut Self {
+	/// Sets the transaction version. Fails with [`BuilderError::UnsupportedVersion`] if the
+	/// version is not one of [`Self::SUPPORTED_VERSIONS`], as the network would reject it.
+	pub fn version(&mut self, version: u8) -> Result<&mut Self, BuilderError> {
+		if !Self::SUPPORTED_VERSIONS.contains(&version) {
+			return Err(BuilderError::UnsupportedVersion(version));
+		}
+
 		self.version = version;
-		self
+		self.network_fee = None;
+		Ok(self)
 	}
 
 	pub fn nonce(&mut self, nonce: u32) -> Result<&mut Self, TransactionError> {
@@ -195,6 +272,7 @@ impl<'a, P: JsonRpcProvider + 'static> TransactionBuilder<'a, P> {
 		}
 
 		self.nonce = nonce;
+		self.network_fee = None;
 		Ok(self)
 	}
 
@@ -207,14 +285,15 @@ impl<'a, P: JsonRpcProvider + 'static> TransactionBuilder<'a, P> {
 		}
 
 		self.valid_until_block = Some(block);
+		self.network_fee = None;
 		Ok(self)
 	}
 
-	// Set script
-	// pub fn set_script(&mut self, script: Vec<u8>) -> &mut Self {
-	// 	self.script = Some(script);
-	// 	self
-	// }
+	pub fn set_script(&mut self, script: Option<Bytes>) -> &mut Self {
+		self.script = script;
+		self.network_fee = None;
+		self
+	}
 
 	pub fn first_signer(&mut self, sender: &Account) -> Result<&mut Self, TransactionError> {
 		self.first_signer_by_hash(&sender.get_script_hash())
@@ -227,18 +306,58 @@ impl<'a, P: JsonRpcProvider + 'static> TransactionBuilder<'a, P> {
 		if let Some(pos) = self.signers.iter().position(|s| s.get_signer_hash() == sender) {
 			let s = self.signers.remove(pos);
 			self.signers.insert(0, s);
+			self.network_fee = None;
 			Ok(self)
 		} else {
 			Err(TransactionError::ScriptFormat(format!("Could not find a signer with script hash {}. Make sure to add the signer before calling this method.", sender.to_string()).into()))
 		}
 	}
 
+	/// Sets a script that calls several contracts one after the other in a single transaction,
+	/// e.g. `approve` followed by `swap`. If a call faults, the transaction faults and none of the
+	/// calls take effect. A call that fails by returning `false`, like a NEP-17 `transfer`, does
+	/// not fault, so the calls after it still run.
+	///
+	/// Fails with [`BuilderError::IllegalArgument`] if a contract hash is zero or a method name is
+	/// empty.
+	pub fn multi_invoke(
+		&mut self,
+		calls: &[(ScriptHash, &str, Vec<ContractParameter>)],
+	) -> Result<&mut Self, BuilderError> {
+		if calls.is_empty() {
+			return Err(BuilderError::IllegalArgument(
+				"At least one contract call is required.".to_string(),
+			));
+		}
+
+		let mut sb = ScriptBuilder::new();
+		for (contract_hash, method, params) in calls {
+			if contract_hash.is_zero() {
+				return Err(BuilderError::IllegalArgument(format!(
+					"Invalid contract hash {} for method {}.",
+					contract_hash.to_hex_big_endian(),
+					method
+				)));
+			}
+			if method.is_empty() {
+				return Err(BuilderError::IllegalArgument(format!(
+					"Empty method name for contract {}.",
+					contract_hash.to_hex_big_endian()
+				)));
+			}
+			sb.contract_call(contract_hash, method, params, None)?;
+		}
+		self.set_script(Some(sb.to_bytes()));
+		Ok(self)
+	}
+
 	pub fn extend_script(&mut self, script: Vec<u8>) -> &mut Self {
 		if let Some(ref mut existing_script) = self.script {
 			existing_script.extend(script);
 		} else {
 			self.script = Some(script);
 		}
+		self.network_fee = None;
 		self
 	}
 
@@ -257,7 +376,7 @@ impl<'a, P: JsonRpcProvider + 'static> TransactionBuilder<'a, P> {
 	}
 
 	// Get unsigned transaction
-	pub async fn get_unsigned_tx(&mut self) -> Result<Transaction<P>, TransactionError> {
+	pub async fn get_unsigned_tx(&mut self) -> Result<Transaction<'a, P>, TransactionError> {
 		// Validate configuration
 		if self.signers.is_empty() {
 			return Err(TransactionError::NoSigners);
@@ -274,11 +393,6 @@ impl<'a, P: JsonRpcProvider + 'static> TransactionBuilder<'a, P> {
 			return Err(TransactionError::DuplicateSigner);
 		}
 
-		// Check signer limits
-		if self.signers.len() > NeoConstants::MAX_SIGNER_SUBITEMS as usize {
-			return Err(TransactionError::TooManySigners);
-		}
-
 		// Validate script
 		if let Some(script) = &self.script {
 			if script.is_empty() {
@@ -288,6 +402,9 @@ impl<'a, P: JsonRpcProvider + 'static> TransactionBuilder<'a, P> {
 			return Err(TransactionError::NoScript);
 		}
 
+		// Check protocol limits before any fees are calculated
+		self.check_limits()?;
+
 		if self.valid_until_block.is_none() {
 			self.valid_until_block = Some(
 				self.fetch_current_block_count().await?
@@ -316,7 +433,8 @@ impl<'a, P: JsonRpcProvider + 'static> TransactionBuilder<'a, P> {
 
 		let system_fee = self.get_system_fee().await? + self.additional_system_fee as i64;
 
-		let network_fee = self.get_network_fee().await? + self.additional_network_fee as i64;
+		let network_fee = (self.cached_network_fee().await? + self.additional_network_fee as i64)
+			.max(self.min_network_fee);
 
 		// Check sender balance if needed
 		let mut tx = Transaction {
@@ -333,6 +451,7 @@ impl<'a, P: JsonRpcProvider + 'static> TransactionBuilder<'a, P> {
 			witnesses: vec![],
 			// block_time: None,
 			block_count_when_sent: None,
+			skip_expiry_check: false,
 		};
 
 		// It's impossible to calculate network fee when the tx is unsigned, because there is no witness
@@ -372,6 +491,17 @@ impl<'a, P: JsonRpcProvider + 'static> TransactionBuilder<'a, P> {
 		Ok(i64::from_str(response.gas_consumed.as_str()).unwrap()) // example
 	}
 
+	/// Returns the network fee of the last calculation if no setter changed the transaction
+	/// since, and recalculates it otherwise.
+	async fn cached_network_fee(&mut self) -> Result<i64, TransactionError> {
+		if let Some(fee) = self.network_fee {
+			return Ok(fee);
+		}
+		let fee = self.get_network_fee().await?;
+		self.network_fee = Some(fee);
+		Ok(fee)
+	}
+
 	async fn get_network_fee(&mut self) -> Result<i64, TransactionError> {
 		// Check sender balance if needed
 		let mut tx = Transaction {
@@ -388,6 +518,7 @@ impl<'a, P: JsonRpcProvider + 'static> TransactionBuilder<'a, P> {
 			witnesses: vec![],
 			// block_time: None,
 			block_count_when_sent: None,
+			skip_expiry_check: false,
 		};
 		let mut has_atleast_one_signing_account = false;
 
@@ -497,7 +628,7 @@ impl<'a, P: JsonRpcProvider + 'static> TransactionBuilder<'a, P> {
 	}
 
 	// Sign transaction
-	pub async fn sign(&mut self) -> Result<Transaction<P>, BuilderError> {
+	pub async fn sign(&mut self) -> Result<Transaction<'a, P>, BuilderError> {
 		init_logger();
 		let mut unsigned_tx = self.get_unsigned_tx().await?;
 		// debug!("unsigned_tx: {:?}", unsigned_tx);
@@ -511,10 +642,8 @@ impl<'a, P: JsonRpcProvider + 'static> TransactionBuilder<'a, P> {
 				let account_signer = signer.as_account_signer().unwrap();
 				let acc = &account_signer.account;
 				if acc.is_multi_sig() {
-					return Err(BuilderError::IllegalState(
-						"Transactions with multi-sig signers cannot be signed automatically."
-							.to_string(),
-					));
+					witnesses_to_add.push(self.multisig_witness(acc, &tx_bytes)?);
+					continue;
 				}
 				let key_pair = acc.key_pair().as_ref().ok_or_else(|| {
                     BuilderError::InvalidConfiguration(
@@ -535,9 +664,150 @@ impl<'a, P: JsonRpcProvider + 'static> TransactionBuilder<'a, P> {
 			unsigned_tx.add_witness(witness);
 		}
 
+		if unsigned_tx.witnesses.len() > self.limits.max_signers {
+			return Err(BuilderError::TooManyWitnesses {
+				count: unsigned_tx.witnesses.len(),
+				max: self.limits.max_signers,
+			});
+		}
+		let size = NeoSerializable::size(&unsigned_tx);
+		if size > self.limits.max_transaction_size {
+			return Err(BuilderError::TransactionTooLarge {
+				size,
+				max: self.limits.max_transaction_size,
+			});
+		}
+
 		Ok(unsigned_tx)
 	}
 
+	/// Supplies the cosigner signatures for a multi-sig signer, as `sign` cannot create them
+	/// itself. The signatures are verified against the transaction when it is signed, and the
+	/// first `m` of them in the order of the account's public keys make up the witness.
+	///
+	/// Fails if the account is not a multi-sig account, if a public key is not one of the
+	/// account's or is given twice, or if fewer signatures than the signing threshold are given.
+	pub fn add_multisig_witness(
+		&mut self,
+		multisig_account: &Account,
+		signatures: Vec<(Secp256r1PublicKey, Secp256r1Signature)>,
+	) -> Result<&mut Self, BuilderError> {
+		let verification_script = multisig_account
+			.verification_script()
+			.as_ref()
+			.filter(|script| script.is_multi_sig())
+			.ok_or_else(|| {
+				BuilderError::IllegalArgument(format!(
+					"Account {} is not a multi-sig account",
+					multisig_account.get_address()
+				))
+			})?;
+		let public_keys = verification_script.get_public_keys()?;
+		let threshold = verification_script.get_signing_threshold()?;
+
+		for (i, (public_key, _)) in signatures.iter().enumerate() {
+			if !public_keys.contains(public_key) {
+				return Err(BuilderError::IllegalArgument(format!(
+					"Public key {} is not part of multi-sig account {}",
+					public_key.get_encoded_compressed_hex(),
+					multisig_account.get_address()
+				)));
+			}
+			if signatures[..i].iter().any(|(other, _)| other == public_key) {
+				return Err(BuilderError::IllegalArgument(format!(
+					"Public key {} is given more than once",
+					public_key.get_encoded_compressed_hex()
+				)));
+			}
+		}
+		if signatures.len() < threshold {
+			return Err(BuilderError::NotEnoughSignatures { count: signatures.len(), threshold });
+		}
+
+		let script_hash = multisig_account.get_script_hash();
+		self.multisig_signatures.retain(|(hash, _)| *hash != script_hash);
+		self.multisig_signatures.push((script_hash, signatures));
+		self.network_fee = None;
+		Ok(self)
+	}
+
+	fn multisig_witness(&self, account: &Account, message: &[u8]) -> Result<Witness, BuilderError> {
+		let script_hash = account.get_script_hash();
+		let signatures = self
+			.multisig_signatures
+			.iter()
+			.find(|(hash, _)| *hash == script_hash)
+			.map(|(_, signatures)| signatures)
+			.ok_or_else(|| {
+				BuilderError::IllegalState(format!(
+					"No signatures were added for multi-sig signer {}, use add_multisig_witness",
+					account.get_address()
+				))
+			})?;
+		// add_multisig_witness only accepts multi-sig accounts
+		let verification_script = account.verification_script().clone().unwrap();
+
+		for (public_key, signature) in signatures {
+			public_key.verify(message, signature).map_err(|_| {
+				BuilderError::InvalidSignature(format!(
+					"The signature of {} does not verify against the transaction",
+					public_key.get_encoded_compressed_hex()
+				))
+			})?;
+		}
+		// CheckMultisig expects the signatures in the order of the public keys in the
+		// verification script, which are sorted
+		let threshold = verification_script.get_signing_threshold()?;
+		let mut sorted: Vec<_> = signatures.iter().collect();
+		sorted.sort_by(|(a, _), (b, _)| a.cmp(b));
+		let ordered = sorted
+			.into_iter()
+			.take(threshold)
+			.map(|(_, signature)| signature.clone())
+			.collect();
+		Witness::create_multi_sig_witness_script(ordered, verification_script)
+	}
+
+	/// Pads the calculated network fee by `gas` fractions of GAS, e.g. to prioritise the
+	/// transaction. Adds to any additional network fee set before.
+	pub fn with_extra_network_fee(&mut self, gas: u64) -> &mut Self {
+		self.additional_network_fee = self.additional_network_fee.saturating_add(gas);
+		self
+	}
+
+	/// Sets the protocol limits the transaction is checked against. Use this for networks whose
+	/// protocol settings differ from Neo N3's defaults.
+	pub fn set_limits(&mut self, limits: TransactionLimits) -> &mut Self {
+		self.limits = limits;
+		self
+	}
+
+	fn check_limits(&self) -> Result<(), BuilderError> {
+		let limits = &self.limits;
+		if self.signers.len() > limits.max_signers {
+			return Err(BuilderError::TooManySigners(format!(
+				"{} signers, the maximum is {}",
+				self.signers.len(),
+				limits.max_signers
+			)));
+		}
+		let attributes = self.signers.len() + self.attributes.len();
+		if attributes > limits.max_attributes {
+			return Err(BuilderError::TooManyAttributes {
+				count: attributes,
+				max: limits.max_attributes,
+			});
+		}
+		let script_size = self.script.as_ref().map_or(0, |script| script.len());
+		if script_size > limits.max_script_size {
+			return Err(BuilderError::ScriptTooLarge {
+				size: script_size,
+				max: limits.max_script_size,
+			});
+		}
+		Ok(())
+	}
+
 	fn signers_contain_multi_sig_with_committee_member(&self, committee: &HashSet<H160>) -> bool {
 		for signer in &self.signers {
 			if let Some(account_signer) = signer.as_account_signer() {
@@ -567,6 +837,7 @@ impl<'a, P: JsonRpcProvider + 'static> TransactionBuilder<'a, P> {
 		self.check_and_throw_if_max_attributes_exceeded(signers.len(), self.attributes.len())?;
 
 		self.signers = signers;
+		self.network_fee = None;
 		Ok(self)
 	}
 
@@ -598,6 +869,7 @@ impl<'a, P: JsonRpcProvider + 'static> TransactionBuilder<'a, P> {
 				},
 			}
 		}
+		self.network_fee = None;
 		Ok(self)
 	}
 
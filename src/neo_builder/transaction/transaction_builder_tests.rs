@@ -6,11 +6,13 @@ mod tests {
 		neo_protocol::{NeoProtocol, NeoVersion},
 		neo_types::ScriptHashExtension,
 		prelude::{
-			init_logger, ApplicationLog, BuilderError, ContractParameter, ContractSigner,
-			InvocationResult, Signer, StackItem, TestConstants, TransactionAttribute,
+			init_logger, ApplicationLog, BuilderError, Bytes, ContractParameter, ContractSigner,
+			InvocationResult, Signer, SignerTrait, StackItem, TestConstants, TransactionAttribute,
 			TransactionError, Witness, WitnessScope,
 		},
+		types::NeoVMStateType,
 	};
+	use base64::{engine::general_purpose, Engine};
 	use lazy_static::lazy_static;
 	use log::info;
 	use neo::{
@@ -18,9 +20,11 @@ mod tests {
 		config::{NeoConfig, NEOCONFIG},
 		prelude::{
 			APITrait, Account, AccountSigner, AccountTrait, Http, HttpProvider, KeyPair,
-			NeoConstants, RawTransaction, RpcClient, ScriptBuilder, Secp256r1PrivateKey,
-			TransactionBuilder,
+			NeoConstants, NeoSerializable, ProviderError, RawTransaction, RpcClient, ScriptBuilder,
+			Secp256r1PrivateKey, Transaction, TransactionBroadcaster, TransactionBuilder,
+			TransactionLimits,
 		},
+		types::VMState,
 	};
 	use num_bigint::BigInt;
 	use primitive_types::{H160, H256};
@@ -29,8 +33,6 @@ mod tests {
 	use std::{default, ops::Deref, str::FromStr, sync::Arc};
 	use tokio::sync::{Mutex, OnceCell};
 	use tracing::debug;
-	use neo::types::VMState;
-	use crate::types::NeoVMStateType;
 
 	lazy_static! {
 		pub static ref ACCOUNT1: Account = Account::from_key_pair(
@@ -438,6 +440,171 @@ mod tests {
 		}
 	}
 
+	#[derive(Default)]
+	struct RecordingBroadcaster {
+		sent: std::sync::Mutex<Vec<Bytes>>,
+	}
+
+	#[async_trait::async_trait]
+	impl TransactionBroadcaster for RecordingBroadcaster {
+		async fn broadcast(&self, signed_tx: Bytes) -> Result<H256, ProviderError> {
+			self.sent.lock().unwrap().push(signed_tx);
+			Ok(H256::repeat_byte(0xab))
+		}
+	}
+
+	#[tokio::test]
+	async fn test_send_tx_with_custom_broadcaster() {
+		let mut mock_provider = MockClient::new().await;
+		mock_provider
+			.mock_response_with_file_ignore_param("invokescript", "invokescript_symbol_neo.json")
+			.await;
+		mock_provider
+			.mock_response_with_file_ignore_param("getblockcount", "getblockcount_1000.json")
+			.await;
+		mock_provider
+			.mock_response_with_file_ignore_param("calculatenetworkfee", "calculatenetworkfee.json")
+			.await;
+		mock_provider.mount_mocks().await;
+		let client = mock_provider.into_client();
+
+		let mut tb = TransactionBuilder::with_client(&client);
+		tb.set_script(Some(vec![1, 2, 3]))
+			.set_signers(vec![AccountSigner::called_by_entry(ACCOUNT1.deref()).unwrap().into()])
+			.unwrap();
+		let mut tx = tb.sign().await.unwrap();
+
+		// No sendrawtransaction mock is mounted, so the node is never asked to relay it
+		let broadcaster = RecordingBroadcaster::default();
+		let response = tx.send_tx_with(&broadcaster).await.unwrap();
+
+		assert_eq!(response.hash, H256::repeat_byte(0xab));
+		assert_eq!(*broadcaster.sent.lock().unwrap(), vec![tx.to_array()]);
+	}
+
+	#[tokio::test]
+	async fn test_send_tx_with_skipped_expiry_check_needs_no_client() {
+		let mut tx = Transaction::<HttpProvider> {
+			valid_until_block: 500,
+			signers: vec![AccountSigner::called_by_entry(ACCOUNT1.deref()).unwrap().into()],
+			script: vec![1, 2, 3],
+			witnesses: vec![Witness::new()],
+			..Default::default()
+		};
+		let broadcaster = RecordingBroadcaster::default();
+
+		let result = tx.send_tx_with(&broadcaster).await;
+		assert!(matches!(result, Err(TransactionError::IllegalState(_))));
+		assert!(broadcaster.sent.lock().unwrap().is_empty());
+
+		tx.set_skip_expiry_check(true);
+		let response = tx.send_tx_with(&broadcaster).await.unwrap();
+		assert_eq!(response.hash, H256::repeat_byte(0xab));
+		assert_eq!(*broadcaster.sent.lock().unwrap(), vec![tx.to_array()]);
+	}
+
+	#[tokio::test]
+	async fn test_send_tx_with_skipped_expiry_check_records_block_count() {
+		let mut mock_provider = MockClient::new().await;
+		mock_provider
+			.mock_response_with_file_ignore_param("getblockcount", "getblockcount_1000.json")
+			.await;
+		mock_provider
+			.mock_get_application_log(Some(ApplicationLog {
+				transaction_id: H256::repeat_byte(0x01),
+				..Default::default()
+			}))
+			.await;
+		mock_provider.mount_mocks().await;
+		let client = mock_provider.into_client();
+
+		// Expired at block 1000, but the expiry check is skipped
+		let mut tx = Transaction {
+			network: Some(&client),
+			valid_until_block: 500,
+			signers: vec![AccountSigner::called_by_entry(ACCOUNT1.deref()).unwrap().into()],
+			script: vec![1, 2, 3],
+			witnesses: vec![Witness::new()],
+			..Default::default()
+		};
+		tx.set_skip_expiry_check(true);
+		tx.send_tx_with(&RecordingBroadcaster::default()).await.unwrap();
+
+		let application_log = tx.get_application_log(&client).await.unwrap();
+		assert_eq!(application_log.transaction_id, H256::repeat_byte(0x01));
+	}
+
+	/// Rejects every transaction as already in the node's memory pool.
+	struct DuplicateBroadcaster;
+
+	#[async_trait::async_trait]
+	impl TransactionBroadcaster for DuplicateBroadcaster {
+		async fn broadcast(&self, _signed_tx: Bytes) -> Result<H256, ProviderError> {
+			Err(ProviderError::AlreadyInMempool("AlreadyInPool".to_string()))
+		}
+	}
+
+	#[tokio::test]
+	async fn test_send_tx_with_returns_the_provider_error() {
+		let mut tx = Transaction::<HttpProvider> {
+			valid_until_block: 500,
+			signers: vec![AccountSigner::called_by_entry(ACCOUNT1.deref()).unwrap().into()],
+			script: vec![1, 2, 3],
+			witnesses: vec![Witness::new()],
+			..Default::default()
+		};
+		tx.set_skip_expiry_check(true);
+
+		let result = tx.send_tx_with(&DuplicateBroadcaster).await;
+		assert!(matches!(
+			result,
+			Err(TransactionError::ProviderError(ProviderError::AlreadyInMempool(_)))
+		));
+	}
+
+	#[tokio::test]
+	async fn test_bump_fee() {
+		let mut mock_provider = MockClient::new().await;
+		mock_provider
+			.mock_response_with_file_ignore_param("invokescript", "invokescript_symbol_neo.json")
+			.await;
+		mock_provider
+			.mock_response_with_file_ignore_param("getblockcount", "getblockcount_1000.json")
+			.await;
+		mock_provider
+			.mock_response_with_file_ignore_param("calculatenetworkfee", "calculatenetworkfee.json")
+			.await;
+		mock_provider.mount_mocks().await;
+		let client = mock_provider.into_client();
+
+		let mut tb = TransactionBuilder::with_client(&client);
+		tb.set_script(Some(vec![1, 2, 3]))
+			.set_signers(vec![AccountSigner::called_by_entry(ACCOUNT1.deref()).unwrap().into()])
+			.unwrap()
+			.nonce(42)
+			.unwrap()
+			.valid_until_block(2000)
+			.unwrap();
+		let original = tb.sign().await.unwrap();
+
+		let replacement = TransactionBuilder::bump_fee(&original, 500_000)
+			.await
+			.unwrap()
+			.sign()
+			.await
+			.unwrap();
+
+		assert_eq!(replacement.net_fee, original.net_fee + 500_000);
+		assert_eq!(replacement.nonce, 42);
+		assert_eq!(replacement.valid_until_block, 2000);
+		assert_eq!(replacement.script, original.script);
+		assert_eq!(replacement.signers, original.signers);
+		assert_eq!(
+			replacement.attributes,
+			vec![TransactionAttribute::Conflicts { hash: original.get_tx_id().unwrap() }]
+		);
+	}
+
 	#[tokio::test]
 	async fn test_fail_building_transaction_with_incorrect_nonce() {
 		let client = CLIENT.get_or_init(|| async { MockClient::new().await.into_client() }).await;
@@ -1251,6 +1418,227 @@ mod tests {
 			.contains("The transaction does not have the same number of signers and witnesses."));
 	}
 
+	#[tokio::test]
+	async fn test_fail_sending_expired_transaction() {
+		let mock_provider = Arc::new(Mutex::new(MockClient::new().await));
+		{
+			let mut mock_provider_guard = mock_provider.lock().await;
+			let mut mock_provider_guard = mock_provider_guard
+				.mock_response_with_file_ignore_param("getblockcount", "getblockcount_1000.json")
+				.await;
+			mock_provider_guard.mount_mocks().await;
+		}
+		let client = {
+			let mock_provider = mock_provider.lock().await;
+			mock_provider.into_client()
+		};
+
+		let mut tx = Transaction::<HttpProvider> {
+			network: Some(&client),
+			valid_until_block: 500,
+			signers: vec![AccountSigner::called_by_entry(&ACCOUNT1).unwrap().into()],
+			script: vec![1, 2, 3],
+			witnesses: vec![Witness::new()],
+			..Default::default()
+		};
+
+		let result = tx.send_tx().await;
+		assert_eq!(
+			result,
+			Err(TransactionError::ProviderError(ProviderError::TransactionExpired {
+				valid_until: 500,
+				current: 999,
+			}))
+		);
+	}
+
+	#[test]
+	fn test_multi_invoke() {
+		let neo = H160::from_str(TestConstants::NEO_TOKEN_HASH).unwrap();
+		let gas = H160::from_str(TestConstants::GAS_TOKEN_HASH).unwrap();
+		let spender = ACCOUNT2.get_script_hash();
+		let amount = ContractParameter::integer(10);
+		let approve = vec![ACCOUNT1.get_script_hash().into(), spender.into(), amount.clone()];
+		let swap = vec![spender.into(), amount];
+
+		let mut tb = TransactionBuilder::<HttpProvider>::new();
+		tb.multi_invoke(&[(gas, "approve", approve.clone()), (neo, "swap", swap.clone())])
+			.unwrap();
+
+		let mut expected = ScriptBuilder::new();
+		expected
+			.contract_call(&gas, "approve", &approve, None)
+			.unwrap()
+			.contract_call(&neo, "swap", &swap, None)
+			.unwrap();
+		assert_eq!(tb.script().as_ref().unwrap(), &expected.to_bytes());
+
+		let err = tb.multi_invoke(&[(gas, "approve", approve), (H160::zero(), "swap", swap)]);
+		assert!(matches!(err, Err(BuilderError::IllegalArgument(_))));
+	}
+
+	#[tokio::test]
+	async fn test_network_fee_recalculated_only_after_setters() {
+		let mut mock_provider = MockClient::new().await;
+		mock_provider
+			.mock_response_with_file_ignore_param("invokescript", "invokescript_symbol_neo.json")
+			.await;
+		mock_provider
+			.mock_response_with_file_ignore_param("calculatenetworkfee", "calculatenetworkfee.json")
+			.await;
+		mock_provider.mount_mocks().await;
+		let client = mock_provider.into_client();
+
+		let fee_requests = || async {
+			mock_provider
+				.server()
+				.received_requests()
+				.await
+				.unwrap()
+				.iter()
+				.filter(|request| {
+					String::from_utf8_lossy(&request.body).contains("calculatenetworkfee")
+				})
+				.count()
+		};
+
+		let mut tb = TransactionBuilder::with_client(&client);
+		tb.set_script(Some(vec![1, 2, 3]))
+			.set_signers(vec![AccountSigner::called_by_entry(ACCOUNT1.deref()).unwrap().into()])
+			.unwrap()
+			.valid_until_block(1000)
+			.unwrap()
+			.with_extra_network_fee(100);
+
+		let tx = tb.get_unsigned_tx().await.unwrap();
+		assert_eq!(tx.net_fee, 1230610 + 100);
+		tb.get_unsigned_tx().await.unwrap();
+		assert_eq!(fee_requests().await, 1);
+
+		// The extra fee pads the calculated fee, so it is added without recalculating it
+		let tx = tb.with_extra_network_fee(50).get_unsigned_tx().await.unwrap();
+		assert_eq!(tx.net_fee, 1230610 + 150);
+		assert_eq!(fee_requests().await, 1);
+
+		tb.nonce(1).unwrap();
+		tb.get_unsigned_tx().await.unwrap();
+		assert_eq!(fee_requests().await, 2);
+
+		tb.set_script(Some(vec![1, 2, 3, 4]));
+		tb.get_unsigned_tx().await.unwrap();
+		assert_eq!(fee_requests().await, 3);
+	}
+
+	#[tokio::test]
+	async fn test_sign_with_multisig_witness() {
+		let mut mock_provider = MockClient::new().await;
+		mock_provider
+			.mock_response_with_file_ignore_param("invokescript", "invokescript_symbol_neo.json")
+			.await;
+		mock_provider
+			.mock_response_with_file_ignore_param("calculatenetworkfee", "calculatenetworkfee.json")
+			.await;
+		mock_provider.mount_mocks().await;
+		let client = mock_provider.into_client();
+
+		let key_pairs: Vec<KeyPair> = (0..3).map(|_| KeyPair::new_random()).collect();
+		let mut public_keys: Vec<_> =
+			key_pairs.iter().map(|key_pair| key_pair.public_key()).collect();
+		let multisig = Account::multi_sig_from_public_keys(&mut public_keys, 2).unwrap();
+
+		let mut tb = TransactionBuilder::with_client(&client);
+		tb.set_script(Some(vec![1, 2, 3]))
+			.set_signers(vec![AccountSigner::called_by_entry(&multisig).unwrap().into()])
+			.unwrap()
+			.valid_until_block(1000)
+			.unwrap();
+		let message = tb.get_unsigned_tx().await.unwrap().get_hash_data().await.unwrap();
+		let sign = |key_pair: &KeyPair| {
+			(key_pair.public_key(), key_pair.private_key.sign_tx(&message).unwrap())
+		};
+
+		assert_eq!(
+			tb.add_multisig_witness(&multisig, vec![sign(&key_pairs[0])]).err(),
+			Some(BuilderError::NotEnoughSignatures { count: 1, threshold: 2 })
+		);
+
+		// A signature claimed for the wrong public key
+		let forged = (key_pairs[1].public_key(), sign(&key_pairs[0]).1);
+		tb.add_multisig_witness(&multisig, vec![forged, sign(&key_pairs[2])]).unwrap();
+		assert!(matches!(tb.sign().await, Err(BuilderError::InvalidSignature(_))));
+
+		tb.add_multisig_witness(&multisig, vec![sign(&key_pairs[2]), sign(&key_pairs[0])])
+			.unwrap();
+		let tx = tb.sign().await.unwrap();
+
+		let witness = &tx.witnesses[0];
+		assert_eq!(witness.verification, multisig.verification_script.clone().unwrap());
+		let signatures = witness.invocation.get_signatures();
+		assert_eq!(signatures.len(), 2);
+		let signing_keys = witness
+			.verification
+			.get_public_keys()
+			.unwrap()
+			.into_iter()
+			.filter(|key| *key != key_pairs[1].public_key());
+		for (key, signature) in signing_keys.zip(&signatures) {
+			assert!(key.verify(&message, signature).is_ok());
+		}
+
+		// With more signatures than the threshold, those of the lowest public keys are used
+		tb.add_multisig_witness(
+			&multisig,
+			vec![sign(&key_pairs[1]), sign(&key_pairs[2]), sign(&key_pairs[0])],
+		)
+		.unwrap();
+		let tx = tb.sign().await.unwrap();
+		let signatures = tx.witnesses[0].invocation.get_signatures();
+		assert_eq!(signatures.len(), 2);
+		let signing_keys = tx.witnesses[0].verification.get_public_keys().unwrap();
+		for (key, signature) in signing_keys.iter().zip(&signatures) {
+			assert!(key.verify(&message, signature).is_ok());
+		}
+	}
+
+	#[tokio::test]
+	async fn test_fail_building_transaction_exceeding_signer_limit() {
+		let client = CLIENT.get_or_init(|| async { MockClient::new().await.into_client() }).await;
+		let mut tb = TransactionBuilder::with_client(&client);
+		tb.set_script(Some(vec![1, 2, 3]))
+			.set_limits(TransactionLimits { max_signers: 1, ..Default::default() })
+			.set_signers(vec![
+				AccountSigner::called_by_entry(ACCOUNT1.deref()).unwrap().into(),
+				AccountSigner::called_by_entry(ACCOUNT2.deref()).unwrap().into(),
+			])
+			.unwrap();
+
+		let err = tb.get_unsigned_tx().await.err().unwrap();
+		assert_eq!(
+			err,
+			TransactionError::BuilderError(BuilderError::TooManySigners(
+				"2 signers, the maximum is 1".to_string()
+			))
+		);
+	}
+
+	#[tokio::test]
+	async fn test_fail_building_transaction_exceeding_script_size_limit() {
+		let client = CLIENT.get_or_init(|| async { MockClient::new().await.into_client() }).await;
+		let mut tb = TransactionBuilder::with_client(&client);
+		tb.set_script(Some(vec![0x40; NeoConstants::MAX_TRANSACTION_SCRIPT_SIZE as usize + 1]))
+			.set_signers(vec![AccountSigner::called_by_entry(ACCOUNT1.deref()).unwrap().into()])
+			.unwrap();
+
+		let err = tb.get_unsigned_tx().await.err().unwrap();
+		assert_eq!(
+			err,
+			TransactionError::BuilderError(BuilderError::ScriptTooLarge {
+				size: NeoConstants::MAX_TRANSACTION_SCRIPT_SIZE as usize + 1,
+				max: NeoConstants::MAX_TRANSACTION_SCRIPT_SIZE as usize,
+			})
+		);
+	}
+
 	#[tokio::test]
 	async fn test_contract_witness() {
 		let mock_provider = Arc::new(Mutex::new(MockClient::new().await));
@@ -1629,9 +2017,13 @@ mod tests {
 		let mut tx_builder = TransactionBuilder::with_client(&client);
 		let _ = tx_builder.do_if_sender_cannot_cover_fees(Box::new(|_, _| {}));
 
-		let result = tx_builder.throw_if_sender_cannot_cover_fees(TransactionError::InsufficientFunds);
+		let result =
+			tx_builder.throw_if_sender_cannot_cover_fees(TransactionError::InsufficientFunds);
 		assert!(result.is_err());
-		assert!(result.unwrap_err().to_string().contains("Cannot handle a supplier for this case, since a consumer "));
+		assert!(result
+			.unwrap_err()
+			.to_string()
+			.contains("Cannot handle a supplier for this case, since a consumer "));
 	}
 
 	#[tokio::test]
@@ -1794,8 +2186,10 @@ mod tests {
 			Account::from_wif("L1WMhxazScMhUrdv34JqQb1HFSQmWeN2Kpc1R9JGKwL7CDNP21uR").unwrap();
 
 		let mut tx_builder = TransactionBuilder::with_client(&client);
+		assert!(matches!(tx_builder.version(1), Err(BuilderError::UnsupportedVersion(1))));
 		tx_builder
-			.version(1)
+			.version(0)
+			.unwrap()
 			.set_script(Some(vec![1, 2, 3]))
 			.set_signers(vec![AccountSigner::called_by_entry(&account1).unwrap().into()]);
 
@@ -1804,7 +2198,7 @@ mod tests {
 			Err(e) => panic!("Error: {}", e),
 		};
 
-		assert_eq!(tx.version, 1);
+		assert_eq!(tx.version, 0);
 	}
 
 	#[tokio::test]
@@ -1966,7 +2360,10 @@ mod tests {
 
 		let result = tx_builder.first_signer(&account2);
 		assert!(result.is_err());
-		assert!(result.unwrap_err().to_string().contains("contains a signer with fee-only witness scope"));
+		assert!(result
+			.unwrap_err()
+			.to_string()
+			.contains("contains a signer with fee-only witness scope"));
 	}
 
 	#[tokio::test]
@@ -1985,7 +2382,10 @@ mod tests {
 
 		let result = tx_builder.first_signer(&account2);
 		assert!(result.is_err());
-		assert!(result.unwrap_err().to_string().contains("Could not find a signer with script hash "));
+		assert!(result
+			.unwrap_err()
+			.to_string()
+			.contains("Could not find a signer with script hash "));
 	}
 
 	#[tokio::test]
@@ -2300,9 +2700,15 @@ mod tests {
 		let client = {
 			let mut mock_provider = mock_provider.lock().await;
 			mock_provider
-				.mock_response_with_file_ignore_param("invokescript", "invokescript_fault.json", ).await
-				.mock_response_with_file_ignore_param("getblockcount", "getblockcount_1000.json").await
-				.mock_response_with_file_ignore_param("calculatenetworkfee", "calculatenetworkfee.json", ).await
+				.mock_response_with_file_ignore_param("invokescript", "invokescript_fault.json")
+				.await
+				.mock_response_with_file_ignore_param("getblockcount", "getblockcount_1000.json")
+				.await
+				.mock_response_with_file_ignore_param(
+					"calculatenetworkfee",
+					"calculatenetworkfee.json",
+				)
+				.await
 				.mount_mocks()
 				.await;
 			Arc::new(mock_provider.into_client())
@@ -2343,10 +2749,17 @@ mod tests {
 		let client = {
 			let mut mock_provider = mock_provider.lock().await;
 			mock_provider
-				.mock_response_with_file_ignore_param("invokescript", "invokescript_fault.json", ).await
-				.mock_response_with_file_ignore_param("getblockcount", "getblockcount_1000.json").await
-				.mock_response_with_file_ignore_param("calculatenetworkfee", "calculatenetworkfee.json", ).await
-				.mount_mocks().await;
+				.mock_response_with_file_ignore_param("invokescript", "invokescript_fault.json")
+				.await
+				.mock_response_with_file_ignore_param("getblockcount", "getblockcount_1000.json")
+				.await
+				.mock_response_with_file_ignore_param(
+					"calculatenetworkfee",
+					"calculatenetworkfee.json",
+				)
+				.await
+				.mount_mocks()
+				.await;
 			Arc::new(mock_provider.into_client())
 		};
 
@@ -2407,6 +2820,168 @@ mod tests {
 			.any(|w| w.verification == account2.verification_script().clone().unwrap()));
 	}
 
+	#[tokio::test]
+	async fn test_signers_and_witnesses_in_order() {
+		let mock_provider = Arc::new(Mutex::new(MockClient::new().await));
+		let client = {
+			let mut mock_provider = mock_provider.lock().await;
+			mock_provider.mock_default_responses().await.mount_mocks().await;
+			Arc::new(mock_provider.into_client())
+		};
+
+		let account1 =
+			Account::from_wif("L1WMhxazScMhUrdv34JqQb1HFSQmWeN2Kpc1R9JGKwL7CDNP21uR").unwrap();
+		let account2 =
+			Account::from_wif("KysNqEuLb3wmZJ6PsxbA9Bh6ewTybEda4dEiN9X7X48dJPkLWZ5a").unwrap();
+
+		let mut tx_builder = TransactionBuilder::with_client(&client);
+		tx_builder
+			.set_script(Some(vec![1, 2, 3]))
+			.set_signers(vec![
+				AccountSigner::called_by_entry(&account1).unwrap().into(),
+				AccountSigner::called_by_entry(&account2).unwrap().into(),
+			])
+			.unwrap();
+
+		let tx = tx_builder.sign().await.unwrap();
+
+		let signers = tx.signers();
+		assert_eq!(signers.len(), 2);
+		assert_eq!(signers[0].get_signer_hash(), &account1.get_script_hash());
+		assert_eq!(signers[1].get_signer_hash(), &account2.get_script_hash());
+
+		let witnesses = tx.witnesses();
+		assert_eq!(witnesses.len(), 2);
+		assert_eq!(witnesses[0].verification, account1.verification_script().clone().unwrap());
+		assert_eq!(witnesses[1].verification, account2.verification_script().clone().unwrap());
+
+		assert_eq!(tx.signer_for(&account2.get_script_hash()), Some(&signers[1]));
+		assert_eq!(tx.signer_for(&H160::zero()), None);
+	}
+
+	#[test]
+	fn test_network_fee_breakdown_single_sig() {
+		let tx = Transaction::<HttpProvider> {
+			signers: vec![AccountSigner::called_by_entry(&ACCOUNT1).unwrap().into()],
+			script: vec![1, 2, 3],
+			witnesses: vec![Witness::from_scripts(
+				vec![0; 66],
+				ACCOUNT1.verification_script().clone().unwrap().script().clone(),
+			)],
+			..Default::default()
+		};
+
+		let breakdown = tx.network_fee_breakdown(1000, 30);
+		assert_eq!(breakdown.size_fee, tx.to_array().len() as u64 * 1000);
+		assert_eq!(
+			breakdown.verification_fee_per_signer,
+			vec![(ACCOUNT1.get_script_hash(), 32784 * 30)]
+		);
+		assert_eq!(breakdown.total(), breakdown.size_fee + 32784 * 30);
+	}
+
+	#[test]
+	fn test_network_fee_breakdown_multi_sig() {
+		let multi_sig_account = Account::multi_sig_from_public_keys(
+			vec![
+				ACCOUNT1.get_public_key().unwrap(),
+				ACCOUNT2.get_public_key().unwrap(),
+				KeyPair::new_random().public_key(),
+			]
+			.as_mut(),
+			2,
+		)
+		.unwrap();
+		let tx = Transaction::<HttpProvider> {
+			signers: vec![
+				AccountSigner::none(&multi_sig_account).unwrap().into(),
+				AccountSigner::called_by_entry(&ACCOUNT2).unwrap().into(),
+			],
+			script: vec![1, 2, 3],
+			..Default::default()
+		};
+
+		let breakdown = tx.network_fee_breakdown(1000, 30);
+		assert_eq!(breakdown.size_fee, tx.to_array().len() as u64 * 1000);
+		// Two signatures and three keys are pushed, and each key costs one CheckSig.
+		let multi_sig_cost = 8 * 5 + 2 + 32768 * 3;
+		assert_eq!(
+			breakdown.verification_fee_per_signer,
+			vec![
+				(multi_sig_account.get_script_hash(), multi_sig_cost * 30),
+				(ACCOUNT2.get_script_hash(), 32784 * 30),
+			]
+		);
+	}
+
+	// A transaction with every kind of attribute and one witness
+	const RAW_TRANSACTION: &str = "000100000040420f0000000000b0c4120000000000881300000169ecca587293047be4c59159bf8bc399985c160d01040120d2040000211f1e1d1c1b1a191817161514131211100f0e0d0c0b0a09080706050403020100110700000000000000000361626302114001420c40000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f202122232425262728292a2b2c2d2e2f303132333435363738393a3b3c3d3e3f280c2102000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f4156e7b327";
+
+	#[test]
+	fn test_decode_raw_transaction() {
+		let tx = Transaction::<HttpProvider>::from_hex(RAW_TRANSACTION).unwrap();
+		assert_eq!(tx.nonce, 1);
+		assert_eq!(tx.sys_fee, 1_000_000);
+		assert_eq!(tx.net_fee, 1_230_000);
+		assert_eq!(tx.valid_until_block, 5000);
+		assert_eq!(tx.script, vec![0x11, 0x40]);
+		assert_eq!(tx.signers.len(), 1);
+		assert_eq!(
+			tx.signers[0].get_signer_hash(),
+			&H160::from_str("69ecca587293047be4c59159bf8bc399985c160d").unwrap()
+		);
+		assert_eq!(
+			tx.attributes[..3],
+			[
+				TransactionAttribute::HighPriority,
+				TransactionAttribute::NotValidBefore { height: 1234 },
+				TransactionAttribute::Conflicts {
+					hash: H256::from_str(
+						"000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"
+					)
+					.unwrap()
+				},
+			]
+		);
+		assert!(matches!(tx.attributes[3], TransactionAttribute::OracleResponse(_)));
+		assert_eq!(tx.witnesses.len(), 1);
+		assert_eq!(
+			tx.get_tx_id().unwrap(),
+			H256::from_str("c501016d5bcaab4b07d25569df6126bac71ee87327532a2434497668a5d1b614")
+				.unwrap()
+		);
+
+		// Decoding and encoding again gives the same transaction
+		assert_eq!(hex::encode(tx.to_array()), RAW_TRANSACTION);
+		let base64 = general_purpose::STANDARD.encode(hex::decode(RAW_TRANSACTION).unwrap());
+		assert_eq!(Transaction::<HttpProvider>::from_base64(&base64).unwrap(), tx);
+		assert_eq!(
+			Transaction::<HttpProvider>::from_hex(&format!("0x{RAW_TRANSACTION}")).unwrap(),
+			tx
+		);
+	}
+
+	#[test]
+	fn test_decode_raw_transaction_rejects_invalid_data() {
+		let bytes = hex::decode(RAW_TRANSACTION).unwrap();
+		let decode = |bytes: &[u8]| Transaction::<HttpProvider>::from_bytes(bytes);
+
+		assert!(decode(&bytes[..bytes.len() - 1]).is_err());
+		assert!(decode(&bytes[..10]).is_err());
+
+		let mut trailing = bytes.clone();
+		trailing.push(0);
+		assert!(matches!(decode(&trailing), Err(TransactionError::TransactionConfiguration(_))));
+
+		// The witness is removed, leaving a signer without one
+		let witness_start = RAW_TRANSACTION.find("01420c40").unwrap() / 2;
+		let mut unsigned = bytes[..witness_start].to_vec();
+		unsigned.push(0);
+		assert!(matches!(decode(&unsigned), Err(TransactionError::TransactionConfiguration(_))));
+
+		assert!(Transaction::<HttpProvider>::from_hex("zz").is_err());
+	}
+
 	// #[tokio::test]
 	// async fn test_get_network_fee() {
 	// 	let mock_provider = Arc::new(Mutex::new(MockClient::new().await));
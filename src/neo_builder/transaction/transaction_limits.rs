@@ -0,0 +1,29 @@
+use neo::prelude::NeoConstants;
+
+/// The protocol limits a transaction has to stay within to be accepted by the network.
+///
+/// The defaults are the limits of Neo N3. Networks with different protocol settings can override
+/// them with [`TransactionBuilder::set_limits`](crate::neo_builder::TransactionBuilder::set_limits).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TransactionLimits {
+	/// The maximum number of signers, which is also the maximum number of witnesses.
+	pub max_signers: usize,
+	/// The maximum number of attributes, counting the signers as attributes.
+	pub max_attributes: usize,
+	/// The maximum size of the transaction script in bytes.
+	pub max_script_size: usize,
+	/// The maximum size of the serialized transaction in bytes.
+	pub max_transaction_size: usize,
+}
+
+impl Default for TransactionLimits {
+	fn default() -> Self {
+		Self {
+			// Neo N3 reads at most as many signers as attributes
+			max_signers: NeoConstants::MAX_TRANSACTION_ATTRIBUTES as usize,
+			max_attributes: NeoConstants::MAX_TRANSACTION_ATTRIBUTES as usize,
+			max_script_size: NeoConstants::MAX_TRANSACTION_SCRIPT_SIZE as usize,
+			max_transaction_size: NeoConstants::MAX_TRANSACTION_SIZE as usize,
+		}
+	}
+}
@@ -205,6 +205,30 @@ impl VerificationScript {
 			Err(e) => Err(e),
 		}
 	}
+
+	/// Returns the execution cost of verifying a witness for this script, before the exec fee
+	/// factor is applied.
+	///
+	/// This includes pushing the signatures from the invocation script. Only standard single-sig
+	/// and multi-sig scripts have a known cost; `None` is returned for any other script.
+	pub fn execution_cost(&self) -> Option<u64> {
+		let push_data = OpCode::PushData1.price() as u64;
+		let check_sig = InteropService::SystemCryptoCheckSig.price();
+		if self.is_single_sig() {
+			Some(push_data * 2 + OpCode::Syscall.price() as u64 + check_sig)
+		} else if self.is_multi_sig() {
+			let threshold = self.get_signing_threshold().ok()? as u64;
+			let nr_of_accounts = self.get_nr_of_accounts().ok()? as u64;
+			Some(
+				push_data * (threshold + nr_of_accounts)
+					+ OpCode::PushInt8.price() as u64 * 2
+					+ OpCode::Syscall.price() as u64
+					+ check_sig * nr_of_accounts,
+			)
+		} else {
+			None
+		}
+	}
 }
 
 impl NeoSerializable for VerificationScript {
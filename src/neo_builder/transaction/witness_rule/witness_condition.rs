@@ -128,14 +128,12 @@ where
 			let group = v["group"]
 				.as_str()
 				.ok_or(serde::de::Error::custom("Expected a string for Group/CalledByGroup"))?;
+			let group = Secp256r1PublicKey::from_encoded(group)
+				.ok_or(serde::de::Error::custom("Failed to parse Group/CalledByGroup"))?;
 			let condition = if v["type"] == "Group" {
-				WitnessCondition::Group(
-					Secp256r1PublicKey::from_bytes(&group.from_hex().unwrap()).unwrap(),
-				)
+				WitnessCondition::Group(group)
 			} else {
-				WitnessCondition::CalledByGroup(
-					Secp256r1PublicKey::from_bytes(&group.from_hex().unwrap()).unwrap(),
-				)
+				WitnessCondition::CalledByGroup(group)
 			};
 			Ok(condition)
 		},
@@ -144,7 +142,11 @@ where
 			let hash = v["hash"]
 				.as_str()
 				.ok_or(serde::de::Error::custom("Expected a string for CalledByContract"))?;
-			Ok(WitnessCondition::CalledByContract(H160::from_slice(&hash.from_hex().unwrap())))
+			Ok(WitnessCondition::CalledByContract(
+				H160::from_hex(hash.trim_start_matches("0x")).map_err(|e| {
+					serde::de::Error::custom(format!("Failed to parse CalledByContract: {}", e))
+				})?,
+			))
 		},
 		_ => Err(serde::de::Error::custom("Unknown WitnessCondition type")),
 	}
@@ -356,24 +358,44 @@ impl NeoSerializable for WitnessCondition {
 	}
 
 	fn decode(reader: &mut Decoder) -> Result<Self, Self::Error> {
-		let byte = reader.read_u8();
+		Self::decode_with_depth(reader, Self::MAX_NESTING_DEPTH)
+	}
+
+	fn to_array(&self) -> Vec<u8> {
+		let mut writer = Encoder::new();
+		self.encode(&mut writer);
+		writer.to_bytes()
+	}
+}
+
+impl WitnessCondition {
+	/// Decodes a condition in which `Not`, `And` and `Or` may nest at most `max_depth` deep,
+	/// as the node enforces.
+	fn decode_with_depth(reader: &mut Decoder, max_depth: usize) -> Result<Self, TransactionError> {
+		let byte = reader.read_bytes(1)?[0];
 		match byte {
 			WitnessCondition::BOOLEAN_BYTE => {
-				let b = reader.read_bool();
+				let b = reader.read_bytes(1)?[0] == 1;
 				Ok(WitnessCondition::Boolean(b))
 			},
 			WitnessCondition::NOT_BYTE => {
-				let exp = WitnessCondition::decode(reader)?;
+				if max_depth == 0 {
+					return Err(TransactionError::InvalidWitnessCondition);
+				}
+				let exp = WitnessCondition::decode_with_depth(reader, max_depth - 1)?;
 				Ok(WitnessCondition::Not(Box::from(exp)))
 			},
 			WitnessCondition::OR_BYTE | WitnessCondition::AND_BYTE => {
+				if max_depth == 0 {
+					return Err(TransactionError::InvalidWitnessCondition);
+				}
 				let len = reader.read_var_int()? as usize;
 				if len > Self::MAX_SUBITEMS {
 					return Err(TransactionError::InvalidWitnessCondition);
 				}
 				let mut expressions = Vec::with_capacity(len);
 				for _ in 0..len {
-					expressions.push(WitnessCondition::decode(reader)?);
+					expressions.push(WitnessCondition::decode_with_depth(reader, max_depth - 1)?);
 				}
 				if byte == Self::OR_BYTE {
 					Ok(WitnessCondition::Or(expressions))
@@ -401,10 +423,4 @@ impl NeoSerializable for WitnessCondition {
 			_ => Err(TransactionError::InvalidTransaction),
 		}
 	}
-
-	fn to_array(&self) -> Vec<u8> {
-		let mut writer = Encoder::new();
-		self.encode(&mut writer);
-		writer.to_bytes()
-	}
 }
@@ -29,9 +29,10 @@ impl NeoSerializable for WitnessRule {
 	}
 
 	fn decode(reader: &mut Decoder) -> Result<Self, Self::Error> {
-		let action = reader.read_u8();
+		let action = WitnessAction::try_from(reader.read_bytes(1)?[0])
+			.map_err(|_| TransactionError::InvalidTransaction)?;
 		let condition = WitnessCondition::decode(reader)?;
-		Ok(Self { action: WitnessAction::try_from(action).unwrap(), condition })
+		Ok(Self { action, condition })
 	}
 	fn to_array(&self) -> Vec<u8> {
 		let mut writer = Encoder::new();
@@ -92,6 +93,20 @@ mod tests {
 		));
 	}
 
+	#[test]
+	fn test_decode_rejects_conditions_nested_too_deep() {
+		// Not(Not(CalledByEntry)) is as deep as the node allows
+		assert!(WitnessCondition::from_bytes(&hex::decode("010120").unwrap()).is_ok());
+		assert!(WitnessCondition::from_bytes(&hex::decode("020102010001").unwrap()).is_ok());
+
+		for bytes in ["01010120", "0201020102010001"] {
+			assert_eq!(
+				WitnessCondition::from_bytes(&hex::decode(bytes).unwrap()),
+				Err(TransactionError::InvalidWitnessCondition)
+			);
+		}
+	}
+
 	#[test]
 	fn test_and_condition_serialize_deserialize() {
 		let condition = WitnessCondition::And(vec![
@@ -50,11 +50,12 @@ impl WitnessScope {
 
 	// Split bit flags
 	pub fn split(flags: u8) -> Vec<Self> {
-		let mut scopes = Vec::new();
-
-		if flags & Self::None.byte_repr() != 0 {
-			scopes.push(Self::None);
+		// `None` has no bit of its own, it is the absence of all other scopes.
+		if flags == Self::None.byte_repr() {
+			return vec![Self::None];
 		}
+
+		let mut scopes = Vec::new();
 		if flags & Self::CalledByEntry.byte_repr() != 0 {
 			scopes.push(Self::CalledByEntry);
 		}
@@ -74,3 +75,18 @@ impl WitnessScope {
 		scopes
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::WitnessScope;
+
+	#[test]
+	fn test_combine_and_split() {
+		let scopes = vec![WitnessScope::CalledByEntry, WitnessScope::CustomContracts];
+		assert_eq!(WitnessScope::combine(&scopes), 0x11);
+		assert_eq!(WitnessScope::split(0x11), scopes);
+
+		assert_eq!(WitnessScope::combine(&[WitnessScope::None]), 0x00);
+		assert_eq!(WitnessScope::split(0x00), vec![WitnessScope::None]);
+	}
+}
@@ -20,6 +20,11 @@ pub trait APITrait: Sync + Send + Debug {
 
 	async fn network(&self) -> u32;
 
+	/// The magic of the network the client is connected to, as used when signing transactions.
+	async fn network_magic(&self) -> NetworkMagic {
+		NetworkMagic::from_u32(self.network().await)
+	}
+
 	fn nns_resolver(&self) -> H160 {
 		H160::from(NEOCONFIG.lock().unwrap().nns_resolver.clone())
 	}
@@ -84,6 +89,12 @@ pub trait APITrait: Sync + Send + Debug {
 
 	async fn get_raw_transaction(&self, tx_hash: H256) -> Result<String, Self::Error>;
 
+	async fn get_raw_transaction_info(
+		&self,
+		tx_hash: H256,
+		verbose: bool,
+	) -> Result<RawTransactionInfo, Self::Error>;
+
 	async fn get_storage(&self, contract_hash: H160, key: &str) -> Result<String, Self::Error>;
 
 	async fn find_storage(
@@ -0,0 +1,245 @@
+//! A generic retry helper with an exponential backoff, usable for any async operation.
+
+use std::{future::Future, time::Duration};
+
+use futures_timer::Delay;
+use rand::Rng;
+use tracing::trace;
+
+/// Configures how often and how fast [`with_backoff`] retries a failing operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackoffPolicy {
+	/// How often the operation is attempted at most, including the first attempt.
+	max_attempts: u32,
+	/// How long to wait before the first retry.
+	initial_backoff: Duration,
+	/// Upper bound for the wait between two attempts.
+	max_backoff: Duration,
+	/// Factor the wait grows by after every failed attempt.
+	multiplier: u32,
+	/// Whether to randomize each wait to between half and all of its computed value.
+	jitter: bool,
+}
+
+impl BackoffPolicy {
+	/// Sets how often the operation is attempted at most, including the first attempt.
+	pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+		self.max_attempts = max_attempts.max(1);
+		self
+	}
+
+	/// Sets how long to wait before the first retry.
+	pub fn initial_backoff(mut self, initial_backoff: Duration) -> Self {
+		self.initial_backoff = initial_backoff;
+		self
+	}
+
+	/// Sets the upper bound for the wait between two attempts.
+	pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+		self.max_backoff = max_backoff;
+		self
+	}
+
+	/// Sets the factor the wait grows by after every failed attempt.
+	pub fn multiplier(mut self, multiplier: u32) -> Self {
+		self.multiplier = multiplier;
+		self
+	}
+
+	/// Sets whether to randomize the waits, so that many clients do not retry in lockstep.
+	pub fn jitter(mut self, jitter: bool) -> Self {
+		self.jitter = jitter;
+		self
+	}
+
+	/// How often a failing operation is retried at most, not counting the first attempt.
+	pub fn retries(&self) -> u32 {
+		self.max_attempts - 1
+	}
+
+	/// The wait before the given retry, counting from 0 for the first one.
+	pub fn backoff(&self, retry: u32) -> Duration {
+		let factor = self.multiplier.saturating_pow(retry);
+		let backoff = self.initial_backoff.saturating_mul(factor).min(self.max_backoff);
+		if self.jitter && !backoff.is_zero() {
+			let half = backoff / 2;
+			half + rand::thread_rng().gen_range(Duration::ZERO..=backoff - half)
+		} else {
+			backoff
+		}
+	}
+}
+
+// Some sensible defaults
+impl Default for BackoffPolicy {
+	fn default() -> Self {
+		Self {
+			max_attempts: 5,
+			initial_backoff: Duration::from_millis(500),
+			max_backoff: Duration::from_secs(30),
+			multiplier: 2,
+			jitter: true,
+		}
+	}
+}
+
+/// Runs `op` until it succeeds, fails with an error `is_retryable` rejects, or the policy's
+/// maximum number of attempts is reached. Waits with an exponential backoff between attempts.
+/// Returns the last error if all attempts fail.
+pub async fn with_backoff<F, Fut, T, E>(
+	policy: &BackoffPolicy,
+	is_retryable: impl Fn(&E) -> bool,
+	op: F,
+) -> Result<T, E>
+where
+	F: FnMut() -> Fut,
+	Fut: Future<Output = Result<T, E>>,
+{
+	with_backoff_by(
+		|err, retry| (retry < policy.retries() && is_retryable(err)).then(|| policy.backoff(retry)),
+		op,
+	)
+	.await
+}
+
+/// Runs `op` until it succeeds or `next_backoff` gives up. After every failed attempt,
+/// `next_backoff` gets the error and the number of retries made so far, and returns how long to
+/// wait before the next attempt, or `None` to return the error.
+pub async fn with_backoff_by<F, Fut, T, E>(
+	mut next_backoff: impl FnMut(&E, u32) -> Option<Duration>,
+	mut op: F,
+) -> Result<T, E>
+where
+	F: FnMut() -> Fut,
+	Fut: Future<Output = Result<T, E>>,
+{
+	let mut retry = 0;
+	loop {
+		let err = match op().await {
+			Ok(value) => return Ok(value),
+			Err(err) => err,
+		};
+		match next_backoff(&err, retry) {
+			Some(backoff) => {
+				trace!("attempt {} failed, retrying in {:?}", retry + 1, backoff);
+				Delay::new(backoff).await;
+				retry += 1;
+			},
+			None => return Err(err),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{
+		sync::atomic::{AtomicU32, Ordering},
+		time::Duration,
+	};
+
+	use super::{with_backoff, with_backoff_by, BackoffPolicy};
+
+	#[derive(Debug, PartialEq, Eq)]
+	enum TestError {
+		Transient,
+		Fatal,
+	}
+
+	fn policy() -> BackoffPolicy {
+		BackoffPolicy::default()
+			.max_attempts(4)
+			.initial_backoff(Duration::from_millis(1))
+	}
+
+	#[tokio::test]
+	async fn test_with_backoff_succeeds_after_failures() {
+		let calls = AtomicU32::new(0);
+		let result = with_backoff(
+			&policy(),
+			|err| *err == TestError::Transient,
+			|| async {
+				if calls.fetch_add(1, Ordering::SeqCst) < 2 {
+					Err(TestError::Transient)
+				} else {
+					Ok(42)
+				}
+			},
+		)
+		.await;
+
+		assert_eq!(result, Ok(42));
+		assert_eq!(calls.load(Ordering::SeqCst), 3);
+	}
+
+	#[tokio::test]
+	async fn test_with_backoff_exhausts_attempts() {
+		let calls = AtomicU32::new(0);
+		let result: Result<(), _> = with_backoff(
+			&policy(),
+			|err| *err == TestError::Transient,
+			|| async {
+				calls.fetch_add(1, Ordering::SeqCst);
+				Err(TestError::Transient)
+			},
+		)
+		.await;
+
+		assert_eq!(result, Err(TestError::Transient));
+		assert_eq!(calls.load(Ordering::SeqCst), 4);
+	}
+
+	#[tokio::test]
+	async fn test_with_backoff_fails_immediately_on_non_retryable_error() {
+		let calls = AtomicU32::new(0);
+		let result: Result<(), _> = with_backoff(
+			&policy(),
+			|err| *err == TestError::Transient,
+			|| async {
+				calls.fetch_add(1, Ordering::SeqCst);
+				Err(TestError::Fatal)
+			},
+		)
+		.await;
+
+		assert_eq!(result, Err(TestError::Fatal));
+		assert_eq!(calls.load(Ordering::SeqCst), 1);
+	}
+
+	#[tokio::test]
+	async fn test_with_backoff_by_uses_the_returned_backoff() {
+		let calls = AtomicU32::new(0);
+		let mut retries = Vec::new();
+		let result: Result<(), _> = with_backoff_by(
+			|_, retry| {
+				retries.push(retry);
+				(retry < 2).then_some(Duration::ZERO)
+			},
+			|| async {
+				calls.fetch_add(1, Ordering::SeqCst);
+				Err(TestError::Transient)
+			},
+		)
+		.await;
+
+		assert_eq!(result, Err(TestError::Transient));
+		assert_eq!(calls.load(Ordering::SeqCst), 3);
+		assert_eq!(retries, vec![0, 1, 2]);
+	}
+
+	#[test]
+	fn test_backoff_grows_exponentially_up_to_the_maximum() {
+		let policy = BackoffPolicy::default()
+			.initial_backoff(Duration::from_millis(100))
+			.max_backoff(Duration::from_millis(500))
+			.jitter(false);
+		assert_eq!(policy.backoff(0), Duration::from_millis(100));
+		assert_eq!(policy.backoff(2), Duration::from_millis(400));
+		assert_eq!(policy.backoff(3), Duration::from_millis(500));
+
+		let policy = policy.jitter(true);
+		for retry in 0..4 {
+			let backoff = policy.backoff(retry);
+			assert!(backoff >= Duration::from_millis(50) && backoff <= Duration::from_millis(500));
+		}
+	}
+}
@@ -0,0 +1,27 @@
+//! Decouples broadcasting signed transactions from signing them, e.g. to submit them through a
+//! relayer instead of directly to a node.
+
+use async_trait::async_trait;
+use primitive_types::H256;
+
+use neo::prelude::{APITrait, Bytes, JsonRpcProvider, ProviderError, RpcClient};
+
+/// Submits signed transactions to the network.
+///
+/// [`RpcClient`] implements it with `sendrawtransaction`, which is what
+/// `Transaction::send_tx` uses. Implement it to send transactions through another service and
+/// pass the implementation to `Transaction::send_tx_with`.
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait TransactionBroadcaster: Send + Sync {
+	/// Broadcasts the serialized, signed transaction and returns its hash.
+	async fn broadcast(&self, signed_tx: Bytes) -> Result<H256, ProviderError>;
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<P: JsonRpcProvider> TransactionBroadcaster for RpcClient<P> {
+	async fn broadcast(&self, signed_tx: Bytes) -> Result<H256, ProviderError> {
+		Ok(self.send_raw_transaction(hex::encode(signed_tx)).await?.hash)
+	}
+}
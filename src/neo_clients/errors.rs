@@ -24,6 +24,15 @@ pub enum ProviderError {
 	/// Error in underlying lib `reqwest`
 	#[error(transparent)]
 	HTTPError(#[from] Arc<reqwest::Error>),
+	/// The request did not complete within the configured timeout
+	#[error("request timed out: {0}")]
+	Timeout(#[source] Arc<reqwest::Error>),
+	/// The connection to the node could not be established
+	#[error("connection failed: {0}")]
+	ConnectionFailed(#[source] Arc<reqwest::Error>),
+	/// The response body could not be read or decoded
+	#[error("failed to decode response: {0}")]
+	Deserialization(#[source] Arc<reqwest::Error>),
 	/// Reponse error
 	#[error(transparent)]
 	JsonRpcError(#[from] JsonRpcError),
@@ -33,6 +42,9 @@ pub enum ProviderError {
 	/// RPC method is not supported by this provider
 	#[error("unsupported RPC")]
 	UnsupportedRPC,
+	/// The node does not support the requested method or subscription
+	#[error("unsupported method: {0}")]
+	UnsupportedMethod(String),
 	/// Node is not supported by this provider
 	#[error("unsupported node client")]
 	UnsupportedNodeClient,
@@ -50,6 +62,54 @@ pub enum ProviderError {
 	TypeError(#[from] TypeError),
 	#[error("Invalid password")]
 	InvalidPassword,
+	/// The response body exceeded the configured maximum size
+	#[error("response exceeds the maximum size of {limit} bytes")]
+	ResponseTooLarge { limit: usize },
+	/// The transaction's `valid_until_block` is not above the current block height
+	#[error("transaction expired: valid until block {valid_until}, current height is {current}")]
+	TransactionExpired { valid_until: u32, current: u32 },
+	/// The node did not answer the `getversion` request sent when connecting to it
+	#[error("could not connect to the node at {url}: {source}")]
+	PreflightFailed { url: String, source: Box<ProviderError> },
+	/// The node already has the sent transaction, in its memory pool or in a block
+	#[error("transaction already exists: {0}")]
+	AlreadyInMempool(String),
+	/// The sent transaction conflicts with a transaction in the node's memory pool
+	#[error("transaction conflicts with the memory pool: {0}")]
+	MempoolConflict(String),
+}
+
+impl ProviderError {
+	/// Error code of a transaction that failed verification, with the verification result as
+	/// `data` since Neo 3.7 and as message before
+	const VERIFICATION_FAILED_CODE: i64 = -500;
+	/// Error code of a transaction that is already in a block, since Neo 3.7
+	const ALREADY_EXISTS_CODE: i64 = -501;
+	/// Error code of a transaction that is already in the memory pool, since Neo 3.7
+	const ALREADY_IN_POOL_CODE: i64 = -503;
+
+	/// Maps the errors a node returns when relaying a transaction it already has, or one that
+	/// conflicts with its memory pool, to [`ProviderError::AlreadyInMempool`] and
+	/// [`ProviderError::MempoolConflict`]. Other verification failures only carry the
+	/// verification result, in `data` since Neo 3.7 and as message before.
+	pub(crate) fn from_send_error(err: ProviderError) -> ProviderError {
+		let ProviderError::JsonRpcError(rpc_error) = &err else {
+			return err;
+		};
+		let reason = match &rpc_error.data {
+			Some(serde_json::Value::String(data)) => data.as_str(),
+			_ => rpc_error.message.as_str(),
+		};
+
+		match (rpc_error.code, reason) {
+			(Self::ALREADY_EXISTS_CODE | Self::ALREADY_IN_POOL_CODE, _)
+			| (Self::VERIFICATION_FAILED_CODE, "AlreadyExists" | "AlreadyInPool") =>
+				ProviderError::AlreadyInMempool(rpc_error.message.clone()),
+			(Self::VERIFICATION_FAILED_CODE, "HasConflicts") =>
+				ProviderError::MempoolConflict(rpc_error.message.clone()),
+			_ => err,
+		}
+	}
 }
 
 impl PartialEq for ProviderError {
@@ -58,8 +118,15 @@ impl PartialEq for ProviderError {
 			(ProviderError::SerdeJson(a), ProviderError::SerdeJson(b)) =>
 				a.to_string() == b.to_string(),
 			(ProviderError::HTTPError(a), ProviderError::HTTPError(b)) => a.status() == b.status(),
+			(ProviderError::Timeout(a), ProviderError::Timeout(b)) => a.url() == b.url(),
+			(ProviderError::ConnectionFailed(a), ProviderError::ConnectionFailed(b)) =>
+				a.url() == b.url(),
+			(ProviderError::Deserialization(a), ProviderError::Deserialization(b)) =>
+				a.to_string() == b.to_string(),
+			(ProviderError::JsonRpcError(a), ProviderError::JsonRpcError(b)) => a == b,
 			(ProviderError::CustomError(a), ProviderError::CustomError(b)) => a == b,
 			(ProviderError::UnsupportedRPC, ProviderError::UnsupportedRPC) => true,
+			(ProviderError::UnsupportedMethod(a), ProviderError::UnsupportedMethod(b)) => a == b,
 			(ProviderError::UnsupportedNodeClient, ProviderError::UnsupportedNodeClient) => true,
 			(ProviderError::SignerUnavailable, ProviderError::SignerUnavailable) => true,
 			(ProviderError::IllegalState(a), ProviderError::IllegalState(b)) => a == b,
@@ -67,6 +134,20 @@ impl PartialEq for ProviderError {
 			(ProviderError::CryptoError(a), ProviderError::CryptoError(b)) => a == b,
 			(ProviderError::TypeError(a), ProviderError::TypeError(b)) => a == b,
 			(ProviderError::InvalidPassword, ProviderError::InvalidPassword) => true,
+			(
+				ProviderError::ResponseTooLarge { limit: a },
+				ProviderError::ResponseTooLarge { limit: b },
+			) => a == b,
+			(
+				ProviderError::TransactionExpired { valid_until: a, current: b },
+				ProviderError::TransactionExpired { valid_until: c, current: d },
+			) => a == c && b == d,
+			(
+				ProviderError::PreflightFailed { url: a, source: b },
+				ProviderError::PreflightFailed { url: c, source: d },
+			) => a == c && b == d,
+			(ProviderError::AlreadyInMempool(a), ProviderError::AlreadyInMempool(b)) => a == b,
+			(ProviderError::MempoolConflict(a), ProviderError::MempoolConflict(b)) => a == b,
 			_ => false,
 		}
 	}
@@ -83,10 +164,17 @@ impl Clone for ProviderError {
 			)),
 			ProviderError::HexError(error) => ProviderError::HexError(error.clone()),
 			ProviderError::HTTPError(error) => ProviderError::HTTPError(Arc::clone(error)),
+			ProviderError::Timeout(error) => ProviderError::Timeout(Arc::clone(error)),
+			ProviderError::ConnectionFailed(error) =>
+				ProviderError::ConnectionFailed(Arc::clone(error)),
+			ProviderError::Deserialization(error) =>
+				ProviderError::Deserialization(Arc::clone(error)),
 
 			ProviderError::JsonRpcError(error) => ProviderError::JsonRpcError(error.clone()),
 			ProviderError::CustomError(message) => ProviderError::CustomError(message.clone()),
 			ProviderError::UnsupportedRPC => ProviderError::UnsupportedRPC,
+			ProviderError::UnsupportedMethod(method) =>
+				ProviderError::UnsupportedMethod(method.clone()),
 			ProviderError::UnsupportedNodeClient => ProviderError::UnsupportedNodeClient,
 			ProviderError::SignerUnavailable => ProviderError::SignerUnavailable,
 			ProviderError::IllegalState(message) => ProviderError::IllegalState(message.clone()),
@@ -94,6 +182,160 @@ impl Clone for ProviderError {
 			ProviderError::CryptoError(error) => ProviderError::CryptoError(error.clone()),
 			ProviderError::TypeError(error) => ProviderError::TypeError(error.clone()),
 			ProviderError::InvalidPassword => ProviderError::InvalidPassword,
+			ProviderError::ResponseTooLarge { limit } =>
+				ProviderError::ResponseTooLarge { limit: *limit },
+			ProviderError::TransactionExpired { valid_until, current } =>
+				ProviderError::TransactionExpired { valid_until: *valid_until, current: *current },
+			ProviderError::PreflightFailed { url, source } =>
+				ProviderError::PreflightFailed { url: url.clone(), source: source.clone() },
+			ProviderError::AlreadyInMempool(message) =>
+				ProviderError::AlreadyInMempool(message.clone()),
+			ProviderError::MempoolConflict(message) =>
+				ProviderError::MempoolConflict(message.clone()),
+		}
+	}
+}
+
+impl From<reqwest::Error> for ProviderError {
+	fn from(err: reqwest::Error) -> Self {
+		if err.is_timeout() {
+			return ProviderError::Timeout(Arc::new(err));
+		}
+
+		#[cfg(not(target_arch = "wasm32"))]
+		if err.is_connect() {
+			return ProviderError::ConnectionFailed(Arc::new(err));
+		}
+
+		if err.is_decode() || err.is_body() {
+			return ProviderError::Deserialization(Arc::new(err));
+		}
+
+		ProviderError::HTTPError(Arc::new(err))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::Duration;
+
+	use serde_json::Value;
+	use wiremock::{matchers::method, Mock, MockServer, ResponseTemplate};
+
+	use neo::prelude::JsonRpcError;
+
+	use super::ProviderError;
+
+	#[tokio::test]
+	async fn test_reqwest_timeout_maps_to_timeout() {
+		let mock_server = MockServer::start().await;
+		Mock::given(method("POST"))
+			.respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(5)))
+			.mount(&mock_server)
+			.await;
+
+		let client = reqwest::Client::builder().timeout(Duration::from_millis(50)).build().unwrap();
+		let err = client.post(mock_server.uri()).send().await.unwrap_err();
+
+		assert!(matches!(ProviderError::from(err), ProviderError::Timeout(_)));
+	}
+
+	#[tokio::test]
+	async fn test_reqwest_connect_error_maps_to_connection_failed() {
+		// The listener is dropped right away, so nothing listens on its port anymore
+		let addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+		let err = reqwest::Client::new().post(format!("http://{addr}")).send().await.unwrap_err();
+
+		assert!(matches!(ProviderError::from(err), ProviderError::ConnectionFailed(_)));
+	}
+
+	#[tokio::test]
+	async fn test_reqwest_decode_error_maps_to_deserialization() {
+		let mock_server = MockServer::start().await;
+		Mock::given(method("POST"))
+			.respond_with(ResponseTemplate::new(200).set_body_string("not json"))
+			.mount(&mock_server)
+			.await;
+
+		let response = reqwest::Client::new().post(mock_server.uri()).send().await.unwrap();
+		let err = response.json::<Value>().await.unwrap_err();
+
+		assert!(matches!(ProviderError::from(err), ProviderError::Deserialization(_)));
+	}
+
+	#[tokio::test]
+	async fn test_reqwest_status_error_maps_to_http_error() {
+		let mock_server = MockServer::start().await;
+		Mock::given(method("POST"))
+			.respond_with(ResponseTemplate::new(503))
+			.mount(&mock_server)
+			.await;
+
+		let response = reqwest::Client::new().post(mock_server.uri()).send().await.unwrap();
+		let err = response.error_for_status().unwrap_err();
+
+		assert!(matches!(ProviderError::from(err), ProviderError::HTTPError(_)));
+	}
+
+	fn rpc_error(code: i64, message: &str) -> ProviderError {
+		ProviderError::JsonRpcError(JsonRpcError { code, message: message.to_string(), data: None })
+	}
+
+	fn rpc_error_with_data(code: i64, message: &str, data: &str) -> ProviderError {
+		ProviderError::JsonRpcError(JsonRpcError {
+			code,
+			message: message.to_string(),
+			data: Some(data.into()),
+		})
+	}
+
+	#[test]
+	fn test_send_error_maps_already_in_mempool() {
+		for err in [
+			rpc_error(-501, "Inventory already exists"),
+			rpc_error(-503, "Already in pool"),
+			rpc_error(-500, "AlreadyExists"),
+			rpc_error(-500, "AlreadyInPool"),
+		] {
+			assert!(matches!(
+				ProviderError::from_send_error(err),
+				ProviderError::AlreadyInMempool(_)
+			));
 		}
 	}
+
+	#[test]
+	fn test_send_error_maps_mempool_conflict() {
+		let err = ProviderError::from_send_error(rpc_error(-500, "HasConflicts"));
+		assert_eq!(err, ProviderError::MempoolConflict("HasConflicts".to_string()));
+
+		let err = ProviderError::from_send_error(rpc_error_with_data(
+			-500,
+			"Inventory verification failed. - HasConflicts",
+			"HasConflicts",
+		));
+		assert_eq!(
+			err,
+			ProviderError::MempoolConflict(
+				"Inventory verification failed. - HasConflicts".to_string()
+			)
+		);
+	}
+
+	#[test]
+	fn test_send_error_keeps_other_errors() {
+		let err = rpc_error(-504, "Insufficient network fee");
+		assert_eq!(ProviderError::from_send_error(err.clone()), err);
+		// Only the verification result tells a conflict apart, not words in the message
+		let err = rpc_error_with_data(
+			-500,
+			"Inventory verification failed. - Conflicting attribute",
+			"InvalidAttribute",
+		);
+		assert_eq!(ProviderError::from_send_error(err.clone()), err);
+		assert_eq!(
+			ProviderError::from_send_error(ProviderError::InvalidAddress),
+			ProviderError::InvalidAddress
+		);
+	}
 }
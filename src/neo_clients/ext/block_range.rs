@@ -0,0 +1,116 @@
+use futures::{
+	future,
+	stream::{self, Stream, StreamExt},
+};
+
+use neo::prelude::{APITrait, JsonRpcProvider, NeoBlock, ProviderError, RpcClient};
+
+impl<P: JsonRpcProvider> RpcClient<P> {
+	/// Streams the blocks from index `from` up to and including `to`, in order.
+	///
+	/// Up to `lookahead` blocks are requested concurrently so the round trips overlap; a
+	/// `lookahead` of 0 is treated as 1. The chain height is fetched once up front and the stream
+	/// ends at the last existing block if `to` lies beyond it. With `full_transactions` the blocks
+	/// include their transactions, otherwise only the headers are fetched.
+	///
+	/// # Example
+	///
+	/// ```no_run
+	/// use futures::StreamExt;
+	/// use NeoRust::prelude::{HttpProvider, RpcClient};
+	///
+	/// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+	/// let client = RpcClient::new(HttpProvider::new("http://localhost:10332")?);
+	/// let mut blocks = Box::pin(client.block_range(0, 99, true, 8));
+	/// while let Some(block) = blocks.next().await {
+	///     println!("{}", block?.hash);
+	/// }
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn block_range(
+		&self,
+		from: u32,
+		to: u32,
+		full_transactions: bool,
+		lookahead: usize,
+	) -> impl Stream<Item = Result<NeoBlock, ProviderError>> + '_ {
+		stream::once(self.get_block_count())
+			.map(move |block_count| match block_count {
+				Ok(block_count) => stream::iter(from..to.saturating_add(1).min(block_count))
+					.map(move |index| self.get_block_by_index(index, full_transactions))
+					.buffered(lookahead.max(1))
+					.left_stream(),
+				Err(err) => stream::once(future::ready(Err(err))).right_stream(),
+			})
+			.flatten()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use futures::StreamExt;
+	use serde_json::json;
+	use url::Url;
+	use wiremock::{
+		matchers::{body_partial_json, method},
+		Mock, MockServer, ResponseTemplate,
+	};
+
+	use neo::prelude::{HttpProvider, RpcClient};
+
+	async fn mock_rpc(
+		mock_server: &MockServer,
+		request: serde_json::Value,
+		result: serde_json::Value,
+	) {
+		Mock::given(method("POST"))
+			.and(body_partial_json(request))
+			.respond_with(ResponseTemplate::new(200).set_body_json(json!({
+				"jsonrpc": "2.0",
+				"id": 1,
+				"result": result
+			})))
+			.mount(mock_server)
+			.await;
+	}
+
+	fn block(index: u32) -> serde_json::Value {
+		json!({
+			"hash": format!("0x{:064x}", index + 1),
+			"size": 697,
+			"version": 0,
+			"previousblockhash": format!("0x{:064x}", index),
+			"merkleroot": format!("0x{:064x}", 0),
+			"time": 1627894840 + index,
+			"nonce": "0",
+			"index": index,
+			"primary": 0,
+			"nextconsensus": "NgPkjjLTNcQad99iRYeXRUuowE4gxLAnDL",
+			"witnesses": [],
+			"tx": [],
+			"confirmations": 1,
+			"nextblockhash": format!("0x{:064x}", index + 2)
+		})
+	}
+
+	#[tokio::test]
+	async fn test_block_range_stops_at_chain_height() {
+		let mock_server = MockServer::start().await;
+		mock_rpc(&mock_server, json!({ "method": "getblockcount" }), json!(5)).await;
+		for index in 0..5 {
+			let request = json!({ "method": "getblock", "params": [index, 1] });
+			mock_rpc(&mock_server, request, block(index)).await;
+		}
+
+		let url = Url::parse(&mock_server.uri()).unwrap();
+		let client = RpcClient::new(HttpProvider::new(url).unwrap());
+		let indices: Vec<i32> = client
+			.block_range(2, 10, true, 3)
+			.map(|block| block.unwrap().index)
+			.collect()
+			.await;
+
+		assert_eq!(indices, vec![2, 3, 4]);
+	}
+}
@@ -4,5 +4,7 @@ pub use dev_rpc::{DevRpcMiddleware, DevRpcMiddlewareError};
 /// Types for the admin api
 pub mod nns;
 
+mod block_range;
+
 #[cfg(feature = "dev-rpc")]
 pub mod dev_rpc;
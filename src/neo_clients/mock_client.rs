@@ -102,6 +102,22 @@ impl MockClient {
 		self
 	}
 
+	/// Builds the result of an `invokefunction` or `invokescript` call that halted with `stack`.
+	pub fn halt_result(stack: Value) -> Value {
+		json!({
+			"script": "",
+			"state": "HALT",
+			"gasconsumed": "100000",
+			"exception": null,
+			"stack": stack
+		})
+	}
+
+	/// Mocks `method_name` to halt with `stack`, whatever its parameters.
+	pub async fn mock_halt_result(&mut self, method_name: &str, stack: Value) -> &mut Self {
+		self.mock_response_ignore_param(method_name, Self::halt_result(stack)).await
+	}
+
 	pub async fn mock_response_with_file(
 		&mut self,
 		method_name: &str,
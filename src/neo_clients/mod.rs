@@ -8,16 +8,23 @@
 use lazy_static::lazy_static;
 
 pub use api_trait::*;
+pub use backoff::*;
+pub use broadcaster::*;
 pub use errors::ProviderError;
 pub use ext::*;
 pub use mock_client::MockClient;
 use neo::prelude::NeoConstants;
 pub use rpc::*;
+pub use rx::{EventDecoders, EventStream};
+#[cfg(feature = "sim")]
+pub use sim_blockchain::SimBlockchain;
 #[allow(deprecated)]
 pub use test_provider::{MAINNET, TESTNET};
 pub use utils::*;
 
 mod api_trait;
+mod backoff;
+mod broadcaster;
 /// Errors
 mod errors;
 mod ext;
@@ -25,6 +32,8 @@ mod mock_blocks;
 mod mock_client;
 mod rpc;
 mod rx;
+#[cfg(feature = "sim")]
+mod sim_blockchain;
 /// Crate utilities and type aliases
 mod utils;
 
@@ -76,13 +85,13 @@ mod test_provider {
 		}
 
 		#[cfg(feature = "ws")]
-		pub async fn ws(&self) -> RpcClient<crate::Ws> {
+		pub async fn ws(&self) -> RpcClient<Ws> {
 			let url = format!(
 				"wss://{}.infura.neo.io/ws/v3/{}",
 				self.network,
 				self.keys.lock().unwrap().next().unwrap()
 			);
-			RpcClient::connect(url.as_str()).await.unwrap()
+			RpcClient::connect_ws(url).await.unwrap()
 		}
 	}
 }
@@ -1,5 +1,5 @@
 pub use connections::*;
-pub use pubsub::{PubsubClient, SubscriptionStream};
+pub use pubsub::{MempoolEvent, PubsubClient, SubscriptionEvent, SubscriptionStream};
 pub use rpc_client::*;
 pub use transports::*;
 
@@ -1,23 +1,37 @@
 use std::{
 	collections::VecDeque,
+	future::Future,
 	marker::PhantomData,
 	pin::Pin,
 	task::{Context, Poll},
 };
 
-use futures_util::stream::Stream;
+use futures_util::{
+	future,
+	stream::{self, Stream, StreamExt},
+};
 use pin_project::{pin_project, pinned_drop};
-use primitive_types::U256;
-use serde::de::DeserializeOwned;
-use serde_json::value::RawValue;
+use primitive_types::{H256, U256};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, value::RawValue, Value};
 use tracing::error;
 
-use neo::prelude::{JsonRpcProvider, RpcClient};
+use neo::prelude::{
+	deserialize_h256, encode_string_h256, serialize_h256, APITrait, ApplicationLog, Execution,
+	JsonRpcProvider, ProviderError, RTransaction, RpcClient,
+};
+
+/// JSON-RPC error code of a method the node does not know.
+const METHOD_NOT_FOUND: i64 = -32601;
+/// JSON-RPC error code of invalid parameters, returned for unknown subscription events.
+const INVALID_PARAMS: i64 = -32602;
 
 /// A transport implementation supporting pub sub subscriptions.
 pub trait PubsubClient: JsonRpcProvider {
 	/// The type of stream this transport returns
-	type NotificationStream: futures_core::Stream<Item = Box<RawValue>> + Send + Unpin;
+	type NotificationStream: futures_core::Stream<Item = SubscriptionEvent<Box<RawValue>>>
+		+ Send
+		+ Unpin;
 
 	/// Add a subscription to this transport
 	fn subscribe<T: Into<U256>>(&self, id: T) -> Result<Self::NotificationStream, Self::Error>;
@@ -26,9 +40,147 @@ pub trait PubsubClient: JsonRpcProvider {
 	fn unsubscribe<T: Into<U256>>(&self, id: T) -> Result<(), Self::Error>;
 }
 
+/// An item of a subscription stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubscriptionEvent<T> {
+	/// A notification of the subscribed event
+	Notification(T),
+	/// The connection to the node was lost and re-established, and the subscription re-issued.
+	/// Notifications sent while disconnected were missed.
+	Reconnected,
+}
+
+impl<T> SubscriptionEvent<T> {
+	/// Maps the notification with `f`, and keeps a reconnection marker.
+	pub fn map<U>(self, f: impl FnOnce(T) -> U) -> SubscriptionEvent<U> {
+		match self {
+			SubscriptionEvent::Notification(notification) =>
+				SubscriptionEvent::Notification(f(notification)),
+			SubscriptionEvent::Reconnected => SubscriptionEvent::Reconnected,
+		}
+	}
+}
+
+/// A change to the node's mempool, as streamed by [`RpcClient::subscribe_mempool`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum MempoolEvent {
+	/// A transaction entered the mempool
+	Added(RTransaction),
+	/// The transaction with this hash left the mempool, because it was included in a block and
+	/// executed
+	Removed(H256),
+}
+
+/// The execution of a transaction, as streamed by `transaction_executed` subscriptions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct ExecutionNotification {
+	#[serde(serialize_with = "serialize_h256", deserialize_with = "deserialize_h256")]
+	container: H256,
+	#[serde(flatten)]
+	execution: Execution,
+}
+
+impl<P: PubsubClient> RpcClient<P> {
+	/// Subscribes to the changes of the node's mempool. Transactions entering it are streamed
+	/// from NeoGo's `transaction_added` event, and transactions leaving it from the
+	/// `transaction_executed` event. Nodes do not notify transactions that expire or are
+	/// replaced.
+	///
+	/// Returns [`ProviderError::UnsupportedMethod`] if the node does not support subscriptions or
+	/// mempool events.
+	pub async fn subscribe_mempool(
+		&self,
+	) -> Result<impl Stream<Item = SubscriptionEvent<MempoolEvent>> + '_, ProviderError> {
+		let id = self.subscribe_to_event("transaction_added", None).await?;
+		let added: SubscriptionStream<'_, P, RTransaction> =
+			SubscriptionStream::new(id, self).map_err(Into::into)?;
+		let id = self.subscribe_to_event("transaction_executed", None).await?;
+		let executed: SubscriptionStream<'_, P, ExecutionNotification> =
+			SubscriptionStream::new(id, self).map_err(Into::into)?;
+
+		let added = added.map(|event| event.map(MempoolEvent::Added));
+		// Both subscriptions are re-issued together, so the reconnection marker of the added
+		// transactions is enough. Block executions are not transactions leaving the mempool.
+		let removed = executed.filter_map(|event| {
+			future::ready(match event {
+				SubscriptionEvent::Notification(notification)
+					if notification.execution.trigger == "Application" =>
+					Some(SubscriptionEvent::Notification(MempoolEvent::Removed(
+						notification.container,
+					))),
+				_ => None,
+			})
+		});
+		Ok(stream::select(added, removed))
+	}
+
+	/// Subscribes to the execution of the transaction `tx_hash`. The returned future resolves to
+	/// the transaction's application log as soon as the node has executed it, so subscribe before
+	/// sending the transaction to not miss its execution.
+	///
+	/// Returns [`ProviderError::UnsupportedMethod`] if the node does not support subscriptions or
+	/// execution events.
+	pub async fn subscribe_to_execution(
+		&self,
+		tx_hash: H256,
+	) -> Result<impl Future<Output = Result<ApplicationLog, ProviderError>> + '_, ProviderError>
+	{
+		let filter = json!({ "container": encode_string_h256(&tx_hash) });
+		let id = self.subscribe_to_event("transaction_executed", Some(filter)).await?;
+		let mut notifications: SubscriptionStream<'_, P, ExecutionNotification> =
+			SubscriptionStream::new(id, self).map_err(Into::into)?;
+
+		Ok(async move {
+			// The node filters by container, but nodes that ignore the filter send all executions
+			while let Some(event) = notifications.next().await {
+				match event {
+					SubscriptionEvent::Notification(notification)
+						if notification.container == tx_hash =>
+						return Ok(ApplicationLog {
+							transaction_id: tx_hash,
+							executions: vec![notification.execution],
+						}),
+					SubscriptionEvent::Notification(_) => {},
+					// The execution may have been missed while disconnected
+					SubscriptionEvent::Reconnected =>
+						if let Ok(application_log) = self.get_application_log(tx_hash).await {
+							return Ok(application_log);
+						},
+				}
+			}
+			Err(ProviderError::CustomError(format!(
+				"The subscription ended before transaction {} was executed",
+				encode_string_h256(&tx_hash)
+			)))
+		})
+	}
+
+	async fn subscribe_to_event(
+		&self,
+		event: &str,
+		filter: Option<Value>,
+	) -> Result<U256, ProviderError> {
+		let mut params = vec![json!(event)];
+		params.extend(filter);
+		// NeoGo returns the id as a decimal string
+		let id: String = self.request("subscribe", params).await.map_err(|err| match err {
+			ProviderError::JsonRpcError(err)
+				if err.code == METHOD_NOT_FOUND || err.code == INVALID_PARAMS =>
+				ProviderError::UnsupportedMethod(format!(
+					"{} subscriptions: {}",
+					event, err.message
+				)),
+			err => err,
+		})?;
+		U256::from_dec_str(&id).map_err(|_| {
+			ProviderError::CustomError(format!("invalid {} subscription id: {}", event, id))
+		})
+	}
+}
+
 #[must_use = "subscriptions do nothing unless you stream them"]
 #[pin_project(PinnedDrop)]
-/// Streams data from an installed filter via `neo_subscribe`
+/// Streams the notifications of a subscription installed via `subscribe`
 pub struct SubscriptionStream<'a, P: PubsubClient, R: DeserializeOwned> {
 	/// The subscription's installed id on the neo node
 	pub id: U256,
@@ -81,24 +233,27 @@ where
 	P: PubsubClient,
 	R: DeserializeOwned,
 {
-	type Item = R;
+	type Item = SubscriptionEvent<R>;
 
 	fn poll_next(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Option<Self::Item>> {
 		if !self.loaded_elements.is_empty() {
 			let next_element = self.get_mut().loaded_elements.pop_front();
-			return Poll::Ready(next_element);
+			return Poll::Ready(next_element.map(SubscriptionEvent::Notification));
 		}
 
 		let mut this = self.project();
 		loop {
 			return match futures_util::ready!(this.rx.as_mut().poll_next(ctx)) {
-				Some(item) => match serde_json::from_str(item.get()) {
-					Ok(res) => Poll::Ready(Some(res)),
-					Err(err) => {
-						error!("failed to deserialize item {:?}", err);
-						continue;
+				Some(SubscriptionEvent::Notification(item)) =>
+					match serde_json::from_str(item.get()) {
+						Ok(res) => Poll::Ready(Some(SubscriptionEvent::Notification(res))),
+						Err(err) => {
+							error!("failed to deserialize item {:?}", err);
+							continue;
+						},
 					},
-				},
+				Some(SubscriptionEvent::Reconnected) =>
+					Poll::Ready(Some(SubscriptionEvent::Reconnected)),
 				None => Poll::Ready(None),
 			};
 		}
@@ -118,3 +273,164 @@ where
 		let _ = (*self.provider).as_ref().unsubscribe(self.id);
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use std::{collections::HashMap, fmt::Debug, sync::Mutex};
+
+	use async_trait::async_trait;
+	use futures_util::{stream, StreamExt};
+	use primitive_types::{H256, U256};
+	use serde::{de::DeserializeOwned, Serialize};
+	use serde_json::{json, value::RawValue, Value};
+
+	use neo::prelude::{
+		JsonRpcProvider, MempoolEvent, ProviderError, PubsubClient, RpcClient, SubscriptionEvent,
+	};
+
+	/// A transport that answers the n-th request with subscription id n, and streams the
+	/// notifications queued for an id to its subscriber.
+	#[derive(Debug, Default)]
+	struct MockPubsub {
+		requests: Mutex<Vec<(String, serde_json::Value)>>,
+		notifications: Mutex<HashMap<U256, Vec<SubscriptionEvent<Box<RawValue>>>>>,
+	}
+
+	#[async_trait]
+	impl JsonRpcProvider for MockPubsub {
+		type Error = ProviderError;
+
+		async fn fetch<T, R>(&self, method: &str, params: T) -> Result<R, ProviderError>
+		where
+			T: Debug + Serialize + Send + Sync,
+			R: DeserializeOwned + Send,
+		{
+			let params = serde_json::to_value(params)?;
+			let mut requests = self.requests.lock().unwrap();
+			requests.push((method.to_string(), params));
+			Ok(serde_json::from_value(json!(requests.len().to_string()))?)
+		}
+	}
+
+	impl PubsubClient for MockPubsub {
+		type NotificationStream =
+			stream::Iter<std::vec::IntoIter<SubscriptionEvent<Box<RawValue>>>>;
+
+		fn subscribe<T: Into<U256>>(
+			&self,
+			id: T,
+		) -> Result<Self::NotificationStream, ProviderError> {
+			let notifications = self.notifications.lock().unwrap().remove(&id.into());
+			Ok(stream::iter(notifications.unwrap_or_default()))
+		}
+
+		fn unsubscribe<T: Into<U256>>(&self, _id: T) -> Result<(), ProviderError> {
+			Ok(())
+		}
+	}
+
+	fn notification(payload: Value) -> SubscriptionEvent<Box<RawValue>> {
+		SubscriptionEvent::Notification(RawValue::from_string(payload.to_string()).unwrap())
+	}
+
+	fn execution(container: H256, trigger: &str, gas_consumed: &str) -> Value {
+		json!({
+			"container": format!("{:?}", container),
+			"trigger": trigger,
+			"vmstate": "HALT",
+			"exception": null,
+			"gasconsumed": gas_consumed,
+			"stack": [],
+			"notifications": []
+		})
+	}
+
+	#[tokio::test]
+	async fn test_subscribe_to_execution() {
+		let tx_hash = H256::repeat_byte(0xab);
+		let transport = MockPubsub::default();
+		transport.notifications.lock().unwrap().insert(
+			U256::one(),
+			vec![
+				notification(execution(H256::repeat_byte(0x01), "Application", "100")),
+				notification(execution(tx_hash, "Application", "9007990")),
+			],
+		);
+		let client = RpcClient::new(transport);
+
+		let application_log = client.subscribe_to_execution(tx_hash).await.unwrap().await.unwrap();
+
+		assert_eq!(application_log.transaction_id, tx_hash);
+		assert_eq!(application_log.executions.len(), 1);
+		assert_eq!(application_log.executions[0].gas_consumed, "9007990");
+		assert_eq!(
+			client.as_ref().requests.lock().unwrap()[0],
+			(
+				"subscribe".to_string(),
+				json!(["transaction_executed", { "container": format!("{:?}", tx_hash) }])
+			)
+		);
+	}
+
+	#[tokio::test]
+	async fn test_subscribe_mempool() {
+		let transaction_hash = "0x8b8b222ba4ae17eaf37d444210f3d9503f2ed4f0c8fbaea8e3b8ae5e4fe05f1f";
+		let transaction = json!({
+			"hash": transaction_hash,
+			"size": 252,
+			"version": 0,
+			"nonce": 1_046_354_582,
+			"sender": "NKuyBkoGdZZSLyPbJEetheRhMjeznFZszf",
+			"sysfee": "9007990",
+			"netfee": "1230610",
+			"validuntilblock": 2_106_392,
+			"script": "CxAMFPlu76Cuc+bgteStE4ozsOWTNUdrDBQtYNweHko3YcnMFOes3ceblcI/lRTAHwwIdHJhbnNmZXIMFPVj6kC8KD1NDgXEjqMFs/Kgc0DvQWJ9W1I5"
+		});
+		let transaction_hash: H256 = transaction_hash.parse().unwrap();
+		let transport = MockPubsub::default();
+		transport.notifications.lock().unwrap().extend([
+			(U256::from(1), vec![notification(transaction), SubscriptionEvent::Reconnected]),
+			(
+				U256::from(2),
+				vec![
+					notification(execution(H256::repeat_byte(0x01), "OnPersist", "0")),
+					SubscriptionEvent::Reconnected,
+					notification(execution(transaction_hash, "Application", "9007990")),
+				],
+			),
+		]);
+		let client = RpcClient::new(transport);
+
+		let events: Vec<_> = client.subscribe_mempool().await.unwrap().collect().await;
+
+		let added: Vec<_> = events
+			.iter()
+			.filter_map(|event| match event {
+				SubscriptionEvent::Notification(MempoolEvent::Added(transaction)) =>
+					Some(transaction.hash),
+				_ => None,
+			})
+			.collect();
+		let removed: Vec<_> = events
+			.iter()
+			.filter_map(|event| match event {
+				SubscriptionEvent::Notification(MempoolEvent::Removed(hash)) => Some(*hash),
+				_ => None,
+			})
+			.collect();
+		assert_eq!(added, vec![transaction_hash]);
+		assert_eq!(removed, vec![transaction_hash]);
+		// Both subscriptions are re-issued together, one marker is streamed
+		assert_eq!(
+			events.iter().filter(|event| **event == SubscriptionEvent::Reconnected).count(),
+			1
+		);
+		assert_eq!(
+			client.as_ref().requests.lock().unwrap().clone(),
+			vec![
+				("subscribe".to_string(), json!(["transaction_added"])),
+				("subscribe".to_string(), json!(["transaction_executed"])),
+			]
+		);
+	}
+}
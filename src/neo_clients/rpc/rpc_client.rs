@@ -1,3 +1,4 @@
+use ::base64::{engine::general_purpose, Engine};
 use async_trait::async_trait;
 use futures_util::lock::Mutex;
 use getset::{Getters, Setters};
@@ -142,6 +143,15 @@ impl<P: JsonRpcProvider> RpcClient<P> {
 		.await?;
 		Ok(res)
 	}
+
+	/// Sends a signed transaction to a Neo X (EVM) node with `eth_sendRawTransaction` and
+	/// returns its 0x-prefixed hash.
+	pub async fn send_neox_transaction(
+		&self,
+		transaction: &SignedNeoXTransaction,
+	) -> Result<String, ProviderError> {
+		self.request("eth_sendRawTransaction", [transaction.raw_hex()]).await
+	}
 }
 
 #[cfg_attr(target_arch = "wasm32", async_trait(? Send))]
@@ -287,13 +297,29 @@ impl<P: JsonRpcProvider> APITrait for RpcClient<P> {
 		self.request("getrawtransaction", vec![hash.to_value(), 1.to_value()]).await
 	}
 
-	/// Gets the corresponding transaction information based on the specified transaction hash.
+	/// Gets the serialized transaction with the specified hash as a base64 string, which
+	/// [`Transaction::from_base64`] decodes.
 	/// - Parameter txHash: The transaction hash
 	/// - Returns: The request object
 	async fn get_raw_transaction(&self, tx_hash: H256) -> Result<String, ProviderError> {
 		self.request("getrawtransaction", vec![tx_hash.to_value(), 0.to_value()]).await
 	}
 
+	/// Gets the transaction with the specified hash, either as the verbose transaction including
+	/// its confirmations, block hash and block time, or as the serialized base64 string.
+	/// - Parameters:
+	///   - txHash: The transaction hash
+	///   - verbose: Whether to return the verbose transaction
+	/// - Returns: The request object
+	async fn get_raw_transaction_info(
+		&self,
+		tx_hash: H256,
+		verbose: bool,
+	) -> Result<RawTransactionInfo, ProviderError> {
+		self.request("getrawtransaction", vec![tx_hash.to_value(), (verbose as i32).to_value()])
+			.await
+	}
+
 	/// Gets the stored value according to the contract hash and the key.
 	/// - Parameters:
 	///   - contractHash: The contract hash
@@ -388,8 +414,13 @@ impl<P: JsonRpcProvider> APITrait for RpcClient<P> {
 	/// Broadcasts a transaction over the NEO network.
 	/// - Parameter rawTransactionHex: The raw transaction in hexadecimal
 	/// - Returns: The request object
+	///
+	/// Fails with [`ProviderError::AlreadyInMempool`] if the node already has the transaction
+	/// and with [`ProviderError::MempoolConflict`] if it conflicts with the memory pool.
 	async fn send_raw_transaction(&self, hex: String) -> Result<RawTransaction, ProviderError> {
-		self.request("sendrawtransaction", vec![Base64Encode::to_base64(&hex)]).await
+		self.request("sendrawtransaction", vec![Base64Encode::to_base64(&hex)])
+			.await
+			.map_err(ProviderError::from_send_error)
 	}
 
 	/// Broadcasts a new block over the NEO network.
@@ -733,7 +764,8 @@ impl<P: JsonRpcProvider> APITrait for RpcClient<P> {
 	///
 	/// The properties are a mapping from the property name string to the value string.
 	/// The value is plain text if the key is one of the properties defined in the NEP-11 standard.
-	/// Otherwise, the value is a Base64-encoded byte array.
+	/// Otherwise, the value is a Base64-encoded byte array. The values are returned as the node
+	/// sends them, [`decode_nep11_property`] decodes one according to its key.
 	///
 	/// To receive custom property values that consist of nested types (e.g., Maps or Arrays) use ``invokeFunction(_:_:_:)``  to directly invoke the method `properties` of the NEP-11 smart contract.
 	/// - Parameters:
@@ -1058,11 +1090,42 @@ impl RpcClient<crate::Ipc> {
 }
 
 impl RpcClient<Http> {
+	/// Connects to the node at `url` and sends it a `getversion` request, so that a malformed
+	/// or unreachable endpoint is reported here rather than on the first call that uses the
+	/// client. Use [`RpcClient::new`] to create a client without contacting the node.
+	pub async fn connect(url: &str) -> Result<Self, ProviderError> {
+		let provider = Http::new(url).map_err(|err| {
+			ProviderError::CustomError(format!("invalid RPC URL `{}`: {}", url, err))
+		})?;
+		let client = Self::new(provider);
+		client.get_version().await.map_err(|err| ProviderError::PreflightFailed {
+			url: url.to_string(),
+			source: Box::new(err),
+		})?;
+		Ok(client)
+	}
+
 	/// The Url to which requests are made
 	pub fn url(&self) -> &Url {
 		self.provider.url()
 	}
 
+	/// Like [`APITrait::traverse_iterator`], but yields the stack items as they are read from
+	/// the response instead of buffering and parsing the whole response first.
+	pub fn traverse_iterator_stream<'a>(
+		&'a self,
+		session_id: &str,
+		iterator_id: &str,
+		count: u32,
+	) -> impl futures_util::Stream<Item = Result<StackItem, ProviderError>> + 'a {
+		use futures_util::StreamExt;
+
+		let params = vec![session_id.to_value(), iterator_id.to_value(), count.to_value()];
+		self.provider
+			.fetch_array_stream("traverseiterator", params)
+			.map(|item| item.map_err(Into::into))
+	}
+
 	/// Mutable access to the Url to which requests are made
 	pub fn url_mut(&mut self) -> &mut Url {
 		self.provider.url_mut()
@@ -1256,6 +1319,21 @@ pub fn is_local_endpoint(endpoint: &str) -> bool {
 	false
 }
 
+/// NEP-11 properties that the node returns as plain UTF-8 strings. All other property values
+/// are Base64 encoded.
+const NEP11_STRING_PROPERTIES: [&str; 4] = ["name", "description", "image", "tokenURI"];
+
+/// Decodes a `getnep11properties` value into the bytes of the property. The properties defined
+/// by NEP-11 are plain strings, all other properties are byte arrays that must be Base64.
+pub fn decode_nep11_property(key: &str, value: &str) -> Result<Vec<u8>, ProviderError> {
+	if NEP11_STRING_PROPERTIES.contains(&key) {
+		return Ok(value.as_bytes().to_vec());
+	}
+	general_purpose::STANDARD
+		.decode(value)
+		.map_err(|e| TypeError::InvalidEncoding(format!("{key}: {e}")).into())
+}
+
 #[cfg(test)]
 mod tests {
 	use base64::{engine::general_purpose, Engine};
@@ -1303,6 +1381,8 @@ mod tests {
 		providers::RpcClient,
 	};
 
+	use super::decode_nep11_property;
+
 	async fn setup_mock_server() -> MockServer {
 		MockServer::start().await
 	}
@@ -3602,6 +3682,98 @@ mod tests {
 		verify_request(&mock_server, expected_request_body).await.unwrap();
 	}
 
+	#[tokio::test]
+	async fn test_get_raw_transaction_info_base64() {
+		const RAW_TRANSACTION: &str = "AAEAAABAQg8AAAAAALDEEgAAAAAAiBMAAAFp7MpYcpMEe+TFkVm/i8OZmFwWDQEEASDSBAAAIR8eHRwbGhkYFxYVFBMSERAPDg0MCwoJCAcGBQQDAgEAEQcAAAAAAAAAAANhYmMCEUABQgxAAAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0+PygMIQIAAQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eH0FW57Mn";
+
+		let mock_server = setup_mock_server().await;
+		let provider = mock_rpc_response(
+			&mock_server,
+			"getrawtransaction",
+			json!(["7da6ae7ff9d0b7af3d32f3a2feb2aa96c2a27ef8b651f9a132cfaad6ef20724c", 0]),
+			json!(RAW_TRANSACTION),
+		)
+		.await;
+
+		let result = provider
+			.get_raw_transaction_info(
+				H256::from_str(
+					"0x7da6ae7ff9d0b7af3d32f3a2feb2aa96c2a27ef8b651f9a132cfaad6ef20724c",
+				)
+				.unwrap(),
+				false,
+			)
+			.await
+			.unwrap();
+
+		assert_eq!(result.base64(), Some(RAW_TRANSACTION));
+		assert!(result.transaction().is_none());
+		assert_eq!(result.confirmations(), None);
+		let transaction = result.decode::<HttpProvider>().unwrap().unwrap();
+		assert_eq!(
+			transaction.get_tx_id().unwrap(),
+			H256::from_str("c501016d5bcaab4b07d25569df6126bac71ee87327532a2434497668a5d1b614")
+				.unwrap()
+		);
+	}
+
+	#[tokio::test]
+	async fn test_get_raw_transaction_info_verbose() {
+		let mock_server = setup_mock_server().await;
+		let provider = mock_rpc_response(
+			&mock_server,
+			"getrawtransaction",
+			json!(["7da6ae7ff9d0b7af3d32f3a2feb2aa96c2a27ef8b651f9a132cfaad6ef20724c", 1]),
+			json!({
+				"hash": "0x7da6ae7ff9d0b7af3d32f3a2feb2aa96c2a27ef8b651f9a132cfaad6ef20724c",
+				"size": 267,
+				"version": 0,
+				"nonce": 1046354582,
+				"sender": "NZNovBUJ6Pp4fTuq2ALj5J6vZrjMz2gyV2",
+				"sysfee": "9007810",
+				"netfee": "1267450",
+				"validuntilblock": 2103622,
+				"signers": [
+					{
+						"account": "0x69ecca587293047be4c59159bf8bc399985c160d",
+						"scopes": "CalledByEntry"
+					}
+				],
+				"attributes": [],
+				"script": "AGQMFObBATZUrxE9ipaL3KUsmUioK5U9DBQP7O1Ep0MA2doEn6k2cKQxFxiP9hPADAh0cmFuc2ZlcgwUiXcg2M129PAKv6N8Dt2InCCP3ptBYn1bUjg=",
+				"witnesses": [],
+				"blockhash": "0x8529cf7301d13cc13d85913b8367700080a6e96db045687b8db720e91e803299",
+				"confirmations": 1388,
+				"blocktime": 1589019142879i64,
+				"vmstate": "HALT"
+			}),
+		)
+		.await;
+
+		let result = provider
+			.get_raw_transaction_info(
+				H256::from_str(
+					"0x7da6ae7ff9d0b7af3d32f3a2feb2aa96c2a27ef8b651f9a132cfaad6ef20724c",
+				)
+				.unwrap(),
+				true,
+			)
+			.await
+			.unwrap();
+
+		assert!(result.base64().is_none());
+		assert!(result.decode::<HttpProvider>().is_none());
+		assert_eq!(result.confirmations(), Some(1388));
+		let transaction = result.transaction().unwrap();
+		assert_eq!(*transaction.sys_fee(), "9007810".to_string());
+		assert_eq!(transaction.block_time(), &1589019142879);
+		assert_eq!(
+			transaction.block_hash(),
+			&H256::from_str("0x8529cf7301d13cc13d85913b8367700080a6e96db045687b8db720e91e803299")
+				.unwrap()
+		);
+	}
+
 	#[tokio::test]
 	async fn test_get_storge() {
 		let mock_server = setup_mock_server().await;
@@ -4093,6 +4265,46 @@ mod tests {
 		verify_request(&mock_server, &expected_request_body).await.unwrap();
 	}
 
+	#[tokio::test]
+	async fn test_connect() {
+		let mock_server = setup_mock_server().await;
+		mock_rpc_response(
+			&mock_server,
+			"getversion",
+			json!([]),
+			json!({
+				"tcpport": 40333,
+				"wsport": 40334,
+				"nonce": 224036820,
+				"useragent": "/Neo:3.0.0/"
+			}),
+		)
+		.await;
+
+		let client = RpcClient::connect(&mock_server.uri()).await.unwrap();
+		assert_eq!(client.url().as_str().trim_end_matches('/'), mock_server.uri());
+		assert_eq!(mock_server.received_requests().await.unwrap().len(), 1);
+	}
+
+	#[tokio::test]
+	async fn test_connect_bad_url() {
+		let err = RpcClient::connect("not a url").await.unwrap_err();
+		assert!(
+			matches!(err, ProviderError::CustomError(ref message) if message.contains("not a url"))
+		);
+
+		// The listener is dropped right away, so nothing listens on its port anymore
+		let addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+		let err = RpcClient::connect(&format!("http://{addr}")).await.unwrap_err();
+		match err {
+			ProviderError::PreflightFailed { url, source } => {
+				assert_eq!(url, format!("http://{addr}"));
+				assert!(matches!(*source, ProviderError::ConnectionFailed(_)));
+			},
+			err => panic!("unexpected error: {:?}", err),
+		}
+	}
+
 	#[tokio::test]
 	async fn test_send_raw_transaction() {
 		let mock_server = setup_mock_server().await;
@@ -4128,6 +4340,49 @@ mod tests {
 		verify_request(&mock_server, &expected_request_body).await.unwrap();
 	}
 
+	#[tokio::test]
+	async fn test_send_raw_transaction_conflict() {
+		let mut mock_provider = MockClient::new().await;
+		// As returned by a Neo 3.7 node
+		mock_provider
+			.mock_response_error(json!({
+				"code": -500,
+				"message": "Inventory verification failed. - HasConflicts",
+				"data": "HasConflicts"
+			}))
+			.await;
+		mock_provider.mount_mocks().await;
+		let client = mock_provider.into_client();
+
+		let result = client.send_raw_transaction("00".to_string()).await;
+		assert_eq!(
+			result.unwrap_err(),
+			ProviderError::MempoolConflict(
+				"Inventory verification failed. - HasConflicts".to_string()
+			)
+		);
+	}
+
+	#[tokio::test]
+	async fn test_send_neox_transaction() {
+		let mock_server = setup_mock_server().await;
+		let transaction = neo::prelude::NeoXTransaction::legacy(47763, 40_000_000_000)
+			.to(H160::repeat_byte(0x35))
+			.value(1_000_000_000_000_000_000);
+		let signer = neo::prelude::NeoXSigner::from_slice(&[0x46; 32]).unwrap();
+		let signed = signer.sign_transaction(&transaction).unwrap();
+		let provider = mock_rpc_response(
+			&mock_server,
+			"eth_sendRawTransaction",
+			json!([signed.raw_hex()]),
+			json!(signed.hash_hex()),
+		)
+		.await;
+
+		let hash = provider.send_neox_transaction(&signed).await.unwrap();
+		assert_eq!(hash, signed.hash_hex());
+	}
+
 	#[tokio::test]
 	async fn test_submit_block() {
 		let mock_server = setup_mock_server().await;
@@ -4156,6 +4411,32 @@ mod tests {
 		assert_eq!(result.unwrap().get_submit_block(), true);
 	}
 
+	#[tokio::test]
+	async fn test_submit_block_relay_result() {
+		let mock_server = setup_mock_server().await;
+		let block_hex = "00000000000000000000000000000000".to_string();
+
+		let provider = mock_rpc_response(
+			&mock_server,
+			"submitblock",
+			json!([block_hex]),
+			json!({
+				"hash": "0xbe153a2ef9e9160906f7054ed8f2fa48fea1d1ba1b0a0a1e4c8d4ecf5f3b1c49"
+			}),
+		)
+		.await;
+
+		let result = provider.submit_block(block_hex).await.unwrap();
+		assert!(result.get_submit_block());
+		assert_eq!(
+			result.hash(),
+			Some(
+				H256::from_str("be153a2ef9e9160906f7054ed8f2fa48fea1d1ba1b0a0a1e4c8d4ecf5f3b1c49")
+					.unwrap()
+			)
+		);
+	}
+
 	// SmartContract Methods
 
 	#[tokio::test]
@@ -4323,7 +4604,7 @@ mod tests {
 
 		let stack_item_list = notifications.get(0).unwrap().state.as_array().unwrap();
 		let item0 = stack_item_list[0].as_int();
-		assert_eq!(item0, Some(1));
+		assert_eq!(item0, Ok(1));
 		assert_eq!(stack_item_list[1].as_string().unwrap(), "token1".to_string());
 		assert_eq!(
 			notifications.get(1).unwrap().contract,
@@ -4537,7 +4818,7 @@ mod tests {
 			))
 			.unwrap()
 			.as_int();
-		assert_eq!(value, Some(12));
+		assert_eq!(value, Ok(12));
 	}
 
 	#[tokio::test]
@@ -6171,7 +6452,7 @@ mod tests {
 
 		assert!(result.is_ok(), "Result is not okay: {:?}", result);
 		let unclaimed_gas = result.unwrap();
-		assert_eq!(unclaimed_gas.unclaimed, "79199824176".to_string());
+		assert_eq!(unclaimed_gas.unclaimed, 79199824176);
 		assert_eq!(unclaimed_gas.address, "AGZLEiwUyCC4wiL5sRZA3LbxWPs9WrZeyN".to_string());
 	}
 
@@ -7234,7 +7515,7 @@ mod tests {
 			matches!(stack_item, StackItem::Integer { .. }),
 			"The stack item type is not Integer as expected"
 		);
-		assert_eq!(stack_item.as_int(), Some(1));
+		assert_eq!(stack_item.as_int(), Ok(1));
 		let mut result = execution.get_stack_item(1);
 		assert!(matches!(result, Err(TypeError::IndexOutOfBounds(_))));
 		if let Err(TypeError::IndexOutOfBounds(msg)) = result {
@@ -8231,6 +8512,49 @@ mod tests {
 		assert_eq!(properties.get("keyProp2"), Some(&"valueProp2".to_string()));
 	}
 
+	#[tokio::test]
+	async fn test_decode_nep11_properties_by_key() {
+		let mock_server = setup_mock_server().await;
+
+		let provider = mock_rpc_response(
+			&mock_server,
+			"getnep11properties",
+			json!(["NfWL3Kx7qtZzXrajmggAD4b6r2kGzajbaJ", "12345"]),
+			json!({
+				"name": "Neo Gem #1",
+				"description": "A shiny gem",
+				"image": "https://example.com/gem1.png",
+				"tokenURI": "https://example.com/gem1.json",
+				"rarity": "bGVnZW5kYXJ5",
+				"raw": "AAEC/w==",
+				"invalid": "not base64!"
+			}),
+		)
+		.await;
+
+		let properties = provider
+			.get_nep11_properties(
+				H160::from_str("2eeda865e7824c71b3fe14bed35d04d0f2f0e9d6").unwrap(),
+				"12345",
+			)
+			.await
+			.unwrap();
+
+		assert_eq!(properties.len(), 7);
+		assert_eq!(properties.get("rarity"), Some(&"bGVnZW5kYXJ5".to_string()));
+		let decode = |key: &str| decode_nep11_property(key, &properties[key]);
+		assert_eq!(decode("name").unwrap(), b"Neo Gem #1");
+		assert_eq!(decode("description").unwrap(), b"A shiny gem");
+		assert_eq!(decode("image").unwrap(), b"https://example.com/gem1.png");
+		assert_eq!(decode("tokenURI").unwrap(), b"https://example.com/gem1.json");
+		assert_eq!(decode("rarity").unwrap(), b"legendary");
+		assert_eq!(decode("raw").unwrap(), vec![0, 1, 2, 255]);
+		assert!(matches!(decode("invalid"), Err(ProviderError::TypeError(_))));
+
+		// A standard property is a plain string even if it happens to be valid Base64
+		assert_eq!(decode_nep11_property("name", "R2Vt").unwrap(), b"R2Vt");
+	}
+
 	async fn verify_request(
 		mock_server: &MockServer,
 		expected: &str,
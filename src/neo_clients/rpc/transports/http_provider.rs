@@ -2,15 +2,24 @@
 
 use std::{
 	str::FromStr,
-	sync::atomic::{AtomicU64, Ordering},
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc,
+	},
+	time::Duration,
 };
 
 use async_trait::async_trait;
+use futures::stream::{self, Stream};
 use http::HeaderValue;
 use log::debug;
-use reqwest::{header, Client, Error as ReqwestError};
+use reqwest::{
+	header::{self, HeaderMap},
+	Client, Error as ReqwestError, Response as HttpResponse,
+};
 use serde::{de::DeserializeOwned, Serialize};
 use thiserror::Error;
+use tokio::sync::{Semaphore, SemaphorePermit};
 use url::Url;
 
 use neo::{
@@ -20,7 +29,10 @@ use neo::{
 
 use crate::prelude::Authorization;
 
-use super::common::{JsonRpcError, Request, Response};
+use super::{
+	common::{JsonRpcError, Request, Response},
+	json_stream::ResultArrayScanner,
+};
 
 /// A low-level JSON-RPC Client over HTTP.
 ///
@@ -44,6 +56,10 @@ pub struct HttpProvider {
 	id: AtomicU64,
 	client: Client,
 	url: Url,
+	/// Caps the number of requests in flight, shared by all clones of the provider
+	connection_limit: Option<Arc<Semaphore>>,
+	/// Reading a response is aborted once its body exceeds this many bytes
+	max_response_bytes: usize,
 }
 
 #[derive(Error, Debug)]
@@ -64,17 +80,25 @@ pub enum ClientError {
 		/// The contents of the HTTP response that could not be deserialized
 		text: String,
 	},
+
+	/// Thrown if the response body exceeds the configured maximum size
+	#[error("response exceeds the maximum size of {limit} bytes")]
+	ResponseTooLarge {
+		/// The configured maximum size
+		limit: usize,
+	},
 }
 
 impl From<ClientError> for ProviderError {
 	fn from(src: ClientError) -> Self {
 		match src {
-			ClientError::ReqwestError(err) => ProviderError::HTTPError(err.into()),
+			ClientError::ReqwestError(err) => err.into(),
 			ClientError::JsonRpcError(err) => ProviderError::JsonRpcError(err),
 			ClientError::SerdeJson { err, text } => {
 				debug!("SerdeJson Error: {:#?}, Response: {:#?}", err, text);
 				ProviderError::SerdeJson(err)
 			},
+			ClientError::ResponseTooLarge { limit } => ProviderError::ResponseTooLarge { limit },
 			_ => ProviderError::IllegalState("unexpected error".to_string()),
 		}
 	}
@@ -89,12 +113,37 @@ impl JsonRpcProvider for HttpProvider {
 		&self,
 		method: &str,
 		params: T,
+	) -> Result<R, ClientError> {
+		self.fetch_with_headers(method, params, HeaderMap::new()).await
+	}
+}
+
+impl HttpProvider {
+	/// Sends a JSON-RPC request with `headers` added to this request only. They take precedence
+	/// over the provider's default headers of the same name, e.g. to use a different API key for
+	/// a single call.
+	pub async fn fetch_with_headers<T: Serialize + Send + Sync, R: DeserializeOwned>(
+		&self,
+		method: &str,
+		params: T,
+		headers: HeaderMap,
 	) -> Result<R, ClientError> {
 		let next_id = self.id.fetch_add(1, Ordering::SeqCst);
 		let payload = Request::new(next_id, method, params);
 
-		let res = self.client.post(self.url.as_ref()).json(&payload).send().await?;
-		let body = res.bytes().await?;
+		// Held until the response body has been read, as the connection is busy until then
+		let _permit = match &self.connection_limit {
+			Some(limit) => Some(limit.acquire().await.expect("the semaphore is never closed")),
+			None => None,
+		};
+		let res = self
+			.client
+			.post(self.url.as_ref())
+			.headers(headers)
+			.json(&payload)
+			.send()
+			.await?;
+		let body = self.read_body(res).await?;
 
 		let raw = match serde_json::from_slice(&body) {
 			Ok(Response::Success { result, .. }) => result.to_owned(),
@@ -118,6 +167,116 @@ impl JsonRpcProvider for HttpProvider {
 
 		Ok(res)
 	}
+
+	/// Reads the response body chunk by chunk, aborting as soon as it exceeds
+	/// `max_response_bytes` instead of buffering an arbitrarily large body first.
+	async fn read_body(&self, mut res: HttpResponse) -> Result<Vec<u8>, ClientError> {
+		let limit = self.max_response_bytes;
+		if res.content_length().map_or(false, |len| len > limit as u64) {
+			return Err(ClientError::ResponseTooLarge { limit });
+		}
+
+		let mut body = Vec::new();
+		while let Some(chunk) = res.chunk().await? {
+			if body.len() + chunk.len() > limit {
+				return Err(ClientError::ResponseTooLarge { limit });
+			}
+			body.extend_from_slice(&chunk);
+		}
+		Ok(body)
+	}
+}
+
+/// Progress of a request streamed by [`HttpProvider::fetch_array_stream`]
+enum ArrayStreamState<'a, T> {
+	Pending(T),
+	Reading(HttpResponse, ResultArrayScanner, Option<SemaphorePermit<'a>>),
+	Done,
+}
+
+impl HttpProvider {
+	/// Sends a JSON-RPC request whose result is an array and streams the array's elements as
+	/// they are read from the response body. Unlike [`JsonRpcProvider::fetch`], the response is
+	/// never buffered as a whole, so memory use is bounded by the largest element, e.g. when
+	/// traversing an iterator over a large collection.
+	pub fn fetch_array_stream<'a, T, R>(
+		&'a self,
+		method: &'a str,
+		params: T,
+	) -> impl Stream<Item = Result<R, ClientError>> + 'a
+	where
+		T: Serialize + Send + Sync + 'a,
+		R: DeserializeOwned + 'a,
+	{
+		stream::unfold(ArrayStreamState::Pending(params), move |mut state| async move {
+			loop {
+				state = match state {
+					ArrayStreamState::Pending(params) => {
+						let next_id = self.id.fetch_add(1, Ordering::SeqCst);
+						let payload = Request::new(next_id, method, params);
+						let permit = match &self.connection_limit {
+							Some(limit) =>
+								Some(limit.acquire().await.expect("the semaphore is never closed")),
+							None => None,
+						};
+						match self.client.post(self.url.as_ref()).json(&payload).send().await {
+							Ok(response) => ArrayStreamState::Reading(
+								response,
+								ResultArrayScanner::default(),
+								permit,
+							),
+							Err(err) => return Some((Err(err.into()), ArrayStreamState::Done)),
+						}
+					},
+					ArrayStreamState::Reading(mut response, mut scanner, permit) => {
+						if let Some(element) = scanner.next_element() {
+							let item = serde_json::from_slice(&element).map_err(|err| {
+								ClientError::SerdeJson {
+									err,
+									text: String::from_utf8_lossy(&element).to_string(),
+								}
+							});
+							let state = ArrayStreamState::Reading(response, scanner, permit);
+							return Some((item, state));
+						}
+						match response.chunk().await {
+							Ok(Some(chunk)) => {
+								scanner.feed(&chunk);
+								// The body is never held as a whole, so the limit applies to the
+								// bytes held at once
+								if scanner.buffered() > self.max_response_bytes {
+									let err = ClientError::ResponseTooLarge {
+										limit: self.max_response_bytes,
+									};
+									return Some((Err(err), ArrayStreamState::Done));
+								}
+								ArrayStreamState::Reading(response, scanner, permit)
+							},
+							Ok(None) => {
+								let err = match scanner.finish() {
+									Ok(()) => return None,
+									Err(Ok(error)) => match serde_json::from_slice(&error) {
+										Ok(error) => ClientError::JsonRpcError(error),
+										Err(err) => ClientError::SerdeJson {
+											err,
+											text: String::from_utf8_lossy(&error).to_string(),
+										},
+									},
+									Err(Err(message)) => ClientError::SerdeJson {
+										err: serde::de::Error::custom(message),
+										text: String::new(),
+									},
+								};
+								return Some((Err(err), ArrayStreamState::Done));
+							},
+							Err(err) => return Some((Err(err.into()), ArrayStreamState::Done)),
+						}
+					},
+					ArrayStreamState::Done => return None,
+				}
+			}
+		})
+	}
 }
 
 impl Default for HttpProvider {
@@ -128,7 +287,8 @@ impl Default for HttpProvider {
 }
 
 impl HttpProvider {
-	/// Initializes a new HTTP Client
+	/// Initializes a new HTTP Client. Query parameters of the URL, e.g. an API key required by
+	/// the RPC provider, are kept and sent with every request.
 	///
 	/// # Example
 	///
@@ -179,12 +339,123 @@ impl HttpProvider {
 		let mut auth_value = HeaderValue::from_str(&auth.to_string())?;
 		auth_value.set_sensitive(true);
 
-		let mut headers = reqwest::header::HeaderMap::new();
-		headers.insert(reqwest::header::AUTHORIZATION, auth_value);
+		let mut headers = HeaderMap::new();
+		headers.insert(header::AUTHORIZATION, auth_value);
 
-		let client = Client::builder().default_headers(headers).build()?;
+		Self::with_headers(url, headers)
+	}
 
-		Ok(Self::new_with_client(url, client))
+	/// Initializes a new HTTP Client that sends the given headers with every request, e.g. an
+	/// API key required by a gateway or a custom `User-Agent`
+	///
+	/// # Example
+	///
+	/// ```
+	/// use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
+	/// use url::Url;
+	/// use NeoRust::prelude::HttpProvider;
+	///
+	/// let mut headers = HeaderMap::new();
+	/// headers.insert("x-api-key", HeaderValue::from_static("my-key"));
+	/// headers.insert(USER_AGENT, HeaderValue::from_static("my-indexer/1.0"));
+	///
+	/// let url = Url::parse("http://localhost:10332").unwrap();
+	/// let provider = HttpProvider::with_headers(url, headers)?;
+	/// ```
+	pub fn with_headers(url: impl Into<Url>, headers: HeaderMap) -> Result<Self, HttpClientError> {
+		Self::new_with_config(url, HttpClientConfig::default().headers(headers))
+	}
+
+	/// Initializes a new HTTP Client from a [`HttpClientConfig`]
+	///
+	/// # Example
+	///
+	/// ```
+	/// use url::Url;
+	/// use NeoRust::prelude::{HttpClientConfig, HttpProvider};
+	///
+	/// let url = Url::parse("http://localhost:10332").unwrap();
+	/// let config = HttpClientConfig::default().accept_compression(false);
+	/// let provider = HttpProvider::new_with_config(url, config)?;
+	/// ```
+	pub fn new_with_config(
+		url: impl Into<Url>,
+		config: HttpClientConfig,
+	) -> Result<Self, HttpClientError> {
+		let mut provider = Self::new_with_client(url, config.build_client()?);
+		provider.connection_limit = config.max_connections.map(|max| Arc::new(Semaphore::new(max)));
+		provider.max_response_bytes = config.max_response_bytes;
+		Ok(provider)
+	}
+
+	/// Returns a [`HttpClientConfig`] to configure headers, timeouts and connection pooling
+	/// before building the provider with [`HttpClientConfig::build`]
+	///
+	/// # Example
+	///
+	/// ```
+	/// use std::time::Duration;
+	/// use reqwest::header::{HeaderMap, HeaderValue};
+	/// use url::Url;
+	/// use NeoRust::prelude::HttpProvider;
+	///
+	/// let mut headers = HeaderMap::new();
+	/// headers.insert("x-api-key", HeaderValue::from_static("my-key"));
+	///
+	/// let provider = HttpProvider::builder()
+	///     .headers(headers)
+	///     .connect_timeout(Duration::from_secs(2))
+	///     .timeout(Duration::from_secs(30))
+	///     .build(Url::parse("http://localhost:10332").unwrap())?;
+	/// ```
+	pub fn builder() -> HttpClientConfig {
+		HttpClientConfig::default()
+	}
+
+	/// Initializes a new HTTP Client with a connection pool tuned for many requests to the same
+	/// node, e.g. an indexer fetching application logs. At most `max_idle_per_host` idle
+	/// connections are kept open for `idle_timeout`, and at most `max_connections` requests are
+	/// in flight at once. Clones of the provider share the pool and the limit.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use std::time::Duration;
+	/// use url::Url;
+	/// use NeoRust::prelude::HttpProvider;
+	///
+	/// let url = Url::parse("http://localhost:10332").unwrap();
+	/// let provider = HttpProvider::with_pool_config(url, 32, Duration::from_secs(90), 64)?;
+	/// ```
+	pub fn with_pool_config(
+		url: impl Into<Url>,
+		max_idle_per_host: usize,
+		idle_timeout: Duration,
+		max_connections: usize,
+	) -> Result<Self, HttpClientError> {
+		Self::builder()
+			.pool_max_idle_per_host(max_idle_per_host)
+			.pool_idle_timeout(idle_timeout)
+			.max_connections(max_connections)
+			.build(url)
+	}
+
+	/// Initializes a new HTTP Client that sends `Authorization: Bearer <token>` with every request
+	///
+	/// # Example
+	///
+	/// ```
+	/// use url::Url;
+	/// use NeoRust::prelude::HttpProvider;
+	///
+	/// let url = Url::parse("http://localhost:10332").unwrap();
+	/// let provider = HttpProvider::with_bearer_token(url, "my-token")?;
+	/// ```
+	pub fn with_bearer_token(
+		url: impl Into<Url>,
+		token: impl Into<String>,
+	) -> Result<Self, HttpClientError> {
+		Self::new_with_auth(url, Authorization::bearer(token))
 	}
 
 	/// Allows to customize the provider by providing your own http client
@@ -200,13 +471,141 @@ impl HttpProvider {
 	/// let provider = Http::new_with_client(url, client);
 	/// ```
 	pub fn new_with_client(url: impl Into<Url>, client: reqwest::Client) -> Self {
-		Self { id: AtomicU64::new(1), client, url: url.into() }
+		Self {
+			id: AtomicU64::new(1),
+			client,
+			url: url.into(),
+			connection_limit: None,
+			max_response_bytes: HttpClientConfig::DEFAULT_MAX_RESPONSE_BYTES,
+		}
 	}
 }
 
 impl Clone for HttpProvider {
 	fn clone(&self) -> Self {
-		Self { id: AtomicU64::new(1), client: self.client.clone(), url: self.url.clone() }
+		// `reqwest::Client` is reference counted, so the clone reuses the same connection pool
+		Self {
+			id: AtomicU64::new(1),
+			client: self.client.clone(),
+			url: self.url.clone(),
+			connection_limit: self.connection_limit.clone(),
+			max_response_bytes: self.max_response_bytes,
+		}
+	}
+}
+
+/// Settings used to build the `reqwest::Client` behind a [`HttpProvider`]
+#[derive(Debug, Clone)]
+pub struct HttpClientConfig {
+	headers: HeaderMap,
+	accept_compression: bool,
+	timeout: Option<Duration>,
+	connect_timeout: Option<Duration>,
+	pool_max_idle_per_host: Option<usize>,
+	pool_idle_timeout: Option<Duration>,
+	max_connections: Option<usize>,
+	max_response_bytes: usize,
+}
+
+impl Default for HttpClientConfig {
+	fn default() -> Self {
+		Self {
+			headers: HeaderMap::new(),
+			accept_compression: true,
+			timeout: None,
+			connect_timeout: None,
+			pool_max_idle_per_host: None,
+			pool_idle_timeout: None,
+			max_connections: None,
+			max_response_bytes: Self::DEFAULT_MAX_RESPONSE_BYTES,
+		}
+	}
+}
+
+impl HttpClientConfig {
+	/// The default maximum size of a response body: 64 MiB
+	pub const DEFAULT_MAX_RESPONSE_BYTES: usize = 64 * 1024 * 1024;
+
+	/// Headers sent with every request
+	pub fn headers(mut self, headers: HeaderMap) -> Self {
+		self.headers = headers;
+		self
+	}
+
+	/// Whether to send `Accept-Encoding: gzip, br` and transparently decompress responses.
+	/// Enabled by default; has no effect unless the `compression` feature is enabled.
+	pub fn accept_compression(mut self, accept_compression: bool) -> Self {
+		self.accept_compression = accept_compression;
+		self
+	}
+
+	/// Timeout for a whole request, from connecting until the response body has been read
+	pub fn timeout(mut self, timeout: Duration) -> Self {
+		self.timeout = Some(timeout);
+		self
+	}
+
+	/// Timeout for establishing a connection, independent of [`Self::timeout`]
+	pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+		self.connect_timeout = Some(connect_timeout);
+		self
+	}
+
+	/// Maximum number of idle connections kept open per host
+	pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+		self.pool_max_idle_per_host = Some(max);
+		self
+	}
+
+	/// How long an idle connection is kept open before it is closed
+	pub fn pool_idle_timeout(mut self, timeout: Duration) -> Self {
+		self.pool_idle_timeout = Some(timeout);
+		self
+	}
+
+	/// Maximum number of requests in flight at once; further requests wait for a free slot. A
+	/// maximum of 0 would block every request, so it is raised to 1
+	pub fn max_connections(mut self, max: usize) -> Self {
+		self.max_connections = Some(max.max(1));
+		self
+	}
+
+	/// Maximum size of a response body in bytes. Reading a larger response is aborted with
+	/// [`ProviderError::ResponseTooLarge`] before it is buffered completely, protecting the
+	/// client from misbehaving endpoints. Defaults to [`Self::DEFAULT_MAX_RESPONSE_BYTES`].
+	pub fn max_response_bytes(mut self, limit: usize) -> Self {
+		self.max_response_bytes = limit;
+		self
+	}
+
+	/// Builds a [`HttpProvider`] for `url` from this configuration
+	pub fn build(self, url: impl Into<Url>) -> Result<HttpProvider, HttpClientError> {
+		HttpProvider::new_with_config(url, self)
+	}
+
+	/// Builds a `reqwest::Client` from this configuration
+	pub fn build_client(&self) -> Result<Client, HttpClientError> {
+		let mut builder = Client::builder().default_headers(self.headers.clone());
+		#[cfg(feature = "compression")]
+		{
+			builder = builder.gzip(self.accept_compression).brotli(self.accept_compression);
+		}
+		#[cfg(not(target_arch = "wasm32"))]
+		{
+			if let Some(timeout) = self.timeout {
+				builder = builder.timeout(timeout);
+			}
+			if let Some(connect_timeout) = self.connect_timeout {
+				builder = builder.connect_timeout(connect_timeout);
+			}
+			if let Some(max) = self.pool_max_idle_per_host {
+				builder = builder.pool_max_idle_per_host(max);
+			}
+			if let Some(timeout) = self.pool_idle_timeout {
+				builder = builder.pool_idle_timeout(timeout);
+			}
+		}
+		Ok(builder.build()?)
 	}
 }
 
@@ -221,3 +620,402 @@ pub enum HttpClientError {
 	#[error(transparent)]
 	ClientBuild(#[from] reqwest::Error),
 }
+
+#[cfg(test)]
+mod tests {
+	use std::{
+		sync::{
+			atomic::{AtomicUsize, Ordering::SeqCst},
+			Arc,
+		},
+		time::Duration,
+	};
+
+	use futures::StreamExt;
+	use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
+	use serde_json::json;
+	use tokio::{
+		io::{AsyncReadExt, AsyncWriteExt},
+		net::{TcpListener, TcpStream},
+	};
+	use url::Url;
+	use wiremock::{
+		matchers::{header, method, query_param},
+		Mock, MockServer, ResponseTemplate,
+	};
+
+	use neo::prelude::{APITrait, ProviderError, RpcClient, StackItem};
+
+	use super::{HttpClientConfig, HttpProvider};
+
+	async fn mock_block_count(mock_server: &MockServer, name: &str, value: &str) {
+		Mock::given(method("POST"))
+			.and(header(name, value))
+			.respond_with(ResponseTemplate::new(200).set_body_json(json!({
+				"jsonrpc": "2.0",
+				"id": 1,
+				"result": 1234
+			})))
+			.mount(mock_server)
+			.await;
+	}
+
+	#[tokio::test]
+	async fn test_with_headers_sends_custom_headers() {
+		let mock_server = MockServer::start().await;
+		mock_block_count(&mock_server, "x-api-key", "secret").await;
+
+		let mut headers = HeaderMap::new();
+		headers.insert("x-api-key", HeaderValue::from_static("secret"));
+		headers.insert(USER_AGENT, HeaderValue::from_static("neo-indexer/1.0"));
+		let url = Url::parse(&mock_server.uri()).unwrap();
+		let client = RpcClient::new(HttpProvider::with_headers(url, headers).unwrap());
+
+		// The headers must be sent on every request, not only the first one
+		assert_eq!(client.get_block_count().await.unwrap(), 1234);
+		assert_eq!(client.get_block_count().await.unwrap(), 1234);
+
+		let requests = mock_server.received_requests().await.unwrap();
+		assert_eq!(requests.len(), 2);
+		for request in requests {
+			assert_eq!(request.headers.get("user-agent").unwrap(), "neo-indexer/1.0");
+		}
+	}
+
+	#[cfg(feature = "compression")]
+	#[tokio::test]
+	async fn test_accept_compression_decodes_gzip_response() {
+		use std::io::Write;
+
+		use flate2::{write::GzEncoder, Compression};
+
+		let body = json!({ "jsonrpc": "2.0", "id": 1, "result": 1234 }).to_string();
+		let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+		encoder.write_all(body.as_bytes()).unwrap();
+		let compressed = encoder.finish().unwrap();
+
+		let mock_server = MockServer::start().await;
+		Mock::given(method("POST"))
+			.respond_with(
+				ResponseTemplate::new(200)
+					.insert_header("content-encoding", "gzip")
+					.insert_header("content-type", "application/json")
+					.set_body_bytes(compressed),
+			)
+			.mount(&mock_server)
+			.await;
+
+		let url = Url::parse(&mock_server.uri()).unwrap();
+		let provider = HttpProvider::new_with_config(url, HttpClientConfig::default()).unwrap();
+		let client = RpcClient::new(provider);
+
+		assert_eq!(client.get_block_count().await.unwrap(), 1234);
+		let requests = mock_server.received_requests().await.unwrap();
+		assert_eq!(requests[0].headers.get("accept-encoding").unwrap(), "gzip, br");
+	}
+
+	#[tokio::test]
+	async fn test_accept_compression_disabled() {
+		let mock_server = MockServer::start().await;
+		mock_block_count(&mock_server, "content-type", "application/json").await;
+
+		let url = Url::parse(&mock_server.uri()).unwrap();
+		let config = HttpClientConfig::default().accept_compression(false);
+		let client = RpcClient::new(HttpProvider::new_with_config(url, config).unwrap());
+
+		assert_eq!(client.get_block_count().await.unwrap(), 1234);
+		let requests = mock_server.received_requests().await.unwrap();
+		assert!(requests[0].headers.get("accept-encoding").is_none());
+	}
+
+	#[tokio::test]
+	async fn test_fetch_with_headers_overrides_default_headers() {
+		let mock_server = MockServer::start().await;
+		mock_block_count(&mock_server, "x-api-key", "per-call").await;
+
+		let mut headers = HeaderMap::new();
+		headers.insert("x-api-key", HeaderValue::from_static("default"));
+		let url = Url::parse(&mock_server.uri()).unwrap();
+		let provider = HttpProvider::with_headers(url, headers).unwrap();
+
+		let mut overrides = HeaderMap::new();
+		overrides.insert("x-api-key", HeaderValue::from_static("per-call"));
+		let count: u32 = provider.fetch_with_headers("getblockcount", (), overrides).await.unwrap();
+		assert_eq!(count, 1234);
+
+		let requests = mock_server.received_requests().await.unwrap();
+		assert_eq!(requests[0].headers.get_all("x-api-key").iter().count(), 1);
+	}
+
+	#[tokio::test]
+	async fn test_api_key_in_query_string_is_sent() {
+		let mock_server = MockServer::start().await;
+		Mock::given(method("POST"))
+			.and(query_param("apikey", "secret"))
+			.respond_with(ResponseTemplate::new(200).set_body_json(json!({
+				"jsonrpc": "2.0",
+				"id": 1,
+				"result": 1234
+			})))
+			.mount(&mock_server)
+			.await;
+
+		let url = Url::parse(&format!("{}/?apikey=secret", mock_server.uri())).unwrap();
+		let client = RpcClient::new(HttpProvider::new(url).unwrap());
+
+		assert_eq!(client.get_block_count().await.unwrap(), 1234);
+	}
+
+	#[tokio::test]
+	async fn test_with_pool_config_shares_pool_between_clones() {
+		let mock_server = MockServer::start().await;
+		mock_block_count(&mock_server, "content-type", "application/json").await;
+
+		let url = Url::parse(&mock_server.uri()).unwrap();
+		let provider = HttpProvider::with_pool_config(url, 4, Duration::from_secs(30), 2).unwrap();
+		let cloned = provider.clone();
+		assert!(Arc::ptr_eq(
+			provider.connection_limit.as_ref().unwrap(),
+			cloned.connection_limit.as_ref().unwrap()
+		));
+
+		let client = RpcClient::new(cloned);
+		let counts = futures::future::join_all((0..5).map(|_| client.get_block_count())).await;
+		assert!(counts.into_iter().all(|count| count.unwrap() == 1234));
+		assert_eq!(provider.connection_limit.unwrap().available_permits(), 2);
+	}
+
+	/// Reads one HTTP request from `stream`, returning `false` once the client closed it
+	async fn read_request(stream: &mut TcpStream) -> bool {
+		let mut request = Vec::new();
+		let mut buf = [0u8; 4096];
+		loop {
+			if let Some(end) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+				let head = String::from_utf8_lossy(&request[..end]).to_lowercase();
+				let body_len = head
+					.lines()
+					.find_map(|line| line.strip_prefix("content-length:"))
+					.map_or(0, |len| len.trim().parse::<usize>().unwrap());
+				if request.len() >= end + 4 + body_len {
+					return true;
+				}
+			}
+			match stream.read(&mut buf).await {
+				Ok(0) | Err(_) => return false,
+				Ok(n) => request.extend_from_slice(&buf[..n]),
+			}
+		}
+	}
+
+	#[tokio::test]
+	async fn test_max_connections_limits_requests_in_flight() {
+		for (max_connections, expected) in [(2, 2), (0, 1)] {
+			// The server holds every request for a while and records how many it held at once
+			let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+			let url = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+			let in_flight = Arc::new(AtomicUsize::new(0));
+			let most_in_flight = Arc::new(AtomicUsize::new(0));
+			let (current, most) = (in_flight.clone(), most_in_flight.clone());
+			let server = tokio::spawn(async move {
+				loop {
+					let (mut stream, _) = listener.accept().await.unwrap();
+					let (current, most) = (current.clone(), most.clone());
+					tokio::spawn(async move {
+						while read_request(&mut stream).await {
+							most.fetch_max(current.fetch_add(1, SeqCst) + 1, SeqCst);
+							tokio::time::sleep(Duration::from_millis(50)).await;
+							current.fetch_sub(1, SeqCst);
+							let body = r#"{"jsonrpc":"2.0","id":1,"result":1234}"#;
+							let response = format!(
+								"HTTP/1.1 200 OK\r\ncontent-type: application/json\r\n\
+								 content-length: {}\r\n\r\n{body}",
+								body.len()
+							);
+							stream.write_all(response.as_bytes()).await.unwrap();
+						}
+					});
+				}
+			});
+
+			let provider = HttpProvider::builder().max_connections(max_connections).build(url);
+			let client = RpcClient::new(provider.unwrap());
+			let counts = tokio::time::timeout(
+				Duration::from_secs(10),
+				futures::future::join_all((0..6).map(|_| client.get_block_count())),
+			)
+			.await
+			.expect("the requests did not complete");
+			assert!(counts.into_iter().all(|count| count.unwrap() == 1234));
+			assert_eq!(most_in_flight.load(SeqCst), expected);
+			assert_eq!(in_flight.load(SeqCst), 0);
+			server.abort();
+		}
+	}
+
+	#[tokio::test]
+	async fn test_builder_request_timeout() {
+		let mock_server = MockServer::start().await;
+		Mock::given(method("POST"))
+			.respond_with(
+				ResponseTemplate::new(200)
+					.set_body_json(json!({ "jsonrpc": "2.0", "id": 1, "result": 1234 }))
+					.set_delay(Duration::from_millis(500)),
+			)
+			.mount(&mock_server)
+			.await;
+
+		let url = Url::parse(&mock_server.uri()).unwrap();
+		let provider = HttpProvider::builder()
+			.connect_timeout(Duration::from_secs(5))
+			.timeout(Duration::from_millis(50))
+			.build(url)
+			.unwrap();
+
+		assert!(RpcClient::new(provider).get_block_count().await.is_err());
+	}
+
+	#[tokio::test]
+	async fn test_traverse_iterator_stream() {
+		let items: Vec<_> = (0..5_000)
+			.map(|i| json!({ "type": "Integer", "value": i.to_string() }))
+			.collect();
+		let mock_server = MockServer::start().await;
+		Mock::given(method("POST"))
+			.respond_with(ResponseTemplate::new(200).set_body_json(json!({
+				"jsonrpc": "2.0",
+				"id": 1,
+				"result": items
+			})))
+			.mount(&mock_server)
+			.await;
+
+		let url = Url::parse(&mock_server.uri()).unwrap();
+		let client = RpcClient::new(HttpProvider::new(url).unwrap());
+		let values: Vec<i64> = client
+			.traverse_iterator_stream("session", "iterator", 5_000)
+			.map(|item| item.unwrap().as_int().unwrap())
+			.collect()
+			.await;
+
+		assert_eq!(values, (0..5_000).collect::<Vec<i64>>());
+	}
+
+	#[tokio::test]
+	async fn test_traverse_iterator_stream_returns_rpc_error() {
+		let mock_server = MockServer::start().await;
+		Mock::given(method("POST"))
+			.respond_with(ResponseTemplate::new(200).set_body_json(json!({
+				"jsonrpc": "2.0",
+				"id": 1,
+				"error": { "code": -100, "message": "Unknown session" }
+			})))
+			.mount(&mock_server)
+			.await;
+
+		let url = Url::parse(&mock_server.uri()).unwrap();
+		let client = RpcClient::new(HttpProvider::new(url).unwrap());
+		let items: Vec<_> =
+			client.traverse_iterator_stream("session", "iterator", 10).collect().await;
+
+		assert_eq!(items.len(), 1);
+		assert!(matches!(&items[0], Err(ProviderError::JsonRpcError(err)) if err.code == -100));
+	}
+
+	#[tokio::test]
+	async fn test_traverse_iterator_stream_yields_before_the_body_is_complete() {
+		// The server sends the first item and then waits for the client to have read it before
+		// it finishes the body, so the test only passes if items are yielded incrementally
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let url = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+		let (first_read, wait_for_first_read) = tokio::sync::oneshot::channel::<()>();
+		let server = tokio::spawn(async move {
+			let (mut stream, _) = listener.accept().await.unwrap();
+			assert!(read_request(&mut stream).await);
+			let head = "HTTP/1.1 200 OK\r\ntransfer-encoding: chunked\r\n\r\n";
+			stream.write_all(head.as_bytes()).await.unwrap();
+			let chunks = [
+				r#"{"jsonrpc":"2.0","id":1,"result":[{"type":"Integer","value":"1"},"#,
+				r#"{"type":"Integer","value":"2"}]}"#,
+			];
+			let frame = |chunk: &str| format!("{:x}\r\n{chunk}\r\n", chunk.len());
+			stream.write_all(frame(chunks[0]).as_bytes()).await.unwrap();
+			wait_for_first_read.await.unwrap();
+			stream.write_all(frame(chunks[1]).as_bytes()).await.unwrap();
+			stream.write_all(b"0\r\n\r\n").await.unwrap();
+		});
+
+		let client = RpcClient::new(HttpProvider::new(url).unwrap());
+		let mut items = Box::pin(client.traverse_iterator_stream("session", "iterator", 2));
+		let first = tokio::time::timeout(Duration::from_secs(5), items.next())
+			.await
+			.expect("the first item was not yielded before the body was complete");
+		assert_eq!(first.unwrap().unwrap(), StackItem::from(1));
+		first_read.send(()).unwrap();
+
+		let rest: Vec<_> = items.map(Result::unwrap).collect().await;
+		assert_eq!(rest, vec![StackItem::from(2)]);
+		server.await.unwrap();
+	}
+
+	#[tokio::test]
+	async fn test_max_response_bytes() {
+		let mock_server = MockServer::start().await;
+		Mock::given(method("POST"))
+			.respond_with(ResponseTemplate::new(200).set_body_json(json!({
+				"jsonrpc": "2.0",
+				"id": 1,
+				"result": "a".repeat(2048)
+			})))
+			.mount(&mock_server)
+			.await;
+
+		let url = Url::parse(&mock_server.uri()).unwrap();
+		let provider = HttpProvider::builder().max_response_bytes(1024).build(url.clone()).unwrap();
+		let result: Result<String, _> = RpcClient::new(provider).request("getversion", ()).await;
+		assert_eq!(result, Err(ProviderError::ResponseTooLarge { limit: 1024 }));
+
+		let provider = HttpProvider::builder().max_response_bytes(4096).build(url).unwrap();
+		let result: String = RpcClient::new(provider).request("getversion", ()).await.unwrap();
+		assert_eq!(result.len(), 2048);
+	}
+
+	#[tokio::test]
+	async fn test_max_response_bytes_aborts_chunked_body() {
+		// The server sends two chunks that together exceed the limit and then stalls without
+		// finishing the body, so the request only returns if reading is aborted partway through
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let url = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+		let server = tokio::spawn(async move {
+			let (mut stream, _) = listener.accept().await.unwrap();
+			let mut request = [0u8; 4096];
+			assert!(stream.read(&mut request).await.unwrap() > 0);
+			let head = "HTTP/1.1 200 OK\r\ntransfer-encoding: chunked\r\n\r\n";
+			stream.write_all(head.as_bytes()).await.unwrap();
+			let chunk = "a".repeat(600);
+			for _ in 0..2 {
+				let frame = format!("{:x}\r\n{}\r\n", chunk.len(), chunk);
+				stream.write_all(frame.as_bytes()).await.unwrap();
+			}
+			std::future::pending::<()>().await;
+		});
+
+		let provider = HttpProvider::builder().max_response_bytes(1024).build(url).unwrap();
+		let request = RpcClient::new(provider).request::<_, String>("getversion", ());
+		let result = tokio::time::timeout(Duration::from_secs(5), request)
+			.await
+			.expect("reading the body was not aborted");
+		assert_eq!(result, Err(ProviderError::ResponseTooLarge { limit: 1024 }));
+		server.abort();
+	}
+
+	#[tokio::test]
+	async fn test_with_bearer_token_sends_authorization_header() {
+		let mock_server = MockServer::start().await;
+		mock_block_count(&mock_server, "authorization", "Bearer my-token").await;
+
+		let url = Url::parse(&mock_server.uri()).unwrap();
+		let client = RpcClient::new(HttpProvider::with_bearer_token(url, "my-token").unwrap());
+
+		assert_eq!(client.get_block_count().await.unwrap(), 1234);
+	}
+}
@@ -0,0 +1,221 @@
+use std::collections::VecDeque;
+
+/// Incrementally splits the `result` array of a JSON-RPC response into its elements.
+///
+/// Bytes are fed as they arrive from the transport. Every element is available from
+/// [`Self::next_element`] as soon as its last byte has been read, and the bytes before the
+/// element being read are released, so memory use is bounded by the largest element rather
+/// than the whole response. An `error` object in place of the `result` is captured and returned
+/// by [`Self::finish`].
+#[derive(Debug, Default)]
+pub(crate) struct ResultArrayScanner {
+	buf: Vec<u8>,
+	/// Index of the next byte of `buf` to scan
+	pos: usize,
+	depth: usize,
+	in_string: bool,
+	escaped: bool,
+	string_start: usize,
+	/// The last string closed directly inside the response object, i.e. the key of the value
+	/// that follows
+	last_key: Vec<u8>,
+	target: Option<Target>,
+	/// Start of the element (or error object) being read
+	value_start: Option<usize>,
+	elements: VecDeque<Vec<u8>>,
+	error: Option<Vec<u8>>,
+	result_seen: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Target {
+	Result,
+	Error,
+}
+
+impl ResultArrayScanner {
+	/// Appends the next chunk of the response body and scans it.
+	pub(crate) fn feed(&mut self, bytes: &[u8]) {
+		self.buf.extend_from_slice(bytes);
+		while self.pos < self.buf.len() {
+			let i = self.pos;
+			self.pos += 1;
+			self.scan(i);
+		}
+		self.compact();
+	}
+
+	/// Returns the next complete element of the `result` array, if any.
+	pub(crate) fn next_element(&mut self) -> Option<Vec<u8>> {
+		self.elements.pop_front()
+	}
+
+	/// Checks the response once the body has been read completely. Returns the raw `error`
+	/// object if the node returned one, or an error message if no `result` array was found.
+	pub(crate) fn finish(&mut self) -> Result<(), Result<Vec<u8>, &'static str>> {
+		if let Some(error) = self.error.take() {
+			return Err(Ok(error));
+		}
+		if !self.result_seen {
+			return Err(Err("the response has no complete result array"));
+		}
+		Ok(())
+	}
+
+	/// The number of bytes currently held, for callers that want to check memory use.
+	pub(crate) fn buffered(&self) -> usize {
+		self.buf.len()
+	}
+
+	fn scan(&mut self, i: usize) {
+		let byte = self.buf[i];
+		if self.in_string {
+			if self.escaped {
+				self.escaped = false;
+			} else if byte == b'\\' {
+				self.escaped = true;
+			} else if byte == b'"' {
+				self.in_string = false;
+				if self.depth == 1 && self.target.is_none() {
+					self.last_key = self.buf[self.string_start + 1..i].to_vec();
+				}
+			}
+			return;
+		}
+
+		match byte {
+			b'"' => {
+				self.start_element(i);
+				self.in_string = true;
+				self.string_start = i;
+			},
+			b'{' | b'[' => {
+				if self.depth == 1 && self.target.is_none() {
+					if byte == b'[' && self.last_key == b"result" {
+						self.target = Some(Target::Result);
+					} else if byte == b'{' && self.last_key == b"error" {
+						self.target = Some(Target::Error);
+						self.value_start = Some(i);
+					}
+				} else {
+					self.start_element(i);
+				}
+				self.depth += 1;
+			},
+			b'}' | b']' => {
+				self.depth = self.depth.saturating_sub(1);
+				match (self.target, self.depth) {
+					// A nested array or object element is complete
+					(Some(Target::Result), 2) => self.end_element(i + 1),
+					// The result array is complete
+					(Some(Target::Result), 1) => {
+						self.end_element(i);
+						self.target = None;
+						self.result_seen = true;
+					},
+					(Some(Target::Error), 1) => {
+						let start = self.value_start.take().unwrap_or(i);
+						self.error = Some(self.buf[start..=i].to_vec());
+						self.target = None;
+					},
+					_ => {},
+				}
+			},
+			b',' if self.target == Some(Target::Result) && self.depth == 2 => self.end_element(i),
+			byte if !byte.is_ascii_whitespace() => self.start_element(i),
+			_ => {},
+		}
+	}
+
+	/// Marks `i` as the start of an element if it is the first byte of one.
+	fn start_element(&mut self, i: usize) {
+		if self.target == Some(Target::Result) && self.depth == 2 && self.value_start.is_none() {
+			self.value_start = Some(i);
+		}
+	}
+
+	/// Completes the element being read, which ends before `end`.
+	fn end_element(&mut self, end: usize) {
+		if let Some(start) = self.value_start.take() {
+			let len = self.buf[start..end]
+				.iter()
+				.rposition(|byte| !byte.is_ascii_whitespace())
+				.map_or(0, |last| last + 1);
+			self.elements.push_back(self.buf[start..start + len].to_vec());
+		}
+	}
+
+	/// Drops the bytes that have been scanned and are not part of a pending value or key.
+	fn compact(&mut self) {
+		let mut keep_from = self.value_start.unwrap_or(self.pos);
+		if self.in_string {
+			keep_from = keep_from.min(self.string_start);
+		}
+		if keep_from == 0 {
+			return;
+		}
+		self.buf.drain(..keep_from);
+		self.pos -= keep_from;
+		self.value_start = self.value_start.map(|start| start - keep_from);
+		self.string_start = self.string_start.saturating_sub(keep_from);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::ResultArrayScanner;
+
+	fn elements(scanner: &mut ResultArrayScanner) -> Vec<String> {
+		std::iter::from_fn(|| scanner.next_element())
+			.map(|element| String::from_utf8(element).unwrap())
+			.collect()
+	}
+
+	#[test]
+	fn test_splits_result_array() {
+		let body = r#"{"jsonrpc":"2.0","id":1,"result":[{"type":"Integer","value":"1"}, 2 ,"a]\"b",[3,[4]],null]}"#;
+		let mut scanner = ResultArrayScanner::default();
+		scanner.feed(body.as_bytes());
+
+		assert_eq!(
+			elements(&mut scanner),
+			vec![r#"{"type":"Integer","value":"1"}"#, "2", r#""a]\"b""#, "[3,[4]]", "null"]
+		);
+		assert!(scanner.finish().is_ok());
+	}
+
+	#[test]
+	fn test_yields_elements_incrementally_with_bounded_buffer() {
+		let item = r#"{"type":"ByteString","value":"dGVzdA=="}"#;
+		let mut body = r#"{"id":1,"jsonrpc":"2.0","result":["#.to_string();
+		body.push_str(&vec![item; 10_000].join(","));
+		body.push_str("]}");
+
+		let mut scanner = ResultArrayScanner::default();
+		let mut count = 0;
+		for chunk in body.as_bytes().chunks(7) {
+			scanner.feed(chunk);
+			while let Some(element) = scanner.next_element() {
+				assert_eq!(element, item.as_bytes());
+				count += 1;
+			}
+			assert!(scanner.buffered() <= item.len() + 7);
+		}
+
+		assert_eq!(count, 10_000);
+		assert!(scanner.finish().is_ok());
+	}
+
+	#[test]
+	fn test_captures_error() {
+		let mut scanner = ResultArrayScanner::default();
+		scanner
+			.feed(br#"{"jsonrpc":"2.0","id":1,"error":{"code":-100,"message":"Unknown session"}}"#);
+
+		assert!(scanner.next_element().is_none());
+		assert_eq!(
+			scanner.finish(),
+			Err(Ok(br#"{"code":-100,"message":"Unknown session"}"#.to_vec()))
+		);
+	}
+}
@@ -1,5 +1,5 @@
 pub use common::*;
-pub use http_provider::{ClientError, HttpProvider};
+pub use http_provider::{ClientError, HttpClientConfig, HttpProvider};
 #[cfg(all(feature = "ipc", any(unix, windows)))]
 pub use ipc::{Ipc, IpcError};
 #[cfg(feature = "legacy-ws")]
@@ -8,7 +8,7 @@ pub use legacy_ws::{ClientError as WsClientError, Ws};
 pub use retry::*;
 pub use rw::{RwClient, RwClientError};
 #[cfg(all(feature = "ws", not(feature = "legacy-ws")))]
-pub use ws::{ConnectionDetails, WsClient as Ws, WsClientError};
+pub use ws::{ConnectionDetails, WsClient, WsClient as Ws, WsClientError, WsError};
 
 pub use self::http_provider::{ClientError as HttpClientError, HttpProvider as Http};
 
@@ -19,6 +19,7 @@ mod ipc;
 // pub use quorum::{JsonRpcClientWrapper, Quorum, QuorumError, QuorumProvider, WeightedProvider};
 
 mod common;
+mod json_stream;
 /// archival websocket
 #[cfg(feature = "legacy-ws")]
 pub mod legacy_ws;
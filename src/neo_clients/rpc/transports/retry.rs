@@ -1,5 +1,5 @@
 //! A [JsonRpcProvider] implementation that retries requests filtered by [RetryPolicy]
-//! with an exponential backoff.
+//! with an exponential backoff, using [with_backoff_by].
 
 use std::{
 	fmt::Debug,
@@ -13,7 +13,7 @@ use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use thiserror::Error;
 use tracing::trace;
 
-use neo::prelude::{JsonRpcProvider, ProviderError};
+use neo::prelude::{with_backoff_by, BackoffPolicy, JsonRpcProvider, ProviderError};
 
 use super::{common::JsonRpcError, http_provider::ClientError};
 
@@ -64,8 +64,8 @@ where
 	timeout_retries: u32,
 	/// How many retries for rate limited responses
 	rate_limit_retries: u32,
-	/// How long to wait initially
-	initial_backoff: Duration,
+	/// How long to wait before each retry of a rate limited request
+	backoff: BackoffPolicy,
 	/// available CPU per second
 	compute_units_per_second: u64,
 }
@@ -125,8 +125,8 @@ pub struct RetryClientBuilder {
 	timeout_retries: u32,
 	/// How many retries for rate limited responses
 	rate_limit_retries: u32,
-	/// How long to wait initially
-	initial_backoff: Duration,
+	/// How long to wait before each retry of a rate limited request
+	backoff: BackoffPolicy,
 	/// available CPU per second
 	compute_units_per_second: u64,
 }
@@ -158,7 +158,16 @@ impl RetryClientBuilder {
 
 	/// Sets the duration to wait initially before retrying
 	pub fn initial_backoff(mut self, initial_backoff: Duration) -> Self {
-		self.initial_backoff = initial_backoff;
+		self.backoff = self.backoff.initial_backoff(initial_backoff);
+		self
+	}
+
+	/// Retries rate limited requests as the policy says: at most [`BackoffPolicy::retries`]
+	/// times, waiting the policy's backoff unless the node asks for a specific wait. This
+	/// replaces the `rate_limit_retries` and `initial_backoff` set so far.
+	pub fn backoff_policy(mut self, policy: BackoffPolicy) -> Self {
+		self.rate_limit_retries = policy.retries();
+		self.backoff = policy;
 		self
 	}
 
@@ -171,7 +180,7 @@ impl RetryClientBuilder {
 		let RetryClientBuilder {
 			timeout_retries,
 			rate_limit_retries,
-			initial_backoff,
+			backoff,
 			compute_units_per_second,
 		} = self;
 		RetryClient {
@@ -180,7 +189,7 @@ impl RetryClientBuilder {
 			policy,
 			timeout_retries,
 			rate_limit_retries,
-			initial_backoff,
+			backoff,
 			compute_units_per_second,
 		}
 	}
@@ -193,7 +202,12 @@ impl Default for RetryClientBuilder {
 			timeout_retries: 3,
 			// this should be enough to even out heavy loads
 			rate_limit_retries: 10,
-			initial_backoff: Duration::from_millis(1000),
+			// a constant wait between retries
+			backoff: BackoffPolicy::default()
+				.initial_backoff(Duration::from_millis(1000))
+				.max_backoff(Duration::MAX)
+				.multiplier(1)
+				.jitter(false),
 			// alchemy max cpus <https://github.com/alchemyplatform/alchemy-docs/blob/master/documentation/compute-units.md#rate-limits-cups>
 			compute_units_per_second: 330,
 		}
@@ -262,45 +276,33 @@ where
 			RetryParams::Value(params)
 		};
 
+		// The inner error, classified once so that the retry decision does not need to convert it
+		enum Failure<E> {
+			RateLimited(E),
+			Other(ProviderError),
+		}
+
 		let ahead_in_queue = self.requests_enqueued.fetch_add(1, Ordering::SeqCst) as u64;
 
 		let mut rate_limit_retry_number: u32 = 0;
 		let mut timeout_retries: u32 = 0;
 
-		loop {
-			let err;
-
-			// hack to not hold `R` across an await in the sleep future and prevent requiring
-			// R: Send + Sync
-			{
-				let resp = match params {
-					RetryParams::Value(ref params) => self.inner.fetch(method, params).await,
-					RetryParams::Zst(unit) => self.inner.fetch(method, unit).await,
-				};
-				match resp {
-					Ok(ret) => {
-						self.requests_enqueued.fetch_sub(1, Ordering::SeqCst);
-						return Ok(ret);
-					},
-					Err(err_) => err = err_,
-				}
-			}
-
-			let should_retry = self.policy.should_retry(&err);
-			if should_retry {
-				rate_limit_retry_number += 1;
-				if rate_limit_retry_number > self.rate_limit_retries {
+		let next_backoff = |failure: &Failure<T::Error>, _: u32| match failure {
+			Failure::RateLimited(err) => {
+				if rate_limit_retry_number >= self.rate_limit_retries {
 					trace!("request timed out after {} retries", self.rate_limit_retries);
-					return Err(RetryClientError::TimeoutError);
+					return None;
 				}
+				rate_limit_retry_number += 1;
 
 				let current_queued_requests = self.requests_enqueued.load(Ordering::SeqCst) as u64;
 
 				// try to extract the requested backoff from the error or compute the next backoff
 				// based on retry count
-				let mut next_backoff = self.policy.backoff_hint(&err).unwrap_or_else(|| {
-					Duration::from_millis(self.initial_backoff.as_millis() as u64)
-				});
+				let mut next_backoff = self
+					.policy
+					.backoff_hint(err)
+					.unwrap_or_else(|| self.backoff.backoff(rate_limit_retry_number - 1));
 
 				// requests are usually weighted and can vary from 10 CU to several 100 CU, cheaper
 				// requests are more common some example alchemy weights:
@@ -318,27 +320,40 @@ where
 					ahead_in_queue,
 				);
 				next_backoff += Duration::from_secs(seconds_to_wait_for_compute_budget);
-
-				trace!("retrying and backing off for {:?}", next_backoff);
-
-				#[cfg(target_arch = "wasm32")]
-				futures_timer::Delay::new(next_backoff).await;
-
-				#[cfg(not(target_arch = "wasm32"))]
-				tokio::time::sleep(next_backoff).await;
-			} else {
-				let err: ProviderError = err.into();
-				if timeout_retries < self.timeout_retries && maybe_connectivity(&err) {
+				Some(next_backoff)
+			},
+			Failure::Other(err) => {
+				if timeout_retries < self.timeout_retries && maybe_connectivity(err) {
 					timeout_retries += 1;
 					trace!(err = ?err, "retrying due to spurious network");
-					continue;
+					return Some(Duration::ZERO);
 				}
 
 				trace!(err = ?err, "should not retry");
-				self.requests_enqueued.fetch_sub(1, Ordering::SeqCst);
-				return Err(RetryClientError::ProviderError(err));
-			}
-		}
+				None
+			},
+		};
+
+		let result = with_backoff_by(next_backoff, || async {
+			let resp = match params {
+				RetryParams::Value(ref params) => self.inner.fetch(method, params).await,
+				RetryParams::Zst(unit) => self.inner.fetch(method, unit).await,
+			};
+			resp.map_err(|err| {
+				if self.policy.should_retry(&err) {
+					Failure::RateLimited(err)
+				} else {
+					Failure::Other(err.into())
+				}
+			})
+		})
+		.await;
+
+		self.requests_enqueued.fetch_sub(1, Ordering::SeqCst);
+		result.map_err(|failure| match failure {
+			Failure::RateLimited(_) => RetryClientError::TimeoutError,
+			Failure::Other(err) => RetryClientError::ProviderError(err),
+		})
 	}
 }
 
@@ -394,6 +409,7 @@ impl RetryPolicy<ClientError> for HttpRateLimitRetryPolicy {
 				}
 				false
 			},
+			ClientError::ResponseTooLarge { .. } => false,
 		}
 	}
 
@@ -447,6 +463,10 @@ fn compute_unit_offset_in_secs(
 /// Checks whether the `error` is the result of a connectivity issue, like
 /// `request::Error::TimedOut`
 fn maybe_connectivity(err: &ProviderError) -> bool {
+	if let ProviderError::Timeout(_) | ProviderError::ConnectionFailed(_) = err {
+		return true;
+	}
+
 	if let ProviderError::HTTPError(reqwest_err) = err {
 		if reqwest_err.is_timeout() {
 			return true;
@@ -470,6 +490,12 @@ fn maybe_connectivity(err: &ProviderError) -> bool {
 
 #[cfg(test)]
 mod tests {
+	use serde_json::json;
+	use url::Url;
+	use wiremock::{matchers::method, Mock, MockServer, ResponseTemplate};
+
+	use neo::prelude::HttpProvider;
+
 	use super::*;
 
 	// assumed average cost of a request
@@ -565,4 +591,30 @@ mod tests {
 		let should_retry = HttpRateLimitRetryPolicy.should_retry(&err);
 		assert!(should_retry);
 	}
+
+	#[tokio::test]
+	async fn test_backoff_policy_is_applied_when_built() {
+		let mock_server = MockServer::start().await;
+		Mock::given(method("POST"))
+			.respond_with(ResponseTemplate::new(200).set_body_json(json!({
+				"jsonrpc": "2.0",
+				"id": 1,
+				"error": { "code": 429, "message": "Too many requests" }
+			})))
+			.mount(&mock_server)
+			.await;
+
+		let http = HttpProvider::new(Url::parse(&mock_server.uri()).unwrap()).unwrap();
+		let policy = BackoffPolicy::default()
+			.max_attempts(3)
+			.initial_backoff(Duration::from_millis(1));
+		let client = RetryClientBuilder::default()
+			.rate_limit_retries(10)
+			.backoff_policy(policy)
+			.build(http, Box::new(HttpRateLimitRetryPolicy));
+
+		let result: Result<u32, _> = client.fetch("getblockcount", ()).await;
+		assert!(matches!(result, Err(RetryClientError::TimeoutError)));
+		assert_eq!(mock_server.received_requests().await.unwrap().len(), 3);
+	}
 }
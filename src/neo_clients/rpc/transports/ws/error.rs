@@ -1,11 +1,6 @@
-use std::{
-	error::Error,
-	fmt::{Display, Formatter},
-};
-
 use primitive_types::U256;
 
-use crate::{JsonRpcError, ProviderError};
+use neo::prelude::{JsonRpcError, ProviderError};
 
 use super::WsError;
 
@@ -15,7 +10,7 @@ pub enum WsClientError {
 	#[error(transparent)]
 	JsonError(#[from] serde_json::Error),
 
-	/// Thrown if the response could not be parsed
+	/// Thrown if the node answered with an error
 	#[error(transparent)]
 	JsonRpcError(#[from] JsonRpcError),
 
@@ -23,46 +18,25 @@ pub enum WsClientError {
 	#[error(transparent)]
 	InternalError(#[from] WsError),
 
-	/// Remote server sent a Close message
+	/// The connection was closed, by the node or with `WsClient::close`
 	#[error("Websocket closed unexpectedly")]
 	UnexpectedClose,
 
-	/// Unexpected channel closure
-	#[error("Unexpected internal channel closure. This is likely a bug. Please report via github")]
-	DeadChannel,
-
-	/// Thrown if the websocket responds with binary data
-	#[error("Websocket responded with unexpected binary data")]
-	UnexpectedBinary(Vec<u8>),
-
 	/// PubSubClient asked to listen to an unknown subscription id
 	#[error("Attempted to listen to unknown subscription: {0:?}")]
 	UnknownSubscription(U256),
 
-	/// Too Many Reconnects
+	/// The connection was lost and could not be re-established
 	#[error("Reconnect limit reached")]
 	TooManyReconnects,
 }
 
-impl crate::RpcError for WsClientError {
-	fn as_error_response(&self) -> Option<&JsonRpcError> {
-		if let WsClientError::JsonRpcError(err) = self {
-			Some(err)
-		} else {
-			None
-		}
-	}
-
-	fn as_serde_error(&self) -> Option<&serde_json::Error> {
-		match self {
-			WsClientError::JsonError(err) => Some(err),
-			_ => None,
-		}
-	}
-}
-
 impl From<WsClientError> for ProviderError {
 	fn from(src: WsClientError) -> Self {
-		ProviderError::JsonRpcClientError(Box::new(src))
+		match src {
+			WsClientError::JsonError(err) => ProviderError::SerdeJson(err),
+			WsClientError::JsonRpcError(err) => ProviderError::JsonRpcError(err),
+			err => ProviderError::CustomError(err.to_string()),
+		}
 	}
 }
@@ -1,504 +1,401 @@
 use std::{
-	collections::{BTreeMap, HashMap},
-	sync::{
-		atomic::{AtomicU64, Ordering},
-		Arc, Mutex,
-	},
+	collections::HashMap,
+	sync::{Arc, Mutex},
 };
 
 use futures_channel::{mpsc, oneshot};
-use futures_util::{select_biased, StreamExt};
+use futures_util::{future::BoxFuture, SinkExt, StreamExt};
 use primitive_types::U256;
-use serde_json::value::{to_raw_value, RawValue};
+use serde_json::{
+	value::{to_raw_value, RawValue},
+	Value,
+};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_tungstenite::WebSocketStream;
+use tracing::{debug, warn};
 
-use crate::JsonRpcError;
+use neo::prelude::{BackoffPolicy, JsonRpcError, Request, SubscriptionEvent};
 
-#[cfg(not(target_arch = "wasm32"))]
-use super::WebSocketConfig;
-use super::{
-	backend::{BackendDriver, WsBackend},
-	ActiveSub, ConnectionDetails, InFlight, Instruction, Notification, PubSubItem, Response, SubId,
-	WsClient, WsClientError,
-};
+use super::{ActiveSub, Incoming, Instruction, Message, Response, WsClientError, WsError};
 
-pub type SharedChannelMap = Arc<Mutex<HashMap<U256, mpsc::UnboundedReceiver<Box<RawValue>>>>>;
+pub(super) type SharedChannelMap =
+	Arc<Mutex<HashMap<U256, mpsc::UnboundedReceiver<SubscriptionEvent<Box<RawValue>>>>>>;
 
-pub const DEFAULT_RECONNECTS: usize = 5;
+/// Opens a new connection to the node.
+pub(super) type Connector<S> =
+	Box<dyn Fn() -> BoxFuture<'static, Result<WebSocketStream<S>, WsError>> + Send + Sync>;
 
-/// This struct manages the relationship between the u64 request ID, and U256
-/// server-side subscription ID. It does this by aliasing the server ID to the
-/// request ID, and returning the Request ID to the caller (hiding the server
-/// ID in the SubscriptionManager internals.) Giving the caller a "fake"
-/// subscription id allows the subscription to behave consistently across
-/// reconnections
-pub struct SubscriptionManager {
-	// Active subs indexed by request id
-	subs: BTreeMap<u64, ActiveSub>,
-	// Maps active server-side IDs to local subscription IDs
-	aliases: HashMap<U256, u64>,
-	// Used to share notification channels with the WsClient(s)
-	channel_map: SharedChannelMap,
+/// How to re-establish a lost connection.
+pub(super) struct Reconnect {
+	/// How often to try to connect again, in total, before giving up
+	pub max_retries: usize,
+	pub backoff: BackoffPolicy,
 }
 
-impl SubscriptionManager {
-	fn new(channel_map: SharedChannelMap) -> Self {
-		Self { subs: Default::default(), aliases: Default::default(), channel_map }
-	}
+/// A request waiting for the node's response.
+enum InFlight {
+	Request {
+		method: String,
+		params: Box<RawValue>,
+		sender: oneshot::Sender<Response>,
+	},
+	Subscribe {
+		event: String,
+		params: Box<RawValue>,
+		sender: oneshot::Sender<Response>,
+	},
+	/// Nobody waits for the response of an unsubscription
+	Unsubscribe,
+	/// The re-issued `subscribe` request of the active subscription with the local id `id`
+	Resubscribe {
+		id: U256,
+	},
+}
+
+/// Why the connection loop stopped.
+enum Disconnect {
+	/// The node closed the connection or it failed
+	Lost,
+	/// The client closed the connection, or all clients were dropped
+	Closed,
+}
+
+/// Owns the connection to the node. Runs as a task that sends the requests of the `WsClient`s,
+/// routes responses back to them and notifications to their subscriptions.
+pub(super) struct RequestManager<S> {
+	socket: WebSocketStream<S>,
+	connector: Connector<S>,
+	reconnect: Option<Reconnect>,
+	next_id: u64,
+	in_flight: HashMap<u64, InFlight>,
+	/// The local id of the next confirmed subscription
+	next_sub_id: u64,
+	/// Confirmed subscriptions, by the local id returned to the client in place of the node's.
+	/// The node assigns a new id to a subscription that is re-issued after a reconnection, and
+	/// may hand out an id another subscription had on the lost connection.
+	subs: HashMap<U256, ActiveSub>,
+	instructions: mpsc::UnboundedReceiver<Instruction>,
+	channel_map: SharedChannelMap,
+}
 
-	fn count(&self) -> usize {
-		self.subs.len()
+impl<S> RequestManager<S>
+where
+	S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+	pub(super) async fn connect(
+		connector: Connector<S>,
+		reconnect: Option<Reconnect>,
+		instructions: mpsc::UnboundedReceiver<Instruction>,
+		channel_map: SharedChannelMap,
+	) -> Result<Self, WsClientError> {
+		let socket = connector().await?;
+		Ok(Self {
+			socket,
+			connector,
+			reconnect,
+			next_id: 1,
+			in_flight: HashMap::new(),
+			next_sub_id: 1,
+			subs: HashMap::new(),
+			instructions,
+			channel_map,
+		})
 	}
 
-	fn add_alias(&mut self, sub: U256, id: u64) {
-		if let Some(entry) = self.subs.get_mut(&id) {
-			entry.current_server_id = Some(sub);
-		}
-		self.aliases.insert(sub, id);
+	pub(super) fn spawn(self) {
+		tokio::spawn(self.run());
 	}
 
-	fn remove_alias(&mut self, server_id: U256) {
-		if let Some(id) = self.aliases.get(&server_id) {
-			if let Some(sub) = self.subs.get_mut(id) {
-				sub.current_server_id = None;
+	async fn run(mut self) {
+		while let Disconnect::Lost = self.run_connection().await {
+			debug!("websocket connection lost");
+			if !self.reconnect().await {
+				break;
 			}
 		}
-		self.aliases.remove(&server_id);
+		// Pending requests and subscription streams end when their channels are dropped
+		self.channel_map.lock().unwrap().clear();
 	}
 
-	#[tracing::instrument(skip(self))]
-	fn end_subscription(&mut self, id: u64) -> Option<Box<RawValue>> {
-		if let Some(sub) = self.subs.remove(&id) {
-			if let Some(server_id) = sub.current_server_id {
-				tracing::debug!(server_id = format!("0x{server_id:x}"), "Ending subscription");
-				self.remove_alias(server_id);
-				// drop the receiver as we don't need the result
-				let (channel, _) = oneshot::channel();
-				// Serialization errors are ignored, and result in the request
-				// not being dispatched. This is fine, as worst case it will
-				// result in the server sending us notifications we ignore
-				let unsub_request = InFlight {
-					method: "neo_unsubscribe".to_string(),
-					params: SubId(server_id).serialize_raw().ok()?,
-					channel,
-				};
-				// reuse the RPC ID. this is somewhat dirty.
-				return unsub_request.serialize_raw(id).ok();
+	/// Handles instructions and messages until the connection is closed or lost.
+	async fn run_connection(&mut self) -> Disconnect {
+		loop {
+			tokio::select! {
+				instruction = self.instructions.next() => match instruction {
+					Some(Instruction::Close { sender }) => {
+						self.shutdown().await;
+						let _ = sender.send(());
+						return Disconnect::Closed;
+					},
+					Some(instruction) => {
+						if self.handle_instruction(instruction).await.is_err() {
+							return Disconnect::Lost;
+						}
+					},
+					// Every client was dropped
+					None => {
+						self.shutdown().await;
+						return Disconnect::Closed;
+					},
+				},
+				message = self.socket.next() => match message {
+					Some(Ok(Message::Text(text))) => self.handle_message(&text),
+					Some(Ok(Message::Binary(bytes))) => match String::from_utf8(bytes) {
+						Ok(text) => self.handle_message(&text),
+						Err(_) => warn!("ignoring a binary websocket message"),
+					},
+					Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return Disconnect::Lost,
+					// Pings are answered by tungstenite
+					Some(Ok(_)) => {},
+				},
 			}
-			tracing::trace!("No current server id");
 		}
-		tracing::trace!("Cannot end unknown subscription");
-		None
 	}
 
-	#[tracing::instrument(skip_all, fields(server_id = ? notification.subscription))]
-	fn handle_notification(&mut self, notification: Notification) {
-		let server_id = notification.subscription;
-
-		// If no alias, just return
-		let id_opt = self.aliases.get(&server_id).copied();
-		if id_opt.is_none() {
-			tracing::debug!(
-				server_id = format!("0x{server_id:x}"),
-				"No aliased subscription found"
-			);
-			return;
-		}
-		let id = id_opt.unwrap();
-
-		// alias exists, or should be dropped from alias table
-		let sub_opt = self.subs.get(&id);
-		if sub_opt.is_none() {
-			tracing::trace!(id, "Aliased subscription found, but not active");
-			self.aliases.remove(&server_id);
-		}
-		let active = sub_opt.unwrap();
-
-		tracing::debug!(id, "Forwarding notification to listener");
-		// send the notification over the channel
-		let send_res = active.channel.unbounded_send(notification.result);
-
-		// receiver has dropped, so we drop the sub
-		if send_res.is_err() {
-			tracing::debug!(id, "Listener dropped. Dropping alias and subs");
-			// TODO: end subcription here?
-			self.aliases.remove(&server_id);
-			self.subs.remove(&id);
+	/// Connects to the node again, waiting between the attempts as the backoff policy says, and
+	/// re-sends the pending requests and subscriptions. Returns whether it succeeded.
+	async fn reconnect(&mut self) -> bool {
+		let Some(Reconnect { max_retries, backoff }) = &self.reconnect else {
+			return false;
+		};
+		let (max_retries, backoff) = (*max_retries, backoff.clone());
+		for retry in 0..max_retries {
+			tokio::time::sleep(backoff.backoff(retry as u32)).await;
+			match (self.connector)().await {
+				Ok(socket) => self.socket = socket,
+				Err(err) => {
+					debug!("websocket reconnection attempt {} failed: {}", retry + 1, err);
+					continue;
+				},
+			}
+			let failures = self.resend().await;
+			if failures.is_empty() {
+				return true;
+			}
+			for err in failures {
+				debug!("failed to re-send a request after reconnecting: {}", err);
+			}
 		}
+		warn!("{}", WsClientError::TooManyReconnects);
+		false
 	}
 
-	fn req_success(&mut self, id: u64, result: Box<RawValue>) -> Box<RawValue> {
-		if let Ok(server_id) = serde_json::from_str::<SubId>(result.get()) {
-			tracing::debug!(id, server_id = %server_id.0, "Registering new sub alias");
-			self.add_alias(server_id.0, id);
-			let result = U256::from(id);
-			to_raw_value(&format!("0x{result:x}")).expect("valid json")
-		} else {
-			result
+	/// Re-sends the requests the node did not answer before the connection was lost, and
+	/// re-issues the active subscriptions. Returns the errors of the requests that could not be
+	/// sent, which stay in flight to be re-sent with the next connection.
+	async fn resend(&mut self) -> Vec<WsClientError> {
+		let mut failures = Vec::new();
+		let mut in_flight: Vec<_> = self.in_flight.drain().collect();
+		in_flight.sort_by_key(|(id, _)| *id);
+		for (_, request) in in_flight {
+			let sent = match request {
+				InFlight::Request { method, params, sender } => {
+					let request = InFlight::Request { method: method.clone(), params, sender };
+					self.send(&method, request).await
+				},
+				request @ InFlight::Subscribe { .. } => self.send("subscribe", request).await,
+				// Subscriptions are re-issued below, and the lost ones were cancelled with the
+				// connection
+				InFlight::Unsubscribe | InFlight::Resubscribe { .. } => Ok(()),
+			};
+			failures.extend(sent.err());
 		}
-	}
-
-	fn has(&self, id: u64) -> bool {
-		self.subs.contains_key(&id)
-	}
-
-	fn to_reissue(&self) -> impl Iterator<Item = (&u64, &ActiveSub)> {
-		self.subs.iter()
-	}
-
-	fn service_subscription_request(
-		&mut self,
-		id: u64,
-		params: Box<RawValue>,
-	) -> Result<Box<RawValue>, WsClientError> {
-		let (tx, rx) = mpsc::unbounded();
-
-		let active_sub = ActiveSub { params, channel: tx, current_server_id: None };
-		let req = active_sub.serialize_raw(id)?;
-
-		// Explicit scope for the lock
-		// This insertion should be made BEFORE the request returns.
-		// So we make it before the request is even dispatched :)
-		{
-			self.channel_map.lock().unwrap().insert(id.into(), rx);
+		let subs: Vec<_> = self.subs.iter().map(|(id, sub)| (*id, sub.params.clone())).collect();
+		for (id, params) in subs {
+			let sent =
+				self.send_with_params("subscribe", &params, InFlight::Resubscribe { id }).await;
+			failures.extend(sent.err());
 		}
-		self.subs.insert(id, active_sub);
-
-		Ok(req)
-	}
-}
-
-/// The `RequestManager` holds copies of all pending requests (as `InFlight`),
-/// and active subscriptions (as `ActiveSub`). When reconnection occurs, all
-/// pending requests are re-dispatched to the new backend, and all active subs
-/// are re-subscribed
-///
-///  `RequestManager` holds a `BackendDriver`, to communicate with the current
-/// backend. Reconnection is accomplished by instantiating a new `WsBackend` and
-/// swapping out the manager's `BackendDriver`.
-///
-/// In order to provide continuity of subscription IDs to the client, the
-/// `RequestManager` also keeps a `SubscriptionManager`. See the
-/// `SubscriptionManager` docstring for more complete details
-///
-/// The behavior is accessed by the WsClient frontend, which implements ]
-/// `JsonRpcClient`. The `WsClient` is cloneable, so no need for an arc :). It
-/// communicates to the request manager via a channel, and receives
-/// notifications in a shared map for the client to retrieve
-///
-/// The `RequestManager` shuts down and drops when all `WsClient` instances have
-/// been dropped (because all instruction channel `UnboundedSender` instances
-/// will have dropped).
-pub struct RequestManager {
-	// Next JSON-RPC Request ID
-	id: AtomicU64,
-	// How many times we should reconnect the backend before erroring
-	reconnects: usize,
-	// Subscription manager
-	subs: SubscriptionManager,
-	// Requests for which a response has not been receivedc
-	reqs: BTreeMap<u64, InFlight>,
-	// Control of the active WS backend
-	backend: BackendDriver,
-	// The URL and optional auth info for the connection
-	conn: ConnectionDetails,
-	#[cfg(not(target_arch = "wasm32"))]
-	// An Option wrapping a tungstenite WebsocketConfig. If None, the default config is used.
-	config: Option<WebSocketConfig>,
-	// Instructions from the user-facing providers
-	instructions: mpsc::UnboundedReceiver<Instruction>,
-}
-
-impl RequestManager {
-	fn next_id(&mut self) -> u64 {
-		self.id.fetch_add(1, Ordering::Relaxed)
-	}
-
-	pub async fn connect(conn: ConnectionDetails) -> Result<(Self, WsClient), WsClientError> {
-		Self::connect_with_reconnects(conn, DEFAULT_RECONNECTS).await
-	}
-
-	async fn connect_internal(
-		conn: ConnectionDetails,
-	) -> Result<
-		(
-			BackendDriver,
-			(mpsc::UnboundedSender<Instruction>, mpsc::UnboundedReceiver<Instruction>),
-			SharedChannelMap,
-		),
-		WsClientError,
-	> {
-		let (ws, backend) = WsBackend::connect(conn).await?;
-
-		ws.spawn();
-
-		Ok((backend, mpsc::unbounded(), Default::default()))
+		failures
 	}
 
-	#[cfg(target_arch = "wasm32")]
-	pub async fn connect_with_reconnects(
-		conn: ConnectionDetails,
-		reconnects: usize,
-	) -> Result<(Self, WsClient), WsClientError> {
-		let (backend, (instructions_tx, instructions_rx), channel_map) =
-			Self::connect_internal(conn.clone()).await?;
-
-		Ok((
-			Self {
-				id: Default::default(),
-				reconnects,
-				subs: SubscriptionManager::new(channel_map.clone()),
-				reqs: Default::default(),
-				backend,
-				conn,
-				instructions: instructions_rx,
-			},
-			WsClient { instructions: instructions_tx, channel_map },
-		))
-	}
-
-	#[cfg(not(target_arch = "wasm32"))]
-	pub async fn connect_with_reconnects(
-		conn: ConnectionDetails,
-		reconnects: usize,
-	) -> Result<(Self, WsClient), WsClientError> {
-		let (backend, (instructions_tx, instructions_rx), channel_map) =
-			Self::connect_internal(conn.clone()).await?;
-
-		Ok((
-			Self {
-				id: Default::default(),
-				reconnects,
-				subs: SubscriptionManager::new(channel_map.clone()),
-				reqs: Default::default(),
-				backend,
-				conn,
-				config: None,
-				instructions: instructions_rx,
+	async fn handle_instruction(&mut self, instruction: Instruction) -> Result<(), WsClientError> {
+		match instruction {
+			Instruction::Request { method, params, sender } =>
+				if method == "subscribe" {
+					let event = event_of(&params);
+					self.send(&method, InFlight::Subscribe { event, params, sender }).await
+				} else {
+					let request = InFlight::Request { method: method.clone(), params, sender };
+					self.send(&method, request).await
+				},
+			Instruction::Unsubscribe { id } => {
+				self.channel_map.lock().unwrap().remove(&id);
+				match self.subs.remove(&id) {
+					Some(sub) => self.send_unsubscribe(&sub).await,
+					None => Ok(()),
+				}
 			},
-			WsClient { instructions: instructions_tx, channel_map },
-		))
-	}
-
-	#[cfg(not(target_arch = "wasm32"))]
-	pub async fn connect_with_config(
-		conn: ConnectionDetails,
-		config: WebSocketConfig,
-	) -> Result<(Self, WsClient), WsClientError> {
-		Self::connect_with_config_and_reconnects(conn, config, DEFAULT_RECONNECTS).await
+			Instruction::Close { .. } => unreachable!("handled by the connection loop"),
+		}
 	}
 
-	#[cfg(not(target_arch = "wasm32"))]
-	pub async fn connect_with_config_and_reconnects(
-		conn: ConnectionDetails,
-		config: WebSocketConfig,
-		reconnects: usize,
-	) -> Result<(Self, WsClient), WsClientError> {
-		let (backend, (instructions_tx, instructions_rx), channel_map) =
-			Self::connect_internal(conn.clone()).await?;
-
-		Ok((
-			Self {
-				id: Default::default(),
-				reconnects,
-				subs: SubscriptionManager::new(channel_map.clone()),
-				reqs: Default::default(),
-				backend,
-				conn,
-				config: Some(config),
-				instructions: instructions_rx,
+	/// Sends a request that keeps its parameters while in flight.
+	async fn send(&mut self, method: &str, in_flight: InFlight) -> Result<(), WsClientError> {
+		let params = match &in_flight {
+			InFlight::Request { params, .. } | InFlight::Subscribe { params, .. } => params.clone(),
+			InFlight::Unsubscribe | InFlight::Resubscribe { .. } => {
+				unreachable!("sent with their parameters")
 			},
-			WsClient { instructions: instructions_tx, channel_map },
-		))
-	}
-
-	#[cfg(target_arch = "wasm32")]
-	async fn reconnect_backend(&mut self) -> Result<(WsBackend, BackendDriver), WsClientError> {
-		WsBackend::connect(self.conn.clone()).await
+		};
+		self.send_with_params(method, &params, in_flight).await
 	}
 
-	#[cfg(not(target_arch = "wasm32"))]
-	async fn reconnect_backend(&mut self) -> Result<(WsBackend, BackendDriver), WsClientError> {
-		if let Some(config) = self.config {
-			WsBackend::connect_with_config(self.conn.clone(), config, false).await
-		} else {
-			WsBackend::connect(self.conn.clone()).await
-		}
+	async fn send_with_params(
+		&mut self,
+		method: &str,
+		params: &RawValue,
+		in_flight: InFlight,
+	) -> Result<(), WsClientError> {
+		let id = self.next_id;
+		self.next_id += 1;
+		// Requests without parameters are sent with an empty array, which every node accepts
+		let params = if params.get() == "null" { "[]" } else { params.get() };
+		let request = serde_json::to_string(&Request::new(
+			id,
+			method,
+			RawValue::from_string(params.into())?,
+		))?;
+		self.in_flight.insert(id, in_flight);
+		Ok(self.socket.send(Message::Text(request)).await?)
 	}
 
-	async fn reconnect(&mut self) -> Result<(), WsClientError> {
-		if self.reconnects == 0 {
-			return Err(WsClientError::TooManyReconnects);
-		}
-		self.reconnects -= 1;
-
-		tracing::info!(remaining = self.reconnects, url = self.conn.url, "Reconnecting to backend");
-		// create the new backend
-		let (s, mut backend) = self.reconnect_backend().await?;
-
-		// spawn the new backend
-		s.spawn();
-
-		// swap out the backend
-		std::mem::swap(&mut self.backend, &mut backend);
-
-		// rename for clarity
-		let mut old_backend = backend;
-
-		// Drain anything in the backend
-		tracing::debug!("Draining old backend to_handle channel");
-		while let Some(to_handle) = old_backend.to_handle.next().await {
-			self.handle(to_handle);
-		}
-
-		// issue a shutdown command (even though it's likely gone)
-		old_backend.shutdown();
-
-		tracing::debug!(count = self.subs.count(), "Re-starting active subscriptions");
-		let req_cnt = self.reqs.len();
-
-		// reissue subscriptions
-		for (id, sub) in self.subs.to_reissue() {
-			let (tx, _rx) = oneshot::channel();
-			let in_flight = InFlight {
-				method: "neo_subscribe".to_string(),
-				params: sub.params.clone(),
-				channel: tx,
-			};
-			// Need an entry in reqs to ensure response with new server sub ID is processed
-			self.reqs.insert(*id, in_flight);
-		}
-
-		tracing::debug!(count = req_cnt, "Re-issuing pending requests");
-		// reissue requests, including the re-subscription requests we just added above
-		for (id, req) in self.reqs.iter() {
-			self.backend
-				.dispatcher
-				.unbounded_send(req.serialize_raw(*id)?)
-				.map_err(|_| WsClientError::DeadChannel)?;
-		}
-		tracing::info!(subs = self.subs.count(), reqs = req_cnt, "Re-connection complete");
-
-		Ok(())
+	async fn send_unsubscribe(&mut self, sub: &ActiveSub) -> Result<(), WsClientError> {
+		let params = to_raw_value(&[&sub.server_id])?;
+		self.send_with_params("unsubscribe", &params, InFlight::Unsubscribe).await
 	}
 
-	#[tracing::instrument(skip(self, result))]
-	fn req_success(&mut self, id: u64, result: Box<RawValue>) {
-		// pending fut is missing, this is fine
-		tracing::trace!(%result, "Success response received");
-		if let Some(req) = self.reqs.remove(&id) {
-			tracing::debug!("Sending result to request listener");
-			// Allow subscription manager to rewrite the result if the request
-			// corresponds to a known ID
-			let result = if self.subs.has(id) { self.subs.req_success(id, result) } else { result };
-			let _ = req.channel.send(Ok(result));
-		} else {
-			tracing::trace!("No InFlight found");
+	/// Cancels every subscription and closes the connection.
+	async fn shutdown(&mut self) {
+		let subs: Vec<_> = self.subs.drain().map(|(_, sub)| sub).collect();
+		for sub in subs {
+			if let Err(err) = self.send_unsubscribe(&sub).await {
+				debug!("failed to unsubscribe from {}: {}", sub.event, err);
+			}
 		}
-	}
-
-	fn req_fail(&mut self, id: u64, error: JsonRpcError) {
-		// pending fut is missing, this is fine
-		if let Some(req) = self.reqs.remove(&id) {
-			// pending fut has been dropped, this is fine
-			let _ = req.channel.send(Err(error));
+		if let Err(err) = self.socket.close(None).await {
+			debug!("failed to close the websocket: {}", err);
 		}
 	}
 
-	fn handle(&mut self, item: PubSubItem) {
-		match item {
-			PubSubItem::Success { id, result } => self.req_success(id, result),
-			PubSubItem::Error { id, error } => self.req_fail(id, error),
-			PubSubItem::Notification { params } => self.subs.handle_notification(params),
+	fn handle_message(&mut self, text: &str) {
+		let message: Incoming = match serde_json::from_str(text) {
+			Ok(message) => message,
+			Err(err) => {
+				warn!("ignoring an invalid websocket message: {}", err);
+				return;
+			},
+		};
+		match (message.id, message.method) {
+			(Some(id), _) => self.handle_response(id, message.result, message.error),
+			(None, Some(event)) => self.handle_notification(&event, message.params),
+			(None, None) => warn!("ignoring a websocket message without id or method"),
 		}
 	}
 
-	#[tracing::instrument(skip(self, params, sender))]
-	fn service_request(
+	fn handle_response(
 		&mut self,
 		id: u64,
-		method: String,
-		params: Box<RawValue>,
-		sender: oneshot::Sender<Response>,
-	) -> Result<(), WsClientError> {
-		let in_flight = InFlight { method, params, channel: sender };
-		let req = in_flight.serialize_raw(id)?;
-
-		// Ordering matters here. We want this block above the unbounded send,
-		// and after the serialization
-		if in_flight.method == "neo_subscribe" {
-			self.subs.service_subscription_request(id, in_flight.params.clone())?;
-		}
-
-		// Must come after self.subs.service_subscription_request. Do not re-order
-		tracing::debug!("Dispatching request to backend");
-		self.backend
-			.dispatcher
-			.unbounded_send(req)
-			.map_err(|_| WsClientError::DeadChannel)?;
-
-		self.reqs.insert(id, in_flight);
-		Ok(())
-	}
-
-	fn service_instruction(&mut self, instruction: Instruction) -> Result<(), WsClientError> {
-		match instruction {
-			Instruction::Request { method, params, sender } => {
-				let id = self.next_id();
-				self.service_request(id, method, params, sender)?;
+		result: Option<Box<RawValue>>,
+		error: Option<JsonRpcError>,
+	) {
+		let response = match (result, error) {
+			(_, Some(error)) => Err(error),
+			(Some(result), None) => Ok(result),
+			(None, None) => Ok(RawValue::from_string("null".to_string()).unwrap()),
+		};
+		match self.in_flight.remove(&id) {
+			Some(InFlight::Request { sender, .. }) => {
+				let _ = sender.send(response);
 			},
-			Instruction::Unsubscribe { id } => {
-				if let Some(req) = self.subs.end_subscription(id.low_u64()) {
-					self.backend
-						.dispatcher
-						.unbounded_send(req)
-						.map_err(|_| WsClientError::DeadChannel)?;
+			Some(InFlight::Subscribe { event, params, sender }) => {
+				// The client learns the local id of the subscription instead of the node's
+				let response = match response {
+					Ok(result) => match self.add_subscription(event, params, &result) {
+						Some(id) => Ok(to_raw_value(&id.to_string()).unwrap()),
+						None => Ok(result),
+					},
+					Err(err) => Err(err),
+				};
+				let _ = sender.send(response);
+			},
+			Some(InFlight::Resubscribe { id }) => {
+				let server_id = response.ok().and_then(|result| server_id_of(&result));
+				match (server_id, self.subs.get_mut(&id)) {
+					(Some(server_id), Some(sub)) => {
+						sub.server_id = server_id;
+						let _ = sub.channel.unbounded_send(SubscriptionEvent::Reconnected);
+					},
+					// The stream of a subscription that could not be re-issued ends
+					(None, Some(_)) => {
+						warn!("failed to re-issue subscription {}", id);
+						self.subs.remove(&id);
+					},
+					// Unsubscribed in the meantime
+					(_, None) => {},
 				}
 			},
+			Some(InFlight::Unsubscribe) => {},
+			None => warn!("ignoring a response to unknown request {}", id),
 		}
-		Ok(())
 	}
 
-	pub fn spawn(mut self) {
-		let fut = async move {
-			let result = loop {
-				// We bias the loop so that we always handle messages before
-				// reconnecting, and always reconnect before dispatching new
-				// requests
-				select_biased! {
-					item_opt = self.backend.to_handle.next() => {
-						match item_opt {
-							Some(item) => self.handle(item),
-							// Backend is gone, so reconnect
-							None => if let Err(e) = self.reconnect().await {
-								break Err(e);
-							}
-						}
-					},
-					_ = &mut self.backend.error => {
-						if let Err(e) = self.reconnect().await {
-							break Err(e);
-						}
-					},
-					inst_opt = self.instructions.next() => {
-						match inst_opt {
-							Some(instruction) => if let Err(e) = self.service_instruction(instruction) { break Err(e)},
-							// User-facing side is gone, so just exit
-							None => break Ok(()),
-						}
-					}
-				}
-			};
-			if let Err(err) = result {
-				tracing::error!(%err, "Error during reconnection");
-			}
-			// Issue the shutdown command. we don't care if it is received
-			self.backend.shutdown();
+	/// Registers the subscription the node confirmed with `result`, its id, and returns the
+	/// local id of the subscription.
+	fn add_subscription(
+		&mut self,
+		event: String,
+		params: Box<RawValue>,
+		result: &RawValue,
+	) -> Option<U256> {
+		let Some(server_id) = server_id_of(result) else {
+			warn!("invalid subscription id {}", result.get());
+			return None;
 		};
+		let id = U256::from(self.next_sub_id);
+		self.next_sub_id += 1;
+		let filter = filter_of(&params);
+		let (channel, receiver) = mpsc::unbounded();
+		self.channel_map.lock().unwrap().insert(id, receiver);
+		self.subs.insert(id, ActiveSub { event, filter, params, server_id, channel });
+		Some(id)
+	}
 
-		#[cfg(target_arch = "wasm32")]
-		super::spawn_local(fut);
+	/// Notifications do not name the subscription they belong to, so they are sent to the
+	/// subscriptions to the event whose filter they pass.
+	fn handle_notification(&mut self, event: &str, params: Vec<Box<RawValue>>) {
+		let Some(payload) = params.into_iter().next() else {
+			warn!("ignoring a {} notification without parameters", event);
+			return;
+		};
+		if event == "event_missed" {
+			warn!("the node dropped notifications because they were not read fast enough");
+			return;
+		}
+		let Ok(value) = serde_json::from_str::<Value>(payload.get()) else {
+			warn!("ignoring an invalid {} notification", event);
+			return;
+		};
+		for sub in self.subs.values().filter(|sub| sub.event == event && sub.matches(&value)) {
+			let _ = sub.channel.unbounded_send(SubscriptionEvent::Notification(payload.clone()));
+		}
+	}
+}
 
-		#[cfg(not(target_arch = "wasm32"))]
-		tokio::spawn(fut);
+/// The event name, which is the first parameter of a `subscribe` request.
+fn event_of(params: &RawValue) -> String {
+	match serde_json::from_str::<Vec<Value>>(params.get()) {
+		Ok(params) => params.first().and_then(Value::as_str).unwrap_or_default().to_string(),
+		Err(_) => String::new(),
 	}
 }
+
+/// The filter, which is the optional second parameter of a `subscribe` request.
+fn filter_of(params: &RawValue) -> Option<Value> {
+	serde_json::from_str::<Vec<Value>>(params.get()).ok()?.into_iter().nth(1)
+}
+
+/// The subscription id in the result of a `subscribe` request.
+fn server_id_of(result: &RawValue) -> Option<String> {
+	serde_json::from_str(result.get()).ok()
+}
@@ -1,137 +1,149 @@
-#![allow(missing_docs)]
+//! A WebSocket transport for the subscriptions of NeoGo nodes.
 
 use std::{
+	collections::HashMap,
 	fmt,
-	fmt::{Debug, Formatter},
-	future::Future,
-	pin::Pin,
+	fmt::Debug,
+	sync::{Arc, Mutex},
 };
 
 use async_trait::async_trait;
 use futures_channel::{mpsc, oneshot};
+use futures_util::FutureExt;
 use primitive_types::U256;
 use serde::{de::DeserializeOwned, Serialize};
 use serde_json::value::{to_raw_value, RawValue};
+use tokio::{
+	io::{AsyncRead, AsyncWrite},
+	net::TcpStream,
+};
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream};
 
 pub use error::*;
-use manager::{RequestManager, SharedChannelMap};
+use manager::{Connector, Reconnect, RequestManager, SharedChannelMap};
 pub use types::ConnectionDetails;
 use types::*;
 
-#[cfg(not(target_arch = "wasm32"))]
-use crate::Authorization;
-use crate::{JsonRpcClient, ProviderError, PubsubClient};
-
-mod backend;
-
-mod manager;
+use neo::prelude::{
+	Authorization, BackoffPolicy, JsonRpcProvider, ProviderError, PubsubClient, RpcClient,
+	SubscriptionEvent,
+};
 
 mod error;
+mod manager;
 mod types;
+
+/// Errors of the underlying WebSocket connection.
+pub type WsError = tokio_tungstenite::tungstenite::Error;
+
+/// A JSON-RPC client over a WebSocket connection, which supports the `subscribe` and
+/// `unsubscribe` methods of NeoGo nodes.
+///
+/// The connection is owned by a background task, shared by all clones of the client. It is closed
+/// by [`WsClient::close`], or once every clone has been dropped. A client created with
+/// [`WsClient::with_auto_reconnect`] re-establishes a lost connection and its subscriptions.
 #[derive(Clone)]
 pub struct WsClient {
 	// Used to send instructions to the `RequestManager`
 	instructions: mpsc::UnboundedSender<Instruction>,
-	// Used to receive sub notifications channels with the backend
+	// Used to receive the notification channels of new subscriptions
 	channel_map: SharedChannelMap,
 }
 
 impl WsClient {
-	/// Establishes a new websocket connection
+	/// Connects to the node at `conn`, e.g. `ws://localhost:10332/ws`.
 	pub async fn connect(conn: impl Into<ConnectionDetails>) -> Result<Self, WsClientError> {
-		let (man, this) = RequestManager::connect(conn.into()).await?;
-		man.spawn();
-		Ok(this)
+		Self::connect_with(connector(conn.into()), None).await
 	}
 
-	/// Establishes a new websocket connection with auto-reconnects.
-	pub async fn connect_with_reconnects(
+	/// Connects to the node at `conn`, and connects again whenever the connection is lost. Up to
+	/// `max_retries` attempts are made, waiting between them as `backoff` says.
+	///
+	/// Requests the node had not answered are re-sent and active subscriptions are re-issued,
+	/// their streams continue. Notifications sent while disconnected are lost, so the stream of
+	/// each re-issued subscription yields [`SubscriptionEvent::Reconnected`] to mark the gap.
+	pub async fn with_auto_reconnect(
 		conn: impl Into<ConnectionDetails>,
-		reconnects: usize,
+		max_retries: usize,
+		backoff: BackoffPolicy,
 	) -> Result<Self, WsClientError> {
-		let (man, this) = RequestManager::connect_with_reconnects(conn.into(), reconnects).await?;
-		man.spawn();
-		Ok(this)
+		Self::connect_with(connector(conn.into()), Some(Reconnect { max_retries, backoff })).await
 	}
 
-	#[cfg(not(target_arch = "wasm32"))]
-	/// Establishes a new websocket connection. This method allows specifying a custom websocket
-	/// configuration, see the [tungstenite docs](https://docs.rs/tungstenite/latest/tungstenite/protocol/struct.WebSocketConfig.html) for all avaible options.
-	pub async fn connect_with_config(
-		conn: impl Into<ConnectionDetails>,
-		config: impl Into<WebSocketConfig>,
-	) -> Result<Self, WsClientError> {
-		let (man, this) = RequestManager::connect_with_config(conn.into(), config.into()).await?;
-		man.spawn();
-		Ok(this)
+	async fn connect_with<S>(
+		connector: Connector<S>,
+		reconnect: Option<Reconnect>,
+	) -> Result<Self, WsClientError>
+	where
+		S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+	{
+		let (instructions, receiver) = mpsc::unbounded();
+		let channel_map: SharedChannelMap = Arc::new(Mutex::new(HashMap::new()));
+		RequestManager::connect(connector, reconnect, receiver, channel_map.clone())
+			.await?
+			.spawn();
+		Ok(Self { instructions, channel_map })
 	}
 
-	#[cfg(not(target_arch = "wasm32"))]
-	/// Establishes a new websocket connection with auto-reconnects. This method allows specifying a
-	/// custom websocket configuration, see the [tungstenite docs](https://docs.rs/tungstenite/latest/tungstenite/protocol/struct.WebSocketConfig.html) for all avaible options.
-	pub async fn connect_with_config_and_reconnects(
-		conn: impl Into<ConnectionDetails>,
-		config: impl Into<WebSocketConfig>,
-		reconnects: usize,
-	) -> Result<Self, WsClientError> {
-		let (man, this) = RequestManager::connect_with_config_and_reconnects(
-			conn.into(),
-			config.into(),
-			reconnects,
-		)
-		.await?;
-		man.spawn();
-		Ok(this)
+	/// Unsubscribes from all active subscriptions and closes the connection. Their streams end,
+	/// and later requests fail with [`WsClientError::UnexpectedClose`].
+	pub async fn close(&self) -> Result<(), WsClientError> {
+		let (sender, receiver) = oneshot::channel();
+		self.instructions
+			.unbounded_send(Instruction::Close { sender })
+			.map_err(|_| WsClientError::UnexpectedClose)?;
+		receiver.await.map_err(|_| WsClientError::UnexpectedClose)
 	}
 
-	#[tracing::instrument(skip(self, params), err)]
 	async fn make_request<R>(&self, method: &str, params: Box<RawValue>) -> Result<R, WsClientError>
 	where
 		R: DeserializeOwned,
 	{
-		let (tx, rx) = oneshot::channel();
-		let instruction = Instruction::Request { method: method.to_owned(), params, sender: tx };
+		let (sender, receiver) = oneshot::channel();
+		let instruction = Instruction::Request { method: method.to_owned(), params, sender };
 		self.instructions
 			.unbounded_send(instruction)
 			.map_err(|_| WsClientError::UnexpectedClose)?;
 
-		let res = rx.await.map_err(|_| WsClientError::UnexpectedClose)??;
-		tracing::trace!(res = %res, "Received response from request manager");
-		let resp = serde_json::from_str(res.get())?;
-		tracing::trace!("Deserialization success");
-		Ok(resp)
+		let result = receiver.await.map_err(|_| WsClientError::UnexpectedClose)??;
+		Ok(serde_json::from_str(result.get())?)
 	}
 }
 
-impl fmt::Debug for WsClient {
+/// Opens connections to the node at `conn`.
+fn connector(conn: ConnectionDetails) -> Connector<MaybeTlsStream<TcpStream>> {
+	Box::new(move || {
+		let conn = conn.clone();
+		async move { connect_async(conn).await.map(|(socket, _)| socket) }.boxed()
+	})
+}
+
+impl Debug for WsClient {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-		f.debug_struct("Ws").finish_non_exhaustive()
+		f.debug_struct("WsClient").finish_non_exhaustive()
 	}
 }
 
 #[cfg_attr(target_arch = "wasm32", async_trait(? Send))]
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
-impl JsonRpcClient for WsClient {
+impl JsonRpcProvider for WsClient {
 	type Error = WsClientError;
 
 	async fn fetch<T, R>(&self, method: &str, params: T) -> Result<R, WsClientError>
 	where
-		T: Serialize + Send + Sync,
-		R: DeserializeOwned,
+		T: Debug + Serialize + Send + Sync,
+		R: DeserializeOwned + Send,
 	{
 		let params = to_raw_value(&params)?;
-		let res = self.make_request(method, params).await?;
-
-		Ok(res)
+		self.make_request(method, params).await
 	}
 }
 
 impl PubsubClient for WsClient {
-	type NotificationStream = mpsc::UnboundedReceiver<Box<RawValue>>;
+	type NotificationStream = mpsc::UnboundedReceiver<SubscriptionEvent<Box<RawValue>>>;
 
 	fn subscribe<T: Into<U256>>(&self, id: T) -> Result<Self::NotificationStream, WsClientError> {
-		// due to the behavior of the request manager, we know this map has
-		// been populated by the time the `request()` call returns
+		// The request manager registers the channel before answering the `subscribe` request
 		let id = id.into();
 		self.channel_map
 			.lock()
@@ -147,69 +159,345 @@ impl PubsubClient for WsClient {
 	}
 }
 
-impl crate::Provider<WsClient> {
-	/// Direct connection to a websocket endpoint. Defaults to 5 reconnects.
-	///
-	/// # Examples
-	///
-	/// Connect to server via URL
-	///
-	/// ```
-	/// use neo_clients::{Ws, Provider};
-	/// use neo_clients::Middleware;
-	/// # async fn t() {
-	///     let ws = Provider::<Ws>::connect("ws://localhost:8545").await.unwrap();
-	///     let _num = ws.get_block_number().await.unwrap();
-	/// # }
-	/// ```
-	///
-	/// Connect with authentication, see also [Self::connect_with_auth]
-	///
-	/// ```
-	/// use neo_clients::{Ws, Provider, Middleware, ConnectionDetails, Authorization };
-	/// # async fn t() {
-	///     let auth = Authorization::basic("user", "pass");
-	///     let opts = ConnectionDetails::new("ws://localhost:8545", Some(auth));
-	///     let ws = Provider::<Ws>::connect(opts).await.unwrap();
-	///     let _num = ws.get_block_number().await.unwrap();
-	/// # }
-	/// ```
-	pub async fn connect(url: impl Into<ConnectionDetails>) -> Result<Self, ProviderError> {
-		let ws = crate::Ws::connect(url).await?;
-		Ok(Self::new(ws))
-	}
-
-	/// Direct connection to a websocket endpoint, with a set number of
-	/// reconnection attempts
-	pub async fn connect_with_reconnects(
-		url: impl Into<ConnectionDetails>,
-		reconnects: usize,
-	) -> Result<Self, ProviderError> {
-		let ws = crate::Ws::connect_with_reconnects(url, reconnects).await?;
-		Ok(Self::new(ws))
+impl RpcClient<WsClient> {
+	/// Connects to the WebSocket endpoint of a node, e.g. `ws://localhost:10332/ws`.
+	pub async fn connect_ws(url: impl Into<ConnectionDetails>) -> Result<Self, ProviderError> {
+		Ok(Self::new(WsClient::connect(url).await?))
 	}
 
-	/// Connect to a WS RPC provider with authentication details
-	#[cfg(not(target_arch = "wasm32"))]
-	pub async fn connect_with_auth(
+	/// Connects to the WebSocket endpoint of a node that requires authentication.
+	pub async fn connect_ws_with_auth(
 		url: impl AsRef<str>,
 		auth: Authorization,
 	) -> Result<Self, ProviderError> {
-		let conn = ConnectionDetails::new(url, Some(auth));
-		let ws = crate::Ws::connect(conn).await?;
-		Ok(Self::new(ws))
+		Self::connect_ws(ConnectionDetails::new(url, Some(auth))).await
 	}
+}
 
-	/// Connect to a WS RPC provider with authentication details and a set
-	/// number of reconnection attempts
-	#[cfg(not(target_arch = "wasm32"))]
-	pub async fn connect_with_auth_and_reconnects(
-		url: impl AsRef<str>,
-		auth: Authorization,
-		reconnects: usize,
-	) -> Result<Self, ProviderError> {
-		let conn = ConnectionDetails::new(url, Some(auth));
-		let ws = crate::Ws::connect_with_reconnects(conn, reconnects).await?;
-		Ok(Self::new(ws))
+#[cfg(test)]
+mod tests {
+	use std::{collections::VecDeque, time::Duration};
+
+	use futures_util::{SinkExt, StreamExt};
+	use primitive_types::H256;
+	use serde_json::{json, Value};
+	use tokio::io::DuplexStream;
+	use tokio_tungstenite::{tungstenite::protocol::Role, WebSocketStream};
+
+	use neo::prelude::MempoolEvent;
+
+	use super::*;
+
+	type Socket = WebSocketStream<DuplexStream>;
+
+	/// Returns the client and node ends of a connection.
+	async fn socket_pair() -> (Socket, Socket) {
+		let (client, node) = tokio::io::duplex(64 * 1024);
+		(
+			WebSocketStream::from_raw_socket(client, Role::Client, None).await,
+			WebSocketStream::from_raw_socket(node, Role::Server, None).await,
+		)
+	}
+
+	/// Hands out `sockets` as the successive connections to the node.
+	fn sockets(sockets: Vec<Socket>) -> Connector<DuplexStream> {
+		let sockets = Arc::new(Mutex::new(VecDeque::from(sockets)));
+		Box::new(move || {
+			let socket = sockets.lock().unwrap().pop_front();
+			async move { socket.ok_or(WsError::ConnectionClosed) }.boxed()
+		})
+	}
+
+	async fn next_request(node: &mut Socket) -> Value {
+		match node.next().await {
+			Some(Ok(Message::Text(text))) => serde_json::from_str(&text).unwrap(),
+			other => panic!("expected a request, got {:?}", other),
+		}
+	}
+
+	async fn respond(node: &mut Socket, request: &Value, result: Value) {
+		let response = json!({ "jsonrpc": "2.0", "id": request["id"], "result": result });
+		node.send(Message::Text(response.to_string())).await.unwrap();
+	}
+
+	async fn notify(node: &mut Socket, event: &str, payload: Value) {
+		let notification = json!({ "jsonrpc": "2.0", "method": event, "params": [payload] });
+		node.send(Message::Text(notification.to_string())).await.unwrap();
+	}
+
+	fn payload(event: Option<SubscriptionEvent<Box<RawValue>>>) -> Value {
+		match event {
+			Some(SubscriptionEvent::Notification(payload)) =>
+				serde_json::from_str(payload.get()).unwrap(),
+			other => panic!("expected a notification, got {:?}", other),
+		}
+	}
+
+	#[tokio::test]
+	async fn test_close_cancels_subscriptions() {
+		let (client, mut node) = socket_pair().await;
+		let ws = WsClient::connect_with(sockets(vec![client]), None).await.unwrap();
+
+		let node = tokio::spawn(async move {
+			let request = next_request(&mut node).await;
+			assert_eq!(request["method"], "subscribe");
+			assert_eq!(request["params"], json!(["block_added"]));
+			respond(&mut node, &request, json!("5")).await;
+			notify(&mut node, "block_added", json!({ "index": 1 })).await;
+
+			let request = next_request(&mut node).await;
+			assert_eq!(request["method"], "unsubscribe");
+			assert_eq!(request["params"], json!(["5"]));
+			assert!(matches!(node.next().await, Some(Ok(Message::Close(_)))));
+		});
+
+		let id: String = ws.fetch("subscribe", ["block_added"]).await.unwrap();
+		let mut blocks = ws.subscribe(U256::from_dec_str(&id).unwrap()).unwrap();
+		assert_eq!(payload(blocks.next().await)["index"], 1);
+
+		ws.close().await.unwrap();
+		node.await.unwrap();
+		assert!(blocks.next().await.is_none());
+		assert!(matches!(
+			ws.fetch::<_, u64>("getblockcount", ()).await,
+			Err(WsClientError::UnexpectedClose)
+		));
+	}
+
+	#[tokio::test]
+	async fn test_unsubscribe_ends_only_that_subscription() {
+		let (client, mut node) = socket_pair().await;
+		let ws = WsClient::connect_with(sockets(vec![client]), None).await.unwrap();
+
+		let node = tokio::spawn(async move {
+			for id in ["11", "12"] {
+				let request = next_request(&mut node).await;
+				respond(&mut node, &request, json!(id)).await;
+			}
+			let request = next_request(&mut node).await;
+			assert_eq!(request["method"], "unsubscribe");
+			assert_eq!(request["params"], json!(["11"]));
+			notify(&mut node, "block_added", json!({ "index": 7 })).await;
+			node
+		});
+
+		// The client knows the subscriptions by local ids
+		let first: String = ws.fetch("subscribe", ["block_added"]).await.unwrap();
+		let second: String = ws.fetch("subscribe", ["block_added"]).await.unwrap();
+		assert_eq!((first.as_str(), second.as_str()), ("1", "2"));
+		let mut first = ws.subscribe(U256::from_dec_str(&first).unwrap()).unwrap();
+		let mut second = ws.subscribe(U256::from_dec_str(&second).unwrap()).unwrap();
+
+		ws.unsubscribe(U256::one()).unwrap();
+		let _node = node.await.unwrap();
+		assert_eq!(payload(second.next().await)["index"], 7);
+		assert!(first.next().await.is_none());
+	}
+
+	#[tokio::test]
+	async fn test_notifications_are_routed_by_filter() {
+		let (client, mut node) = socket_pair().await;
+		let ws = WsClient::connect_with(sockets(vec![client]), None).await.unwrap();
+		let neo = "0xef4073a0f2b305a38ec4050e4d3d28bc40ea63f5";
+		let gas = "0xd2a4cff31913016155e38e474a2c06d08be276cf";
+
+		let node = tokio::spawn(async move {
+			for id in ["1", "2"] {
+				let request = next_request(&mut node).await;
+				respond(&mut node, &request, json!(id)).await;
+			}
+			// The node sends a notification once, even if several subscriptions match it
+			for contract in [gas, neo] {
+				let notification = json!({ "contract": contract, "eventname": "Transfer" });
+				notify(&mut node, "notification_from_execution", notification).await;
+			}
+			node
+		});
+
+		let subscribe = |contract: &str| {
+			let ws = ws.clone();
+			let params = json!(["notification_from_execution", { "contract": contract }]);
+			async move {
+				let id: String = ws.fetch("subscribe", params).await.unwrap();
+				ws.subscribe(U256::from_dec_str(&id).unwrap()).unwrap()
+			}
+		};
+		let mut neo_transfers = subscribe(neo).await;
+		let mut gas_transfers = subscribe(gas).await;
+
+		let _node = node.await.unwrap();
+		assert_eq!(payload(neo_transfers.next().await)["contract"], neo);
+		assert_eq!(payload(gas_transfers.next().await)["contract"], gas);
+	}
+
+	fn reconnect(max_retries: usize) -> Option<Reconnect> {
+		Some(Reconnect {
+			max_retries,
+			backoff: BackoffPolicy::default().initial_backoff(Duration::ZERO),
+		})
+	}
+
+	#[tokio::test]
+	async fn test_resubscribes_after_reconnecting() {
+		let (first, mut first_node) = socket_pair().await;
+		let (second, mut second_node) = socket_pair().await;
+		let ws = WsClient::connect_with(sockets(vec![first, second]), reconnect(3))
+			.await
+			.unwrap();
+
+		let first_node = tokio::spawn(async move {
+			let request = next_request(&mut first_node).await;
+			respond(&mut first_node, &request, json!("5")).await;
+			first_node
+		});
+		let id: String = ws.fetch("subscribe", ["block_added"]).await.unwrap();
+		let mut blocks = ws.subscribe(U256::from_dec_str(&id).unwrap()).unwrap();
+
+		// The node goes away, and the subscription is re-issued to the next connection
+		drop(first_node.await.unwrap());
+		let request = next_request(&mut second_node).await;
+		assert_eq!(request["method"], "subscribe");
+		assert_eq!(request["params"], json!(["block_added"]));
+		respond(&mut second_node, &request, json!("9")).await;
+		assert_eq!(blocks.next().await, Some(SubscriptionEvent::Reconnected));
+
+		notify(&mut second_node, "block_added", json!({ "index": 2 })).await;
+		assert_eq!(payload(blocks.next().await)["index"], 2);
+
+		// The client keeps its id, the node is asked to cancel its new one
+		ws.unsubscribe(U256::from_dec_str(&id).unwrap()).unwrap();
+		let request = next_request(&mut second_node).await;
+		assert_eq!(request["method"], "unsubscribe");
+		assert_eq!(request["params"], json!(["9"]));
+		assert!(blocks.next().await.is_none());
+	}
+
+	#[tokio::test]
+	async fn test_subscription_ids_stay_unique_across_reconnects() {
+		let (first, mut first_node) = socket_pair().await;
+		let (second, mut second_node) = socket_pair().await;
+		let ws = WsClient::connect_with(sockets(vec![first, second]), reconnect(3))
+			.await
+			.unwrap();
+
+		let first_node = tokio::spawn(async move {
+			let request = next_request(&mut first_node).await;
+			respond(&mut first_node, &request, json!("1")).await;
+			first_node
+		});
+		let id: String = ws.fetch("subscribe", ["block_added"]).await.unwrap();
+		let mut old = ws.subscribe(U256::from_dec_str(&id).unwrap()).unwrap();
+
+		// The restarted node hands out the id of the old subscription to a new one
+		drop(first_node.await.unwrap());
+		let request = next_request(&mut second_node).await;
+		respond(&mut second_node, &request, json!("2")).await;
+		assert_eq!(old.next().await, Some(SubscriptionEvent::Reconnected));
+		let second_node = tokio::spawn(async move {
+			let request = next_request(&mut second_node).await;
+			respond(&mut second_node, &request, json!("1")).await;
+			second_node
+		});
+		let new_id: String = ws.fetch("subscribe", ["block_added"]).await.unwrap();
+		let mut new = ws.subscribe(U256::from_dec_str(&new_id).unwrap()).unwrap();
+		let mut second_node = second_node.await.unwrap();
+		assert_ne!(id, new_id);
+
+		ws.unsubscribe(U256::from_dec_str(&id).unwrap()).unwrap();
+		let request = next_request(&mut second_node).await;
+		assert_eq!(request["params"], json!(["2"]));
+		notify(&mut second_node, "block_added", json!({ "index": 3 })).await;
+		assert_eq!(payload(new.next().await)["index"], 3);
+		assert!(old.next().await.is_none());
+	}
+
+	#[tokio::test]
+	async fn test_subscriptions_end_when_reconnecting_fails() {
+		let (client, mut node) = socket_pair().await;
+		let ws = WsClient::connect_with(sockets(vec![client]), reconnect(2)).await.unwrap();
+
+		let node = tokio::spawn(async move {
+			let request = next_request(&mut node).await;
+			respond(&mut node, &request, json!("5")).await;
+			node
+		});
+		let id: String = ws.fetch("subscribe", ["block_added"]).await.unwrap();
+		let mut blocks = ws.subscribe(U256::from_dec_str(&id).unwrap()).unwrap();
+
+		drop(node.await.unwrap());
+		assert!(blocks.next().await.is_none());
+		assert!(matches!(
+			ws.fetch::<_, u64>("getblockcount", ()).await,
+			Err(WsClientError::UnexpectedClose)
+		));
+	}
+
+	#[tokio::test]
+	async fn test_subscribe_mempool() {
+		let (client, mut node) = socket_pair().await;
+		let ws = WsClient::connect_with(sockets(vec![client]), None).await.unwrap();
+		let client = RpcClient::new(ws);
+		let hash = "0x8b8b222ba4ae17eaf37d444210f3d9503f2ed4f0c8fbaea8e3b8ae5e4fe05f1f";
+
+		let node = tokio::spawn(async move {
+			for (event, id) in [("transaction_added", "3"), ("transaction_executed", "4")] {
+				let request = next_request(&mut node).await;
+				assert_eq!(request["method"], "subscribe");
+				assert_eq!(request["params"], json!([event]));
+				respond(&mut node, &request, json!(id)).await;
+			}
+			let transaction = json!({
+				"hash": hash,
+				"size": 252,
+				"version": 0,
+				"nonce": 1_046_354_582,
+				"sender": "NKuyBkoGdZZSLyPbJEetheRhMjeznFZszf",
+				"sysfee": "9007990",
+				"netfee": "1230610",
+				"validuntilblock": 2_106_392,
+				"script": "CxAMFPlu76Cuc+bgteStE4ozsOWTNUdrDBQtYNweHko3YcnMFOes3ceblcI/lRTAHwwIdHJhbnNmZXIMFPVj6kC8KD1NDgXEjqMFs/Kgc0DvQWJ9W1I5"
+			});
+			notify(&mut node, "transaction_added", transaction).await;
+			let execution = json!({
+				"container": hash,
+				"trigger": "Application",
+				"vmstate": "HALT",
+				"exception": null,
+				"gasconsumed": "9007990",
+				"stack": [],
+				"notifications": []
+			});
+			notify(&mut node, "transaction_executed", execution).await;
+
+			// Dropping the stream cancels both subscriptions
+			let mut unsubscribed = Vec::new();
+			for _ in 0..2 {
+				let request = next_request(&mut node).await;
+				assert_eq!(request["method"], "unsubscribe");
+				unsubscribed.push(request["params"][0].as_str().unwrap().to_string());
+			}
+			unsubscribed.sort();
+			assert_eq!(unsubscribed, ["3", "4"]);
+		});
+
+		let mut mempool = Box::pin(client.subscribe_mempool().await.unwrap());
+		// The two subscriptions are streamed in the order they are polled
+		let (mut added, mut removed) = (None, None);
+		for _ in 0..2 {
+			match mempool.next().await {
+				Some(SubscriptionEvent::Notification(MempoolEvent::Added(transaction))) =>
+					added = Some(transaction),
+				Some(SubscriptionEvent::Notification(MempoolEvent::Removed(hash))) =>
+					removed = Some(hash),
+				event => panic!("unexpected event: {:?}", event),
+			}
+		}
+		let hash = hash.parse::<H256>().unwrap();
+		let added = added.unwrap();
+		assert_eq!(added.hash, hash);
+		assert_eq!(added.net_fee, "1230610");
+		assert_eq!(removed, Some(hash));
+		drop(mempool);
+		node.await.unwrap();
 	}
 }
@@ -0,0 +1,156 @@
+use std::{
+	collections::HashMap,
+	fmt,
+	pin::Pin,
+	task::{Context, Poll},
+};
+
+use futures::stream::Stream;
+use futures_util::StreamExt;
+use log::debug;
+use primitive_types::H160;
+
+use crate::prelude::LogNotification;
+
+type EventDecoder<E> = Box<dyn Fn(&LogNotification) -> Option<E> + Send + Sync>;
+
+/// A registry of typed decoders keyed by the emitting contract and the event name.
+pub struct EventDecoders<E> {
+	decoders: HashMap<(H160, String), EventDecoder<E>>,
+}
+
+impl<E> EventDecoders<E> {
+	pub fn new() -> Self {
+		Self { decoders: HashMap::new() }
+	}
+
+	/// Registers a decoder for the notifications named `event_name` emitted by `contract`.
+	/// Registering a second decoder for the same pair replaces the first one.
+	pub fn register<F>(mut self, contract: H160, event_name: impl Into<String>, decoder: F) -> Self
+	where
+		F: Fn(&LogNotification) -> Option<E> + Send + Sync + 'static,
+	{
+		self.decoders.insert((contract, event_name.into()), Box::new(decoder));
+		self
+	}
+
+	/// Decodes a notification with the decoder registered for its contract and event name.
+	/// Returns `None` if no decoder is registered or the decoder rejects the notification.
+	pub fn decode(&self, notification: &LogNotification) -> Option<E> {
+		let key = (notification.contract, notification.event_name.clone());
+		let Some(decoder) = self.decoders.get(&key) else {
+			debug!(
+				"No decoder registered for {} emitted by {:?}",
+				notification.event_name, notification.contract
+			);
+			return None;
+		};
+		let event = decoder(notification);
+		if event.is_none() {
+			debug!(
+				"Failed to decode {} emitted by {:?}: {:?}",
+				notification.event_name, notification.contract, notification.state
+			);
+		}
+		event
+	}
+
+	/// Wraps a stream of notifications into an [`EventStream`] yielding the decoded events.
+	pub fn stream<S>(self, notifications: S) -> EventStream<S, E>
+	where
+		S: Stream<Item = LogNotification> + Unpin,
+	{
+		EventStream { notifications, decoders: self }
+	}
+}
+
+impl<E> Default for EventDecoders<E> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<E> fmt::Debug for EventDecoders<E> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("EventDecoders").field("events", &self.decoders.keys()).finish()
+	}
+}
+
+/// A stream of domain events decoded from contract notifications. Notifications without a
+/// matching decoder, or rejected by their decoder, are logged and skipped.
+#[derive(Debug)]
+pub struct EventStream<S, E> {
+	notifications: S,
+	decoders: EventDecoders<E>,
+}
+
+impl<S, E> Stream for EventStream<S, E>
+where
+	S: Stream<Item = LogNotification> + Unpin,
+	E: Unpin,
+{
+	type Item = E;
+
+	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<E>> {
+		let this = &mut *self;
+		loop {
+			match this.notifications.poll_next_unpin(cx) {
+				Poll::Ready(Some(notification)) =>
+					if let Some(event) = this.decoders.decode(&notification) {
+						return Poll::Ready(Some(event));
+					},
+				Poll::Ready(None) => return Poll::Ready(None),
+				Poll::Pending => return Poll::Pending,
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use futures::stream;
+	use futures_util::StreamExt;
+	use primitive_types::H160;
+
+	use neo::prelude::{LogNotification, StackItem};
+
+	use super::EventDecoders;
+
+	#[derive(Debug, PartialEq)]
+	enum GameEvent {
+		LevelUp { level: i64 },
+		Reward { amount: i64 },
+	}
+
+	fn notification(contract: H160, event_name: &str, state: Vec<StackItem>) -> LogNotification {
+		LogNotification::new(contract, event_name.to_string(), StackItem::Array { value: state })
+	}
+
+	#[tokio::test]
+	async fn test_event_stream_decodes_registered_events() {
+		let game = H160::repeat_byte(0x01);
+		let other = H160::repeat_byte(0x02);
+
+		let decoders = EventDecoders::new()
+			.register(game, "LevelUp", |n| {
+				Some(GameEvent::LevelUp { level: n.state.get(0)?.as_int().ok()? })
+			})
+			.register(game, "Reward", |n| {
+				Some(GameEvent::Reward { amount: n.state.get(0)?.as_int().ok()? })
+			});
+
+		let notifications = stream::iter(vec![
+			notification(game, "LevelUp", vec![StackItem::Integer { value: 2.into() }]),
+			// Unknown event of a registered contract
+			notification(game, "Transfer", vec![StackItem::Integer { value: 1.into() }]),
+			// Known event name emitted by another contract
+			notification(other, "Reward", vec![StackItem::Integer { value: 100.into() }]),
+			// Rejected by the decoder
+			notification(game, "Reward", vec![]),
+			notification(game, "Reward", vec![StackItem::Integer { value: 50.into() }]),
+		]);
+
+		let events: Vec<GameEvent> = decoders.stream(notifications).collect().await;
+		assert_eq!(events, vec![GameEvent::LevelUp { level: 2 }, GameEvent::Reward { amount: 50 }]);
+	}
+}
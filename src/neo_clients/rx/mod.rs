@@ -1,2 +1,5 @@
+pub use event_stream::*;
 pub use neo_rust_rx_trait::*;
+
+mod event_stream;
 mod neo_rust_rx_trait;
@@ -0,0 +1,537 @@
+use std::{
+	collections::HashMap,
+	str::FromStr,
+	sync::{Arc, Mutex},
+};
+
+use ::base64::{engine::general_purpose, Engine};
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+use primitive_types::{H160, H256};
+use serde_json::{json, Value};
+use url::Url;
+use wiremock::{matchers::method, Mock, MockServer, Request, Respond, ResponseTemplate};
+
+use neo::prelude::*;
+
+const GAS_CONSUMED: i64 = 984060;
+const NETWORK_FEE: i64 = 1230610;
+
+/// A stateful, in-memory stand-in for a Neo node, meant for end-to-end tests of transfer flows.
+///
+/// The simulator keeps a fake block height and a NEP-17 ledger. Transactions accepted through
+/// `sendrawtransaction` are recorded, advance the height by one block and get an application
+/// log. Transfers in their script, as built by `ScriptBuilder::contract_call`, are applied to the
+/// ledger, and `balanceOf` invocations are answered from it.
+pub struct SimBlockchain {
+	server: MockServer,
+	state: Arc<Mutex<SimState>>,
+}
+
+impl SimBlockchain {
+	pub async fn start() -> Self {
+		let server = MockServer::start().await;
+		let state = Arc::new(Mutex::new(SimState::new()));
+		Mock::given(method("POST"))
+			.respond_with(SimResponder { state: state.clone() })
+			.mount(&server)
+			.await;
+		Self { server, state }
+	}
+
+	pub fn url(&self) -> Url {
+		Url::parse(&self.server.uri()).expect("Invalid simulator URL")
+	}
+
+	pub fn client(&self) -> RpcClient<HttpProvider> {
+		let http_provider = HttpProvider::new(self.url()).expect("Failed to create HTTP provider");
+		RpcClient::new(http_provider)
+	}
+
+	pub fn height(&self) -> u32 {
+		self.state.lock().unwrap().height
+	}
+
+	pub fn set_height(&self, height: u32) {
+		self.state.lock().unwrap().height = height;
+	}
+
+	/// The network magic reported by `getversion`. Only used for signing if no network is
+	/// configured in `NEOCONFIG`.
+	pub fn set_network_magic(&self, network_magic: u32) {
+		self.state.lock().unwrap().network_magic = Some(network_magic);
+	}
+
+	pub fn balance(&self, token: &H160, account: &H160) -> i64 {
+		self.state.lock().unwrap().balance(token, account)
+	}
+
+	pub fn set_balance(&self, token: H160, account: H160, amount: i64) {
+		self.state.lock().unwrap().balances.insert((token, account), amount);
+	}
+
+	/// The hashes of the accepted transactions, in submission order.
+	pub fn transactions(&self) -> Vec<H256> {
+		self.state.lock().unwrap().transactions.clone()
+	}
+
+	/// Makes the next submitted transaction fault with the given exception, leaving the ledger
+	/// untouched.
+	pub fn fail_next_transaction(&self, exception: impl Into<String>) {
+		self.state.lock().unwrap().next_exception = Some(exception.into());
+	}
+
+	/// Replaces the application log served for a submitted transaction.
+	pub fn set_application_log(&self, application_log: ApplicationLog) {
+		self.state
+			.lock()
+			.unwrap()
+			.application_logs
+			.insert(application_log.transaction_id, application_log);
+	}
+}
+
+struct SimResponder {
+	state: Arc<Mutex<SimState>>,
+}
+
+impl Respond for SimResponder {
+	fn respond(&self, request: &Request) -> ResponseTemplate {
+		let request: Value = match serde_json::from_slice(&request.body) {
+			Ok(request) => request,
+			Err(err) =>
+				return ResponseTemplate::new(200).set_body_json(json!({
+					"jsonrpc": "2.0",
+					"id": null,
+					"error": { "code": -32700, "message": err.to_string() }
+				})),
+		};
+		let method = request["method"].as_str().unwrap_or_default();
+		let params = request["params"].as_array().cloned().unwrap_or_default();
+
+		let body = match self.state.lock().unwrap().handle(method, &params) {
+			Ok(result) => json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }),
+			Err((code, message)) => json!({
+				"jsonrpc": "2.0",
+				"id": request["id"],
+				"error": { "code": code, "message": message }
+			}),
+		};
+		ResponseTemplate::new(200).set_body_json(body)
+	}
+}
+
+struct SimState {
+	height: u32,
+	network_magic: Option<u32>,
+	balances: HashMap<(H160, H160), i64>,
+	transactions: Vec<H256>,
+	application_logs: HashMap<H256, ApplicationLog>,
+	next_exception: Option<String>,
+}
+
+impl SimState {
+	fn new() -> Self {
+		Self {
+			height: 1000,
+			network_magic: None,
+			balances: HashMap::new(),
+			transactions: vec![],
+			application_logs: HashMap::new(),
+			next_exception: None,
+		}
+	}
+
+	fn balance(&self, token: &H160, account: &H160) -> i64 {
+		self.balances.get(&(*token, *account)).copied().unwrap_or_default()
+	}
+
+	fn handle(&mut self, method: &str, params: &[Value]) -> Result<Value, (i32, String)> {
+		match method {
+			"getblockcount" => Ok(json!(self.height)),
+			"getversion" => {
+				let mut version = NeoVersion::default();
+				if let (Some(protocol), Some(network_magic)) =
+					(version.protocol.as_mut(), self.network_magic)
+				{
+					protocol.network = network_magic;
+				}
+				Ok(json!(version))
+			},
+			"calculatenetworkfee" => Ok(json!({ "networkfee": NETWORK_FEE })),
+			"invokescript" => {
+				let script = Self::base64_param(params, 0)?;
+				let stack = contract_calls(&script)
+					.iter()
+					.map(|call| self.simulate(call))
+					.collect::<Vec<_>>();
+				Ok(Self::invocation_result(&script, stack))
+			},
+			"invokefunction" => {
+				let contract = params
+					.first()
+					.and_then(Value::as_str)
+					.and_then(|hash| H160::from_hex(hash.trim_start_matches("0x")).ok())
+					.ok_or((-32602, "Invalid contract hash".to_string()))?;
+				let method = params
+					.get(1)
+					.and_then(Value::as_str)
+					.ok_or((-32602, "Invalid method".to_string()))?;
+				let args: Vec<ContractParameter> =
+					serde_json::from_value(params.get(2).cloned().unwrap_or(json!([])))
+						.map_err(|err| (-32602, err.to_string()))?;
+				let call = ContractCall {
+					contract,
+					method: method.to_string(),
+					args: args.iter().map(SimValue::from).collect(),
+				};
+				Ok(Self::invocation_result(&[], vec![self.simulate(&call)]))
+			},
+			"sendrawtransaction" => {
+				let bytes = Self::base64_param(params, 0)?;
+				let hash = self.submit(&bytes)?;
+				Ok(json!({ "hash": format!("0x{}", hex::encode(hash.as_bytes())) }))
+			},
+			"getapplicationlog" => {
+				let hash = params
+					.first()
+					.and_then(Value::as_str)
+					.and_then(|hash| H256::from_str(hash.trim_start_matches("0x")).ok())
+					.ok_or((-32602, "Invalid transaction hash".to_string()))?;
+				self.application_logs
+					.get(&hash)
+					.map(|log| json!(log))
+					.ok_or((-100, "Unknown transaction/blockhash".to_string()))
+			},
+			_ => Err((-32601, format!("Method not found: {}", method))),
+		}
+	}
+
+	fn base64_param(params: &[Value], index: usize) -> Result<Vec<u8>, (i32, String)> {
+		params
+			.get(index)
+			.and_then(Value::as_str)
+			.and_then(|param| general_purpose::STANDARD.decode(param).ok())
+			.ok_or((-32602, "Invalid Base64 parameter".to_string()))
+	}
+
+	fn invocation_result(script: &[u8], stack: Vec<StackItem>) -> Value {
+		json!({
+			"script": general_purpose::STANDARD.encode(script),
+			"state": "HALT",
+			"gasconsumed": GAS_CONSUMED.to_string(),
+			"exception": null,
+			"stack": stack,
+		})
+	}
+
+	/// Evaluates a contract call against the ledger without changing it.
+	fn simulate(&self, call: &ContractCall) -> StackItem {
+		match (call.method.as_str(), call.args.as_slice()) {
+			("balanceOf", [account]) => match account.as_hash160() {
+				Some(account) =>
+					StackItem::Integer { value: self.balance(&call.contract, &account).into() },
+				None => StackItem::Any,
+			},
+			("transfer", [from, _, amount, ..]) => {
+				let sufficient = match (from.as_hash160(), amount.as_int()) {
+					(Some(from), Some(amount)) =>
+						amount >= 0 && self.balance(&call.contract, &from) >= amount,
+					_ => false,
+				};
+				StackItem::Boolean { value: sufficient }
+			},
+			_ => StackItem::Any,
+		}
+	}
+
+	/// Applies a transfer to the ledger, returning the emitted `Transfer` notification.
+	fn transfer(&mut self, call: &ContractCall) -> Option<LogNotification> {
+		let [from, to, amount, ..] = call.args.as_slice() else {
+			return None;
+		};
+		let (from, to, amount) = (from.as_hash160()?, to.as_hash160()?, amount.as_int()?);
+		if amount < 0 || self.balance(&call.contract, &from) < amount {
+			return None;
+		}
+		*self.balances.entry((call.contract, from)).or_default() -= amount;
+		*self.balances.entry((call.contract, to)).or_default() += amount;
+
+		// Script hashes are on the stack in little-endian order, as a node emits them.
+		let state = StackItem::Array {
+			value: vec![
+				StackItem::ByteString { value: general_purpose::STANDARD.encode(from.to_le_vec()) },
+				StackItem::ByteString { value: general_purpose::STANDARD.encode(to.to_le_vec()) },
+				StackItem::Integer { value: amount.into() },
+			],
+		};
+		Some(LogNotification::new(call.contract, "Transfer".to_string(), state))
+	}
+
+	fn submit(&mut self, bytes: &[u8]) -> Result<H256, (i32, String)> {
+		let (hash, script) = decode_transaction(bytes).map_err(|err| (-500, err.to_string()))?;
+		if self.application_logs.contains_key(&hash) {
+			return Err((-501, "Already exists".to_string()));
+		}
+
+		let execution = match self.next_exception.take() {
+			Some(exception) =>
+				Execution { state: VMState::Fault, exception: Some(exception), ..Self::execution() },
+			None => {
+				let mut stack = vec![];
+				let mut notifications = vec![];
+				for call in contract_calls(&script) {
+					let result = self.simulate(&call);
+					if call.method == "transfer" {
+						notifications.extend(self.transfer(&call));
+					}
+					stack.push(result);
+				}
+				Execution { stack, notifications, ..Self::execution() }
+			},
+		};
+
+		self.height += 1;
+		self.transactions.push(hash);
+		self.application_logs
+			.insert(hash, ApplicationLog { transaction_id: hash, executions: vec![execution] });
+		Ok(hash)
+	}
+
+	fn execution() -> Execution {
+		Execution {
+			trigger: "Application".to_string(),
+			state: VMState::Halt,
+			exception: None,
+			gas_consumed: GAS_CONSUMED.to_string(),
+			stack: vec![],
+			notifications: vec![],
+		}
+	}
+}
+
+/// Decodes the hash and the script of a serialized transaction.
+fn decode_transaction(bytes: &[u8]) -> Result<(H256, Bytes), CodecError> {
+	let mut reader = Decoder::new(bytes);
+	// version, nonce, system fee, network fee and valid until block
+	reader.read_bytes(25)?;
+	reader.read_serializable_list::<TransactionSigner>()?;
+	reader.read_serializable_list::<TransactionAttribute>()?;
+	let script = reader.read_var_bytes()?;
+
+	let unsigned = bytes[..bytes.len() - reader.available()].to_vec();
+	let hash = unsigned.hash256().into_iter().rev().collect::<Vec<u8>>();
+	Ok((H256::from_slice(&hash), script))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum SimValue {
+	Null,
+	Integer(BigInt),
+	Bytes(Vec<u8>),
+	Array(Vec<SimValue>),
+}
+
+impl SimValue {
+	/// Reads a script hash pushed either as raw bytes or, as `ScriptBuilder::push_param` does for
+	/// `Hash160` parameters, as a hex string.
+	fn as_hash160(&self) -> Option<H160> {
+		match self {
+			SimValue::Bytes(bytes) if bytes.len() == 20 => Some(H160::from_slice(bytes)),
+			SimValue::Bytes(bytes) if bytes.len() == 40 =>
+				H160::from_hex(std::str::from_utf8(bytes).ok()?).ok(),
+			_ => None,
+		}
+	}
+
+	fn as_int(&self) -> Option<i64> {
+		match self {
+			SimValue::Integer(value) => value.to_i64(),
+			_ => None,
+		}
+	}
+}
+
+impl From<&ContractParameter> for SimValue {
+	fn from(param: &ContractParameter) -> Self {
+		match &param.value {
+			Some(ParameterValue::H160(hash)) => H160::from_hex(hash.trim_start_matches("0x"))
+				.map(|hash| SimValue::Bytes(hash.as_bytes().to_vec()))
+				.unwrap_or(SimValue::Null),
+			Some(ParameterValue::Integer(value)) => SimValue::Integer(BigInt::from(*value)),
+			Some(ParameterValue::BigInteger(value)) =>
+				value.parse().map(SimValue::Integer).unwrap_or(SimValue::Null),
+			_ => SimValue::Null,
+		}
+	}
+}
+
+struct ContractCall {
+	contract: H160,
+	method: String,
+	args: Vec<SimValue>,
+}
+
+/// Extracts the `System.Contract.Call` invocations of a script, with arguments laid out the way
+/// `ScriptBuilder::contract_call` pushes them.
+fn contract_calls(script: &[u8]) -> Vec<ContractCall> {
+	let contract_call = hex::decode(InteropService::SystemContractCall.hash()).unwrap_or_default();
+	let mut calls = vec![];
+	let mut stack: Vec<SimValue> = vec![];
+	let mut position = 0;
+
+	while position < script.len() {
+		let Ok(op_code) = OpCode::try_from(script[position]) else {
+			break;
+		};
+		position += 1;
+
+		let operand_len = match op_code.operand_size() {
+			Some(size) if *size.prefix_size() > 0 => {
+				let prefix_size = *size.prefix_size() as usize;
+				let Some(prefix) = script.get(position..position + prefix_size) else {
+					break;
+				};
+				position += prefix_size;
+				prefix.iter().rev().fold(0usize, |len, byte| (len << 8) | *byte as usize)
+			},
+			Some(size) => *size.size() as usize,
+			None => 0,
+		};
+		let Some(operand) = script.get(position..position + operand_len) else {
+			break;
+		};
+		position += operand_len;
+
+		match op_code {
+			OpCode::PushInt8
+			| OpCode::PushInt16
+			| OpCode::PushInt32
+			| OpCode::PushInt64
+			| OpCode::PushInt128
+			| OpCode::PushInt256 => stack.push(SimValue::Integer(BigInt::from_signed_bytes_le(operand))),
+			OpCode::PushM1
+			| OpCode::Push0
+			| OpCode::Push1
+			| OpCode::Push2
+			| OpCode::Push3
+			| OpCode::Push4
+			| OpCode::Push5
+			| OpCode::Push6
+			| OpCode::Push7
+			| OpCode::Push8
+			| OpCode::Push9
+			| OpCode::Push10
+			| OpCode::Push11
+			| OpCode::Push12
+			| OpCode::Push13
+			| OpCode::Push14
+			| OpCode::Push15
+			| OpCode::Push16 =>
+				stack.push(SimValue::Integer(BigInt::from(op_code as i32 - OpCode::Push0 as i32))),
+			OpCode::PushNull => stack.push(SimValue::Null),
+			OpCode::PushData1 | OpCode::PushData2 | OpCode::PushData4 =>
+				stack.push(SimValue::Bytes(operand.to_vec())),
+			OpCode::NewArray0 => stack.push(SimValue::Array(vec![])),
+			OpCode::Pack => {
+				let count = stack.pop().and_then(|count| count.as_int()).unwrap_or_default();
+				// The top of the stack is the first element of the array
+				let mut items = stack.split_off(stack.len().saturating_sub(count as usize));
+				items.reverse();
+				stack.push(SimValue::Array(items));
+			},
+			OpCode::Syscall if operand == contract_call.as_slice() => {
+				let contract = stack.pop().and_then(|hash| hash.as_hash160());
+				let method = stack.pop();
+				let _call_flags = stack.pop();
+				let args = stack.pop();
+				if let (
+					Some(contract),
+					Some(SimValue::Bytes(method)),
+					Some(SimValue::Array(args)),
+				) = (contract, method, args)
+				{
+					calls.push(ContractCall {
+						contract,
+						method: String::from_utf8_lossy(&method).to_string(),
+						args,
+					});
+				}
+			},
+			_ => {},
+		}
+	}
+	calls
+}
+
+#[cfg(test)]
+mod tests {
+	use std::str::FromStr;
+
+	use primitive_types::H160;
+
+	use neo::{
+		builder::GAS_TOKEN_HASH,
+		prelude::{
+			APITrait, Account, AccountSigner, AccountTrait, ContractParameter, ScriptBuilder,
+			ScriptHashExtension, TransactionBuilder, VMState,
+		},
+	};
+
+	use super::SimBlockchain;
+
+	#[tokio::test]
+	async fn test_transfer_updates_balances() {
+		let sim = SimBlockchain::start().await;
+		let client = sim.client();
+
+		let sender =
+			Account::from_wif("L1WMhxazScMhUrdv34JqQb1HFSQmWeN2Kpc1R9JGKwL7CDNP21uR").unwrap();
+		let from = sender.get_script_hash();
+		let to = H160::from_str("969a77db482f74ce27105f760efa139223431394").unwrap();
+		let gas_hash = *GAS_TOKEN_HASH;
+		sim.set_balance(gas_hash, from, 10_00000000);
+
+		let script = ScriptBuilder::new()
+			.contract_call(
+				&gas_hash,
+				"transfer",
+				&[
+					ContractParameter::h160(&from),
+					ContractParameter::h160(&to),
+					ContractParameter::integer(3_00000000),
+					ContractParameter::any(),
+				],
+				None,
+			)
+			.unwrap()
+			.to_bytes();
+
+		let mut tx_builder = TransactionBuilder::with_client(&client);
+		tx_builder
+			.set_script(Some(script))
+			.set_signers(vec![AccountSigner::called_by_entry(&sender).unwrap().into()])
+			.unwrap();
+		let mut tx = tx_builder.sign().await.unwrap();
+		let sent = tx.send_tx().await.unwrap();
+
+		assert_eq!(sim.height(), 1001);
+		assert_eq!(sim.transactions(), vec![sent.hash]);
+
+		let log = client.get_application_log(sent.hash).await.unwrap();
+		let execution = log.get_first_execution().unwrap();
+		assert_eq!(execution.state, VMState::Halt);
+		let transfer = execution.notifications[0].as_nep17_transfer().unwrap();
+		assert_eq!((transfer.from, transfer.to), (from, to));
+		let state = execution.notifications[0].state.as_array().unwrap();
+		assert_eq!(state[0].as_address(), Some(sender.get_address()));
+		assert_eq!(state[1].as_address(), Some(to.to_address()));
+
+		let balance = client
+			.invoke_function(&gas_hash, "balanceOf".to_string(), vec![(&to).into()], None)
+			.await
+			.unwrap();
+		assert_eq!(balance.stack[0].as_int(), Ok(3_00000000));
+		assert_eq!(sim.balance(&gas_hash, &from), 7_00000000);
+	}
+}
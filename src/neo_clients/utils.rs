@@ -8,7 +8,8 @@ use wiremock::{Match, Request};
 
 use neo::prelude::{
 	private_key_to_public_key, HashableForVec, ProviderError, ScriptBuilder, ScriptHash,
-	ScriptHashExtension, Secp256r1PrivateKey, Secp256r1PublicKey, DEFAULT_ADDRESS_VERSION,
+	ScriptHashExtension, Secp256r1PrivateKey, Secp256r1PublicKey, TypeError,
+	DEFAULT_ADDRESS_VERSION,
 };
 
 use crate::prelude::VerificationScript;
@@ -59,6 +60,15 @@ pub fn public_key_to_address(public_key: &Secp256r1PublicKey) -> String {
 	script_hash_to_address(&script_hash)
 }
 
+/// Convert a hex-encoded public key to the address of its single-signature account, encoded with
+/// the given address version.
+pub fn public_key_string_to_address(pubkey_hex: &str, version: u8) -> Result<String, TypeError> {
+	let public_key =
+		Secp256r1PublicKey::from_encoded(pubkey_hex).ok_or(TypeError::InvalidPublicKey)?;
+	let script_hash = public_key_to_script_hash(&public_key);
+	Ok(encode_address(&script_hash, version))
+}
+
 /// Convert a public key to a script hash.
 pub fn public_key_to_script_hash(public_key: &Secp256r1PublicKey) -> ScriptHash {
 	let script = VerificationScript::from_public_key(public_key);
@@ -79,7 +89,11 @@ pub fn private_key_to_address(private_key: &Secp256r1PrivateKey) -> String {
 
 /// Convert a script hash to an address.
 pub fn script_hash_to_address(script_hash: &ScriptHash) -> String {
-	let mut data = vec![DEFAULT_ADDRESS_VERSION];
+	encode_address(script_hash, DEFAULT_ADDRESS_VERSION)
+}
+
+fn encode_address(script_hash: &ScriptHash, version: u8) -> String {
+	let mut data = vec![version];
 	let mut script_hash_bytes = script_hash.clone().as_bytes().to_vec();
 	script_hash_bytes.reverse();
 	data.extend_from_slice(&script_hash_bytes);
@@ -149,3 +163,29 @@ impl Match for BodyRegexMatcher {
 			.unwrap_or(false)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use neo::prelude::{TestConstants, TypeError, DEFAULT_ADDRESS_VERSION};
+
+	use super::public_key_string_to_address;
+
+	#[test]
+	fn test_public_key_string_to_address() {
+		assert_eq!(
+			public_key_string_to_address(
+				TestConstants::DEFAULT_ACCOUNT_PUBLIC_KEY,
+				DEFAULT_ADDRESS_VERSION
+			),
+			Ok(TestConstants::DEFAULT_ACCOUNT_ADDRESS.to_string())
+		);
+		assert_eq!(
+			public_key_string_to_address(&"ff".repeat(33), DEFAULT_ADDRESS_VERSION),
+			Err(TypeError::InvalidPublicKey)
+		);
+		assert_eq!(
+			public_key_string_to_address("not hex", DEFAULT_ADDRESS_VERSION),
+			Err(TypeError::InvalidPublicKey)
+		);
+	}
+}
@@ -54,6 +54,14 @@ impl<'a> Decoder<'a> {
 		Self { data, pointer: 0, marker: 0 }
 	}
 
+	/// Creates a decoder that reads at most `max_remaining` bytes of `data`.
+	///
+	/// Use it for data from untrusted sources, so that no length prefix can make the decoder
+	/// expect, or allocate for, more than that.
+	pub fn with_limit(data: &'a [u8], max_remaining: usize) -> Self {
+		Self::new(&data[..data.len().min(max_remaining)])
+	}
+
 	/// Reads a boolean value from the byte slice.
 	pub fn read_bool(&mut self) -> bool {
 		let val = self.data[self.pointer] == 1;
@@ -139,7 +147,7 @@ impl<'a> Decoder<'a> {
 
 	/// Reads a byte slice of the given length from the byte slice.
 	pub fn read_bytes(&mut self, length: usize) -> Result<Vec<u8>, CodecError> {
-		if self.pointer + length > self.data.len() {
+		if length > self.data.len().saturating_sub(self.pointer) {
 			return Err(CodecError::IndexOutOfBounds("Read beyond end of buffer".to_string()));
 		}
 		let result = self.data[self.pointer..self.pointer + length].to_vec();
@@ -149,23 +157,54 @@ impl<'a> Decoder<'a> {
 
 	/// Reads a variable-length byte slice from the byte slice.
 	pub fn read_var_bytes(&mut self) -> Result<Vec<u8>, CodecError> {
-		let len = self.read_var_int().unwrap() as usize;
+		let len = self.read_var_count()?;
 		self.read_bytes(len)
 	}
 
+	/// Reads the variable-length count of the bytes or items that follow.
+	///
+	/// Every byte or item takes at least one byte, so a count larger than the bytes left is
+	/// rejected before anything is allocated for it.
+	pub fn read_var_count(&mut self) -> Result<usize, CodecError> {
+		let length = self.read_var_int()?;
+		let available = self.available();
+		if length > available as u64 {
+			return Err(CodecError::LengthExceedsBuffer { length, available });
+		}
+		Ok(length as usize)
+	}
+
 	/// Reads a variable-length integer from the byte slice.
-	pub fn read_var_int(&mut self) -> Result<i64, CodecError> {
-		let first = self.read_u8();
-		match first {
-			0xfd => Ok(self.read_i16() as i64),
-			0xfe => Ok(self.read_i32() as i64),
-			0xff => Ok(self.read_i64() as i64),
-			_ => Ok(first as i64),
+	///
+	/// Values that would fit a shorter form are rejected, so every value has a single encoding.
+	pub fn read_var_int(&mut self) -> Result<u64, CodecError> {
+		let first = self.read_var_int_bytes(1)?[0];
+		let (value, min) = match first {
+			0xfd =>
+				(u16::from_le_bytes(self.read_var_int_bytes(2)?.try_into().unwrap()) as u64, 0xfd),
+			0xfe => (
+				u32::from_le_bytes(self.read_var_int_bytes(4)?.try_into().unwrap()) as u64,
+				0x1_0000,
+			),
+			0xff =>
+				(u64::from_le_bytes(self.read_var_int_bytes(8)?.try_into().unwrap()), 0x1_0000_0000),
+			_ => return Ok(first as u64),
+		};
+		if value < min {
+			return Err(CodecError::InvalidVarInt(format!(
+				"{value} is not encoded in its shortest form"
+			)));
 		}
+		Ok(value)
+	}
+
+	fn read_var_int_bytes(&mut self, length: usize) -> Result<Vec<u8>, CodecError> {
+		self.read_bytes(length)
+			.map_err(|_| CodecError::InvalidVarInt("Read beyond end of buffer".to_string()))
 	}
 
 	pub fn read_var_string(&mut self) -> Result<String, CodecError> {
-		let bytes = self.read_var_bytes().unwrap();
+		let bytes = self.read_var_bytes()?;
 
 		let string = match String::from_utf8(bytes.to_vec()) {
 			Ok(s) => s,
@@ -219,7 +258,7 @@ impl<'a> Decoder<'a> {
 
 	/// Reads a push string from the byte slice.
 	pub fn read_push_string(&mut self) -> Result<String, CodecError> {
-		let bytes = self.read_push_bytes().unwrap();
+		let bytes = self.read_push_bytes()?;
 		String::from_utf8(Vec::from(bytes))
 			.map_err(|_| CodecError::InvalidEncoding("Invalid UTF-8".to_string()))
 	}
@@ -231,12 +270,10 @@ impl<'a> Decoder<'a> {
 
 	/// Reads a list of deserializable values from the byte slice.
 	pub fn read_serializable_list<T: NeoSerializable>(&mut self) -> Result<Vec<T>, CodecError> {
-		let len = self.read_var_int().unwrap();
-		let mut list = Vec::with_capacity(len as usize);
+		let len = self.read_var_count()?;
+		let mut list = Vec::with_capacity(len);
 		for _ in 0..len {
-			T::decode(self)
-				.and_then(|item| Ok(list.push(item)))
-				.expect("TODO: panic message");
+			list.push(self.decode_item()?);
 		}
 		Ok(list)
 	}
@@ -244,19 +281,19 @@ impl<'a> Decoder<'a> {
 	pub fn read_serializable_list_var_bytes<T: NeoSerializable>(
 		&mut self,
 	) -> Result<Vec<T>, CodecError> {
-		let len = self.read_var_int().unwrap();
-		let mut bytes_read = 0;
+		let len = self.read_var_count()?;
 		let offset = self.pointer;
-		let mut list = Vec::with_capacity(len as usize);
-		while bytes_read < len {
-			T::decode(self)
-				.and_then(|item| Ok(list.push(item)))
-				.expect("TODO: panic message");
-			bytes_read = (self.pointer - offset) as i64;
+		let mut list = Vec::new();
+		while self.pointer - offset < len {
+			list.push(self.decode_item()?);
 		}
 		Ok(list)
 	}
 
+	fn decode_item<T: NeoSerializable>(&mut self) -> Result<T, CodecError> {
+		T::decode(self).map_err(|e| CodecError::InvalidEncoding(format!("{e:?}")))
+	}
+
 	pub fn mark(&mut self) {
 		self.marker = self.pointer;
 	}
@@ -290,7 +327,7 @@ impl<'a> Decoder<'a> {
 mod tests {
 	use num_bigint::BigInt;
 
-	use neo::prelude::Decoder;
+	use neo::prelude::{CodecError, Decoder, Encoder};
 
 	#[test]
 	fn test_read_push_data_bytes() {
@@ -375,4 +412,89 @@ mod tests {
 		let custom = [0x11, 0x33, 0x22, 0x8c, 0xae, 0x00, 0x00, 0x00, 0xff];
 		assert_eq!(Decoder::new(&custom).read_i64(), 749_675_361_041);
 	}
+
+	#[test]
+	fn test_var_int_round_trip() {
+		for (value, encoded) in [
+			(0xfc, "fc"),
+			(0xfd, "fdfd00"),
+			(0xffff, "fdffff"),
+			(0x1_0000, "fe00000100"),
+			(0xffff_ffff, "feffffffff"),
+			(0x1_0000_0000, "ff0000000001000000"),
+			(u64::MAX, "ffffffffffffffffff"),
+		] {
+			let mut writer = Encoder::new();
+			writer.write_var_int(value);
+			assert_eq!(hex::encode(writer.to_bytes()), encoded);
+
+			let bytes = hex::decode(encoded).unwrap();
+			let mut reader = Decoder::new(&bytes);
+			assert_eq!(reader.read_var_int().unwrap(), value);
+			assert_eq!(reader.available(), 0);
+		}
+	}
+
+	#[test]
+	fn test_read_var_int_rejects_malformed_input() {
+		for encoded in [
+			// Not the shortest form
+			"fdfc00",
+			"fe0000ffff",
+			"ffffffffff00000000",
+			// Truncated
+			"",
+			"fd01",
+			"fe010000",
+			"ff01000000000000",
+		] {
+			let bytes = hex::decode(encoded).unwrap();
+			assert!(matches!(
+				Decoder::new(&bytes).read_var_int(),
+				Err(CodecError::InvalidVarInt(_))
+			));
+		}
+
+		// A length larger than the data fails instead of overflowing
+		let bytes = hex::decode("ffffffffffffffffff").unwrap();
+		assert!(Decoder::new(&bytes).read_var_bytes().is_err());
+	}
+
+	#[test]
+	fn test_count_larger_than_buffer_is_rejected() {
+		// Claims 2^32 items but only 3 bytes follow
+		let bytes = hex::decode("ff0000000001000000010203").unwrap();
+		assert_eq!(
+			Decoder::new(&bytes).read_serializable_list::<u8>(),
+			Err(CodecError::LengthExceedsBuffer { length: 1 << 32, available: 3 })
+		);
+		assert_eq!(
+			Decoder::new(&bytes).read_var_bytes(),
+			Err(CodecError::LengthExceedsBuffer { length: 1 << 32, available: 3 })
+		);
+		assert_eq!(
+			Decoder::new(&bytes).read_var_string(),
+			Err(CodecError::LengthExceedsBuffer { length: 1 << 32, available: 3 })
+		);
+		// PUSHDATA1 of 5 bytes, but only 1 follows
+		let push = hex::decode("0c0561").unwrap();
+		assert!(matches!(
+			Decoder::new(&push).read_push_string(),
+			Err(CodecError::IndexOutOfBounds(_))
+		));
+	}
+
+	#[test]
+	fn test_with_limit() {
+		let bytes = hex::decode("0301020304").unwrap();
+		assert_eq!(Decoder::new(&bytes).read_var_bytes().unwrap(), vec![1, 2, 3]);
+
+		let mut decoder = Decoder::with_limit(&bytes, 3);
+		assert_eq!(decoder.available(), 3);
+		assert_eq!(
+			decoder.read_var_bytes(),
+			Err(CodecError::LengthExceedsBuffer { length: 3, available: 2 })
+		);
+		assert_eq!(Decoder::with_limit(&bytes, 100).available(), 5);
+	}
 }
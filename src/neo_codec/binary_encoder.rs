@@ -67,21 +67,19 @@ impl Encoder {
 		self.data.extend_from_slice(bytes);
 	}
 
-	fn write_var_int(&mut self, v: i64) {
-		if v < 0 {
-			panic!("Negative value not allowed")
-		}
+	/// Writes a variable-length integer in the shortest of its four forms.
+	pub fn write_var_int(&mut self, v: u64) {
 		if v < 0xfd {
 			self.write_u8(v as u8)
-		} else if v <= u16::MAX as i64 {
+		} else if v <= u16::MAX as u64 {
 			self.write_u8(0xfd);
 			self.write_u16(v as u16);
-		} else if v <= u32::MAX as i64 {
+		} else if v <= u32::MAX as u64 {
 			self.write_u8(0xfe);
 			self.write_u32(v as u32);
 		} else {
 			self.write_u8(0xff);
-			self.write_i64(v);
+			self.data.extend_from_slice(&v.to_le_bytes());
 		}
 	}
 
@@ -104,7 +102,7 @@ impl Encoder {
 	}
 
 	pub fn write_var_bytes(&mut self, bytes: &[u8]) {
-		self.write_var_int(bytes.len() as i64);
+		self.write_var_int(bytes.len() as u64);
 		self.write_bytes(bytes);
 	}
 
@@ -116,18 +114,18 @@ impl Encoder {
 	}
 
 	pub fn write_serializable_variable_bytes<S: NeoSerializable>(&mut self, values: &S) {
-		self.write_var_int(values.to_array().len() as i64);
+		self.write_var_int(values.to_array().len() as u64);
 		values.encode(self);
 	}
 
 	pub fn write_serializable_variable_list<S: NeoSerializable>(&mut self, values: &[S]) {
-		self.write_var_int(values.len() as i64);
+		self.write_var_int(values.len() as u64);
 		self.write_serializable_list_fixed(values);
 	}
 
 	pub fn write_serializable_variable_list_bytes<S: NeoSerializable>(&mut self, values: &[S]) {
 		let total_size: usize = values.iter().map(|item| item.to_array().len()).sum();
-		self.write_var_int(total_size as i64);
+		self.write_var_int(total_size as u64);
 		self.write_serializable_list_fixed(values);
 	}
 
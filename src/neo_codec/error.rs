@@ -17,6 +17,10 @@ pub enum CodecError {
 	InvalidEncoding(String),
 	#[error("Invalid op code")]
 	InvalidOpCode,
+	#[error("Invalid variable-length integer: {0}")]
+	InvalidVarInt(String),
+	#[error("Length {length} exceeds the {available} bytes left to read")]
+	LengthExceedsBuffer { length: u64, available: usize },
 	#[error(transparent)]
 	TryFromPrimitiveError(#[from] TryFromPrimitiveError<OpCode>),
 }
@@ -39,6 +43,15 @@ impl Hash for CodecError {
 			},
 			CodecError::InvalidOpCode => 4.hash(state),
 			CodecError::TryFromPrimitiveError(_) => 5.hash(state),
+			CodecError::InvalidVarInt(s) => {
+				6.hash(state);
+				s.hash(state);
+			},
+			CodecError::LengthExceedsBuffer { length, available } => {
+				7.hash(state);
+				length.hash(state);
+				available.hash(state);
+			},
 		}
 	}
 }
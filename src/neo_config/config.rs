@@ -3,6 +3,7 @@ use primitive_types::H160;
 use serde::{Deserialize, Serialize};
 use std::{
 	collections::HashMap,
+	fmt,
 	hash::{Hash, Hasher},
 	sync::{Arc, Mutex},
 };
@@ -34,6 +35,68 @@ impl NeoNetwork {
 	}
 }
 
+/// The magic number identifying a network. It is part of the data signed for a transaction, so a
+/// transaction signed for one network cannot be replayed on another.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum NetworkMagic {
+	MainNet,
+	TestNet,
+	NeoXMainNet,
+	NeoXTestNet,
+	Custom(u32),
+}
+
+impl NetworkMagic {
+	pub const MAINNET: u32 = 860833102;
+	pub const TESTNET: u32 = 894710606;
+	pub const NEOX_MAINNET: u32 = 47763;
+	pub const NEOX_TESTNET: u32 = 12227332;
+
+	pub fn from_u32(magic: u32) -> Self {
+		match magic {
+			Self::MAINNET => NetworkMagic::MainNet,
+			Self::TESTNET => NetworkMagic::TestNet,
+			Self::NEOX_MAINNET => NetworkMagic::NeoXMainNet,
+			Self::NEOX_TESTNET => NetworkMagic::NeoXTestNet,
+			magic => NetworkMagic::Custom(magic),
+		}
+	}
+
+	pub fn as_u32(&self) -> u32 {
+		match self {
+			NetworkMagic::MainNet => Self::MAINNET,
+			NetworkMagic::TestNet => Self::TESTNET,
+			NetworkMagic::NeoXMainNet => Self::NEOX_MAINNET,
+			NetworkMagic::NeoXTestNet => Self::NEOX_TESTNET,
+			NetworkMagic::Custom(magic) => *magic,
+		}
+	}
+}
+
+impl From<u32> for NetworkMagic {
+	fn from(magic: u32) -> Self {
+		Self::from_u32(magic)
+	}
+}
+
+impl From<NetworkMagic> for u32 {
+	fn from(magic: NetworkMagic) -> Self {
+		magic.as_u32()
+	}
+}
+
+impl fmt::Display for NetworkMagic {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			NetworkMagic::MainNet => write!(f, "MainNet ({})", Self::MAINNET),
+			NetworkMagic::TestNet => write!(f, "TestNet ({})", Self::TESTNET),
+			NetworkMagic::NeoXMainNet => write!(f, "Neo X MainNet ({})", Self::NEOX_MAINNET),
+			NetworkMagic::NeoXTestNet => write!(f, "Neo X TestNet ({})", Self::NEOX_TESTNET),
+			NetworkMagic::Custom(magic) => write!(f, "Custom ({})", magic),
+		}
+	}
+}
+
 pub const DEFAULT_BLOCK_TIME: u64 = 15_000;
 pub const DEFAULT_ADDRESS_VERSION: u8 = 0x35;
 pub const MAX_VALID_UNTIL_BLOCK_INCREMENT_BASE: u64 = 86_400_000;
@@ -82,7 +145,7 @@ impl Default for NeoConfig {
 		hardforks.insert("HF_Domovoi".to_string(), 5570000);
 
 		NeoConfig {
-			network: Some(860833102), // TestNet
+			network: Some(NetworkMagic::MAINNET),
 			address_version: 53,
 			milliseconds_per_block: 15000,
 			max_transactions_per_block: 512,
@@ -145,6 +208,15 @@ impl NeoConfig {
 		Ok(())
 	}
 
+	/// The configured network magic, if any.
+	pub fn network_magic(&self) -> Option<NetworkMagic> {
+		self.network.map(NetworkMagic::from_u32)
+	}
+
+	pub fn set_network_magic(&mut self, magic: NetworkMagic) {
+		self.network = Some(magic.as_u32());
+	}
+
 	pub fn get_max_valid_until_block_increment(&self) -> u32 {
 		(MAX_VALID_UNTIL_BLOCK_INCREMENT_BASE / self.milliseconds_per_block as u64) as u32
 	}
@@ -157,7 +229,7 @@ impl NeoConfig {
 		hardforks.insert("HF_Domovoi".to_string(), 5570000);
 
 		NeoConfig {
-			network: Some(860833102),
+			network: Some(NetworkMagic::MAINNET),
 			address_version: 53,
 			milliseconds_per_block: 15000,
 			max_transactions_per_block: 512,
@@ -233,3 +305,35 @@ impl Counter {
 		v
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::{NeoConfig, NetworkMagic};
+
+	#[test]
+	fn test_network_magic_round_trip() {
+		for (magic, network) in [
+			(860833102, NetworkMagic::MainNet),
+			(894710606, NetworkMagic::TestNet),
+			(47763, NetworkMagic::NeoXMainNet),
+			(12227332, NetworkMagic::NeoXTestNet),
+		] {
+			assert_eq!(NetworkMagic::from_u32(magic), network);
+			assert_eq!(network.as_u32(), magic);
+		}
+		assert_eq!(NetworkMagic::MainNet.to_string(), "MainNet (860833102)");
+	}
+
+	#[test]
+	fn test_network_magic_custom() {
+		let network = NetworkMagic::from(1234567);
+		assert_eq!(network, NetworkMagic::Custom(1234567));
+		assert_eq!(u32::from(network), 1234567);
+		assert_eq!(network.to_string(), "Custom (1234567)");
+
+		let mut config = NeoConfig::default();
+		config.set_network_magic(network);
+		assert_eq!(config.network, Some(1234567));
+		assert_eq!(config.network_magic(), Some(network));
+	}
+}
@@ -16,6 +16,7 @@ impl NeoConstants {
 	// Transactions & Contracts
 	pub const CURRENT_TX_VERSION: u8 = 0;
 	pub const MAX_TRANSACTION_SIZE: u32 = 102400;
+	pub const MAX_TRANSACTION_SCRIPT_SIZE: u32 = 0xFFFF;
 	pub const MAX_TRANSACTION_ATTRIBUTES: u32 = 16;
 	pub const MAX_SIGNER_SUBITEMS: u32 = 16;
 	pub const MAX_MANIFEST_SIZE: u32 = 0xFFFF;
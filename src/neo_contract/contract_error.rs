@@ -1,6 +1,6 @@
 use thiserror::Error;
 
-use neo::prelude::ProviderError;
+use neo::prelude::{NeoVMStateType, ProviderError};
 
 /// Custom error type for contract-related errors
 #[derive(Error, Debug)]
@@ -32,6 +32,16 @@ pub enum ContractError {
 	/// Error indicating an invalid argument error
 	#[error("Invalid argument error: {0}")]
 	InvalidArgError(String),
+	/// Error indicating that an invocation faulted, with the VM's exception message if it gave one
+	#[error(
+		"Invocation ended in state {}: {}",
+		.state.as_ref(),
+		.exception.as_deref().unwrap_or("no exception message")
+	)]
+	VmFault { state: NeoVMStateType, exception: Option<String> },
+	/// Error indicating that waiting for something on chain timed out
+	#[error("Timeout: {0}")]
+	Timeout(String),
 	/// Error indicating a provider error, transparently wrapped
 	#[error(transparent)]
 	ProviderError(#[from] ProviderError),
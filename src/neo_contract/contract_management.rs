@@ -116,9 +116,158 @@ impl<'a, P: JsonRpcProvider + 'static> ContractManagement<'a, P> {
 		manifest: &[u8],
 		data: Option<ContractParameter>,
 	) -> Result<TransactionBuilder<P>, ContractError> {
-		let params = vec![nef.into(), manifest.into(), data.unwrap()];
-		let tx = self.invoke_function("deploy", params).await;
-		tx
+		let mut params = vec![nef.into(), manifest.into()];
+		params.extend(data);
+		self.invoke_function("deploy", params).await
+	}
+
+	/// Estimates the GAS a deployment costs by running it with `invokescript`, after checking
+	/// the NEF and manifest with [`Self::validate_manifest`]. `signers` must include the account
+	/// that will deploy the contract, as the contract hash is derived from it.
+	///
+	/// Fails if the deployment would fault, e.g. because the contract is already deployed.
+	pub async fn estimate_deploy_cost(
+		&self,
+		nef: &NefFile,
+		manifest: &ContractManifest,
+		signers: Vec<Signer>,
+	) -> Result<u64, ContractError> {
+		Self::validate_manifest(nef, manifest)?;
+		let manifest = serde_json::to_vec(manifest)
+			.map_err(|e| ContractError::InvalidArgError(e.to_string()))?;
+		let script = self
+			.build_invoke_function_script("deploy", vec![nef.into(), manifest.as_slice().into()])
+			.await?;
+
+		let result = self
+			.provider
+			.ok_or_else(|| ContractError::InvalidStateError("No provider set".to_string()))?
+			.invoke_script(hex::encode(&script), signers)
+			.await?;
+		if result.has_state_fault() {
+			return Err(ContractError::RuntimeError(format!(
+				"The deployment would fail: {}",
+				result.exception.unwrap_or_default()
+			)));
+		}
+		result.gas_consumed.parse().map_err(|_| {
+			ContractError::UnexpectedReturnType(format!(
+				"Invalid gas consumed: {}",
+				result.gas_consumed
+			))
+		})
+	}
+
+	/// Checks a NEF file and manifest for errors that would make a deployment fail: a checksum
+	/// that does not match the NEF file, a missing name or ABI, methods or events without a name
+	/// or with duplicate parameter names, overloads with the same number of parameters, and
+	/// method offsets outside of the script.
+	pub fn validate_manifest(
+		nef: &NefFile,
+		manifest: &ContractManifest,
+	) -> Result<(), ContractError> {
+		let invalid = |message: String| Err(ContractError::InvalidArgError(message));
+		if !nef.has_valid_checksum() {
+			return invalid("The NEF checksum does not match its content".to_string());
+		}
+		if manifest.name.as_deref().map_or(true, str::is_empty) {
+			return invalid("The manifest has no contract name".to_string());
+		}
+		let abi = match &manifest.abi {
+			Some(abi) if !abi.methods.is_empty() => abi,
+			_ => return invalid("The manifest declares no methods".to_string()),
+		};
+
+		for (i, method) in abi.methods.iter().enumerate() {
+			if method.name.is_empty() {
+				return invalid(format!("Method {} has no name", i));
+			}
+			if method.offset >= nef.script.len() {
+				return invalid(format!(
+					"Method {} starts at offset {}, outside of the {} byte script",
+					method.name,
+					method.offset,
+					nef.script.len()
+				));
+			}
+			if abi.methods[..i].iter().any(|other| {
+				other.name == method.name && other.parameters.len() == method.parameters.len()
+			}) {
+				return invalid(format!(
+					"Method {} is declared twice with {} parameters",
+					method.name,
+					method.parameters.len()
+				));
+			}
+			let names: Vec<&str> = method.parameters.iter().map(|p| p.name.as_str()).collect();
+			if let Some(name) = Self::first_duplicate(&names) {
+				return invalid(format!("Method {} has two parameters named {}", method.name, name));
+			}
+		}
+
+		for (i, event) in abi.events.iter().enumerate() {
+			if event.name.is_empty() {
+				return invalid(format!("Event {} has no name", i));
+			}
+			let names: Vec<&str> =
+				event.parameters.iter().map(|p| p.name.as_deref().unwrap_or_default()).collect();
+			if let Some(name) = Self::first_duplicate(&names) {
+				return invalid(format!("Event {} has two parameters named {}", event.name, name));
+			}
+		}
+		Ok(())
+	}
+
+	fn first_duplicate<'n>(names: &[&'n str]) -> Option<&'n str> {
+		(0..names.len()).find(|&i| names[..i].contains(&names[i])).map(|i| names[i])
+	}
+
+	/// Deploys a contract whose manifest declares groups, signing the groups of `group_keys`.
+	///
+	/// A group signature is the group key's signature of the contract hash, which is derived
+	/// from `sender`, the account deploying the contract, the NEF checksum and the contract name.
+	/// Every key must belong to one of the manifest's groups.
+	pub async fn deploy_with_groups(
+		&self,
+		nef: &NefFile,
+		mut manifest: ContractManifest,
+		sender: &H160,
+		group_keys: &[KeyPair],
+		data: Option<ContractParameter>,
+	) -> Result<TransactionBuilder<P>, ContractError> {
+		let name = manifest.name.clone().ok_or_else(|| {
+			ContractError::InvalidArgError("The manifest has no contract name".to_string())
+		})?;
+		let checksum = nef.checksum_integer().ok_or_else(|| {
+			ContractError::InvalidArgError("The NEF file has no checksum".to_string())
+		})?;
+		let contract_hash = Self::calc_contract_hash(*sender, checksum, &name)?;
+		// Groups sign the hash as the network serializes it, i.e. in little-endian order
+		let mut message = contract_hash.to_vec();
+		message.reverse();
+
+		for key_pair in group_keys {
+			let pub_key = key_pair.public_key().get_encoded_compressed_hex();
+			let group = manifest
+				.groups
+				.iter_mut()
+				.find(|group| group.pub_key.eq_ignore_ascii_case(&pub_key))
+				.ok_or_else(|| {
+					ContractError::InvalidArgError(format!(
+						"Key {} does not belong to any group of the manifest",
+						pub_key
+					))
+				})?;
+			let signature = key_pair
+				.private_key
+				.sign_tx(&message)
+				.map_err(|e| ContractError::RuntimeError(e.to_string()))?;
+			group.signature = base64::encode(signature.to_bytes());
+		}
+
+		let manifest = serde_json::to_vec(&manifest)
+			.map_err(|e| ContractError::InvalidArgError(e.to_string()))?;
+		self.deploy(nef, &manifest, data).await
 	}
 }
 
@@ -138,3 +287,177 @@ impl<'a, P: JsonRpcProvider> SmartContractTrait<'a> for ContractManagement<'a, P
 		self.provider
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use std::str::FromStr;
+
+	use num_bigint::BigInt;
+	use primitive_types::H160;
+
+	use neo::prelude::{
+		Account, AccountSigner, AccountTrait, ContractError, ContractGroup, ContractManagement,
+		ContractManifest, Decoder, GasToken, HashableForVec, HttpProvider, KeyPair, MockClient,
+		NefFile, NeoSerializable, NeoToken, OpCode, PolicyContract, ScriptBuilder,
+		ScriptHashExtension, Secp256r1Signature, SmartContractTrait, TestConstants,
+	};
+
+	fn test_contract() -> (NefFile, ContractManifest) {
+		let nef_bytes =
+			include_bytes!("../../test_resources/responses/contract/contracts/TestContract.nef");
+		let nef = NefFile::decode(&mut Decoder::new(nef_bytes)).unwrap();
+		let manifest = serde_json::from_str(include_str!(
+			"../../test_resources/responses/contract/contracts/TestContract.manifest.json"
+		))
+		.unwrap();
+		(nef, manifest)
+	}
+
+	#[test]
+	fn test_calc_native_contract_hashes() {
+		assert_eq!(
+			NeoToken::<HttpProvider>::new(None).script_hash(),
+			H160::from_str("0xef4073a0f2b305a38ec4050e4d3d28bc40ea63f5").unwrap()
+		);
+		assert_eq!(
+			GasToken::<HttpProvider>::new(None).script_hash(),
+			H160::from_str("0xd2a4cff31913016155e38e474a2c06d08be276cf").unwrap()
+		);
+		assert_eq!(
+			PolicyContract::<HttpProvider>::new(None).script_hash(),
+			H160::from_str("0xcc5e4edd9f5f8dba8bb65734541df7a1c081c67b").unwrap()
+		);
+	}
+
+	#[test]
+	fn test_calc_contract_hash_pushes_sender_in_little_endian() {
+		let address = TestConstants::DEFAULT_ACCOUNT_ADDRESS;
+		let sender = H160::from_address(address).unwrap();
+		// An address holds the script hash as the network serializes it
+		let sender_le = &bs58::decode(address).into_vec().unwrap()[1..21];
+
+		// ABORT, PUSHDATA1 of the sender, PUSHINT32 of the checksum, PUSHDATA1 of the name
+		let mut script = vec![0x38, 0x0c, 0x14];
+		script.extend_from_slice(sender_le);
+		script.extend_from_slice(&[0x02, 0x78, 0x56, 0x34, 0x12]);
+		script.extend_from_slice(&[0x0c, 0x04]);
+		script.extend_from_slice(b"Test");
+		let mut expected = script.sha256_ripemd160();
+		expected.reverse();
+
+		assert_eq!(
+			ContractManagement::<HttpProvider>::calc_contract_hash(sender, 0x12345678, "Test")
+				.unwrap(),
+			H160::from_slice(&expected)
+		);
+	}
+
+	#[tokio::test]
+	async fn test_deploy_with_group_signature() {
+		let (nef, mut manifest) = test_contract();
+		let group_key = KeyPair::new_random();
+		let pub_key = group_key.public_key().get_encoded_compressed_hex();
+		manifest.groups = vec![ContractGroup { pub_key, signature: String::new() }];
+
+		let address = TestConstants::DEFAULT_ACCOUNT_ADDRESS;
+		let sender = H160::from_address(address).unwrap();
+		let management = ContractManagement::<HttpProvider>::new(H160::repeat_byte(0xfd), None);
+		let tb = management
+			.deploy_with_groups(&nef, manifest.clone(), &sender, &[group_key.clone()], None)
+			.await
+			.unwrap();
+
+		// The groups sign the contract hash as the network serializes it, which is the hash of
+		// the script deriving it, built here from the sender in the address and the raw NEF file
+		let nef_bytes =
+			include_bytes!("../../test_resources/responses/contract/contracts/TestContract.nef");
+		let checksum = u32::from_le_bytes(nef_bytes[nef_bytes.len() - 4..].try_into().unwrap());
+		let mut sb = ScriptBuilder::new();
+		sb.op_code(&[OpCode::Abort])
+			.push_data(bs58::decode(address).into_vec().unwrap()[1..21].to_vec())
+			.push_integer(BigInt::from(checksum))
+			.push_data(manifest.name.clone().unwrap().into_bytes());
+		let message = sb.to_bytes().sha256_ripemd160();
+
+		let script = tb.script().clone().unwrap();
+		let prefix = br#""signature":""#;
+		let start = script
+			.windows(prefix.len())
+			.position(|window| window == prefix)
+			.expect("the deployed manifest has no group signature")
+			+ prefix.len();
+		let end = start + script[start..].iter().position(|&b| b == b'"').unwrap();
+		let signature =
+			Secp256r1Signature::from_bytes(&base64::decode(&script[start..end]).unwrap()).unwrap();
+		assert!(group_key.public_key().verify(&message, &signature).is_ok());
+
+		let other_key = KeyPair::new_random();
+		let err = management
+			.deploy_with_groups(&nef, manifest, &sender, &[other_key], None)
+			.await
+			.err()
+			.unwrap();
+		assert!(matches!(err, ContractError::InvalidArgError(_)));
+	}
+
+	#[tokio::test]
+	async fn test_deploy_with_groups_without_checksum_fails() {
+		let (mut nef, manifest) = test_contract();
+		nef.checksum.clear();
+		assert_eq!(nef.checksum_integer(), None);
+
+		let management = ContractManagement::<HttpProvider>::new(H160::repeat_byte(0xfd), None);
+		let err = management
+			.deploy_with_groups(&nef, manifest, &H160::repeat_byte(0x01), &[], None)
+			.await
+			.err()
+			.unwrap();
+		assert!(matches!(err, ContractError::InvalidArgError(_)));
+	}
+
+	#[test]
+	fn test_validate_manifest() {
+		let (mut nef, manifest) = test_contract();
+		assert!(ContractManagement::<HttpProvider>::validate_manifest(&nef, &manifest).is_ok());
+
+		let mut overloaded = manifest.clone();
+		let abi = overloaded.abi.as_mut().unwrap();
+		abi.methods.push(abi.methods[0].clone());
+		assert!(matches!(
+			ContractManagement::<HttpProvider>::validate_manifest(&nef, &overloaded),
+			Err(ContractError::InvalidArgError(_))
+		));
+
+		let mut out_of_script = manifest.clone();
+		out_of_script.abi.as_mut().unwrap().methods[0].offset = nef.script.len();
+		let result = ContractManagement::<HttpProvider>::validate_manifest(&nef, &out_of_script);
+		assert!(result.is_err());
+
+		nef.checksum[0] ^= 0xff;
+		assert!(ContractManagement::<HttpProvider>::validate_manifest(&nef, &manifest).is_err());
+	}
+
+	#[tokio::test]
+	async fn test_estimate_deploy_cost() {
+		let mut mock_provider = MockClient::new().await;
+		mock_provider
+			.mock_response_with_file_ignore_param("invokescript", "invokescript_symbol_neo.json")
+			.await;
+		mock_provider.mount_mocks().await;
+		let client = mock_provider.into_client();
+
+		let (nef, manifest) = test_contract();
+		let account = Account::create().unwrap();
+		let management = ContractManagement::new(H160::repeat_byte(0xfd), Some(&client));
+		let cost = management
+			.estimate_deploy_cost(
+				&nef,
+				&manifest,
+				vec![AccountSigner::called_by_entry(&account).unwrap().into()],
+			)
+			.await
+			.unwrap();
+
+		assert_eq!(cost, 984060);
+	}
+}
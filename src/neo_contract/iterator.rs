@@ -1,11 +1,13 @@
 use std::{fmt, sync::Arc};
 
+use futures::{stream, Stream, StreamExt};
+
 use neo::prelude::*;
 
 pub struct NeoIterator<'a, T, P: JsonRpcProvider> {
 	session_id: String,
 	iterator_id: String,
-	mapper: Arc<dyn Fn(StackItem) -> T + Send + Sync>,
+	mapper: Arc<dyn Fn(StackItem) -> Result<T, ContractError> + Send + Sync>,
 	provider: Option<&'a RpcClient<P>>,
 }
 
@@ -24,28 +26,78 @@ impl<'a, T, P: JsonRpcProvider> NeoIterator<'a, T, P> {
 	pub fn new(
 		session_id: String,
 		iterator_id: String,
-		mapper: Arc<dyn Fn(StackItem) -> T + Send + Sync>,
+		mapper: Arc<dyn Fn(StackItem) -> Result<T, ContractError> + Send + Sync>,
 		provider: Option<&'a RpcClient<P>>,
 	) -> Self {
 		Self { session_id, iterator_id, mapper, provider }
 	}
 
+	/// Gets the next `count` items of the iterator. Fails if an item cannot be mapped.
 	pub async fn traverse(&self, count: i32) -> Result<Vec<T>, ContractError> {
 		let result = self
-			.provider
-			.unwrap()
+			.provider()?
 			.traverse_iterator(self.session_id.clone(), self.iterator_id.clone(), count as u32)
 			.await?;
-		let mapped = result.iter().map(|item| (self.mapper)(item.clone())).collect();
-		Ok(mapped)
+		result.iter().map(|item| (self.mapper)(item.clone())).collect()
 	}
 
 	pub async fn terminate_session(&self) -> Result<(), ContractError> {
-		self.provider
-			.unwrap()
-			.terminate_session(&self.session_id)
-			.await
-			.expect("Could not terminate session");
+		self.provider()?.terminate_session(&self.session_id).await?;
 		Ok(())
 	}
+
+	fn provider(&self) -> Result<&'a RpcClient<P>, ContractError> {
+		self.provider
+			.ok_or(ContractError::InvalidStateError("The iterator has no client".to_string()))
+	}
+
+	/// Turns the iterator into a stream of its items, traversing `page_size` items per request.
+	/// The session is terminated once the iterator is exhausted or traversing it fails.
+	pub fn into_stream(self, page_size: u32) -> impl Stream<Item = Result<T, ContractError>> + 'a
+	where
+		T: 'a,
+	{
+		let page_size = page_size.max(1);
+		stream::unfold(Some(self), move |iterator| async move {
+			let iterator = iterator?;
+			match iterator.traverse(page_size as i32).await {
+				Ok(items) => {
+					let exhausted = items.len() < page_size as usize;
+					if exhausted {
+						let _ = iterator.terminate_session().await;
+					}
+					let items: Vec<_> = items.into_iter().map(Ok).collect();
+					Some((items, (!exhausted).then_some(iterator)))
+				},
+				Err(err) => {
+					let _ = iterator.terminate_session().await;
+					Some((vec![Err(err)], None))
+				},
+			}
+		})
+		.flat_map(stream::iter)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+
+	use neo::prelude::{ContractError, HttpProvider, NeoIterator, StackItem};
+
+	#[tokio::test]
+	async fn test_iterator_without_client() {
+		let iterator: NeoIterator<StackItem, HttpProvider> = NeoIterator::new(
+			"session".to_string(),
+			"iterator".to_string(),
+			Arc::new(Ok::<StackItem, ContractError>),
+			None,
+		);
+
+		assert!(matches!(iterator.traverse(10).await, Err(ContractError::InvalidStateError(_))));
+		assert!(matches!(
+			iterator.terminate_session().await,
+			Err(ContractError::InvalidStateError(_))
+		));
+	}
 }
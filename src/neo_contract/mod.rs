@@ -8,9 +8,11 @@ pub use iterator::*;
 pub use name_service::*;
 pub use neo_token::*;
 pub use neo_uri::*;
+pub use neo_x_bridge::*;
 pub use nft_contract::*;
 pub use policy_contract::*;
 pub use role_management::*;
+pub use token_decimals::*;
 pub use traits::*;
 
 mod contract_error;
@@ -21,7 +23,9 @@ mod iterator;
 mod name_service;
 mod neo_token;
 mod neo_uri;
+mod neo_x_bridge;
 mod nft_contract;
 mod policy_contract;
 mod role_management;
+mod token_decimals;
 mod traits;
@@ -8,41 +8,12 @@ use serde::{Deserialize, Serialize};
 
 use neo::prelude::{
 	deserialize_script_hash, deserialize_script_hash_option, serialize_script_hash,
-	serialize_script_hash_option, APITrait, AddressOrScriptHash, ContractError, ContractParameter,
-	JsonRpcProvider, NNSName, NeoIterator, NonFungibleTokenTrait, RpcClient, ScriptHash,
-	SmartContractTrait, StackItem, TokenTrait, TransactionBuilder,
+	serialize_script_hash_option, APITrait, Account, AccountSigner, AddressOrScriptHash,
+	ContractError, ContractParameter, JsonRpcProvider, NNSName, NeoConstants, NeoIterator,
+	NonFungibleTokenTrait, RecordState, RecordType, RpcClient, ScriptHash, SmartContractTrait,
+	StackItem, TokenTrait, TransactionBuilder,
 };
 
-#[repr(u8)]
-enum RecordType {
-	None = 0,
-	Txt = 1,
-	A = 2,
-	Aaaa = 3,
-	Cname = 4,
-	Srv = 5,
-	Url = 6,
-	Oauth = 7,
-	Ipfs = 8,
-	Email = 9,
-	Dnssec = 10,
-	Tlsa = 11,
-	Smimea = 12,
-	Hippo = 13,
-	Http = 14,
-	Sshfp = 15,
-	Onion = 16,
-	Xmpp = 17,
-	Magnet = 18,
-	Tor = 19,
-	I2p = 20,
-	Git = 21,
-	Keybase = 22,
-	Briar = 23,
-	Zcash = 24,
-	Mini = 25,
-}
-
 // NameState struct
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -80,6 +51,9 @@ impl<'a, P: JsonRpcProvider + 'static> NeoNameService<'a, P> {
 	const RESOLVE: &'static str = "resolve";
 	const PROPERTIES: &'static str = "properties";
 
+	/// The longest period a name can be registered or renewed for at once.
+	pub const MAX_REGISTRATION_YEARS: u32 = 10;
+
 	const NAME_PROPERTY: &'static str = "name";
 	const EXPIRATION_PROPERTY: &'static str = "expiration";
 	const ADMIN_PROPERTY: &'static str = "admin";
@@ -97,15 +71,12 @@ impl<'a, P: JsonRpcProvider + 'static> NeoNameService<'a, P> {
 
 	async fn get_roots(&self) -> Result<NeoIterator<String, P>, ContractError> {
 		let args = vec![];
-		let roots = self
-			.call_function_returning_iterator(
-				Self::ROOTS,
-				args,
-				Arc::new(|item: StackItem| item.to_string()),
-			)
-			.await;
-
-		Ok(roots)
+		self.call_function_returning_iterator(
+			Self::ROOTS,
+			args,
+			Arc::new(|item: StackItem| Ok::<_, ContractError>(item.to_string())),
+		)
+		.await
 	}
 
 	async fn get_symbol(&self) -> Result<String, ContractError> {
@@ -116,17 +87,65 @@ impl<'a, P: JsonRpcProvider + 'static> NeoNameService<'a, P> {
 		Ok(0)
 	}
 
-	// Register a name
+	/// Gets the price in GAS fractions of registering or renewing, for one year, a name whose
+	/// second-level fragment has `length` characters. A negative price means that such names can
+	/// only be registered by the committee.
+	pub async fn get_price(&self, length: u8) -> Result<i64, ContractError> {
+		let output =
+			self.call_invoke_function(Self::GET_PRICE, vec![length.into()], vec![]).await?;
+		self.throw_if_fault_state(&output)?;
+
+		output
+			.stack
+			.first()
+			.and_then(|item| item.as_int().ok())
+			.ok_or_else(|| ContractError::UnexpectedReturnType("Integer".to_string()))
+	}
+
+	/// Gets the price in GAS fractions of registering or renewing `name` for `years` years. The
+	/// contract burns it from the system fee of the transaction.
+	pub async fn registration_price(&self, name: &str, years: u32) -> Result<i64, ContractError> {
+		Self::validate_registration(name, years)?;
+
+		let length = name.split('.').next().unwrap_or_default().len();
+		let price = self.get_price(length as u8).await?;
+		if price < 0 {
+			return Err(ContractError::InvalidArgError(format!(
+				"Names of {} characters can only be registered by the committee",
+				length
+			)));
+		}
+		Ok(price * years as i64)
+	}
 
+	/// Builds a transaction, with `account` as signer, that registers `name` to `owner` for
+	/// `years` years.
+	///
+	/// Fails if `name` is not a second-level name, is already taken, or can only be registered
+	/// by the committee.
 	pub async fn register(
 		&self,
 		name: &str,
 		owner: H160,
+		years: u32,
+		account: &Account,
 	) -> Result<TransactionBuilder<P>, ContractError> {
-		self.check_domain_name_availability(name, true).await.unwrap();
-
-		let args = vec![name.into(), owner.into()];
-		self.invoke_function(Self::REGISTER, args).await
+		self.registration_price(name, years).await?;
+		self.check_domain_name_availability(name, true).await?;
+
+		// Registering covers the first year
+		let mut calls: Vec<(&str, Vec<ContractParameter>)> =
+			vec![(Self::REGISTER, vec![name.into(), owner.into()])];
+		if years > 1 {
+			calls.push((Self::RENEW, vec![name.into(), (years - 1).into()]));
+		}
+		let mut script = Vec::new();
+		for (function, args) in calls {
+			script.extend(self.build_invoke_function_script(function, args).await?);
+		}
+		let mut builder = TransactionBuilder::new();
+		builder.set_script(Some(script));
+		Self::signed_by(builder, account)
 	}
 
 	// Set admin for a name
@@ -136,7 +155,7 @@ impl<'a, P: JsonRpcProvider + 'static> NeoNameService<'a, P> {
 		name: &str,
 		admin: H160,
 	) -> Result<TransactionBuilder<P>, ContractError> {
-		self.check_domain_name_availability(name, true).await.unwrap();
+		self.check_domain_name_availability(name, false).await?;
 
 		let args = vec![name.into(), admin.into()];
 		self.invoke_function(Self::SET_ADMIN, args).await
@@ -150,7 +169,7 @@ impl<'a, P: JsonRpcProvider + 'static> NeoNameService<'a, P> {
 		record_type: RecordType,
 		data: &str,
 	) -> Result<TransactionBuilder<P>, ContractError> {
-		let args = vec![name.into(), (record_type as u8).into(), data.into()];
+		let args = vec![name.into(), record_type.byte_repr().into(), data.into()];
 
 		self.invoke_function(Self::SET_RECORD, args).await
 	}
@@ -162,23 +181,127 @@ impl<'a, P: JsonRpcProvider + 'static> NeoNameService<'a, P> {
 		name: &str,
 		record_type: RecordType,
 	) -> Result<TransactionBuilder<P>, ContractError> {
-		let args = vec![name.into(), (record_type as u8).into()];
+		let args = vec![name.into(), record_type.byte_repr().into()];
 		self.invoke_function(Self::DELETE_RECORD, args).await
 	}
 
+	/// Resolves the record of type `record_type` of `name`, following CNAME records. Returns
+	/// `None` if the name has no such record.
+	pub async fn resolve_record(
+		&self,
+		name: &str,
+		record_type: RecordType,
+	) -> Result<Option<String>, ContractError> {
+		self.call_function_returning_record(Self::RESOLVE, name, record_type).await
+	}
+
+	/// Gets the record of type `record_type` set on `name` itself, without following CNAME
+	/// records. Returns `None` if the name has no such record.
+	pub async fn get_record(
+		&self,
+		name: &str,
+		record_type: RecordType,
+	) -> Result<Option<String>, ContractError> {
+		self.call_function_returning_record(Self::GET_RECORD, name, record_type).await
+	}
+
+	/// Gets all records of `name`, of any type.
+	pub async fn get_all_records(&self, name: &str) -> Result<Vec<RecordState>, ContractError> {
+		let records = self
+			.call_function_and_unwrap_iterator(
+				Self::GET_ALL_RECORDS,
+				vec![name.into()],
+				NeoConstants::MAX_ITERATOR_ITEMS_DEFAULT as usize,
+				|item| RecordState::from_stack_item(&item),
+			)
+			.await?;
+		records
+			.into_iter()
+			.map(|record| record.map_err(|e| ContractError::UnexpectedReturnType(e.to_string())))
+			.collect()
+	}
+
+	async fn call_function_returning_record(
+		&self,
+		function: &str,
+		name: &str,
+		record_type: RecordType,
+	) -> Result<Option<String>, ContractError> {
+		let args = vec![name.into(), record_type.byte_repr().into()];
+		let output = self.call_invoke_function(function, args, vec![]).await?;
+		self.throw_if_fault_state(&output)?;
+
+		match output.stack.first() {
+			None | Some(StackItem::Any) => Ok(None),
+			Some(item) => item
+				.as_string()
+				.map(Some)
+				.ok_or_else(|| ContractError::UnexpectedReturnType("String".to_string())),
+		}
+	}
+
 	pub async fn is_available(&self, name: &str) -> Result<bool, ContractError> {
 		let args = vec![name.into()];
 		self.call_function_returning_bool(Self::IS_AVAILABLE, args).await
 	}
+
+	/// Builds a transaction, with `account` as signer, that renews `name` for `years` years.
+	///
+	/// Fails if `name` is not a registered second-level name owned by `account`.
 	pub async fn renew(
 		&self,
 		name: &str,
 		years: u32,
+		account: &Account,
 	) -> Result<TransactionBuilder<P>, ContractError> {
-		self.check_domain_name_availability(name, true).await.unwrap();
+		self.registration_price(name, years).await?;
+		self.check_domain_name_availability(name, false).await?;
+
+		let owner = self.get_owner(name).await?;
+		if owner != account.get_script_hash() {
+			return Err(ContractError::InvalidStateError(format!(
+				"{} is not owned by {}",
+				name,
+				account.get_address()
+			)));
+		}
+
+		let builder = self.invoke_function(Self::RENEW, vec![name.into(), years.into()]).await?;
+		Self::signed_by(builder, account)
+	}
+
+	/// Gets the owner of `name`.
+	pub async fn get_owner(&self, name: &str) -> Result<H160, ContractError> {
+		self.call_function_returning_script_hash(
+			<Self as NonFungibleTokenTrait<P>>::OWNER_OF,
+			vec![name.into()],
+		)
+		.await
+	}
+
+	fn validate_registration(name: &str, years: u32) -> Result<(), ContractError> {
+		NNSName::is_valid(name, false).map_err(|e| ContractError::InvalidNeoName(e.to_string()))?;
+		if !(1..=Self::MAX_REGISTRATION_YEARS).contains(&years) {
+			return Err(ContractError::InvalidArgError(format!(
+				"Names can be registered or renewed for 1 to {} years, got {}",
+				Self::MAX_REGISTRATION_YEARS,
+				years
+			)));
+		}
+		Ok(())
+	}
 
-		let args = vec![name.into(), years.into()];
-		self.invoke_function(Self::RENEW, args).await
+	/// Sets `account` as the signer of `builder`, with the called-by-entry scope.
+	fn signed_by(
+		mut builder: TransactionBuilder<P>,
+		account: &Account,
+	) -> Result<TransactionBuilder<P>, ContractError> {
+		builder
+			.set_signers(vec![AccountSigner::called_by_entry(account)
+				.map_err(|e| ContractError::InvalidArgError(e.to_string()))?
+				.into()])
+			.map_err(|e| ContractError::InvalidArgError(e.to_string()))?;
+		Ok(builder)
 	}
 
 	async fn get_name_state(&self, name: &[u8]) -> Result<NameState, ContractError> {
@@ -220,16 +343,12 @@ impl<'a, P: JsonRpcProvider + 'static> NeoNameService<'a, P> {
 		name: &str,
 		should_be_available: bool,
 	) -> Result<(), ContractError> {
-		let is_available = self.is_available(name).await.unwrap();
+		let is_available = self.is_available(name).await?;
 
 		if should_be_available && !is_available {
-			return Err(ContractError::DomainNameNotAvailable(
-				"Domain name already taken".to_string(),
-			));
+			return Err(ContractError::DomainNameNotAvailable(name.to_string()));
 		} else if !should_be_available && is_available {
-			return Err(ContractError::DomainNameNotRegistered(
-				"Domain name not registered".to_string(),
-			));
+			return Err(ContractError::DomainNameNotRegistered(name.to_string()));
 		}
 
 		Ok(())
@@ -271,7 +390,7 @@ impl<'a, P: JsonRpcProvider> TokenTrait<'a, P> for NeoNameService<'a, P> {
 					"resolve".to_string(),
 					vec![
 						ContractParameter::from(name.name()),
-						ContractParameter::from(RecordType::Txt as u8),
+						ContractParameter::from(RecordType::TXT.byte_repr()),
 					],
 					None,
 				)
@@ -303,3 +422,192 @@ impl<'a, P: JsonRpcProvider> SmartContractTrait<'a> for NeoNameService<'a, P> {
 }
 
 impl<'a, P: JsonRpcProvider> NonFungibleTokenTrait<'a, P> for NeoNameService<'a, P> {}
+
+#[cfg(test)]
+mod tests {
+	use serde_json::{json, Value};
+	use wiremock::{
+		matchers::{method, path},
+		Mock, MockServer, ResponseTemplate,
+	};
+
+	use neo::prelude::{
+		Account, AccountTrait, BodyRegexMatcher, CallFlags, ContractError, HttpProvider,
+		MockClient, NeoNameService, RecordState, RecordType, ScriptBuilder, ScriptHashExtension,
+		SmartContractTrait, TestConstants,
+	};
+
+	async fn mock_nns(method: &str, stack: Value) -> MockClient {
+		let mut mock_provider = MockClient::new().await;
+		mock_provider.mock_halt_result(method, stack).await;
+		mock_provider.mount_mocks().await;
+		mock_provider
+	}
+
+	#[tokio::test]
+	async fn test_resolve_record() {
+		let mock_provider =
+			mock_nns("invokefunction", json!([{ "type": "ByteString", "value": "dmVyaWZpZWQ=" }]))
+				.await;
+		let client = mock_provider.into_client();
+		let nns = NeoNameService::new(Some(&client));
+
+		let record = nns.resolve_record("neo.neo", RecordType::TXT).await.unwrap();
+		assert_eq!(record, Some("verified".to_string()));
+	}
+
+	#[tokio::test]
+	async fn test_get_missing_record() {
+		let mock_provider = mock_nns("invokefunction", json!([{ "type": "Any" }])).await;
+		let client = mock_provider.into_client();
+		let nns = NeoNameService::new(Some(&client));
+
+		assert_eq!(nns.get_record("neo.neo", RecordType::CNAME).await.unwrap(), None);
+	}
+
+	#[tokio::test]
+	async fn test_get_all_records() {
+		let record = |record_type: u8, data: &str| {
+			json!({
+				"type": "Array",
+				"value": [
+					{ "type": "ByteString", "value": "bmVvLm5lbw==" },
+					{ "type": "Integer", "value": record_type.to_string() },
+					{ "type": "ByteString", "value": data }
+				]
+			})
+		};
+		let stack = json!([{
+			"type": "Array",
+			"value": [record(1, "MTAuMC4wLjE="), record(16, "dmVyaWZpZWQ=")]
+		}]);
+		let mock_provider = mock_nns("invokescript", stack).await;
+		let client = mock_provider.into_client();
+		let nns = NeoNameService::new(Some(&client));
+
+		assert_eq!(
+			nns.get_all_records("neo.neo").await.unwrap(),
+			vec![
+				RecordState::new("neo.neo".to_string(), RecordType::A, "10.0.0.1".to_string()),
+				RecordState::new("neo.neo".to_string(), RecordType::TXT, "verified".to_string()),
+			]
+		);
+	}
+
+	async fn mock_invocation(server: &MockServer, function: &str, result: Value) {
+		Mock::given(method("POST"))
+			.and(path("/"))
+			.and(BodyRegexMatcher::new(&format!(".*\"invokefunction\".*\"{}\".*", function)))
+			.respond_with(ResponseTemplate::new(200).set_body_json(json!({
+				"jsonrpc": "2.0",
+				"id": 1,
+				"result": MockClient::halt_result(json!([result]))
+			})))
+			.mount(server)
+			.await;
+	}
+
+	#[tokio::test]
+	async fn test_register() {
+		let mock_provider = MockClient::new().await;
+		let server = mock_provider.server();
+		mock_invocation(server, "isAvailable", json!({ "type": "Boolean", "value": true })).await;
+		mock_invocation(server, "getPrice", json!({ "type": "Integer", "value": "100000000" }))
+			.await;
+		let client = mock_provider.into_client();
+		let nns = NeoNameService::new(Some(&client));
+		let account = Account::from_wif(TestConstants::DEFAULT_ACCOUNT_WIF).unwrap();
+		let owner = account.get_script_hash();
+
+		assert_eq!(nns.registration_price("neo.neo", 2).await.unwrap(), 200_000_000);
+
+		let builder = nns.register("neo.neo", owner, 2, &account).await.unwrap();
+		let expected = ScriptBuilder::new()
+			.contract_call(
+				&nns.script_hash(),
+				"register",
+				&["neo.neo".into(), owner.into()],
+				Some(CallFlags::All),
+			)
+			.unwrap()
+			.contract_call(
+				&nns.script_hash(),
+				"renew",
+				&["neo.neo".into(), 1u32.into()],
+				Some(CallFlags::All),
+			)
+			.unwrap()
+			.to_bytes();
+		assert_eq!(builder.script(), &Some(expected));
+		assert_eq!(builder.signers().len(), 1);
+	}
+
+	#[tokio::test]
+	async fn test_register_rejects_invalid_registrations() {
+		let mock_provider = MockClient::new().await;
+		let server = mock_provider.server();
+		mock_invocation(server, "isAvailable", json!({ "type": "Boolean", "value": true })).await;
+		mock_invocation(server, "getPrice", json!({ "type": "Integer", "value": "-1" })).await;
+		let client = mock_provider.into_client();
+		let nns = NeoNameService::new(Some(&client));
+		let account = Account::from_wif(TestConstants::DEFAULT_ACCOUNT_WIF).unwrap();
+		let owner = account.get_script_hash();
+
+		for name in ["neo", "sub.neo.neo", "-neo.neo", "neo.1neo"] {
+			assert!(matches!(
+				nns.register(name, owner, 1, &account).await,
+				Err(ContractError::InvalidNeoName(_))
+			));
+		}
+		for years in [0, NeoNameService::<HttpProvider>::MAX_REGISTRATION_YEARS + 1] {
+			assert!(matches!(
+				nns.register("neo.neo", owner, years, &account).await,
+				Err(ContractError::InvalidArgError(_))
+			));
+		}
+		// Names of this length are reserved for the committee
+		assert!(matches!(
+			nns.register("neo.neo", owner, 1, &account).await,
+			Err(ContractError::InvalidArgError(_))
+		));
+	}
+
+	#[tokio::test]
+	async fn test_renew_requires_owner() {
+		let owner = Account::from_wif(TestConstants::DEFAULT_ACCOUNT_WIF).unwrap();
+		let mut owner_bytes = owner.get_script_hash().to_vec();
+		owner_bytes.reverse();
+
+		let mock_provider = MockClient::new().await;
+		let server = mock_provider.server();
+		mock_invocation(server, "isAvailable", json!({ "type": "Boolean", "value": false })).await;
+		mock_invocation(server, "getPrice", json!({ "type": "Integer", "value": "100000000" }))
+			.await;
+		mock_invocation(
+			server,
+			"ownerOf",
+			json!({ "type": "ByteString", "value": base64::encode(owner_bytes) }),
+		)
+		.await;
+		let client = mock_provider.into_client();
+		let nns = NeoNameService::new(Some(&client));
+
+		let other = Account::create().unwrap();
+		assert!(matches!(
+			nns.renew("neo.neo", 1, &other).await,
+			Err(ContractError::InvalidStateError(_))
+		));
+
+		let builder = nns.renew("neo.neo", 3, &owner).await.unwrap();
+		let expected = ScriptBuilder::new()
+			.contract_call(
+				&nns.script_hash(),
+				"renew",
+				&["neo.neo".into(), 3u32.into()],
+				Some(CallFlags::All),
+			)
+			.unwrap()
+			.to_bytes();
+		assert_eq!(builder.script(), &Some(expected));
+	}
+}
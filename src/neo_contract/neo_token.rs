@@ -25,7 +25,7 @@ impl<'a, P: JsonRpcProvider + 'static> NeoToken<'a, P> {
 	pub const SYMBOL: &'static str = "NEO";
 	pub const TOTAL_SUPPLY: u64 = 100_000_000;
 
-	pub(crate) fn new(provider: Option<&'a RpcClient<P>>) -> Self {
+	pub fn new(provider: Option<&'a RpcClient<P>>) -> Self {
 		Self {
 			script_hash: Self::calc_native_contract_hash(Self::NAME).unwrap(),
 			total_supply: Some(Self::TOTAL_SUPPLY),
@@ -37,26 +37,23 @@ impl<'a, P: JsonRpcProvider + 'static> NeoToken<'a, P> {
 
 	// Unclaimed Gas
 
-	async fn unclaimed_gas(
+	/// Gets the GAS in fractions that `account` has generated by holding NEO and not yet
+	/// claimed, counted up to block `end_block`.
+	pub async fn unclaimed_gas(
 		&self,
-		account: &Account,
-		block_height: i32,
+		account: &H160,
+		end_block: u32,
 	) -> Result<i64, ContractError> {
-		self.unclaimed_gas_contract(&account.get_script_hash(), block_height).await
-	}
+		let output = self
+			.call_invoke_function("unclaimedGas", vec![account.into(), end_block.into()], vec![])
+			.await?;
+		self.throw_if_fault_state(&output)?;
 
-	async fn unclaimed_gas_contract(
-		&self,
-		script_hash: &H160,
-		block_height: i32,
-	) -> Result<i64, ContractError> {
-		Ok(self
-			.call_function_returning_int(
-				"unclaimedGas",
-				vec![script_hash.into(), block_height.into()],
-			)
-			.await
-			.unwrap() as i64)
+		output
+			.stack
+			.first()
+			.and_then(|item| item.as_int().ok())
+			.ok_or_else(|| ContractError::UnexpectedReturnType("Integer".to_string()))
 	}
 
 	// Candidate Registration
@@ -168,41 +165,28 @@ impl<'a, P: JsonRpcProvider + 'static> NeoToken<'a, P> {
 		self.invoke_function("setRegisterPrice", vec![register_price.into()]).await
 	}
 
-	async fn get_account_state(&self, account: &H160) -> Result<AccountState, ContractError> {
-		let result = self
+	/// Gets the NEO balance of `account`, the height at which the balance last changed, which is
+	/// when its GAS was last claimed, and the candidate it votes for.
+	pub async fn get_account_state(&self, account: &H160) -> Result<AccountState, ContractError> {
+		let output = self
 			.call_invoke_function("getAccountState", vec![account.into()], vec![])
-			.await
-			.unwrap()
-			.stack
-			.first()
-			.unwrap()
-			.clone();
-
-		match result {
-			StackItem::Any => Ok(AccountState::with_no_balance()),
-			StackItem::Array { value: items } if items.len() >= 3 => {
-				let balance = items[0].as_int().unwrap();
-				let update_height = items[1].as_int();
-				let public_key = items[2].clone();
-
-				if let StackItem::Any = public_key {
-					return Ok(AccountState {
-						balance,
-						balance_height: update_height,
-						public_key: None,
-					});
-				} else {
-					let pubkey =
-						Secp256r1PublicKey::from_bytes(public_key.as_bytes().unwrap().as_slice())
-							.unwrap();
-					Ok(AccountState {
-						balance,
-						balance_height: update_height,
-						public_key: Some(pubkey),
-					})
-				}
+			.await?;
+		self.throw_if_fault_state(&output)?;
+
+		let malformed = || ContractError::UnexpectedReturnType("Account state".to_string());
+		match output.stack.first() {
+			None | Some(StackItem::Any) => Ok(AccountState::with_no_balance()),
+			Some(StackItem::Struct { value: items } | StackItem::Array { value: items })
+				if items.len() >= 3 =>
+			{
+				let balance = items[0].as_int().map_err(|_| malformed())?;
+				let public_key = match &items[2] {
+					StackItem::Any => None,
+					item => Some(item.as_public_key().ok_or_else(malformed)?),
+				};
+				Ok(AccountState { balance, balance_height: items[1].as_int().ok(), public_key })
 			},
-			_ => Err(ContractError::InvalidNeoName("Account state malformed".to_string())),
+			_ => Err(malformed()),
 		}
 	}
 
@@ -295,3 +279,62 @@ impl Candidate {
 		Ok(Self { public_key: key, votes })
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use primitive_types::H160;
+	use serde_json::{json, Value};
+
+	use neo::prelude::{AccountState, MockClient, NeoToken, Secp256r1PublicKey, TestConstants};
+
+	async fn mock_invocation(stack: Value) -> MockClient {
+		let mut mock_provider = MockClient::new().await;
+		mock_provider.mock_halt_result("invokefunction", json!([stack])).await;
+		mock_provider.mount_mocks().await;
+		mock_provider
+	}
+
+	#[tokio::test]
+	async fn test_unclaimed_gas() {
+		let mock_provider =
+			mock_invocation(json!({ "type": "Integer", "value": "4200000000" })).await;
+		let client = mock_provider.into_client();
+		let neo = NeoToken::new(Some(&client));
+
+		assert_eq!(neo.unclaimed_gas(&H160::repeat_byte(1), 1000).await.unwrap(), 4_200_000_000);
+	}
+
+	#[tokio::test]
+	async fn test_get_account_state() {
+		let public_key = hex::decode(TestConstants::DEFAULT_ACCOUNT_PUBLIC_KEY).unwrap();
+		let mock_provider = mock_invocation(json!({
+			"type": "Struct",
+			"value": [
+				{ "type": "Integer", "value": "20000" },
+				{ "type": "Integer", "value": "1000" },
+				{ "type": "ByteString", "value": base64::encode(&public_key) },
+				{ "type": "Integer", "value": "0" }
+			]
+		}))
+		.await;
+		let client = mock_provider.into_client();
+		let neo = NeoToken::new(Some(&client));
+
+		assert_eq!(
+			neo.get_account_state(&H160::repeat_byte(1)).await.unwrap(),
+			AccountState {
+				balance: 20000,
+				balance_height: Some(1000),
+				public_key: Some(Secp256r1PublicKey::from_bytes(&public_key).unwrap()),
+			}
+		);
+
+		let mock_provider = mock_invocation(json!({ "type": "Any" })).await;
+		let client = mock_provider.into_client();
+		let neo = NeoToken::new(Some(&client));
+		assert_eq!(
+			neo.get_account_state(&H160::repeat_byte(1)).await.unwrap(),
+			AccountState::with_no_balance()
+		);
+	}
+}
@@ -122,9 +122,7 @@ impl<'a, P: JsonRpcProvider + 'static> NeoURI<'a, P> {
 			return Err(ContractError::InvalidArgError("NEO does not support decimals".to_string()));
 		}
 
-		if Self::is_gas_token(&token_hash)
-			&& amount_scale > GasToken::<P>::new(None).decimals().unwrap() as u32
-		{
+		if Self::is_gas_token(&token_hash) && amount_scale > GasToken::<P>::DECIMALS as u32 {
 			return Err(ContractError::InvalidArgError(
 				"Too many decimal places for GAS".to_string(),
 			));
@@ -0,0 +1,688 @@
+use std::{
+	str::FromStr,
+	time::{Duration, Instant},
+};
+
+use futures_timer::Delay;
+use primitive_types::{H160, H256};
+use serde_json::{json, Value};
+use sha3::{Digest, Keccak256};
+
+use neo::prelude::{
+	gas_n3_to_neox, APITrait, Account, AccountSigner, CallFlags, ContractError, ContractParameter,
+	JsonRpcProvider, NeoXAbi, NeoXSigner, NeoXTransaction, RpcClient, ScriptBuilder, ScriptHash,
+	TransactionBuilder, VMState, GAS_TOKEN_HASH,
+};
+
+/// The bridge between Neo N3 and Neo X.
+///
+/// Deposits are NEP-17 transfers to the bridge contract on Neo N3, with the Neo X recipient as
+/// the transfer data. Withdrawals call the bridge contract on Neo X with the GAS to withdraw
+/// attached, and the Neo N3 recipient as argument.
+///
+/// GAS has 8 decimals on Neo N3 and 18 on Neo X, see [`gas_n3_to_neox`]. Amounts passed to the
+/// bridge are always in Neo N3 fractions, so they convert exactly.
+///
+/// The withdraw function and the deposit and mint events default to
+/// [`Self::DEFAULT_WITHDRAW_FUNCTION`], [`Self::DEFAULT_DEPOSIT_EVENT`] and
+/// [`Self::DEFAULT_MINT_EVENT`]. These are not taken from a published ABI, so check them against
+/// the bridge contracts you use and set them with [`Self::withdraw_function`],
+/// [`Self::deposit_event`] and [`Self::mint_event`].
+#[derive(Debug, Clone)]
+pub struct NeoXBridgeContract<'a, P: JsonRpcProvider> {
+	script_hash: ScriptHash,
+	neo_x_bridge: H160,
+	provider: Option<&'a RpcClient<P>>,
+	neo_x_client: Option<&'a RpcClient<P>>,
+	withdraw_function: String,
+	deposit_event: String,
+	mint_event: String,
+	poll_interval: Duration,
+	timeout: Duration,
+}
+
+impl<'a, P: JsonRpcProvider + 'static> NeoXBridgeContract<'a, P> {
+	/// The default signature of the Neo X function that withdraws the attached GAS to a Neo N3
+	/// account, which is its only argument.
+	pub const DEFAULT_WITHDRAW_FUNCTION: &'static str = "withdrawNative(address)";
+	/// How many Neo X blocks before the first poll are searched for the bridge's logs.
+	pub const NEO_X_LOG_LOOKBACK: u64 = 5_000;
+	/// The default name of the event the Neo N3 bridge emits for a deposit, with the deposit's
+	/// nonce as first value.
+	pub const DEFAULT_DEPOSIT_EVENT: &'static str = "NativeDeposit";
+	/// The default signature of the event the Neo X bridge emits when it mints a deposit. The
+	/// nonce and the recipient are its indexed topics, the amount is its data.
+	pub const DEFAULT_MINT_EVENT: &'static str = "NativeClaimable(uint256,address,uint256)";
+	const TRANSFER: &'static str = "transfer";
+
+	/// Creates the bridge from its Neo N3 contract hash and its Neo X contract address. The
+	/// clients are needed to send transactions and to track them on either chain.
+	pub fn new(
+		script_hash: ScriptHash,
+		neo_x_bridge: H160,
+		provider: Option<&'a RpcClient<P>>,
+		neo_x_client: Option<&'a RpcClient<P>>,
+	) -> Self {
+		Self {
+			script_hash,
+			neo_x_bridge,
+			provider,
+			neo_x_client,
+			withdraw_function: Self::DEFAULT_WITHDRAW_FUNCTION.to_string(),
+			deposit_event: Self::DEFAULT_DEPOSIT_EVENT.to_string(),
+			mint_event: Self::DEFAULT_MINT_EVENT.to_string(),
+			poll_interval: Duration::from_secs(5),
+			timeout: Duration::from_secs(600),
+		}
+	}
+
+	/// Sets the signature of the Neo X function that withdraws the attached GAS, e.g.
+	/// `withdrawNative(address)`. It must take the Neo N3 recipient as its only argument.
+	pub fn withdraw_function(mut self, signature: &str) -> Self {
+		self.withdraw_function = signature.to_string();
+		self
+	}
+
+	/// Sets the name of the event the Neo N3 bridge emits for a deposit. Its first value must be
+	/// the deposit's nonce.
+	pub fn deposit_event(mut self, name: &str) -> Self {
+		self.deposit_event = name.to_string();
+		self
+	}
+
+	/// Sets the signature of the event the Neo X bridge emits when it mints a deposit, e.g.
+	/// `NativeClaimable(uint256,address,uint256)`. The nonce and the recipient must be its indexed
+	/// topics and the amount its data.
+	pub fn mint_event(mut self, signature: &str) -> Self {
+		self.mint_event = signature.to_string();
+		self
+	}
+
+	/// Sets how often [`Self::wait_for_bridge_completion`] polls the chains (default: 5 seconds).
+	pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+		self.poll_interval = poll_interval;
+		self
+	}
+
+	/// Sets how long [`Self::wait_for_bridge_completion`] waits (default: 10 minutes).
+	pub fn timeout(mut self, timeout: Duration) -> Self {
+		self.timeout = timeout;
+		self
+	}
+
+	pub fn script_hash(&self) -> ScriptHash {
+		self.script_hash
+	}
+
+	pub fn neo_x_bridge(&self) -> H160 {
+		self.neo_x_bridge
+	}
+
+	/// Deposits `amount` of `token`, in its Neo N3 fractions, to `neo_x_address`. Signs the
+	/// transfer with `account`, which must hold its private key, sends it and returns its hash.
+	pub async fn deposit(
+		&self,
+		token: &H160,
+		amount: u64,
+		neo_x_address: H160,
+		account: &Account,
+	) -> Result<H256, ContractError> {
+		let client = self.provider()?;
+		let amount = i64::try_from(amount).map_err(|_| {
+			ContractError::InvalidArgError(format!("The amount {amount} is too large"))
+		})?;
+		if amount == 0 {
+			return Err(ContractError::InvalidArgError("The amount must be positive".to_string()));
+		}
+
+		let script = ScriptBuilder::new()
+			.contract_call(
+				token,
+				Self::TRANSFER,
+				&[
+					ContractParameter::h160(&account.get_script_hash()),
+					ContractParameter::h160(&self.script_hash),
+					ContractParameter::integer(amount),
+					ContractParameter::byte_array(neo_x_address.as_bytes().to_vec()),
+				],
+				Some(CallFlags::All),
+			)
+			.map_err(|e| ContractError::InvalidArgError(e.to_string()))?
+			.to_bytes();
+
+		let mut builder = TransactionBuilder::with_client(client);
+		builder
+			.set_script(Some(script))
+			.set_signers(vec![AccountSigner::called_by_entry(account)
+				.map_err(|e| ContractError::InvalidArgError(e.to_string()))?
+				.into()])
+			.map_err(|e| ContractError::InvalidArgError(e.to_string()))?;
+		let mut tx =
+			builder.sign().await.map_err(|e| ContractError::RuntimeError(e.to_string()))?;
+		let sent = tx.send_tx().await.map_err(|e| ContractError::RuntimeError(e.to_string()))?;
+		Ok(sent.hash)
+	}
+
+	/// Withdraws `amount` GAS, in Neo N3 fractions, to `neo_n3_address`. `transaction` sets the
+	/// chain id, nonce, fees and gas limit; its recipient, value and data are filled in. Signs it
+	/// with `signer`, sends it to the Neo X client and returns its hash.
+	pub async fn withdraw(
+		&self,
+		signer: &NeoXSigner,
+		transaction: NeoXTransaction,
+		neo_n3_address: H160,
+		amount: u64,
+	) -> Result<H256, ContractError> {
+		let client = self.neo_x_client()?;
+		if amount == 0 {
+			return Err(ContractError::InvalidArgError("The amount must be positive".to_string()));
+		}
+
+		let data = NeoXAbi::encode(&self.withdraw_function, &[neo_n3_address.into()])
+			.map_err(|e| ContractError::InvalidArgError(e.to_string()))?;
+		let transaction =
+			transaction.to(self.neo_x_bridge).value(gas_n3_to_neox(amount)).data(data);
+		let signed = signer
+			.sign_transaction(&transaction)
+			.map_err(|e| ContractError::RuntimeError(e.to_string()))?;
+		let hash = client.send_neox_transaction(&signed).await?;
+		parse_h256(&hash)
+	}
+
+	/// Waits until the deposit `deposit_tx` to `neo_x_address` has completed on both chains.
+	///
+	/// Polls the application log of the deposit until it is available, fails if the deposit
+	/// faulted or did not transfer anything to the bridge, and then polls the Neo X bridge's
+	/// logs for the mint event with the deposit's nonce, which must mint the deposited
+	/// amount to `neo_x_address`. GAS amounts are converted to 18 decimals; other tokens are
+	/// expected to have the same decimals on both chains.
+	pub async fn wait_for_bridge_completion(
+		&self,
+		deposit_tx: H256,
+		neo_x_address: H160,
+	) -> Result<BridgeReceipt, ContractError> {
+		let client = self.provider()?;
+		let neo_x_client = self.neo_x_client()?;
+		let deadline = Instant::now() + self.timeout;
+
+		let log = loop {
+			match client.get_application_log(deposit_tx).await {
+				Ok(log) => break log,
+				Err(e) =>
+					self.wait_until(deadline, &format!("deposit {deposit_tx:?}: {e}")).await?,
+			}
+		};
+		let execution = log
+			.get_first_execution()
+			.map_err(|e| ContractError::InvalidStateError(e.to_string()))?;
+		if execution.state != VMState::Halt {
+			return Err(ContractError::RuntimeError(format!(
+				"The deposit {deposit_tx:?} faulted: {}",
+				execution.exception.clone().unwrap_or_default()
+			)));
+		}
+		let transfer = execution
+			.notifications
+			.iter()
+			.filter_map(|notification| notification.as_nep17_transfer())
+			.find(|transfer| transfer.to == self.script_hash)
+			.ok_or_else(|| {
+				ContractError::InvalidStateError(format!(
+					"The transaction {deposit_tx:?} transferred nothing to the bridge"
+				))
+			})?;
+		let nonce = execution
+			.notifications
+			.iter()
+			.filter(|notification| {
+				notification.contract == self.script_hash
+					&& notification.event_name == self.deposit_event
+			})
+			.find_map(|notification| notification.state.as_array()?.first()?.as_int().ok())
+			.and_then(|nonce| u64::try_from(nonce).ok())
+			.ok_or_else(|| {
+				ContractError::InvalidStateError(format!(
+					"The transaction {deposit_tx:?} has no {} event",
+					self.deposit_event
+				))
+			})?;
+		let amount = u64::try_from(&transfer.amount).map_err(|_| {
+			ContractError::InvalidStateError(format!("Invalid deposit amount {}", transfer.amount))
+		})?;
+		let neo_x_amount = if transfer.token == *GAS_TOKEN_HASH {
+			gas_n3_to_neox(amount)
+		} else {
+			u128::from(amount)
+		};
+
+		let latest: String = neo_x_client.request("eth_blockNumber", json!([])).await?;
+		let from_block = parse_quantity(&latest)?.saturating_sub(Self::NEO_X_LOG_LOOKBACK);
+		let mint = Mint {
+			topic: H256::from_slice(&Keccak256::digest(self.mint_event.as_bytes())),
+			nonce,
+			recipient: neo_x_address,
+			amount: neo_x_amount,
+		};
+		let nonce_topic = format!("0x{}", hex::encode(uint_word(nonce.into())));
+		loop {
+			let filter = json!({
+				"address": format!("{:?}", self.neo_x_bridge),
+				"fromBlock": format!("{from_block:#x}"),
+				"toBlock": "latest",
+				"topics": [format!("{:?}", mint.topic), &nonce_topic],
+			});
+			let logs: Vec<Value> = neo_x_client.request("eth_getLogs", [filter]).await?;
+			if let Some(log) = logs.iter().find(|log| mint.matches(log)) {
+				let field = |name: &str| {
+					log.get(name).and_then(Value::as_str).ok_or_else(|| {
+						ContractError::UnexpectedReturnType(format!("Neo X log without {name}"))
+					})
+				};
+				return Ok(BridgeReceipt {
+					deposit_tx,
+					nonce,
+					token: transfer.token,
+					amount,
+					neo_x_address,
+					neo_x_amount,
+					neo_x_tx: parse_h256(field("transactionHash")?)?,
+					neo_x_block: parse_quantity(field("blockNumber")?)?,
+				});
+			}
+			self.wait_until(deadline, &format!("the Neo X mint of deposit {deposit_tx:?}"))
+				.await?;
+		}
+	}
+
+	fn provider(&self) -> Result<&'a RpcClient<P>, ContractError> {
+		self.provider
+			.ok_or(ContractError::InvalidStateError("The bridge has no Neo N3 client".to_string()))
+	}
+
+	fn neo_x_client(&self) -> Result<&'a RpcClient<P>, ContractError> {
+		self.neo_x_client
+			.ok_or(ContractError::InvalidStateError("The bridge has no Neo X client".to_string()))
+	}
+
+	/// Sleeps for one poll interval, or fails if that would pass the deadline.
+	async fn wait_until(&self, deadline: Instant, waiting_for: &str) -> Result<(), ContractError> {
+		if Instant::now() + self.poll_interval > deadline {
+			return Err(ContractError::Timeout(format!("Gave up waiting for {waiting_for}")));
+		}
+		Delay::new(self.poll_interval).await;
+		Ok(())
+	}
+}
+
+/// A deposit that completed on both chains, see
+/// [`NeoXBridgeContract::wait_for_bridge_completion`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BridgeReceipt {
+	pub deposit_tx: H256,
+	/// The nonce the Neo N3 bridge assigned to the deposit.
+	pub nonce: u64,
+	pub token: H160,
+	/// The deposited amount, in Neo N3 fractions.
+	pub amount: u64,
+	pub neo_x_address: H160,
+	/// The minted amount, in Neo X fractions.
+	pub neo_x_amount: u128,
+	/// The Neo X transaction that minted the deposit.
+	pub neo_x_tx: H256,
+	pub neo_x_block: u64,
+}
+
+/// The mint of a deposit on Neo X.
+struct Mint {
+	/// The hash of the event's signature, its first topic
+	topic: H256,
+	nonce: u64,
+	recipient: H160,
+	amount: u128,
+}
+
+impl Mint {
+	/// Whether `log` is the mint event of this deposit's nonce, and mints
+	/// its amount to its recipient.
+	fn matches(&self, log: &Value) -> bool {
+		let topics: Vec<Vec<u8>> = match log.get("topics").and_then(Value::as_array) {
+			Some(topics) =>
+				topics.iter().filter_map(Value::as_str).filter_map(decode_hex).collect(),
+			None => return false,
+		};
+		let data = log.get("data").and_then(Value::as_str).and_then(decode_hex).unwrap_or_default();
+		topics.len() == 3
+			&& topics[0] == self.topic.as_bytes()
+			&& topics[1] == uint_word(self.nonce.into())
+			&& topics[2] == address_word(&self.recipient)
+			&& data == uint_word(self.amount)
+	}
+}
+
+/// An unsigned integer as an ABI word.
+fn uint_word(value: u128) -> [u8; 32] {
+	let mut word = [0u8; 32];
+	word[16..].copy_from_slice(&value.to_be_bytes());
+	word
+}
+
+/// An address left-padded to an ABI word.
+fn address_word(address: &H160) -> [u8; 32] {
+	let mut word = [0u8; 32];
+	word[12..].copy_from_slice(address.as_bytes());
+	word
+}
+
+fn decode_hex(value: &str) -> Option<Vec<u8>> {
+	hex::decode(value.trim_start_matches("0x")).ok()
+}
+
+fn parse_quantity(value: &str) -> Result<u64, ContractError> {
+	u64::from_str_radix(value.trim_start_matches("0x"), 16)
+		.map_err(|_| ContractError::UnexpectedReturnType(format!("Invalid quantity {value}")))
+}
+
+fn parse_h256(value: &str) -> Result<H256, ContractError> {
+	H256::from_str(value.trim_start_matches("0x"))
+		.map_err(|_| ContractError::UnexpectedReturnType(format!("Invalid hash {value}")))
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{str::FromStr, time::Duration};
+
+	use primitive_types::{H160, H256};
+	use serde_json::{json, Value};
+	use sha3::{Digest, Keccak256};
+
+	#[cfg(feature = "sim")]
+	use neo::prelude::SimBlockchain;
+	use neo::prelude::{
+		Account, CallFlags, ContractError, ContractParameter, HttpProvider, MockClient,
+		NeoXBridgeContract, RpcClient, ScriptBuilder, GAS_TOKEN_HASH,
+	};
+
+	use super::Mint;
+
+	type Bridge<'a> = NeoXBridgeContract<'a, HttpProvider>;
+
+	fn bridge<'a>(
+		provider: &'a RpcClient<HttpProvider>,
+		neo_x_client: &'a RpcClient<HttpProvider>,
+	) -> Bridge<'a> {
+		NeoXBridgeContract::new(
+			H160::repeat_byte(0xb1),
+			H160::repeat_byte(0xb2),
+			Some(provider),
+			Some(neo_x_client),
+		)
+		.poll_interval(Duration::from_millis(10))
+		.timeout(Duration::from_millis(200))
+	}
+
+	fn mint_topic() -> H256 {
+		H256::from_slice(&Keccak256::digest(Bridge::DEFAULT_MINT_EVENT.as_bytes()))
+	}
+
+	/// A Neo X log of `topic` with the nonce and the recipient `0x0a..0a` as indexed topics.
+	fn mint_log(topic: H256, nonce: u64, amount: u128, tx: H256) -> Value {
+		json!({
+			"address": format!("{:?}", H160::repeat_byte(0xb2)),
+			"topics": [
+				format!("{topic:?}"),
+				format!("0x{nonce:064x}"),
+				format!("0x{}{}", "00".repeat(12), "0a".repeat(20)),
+			],
+			"data": format!("0x{amount:064x}"),
+			"blockNumber": "0x2711",
+			"transactionHash": format!("{tx:?}"),
+		})
+	}
+
+	#[test]
+	fn test_mint_matches() {
+		let mint = Mint {
+			topic: mint_topic(),
+			nonce: 42,
+			recipient: H160::repeat_byte(0x0a),
+			amount: 1_500_000_000_000_000_000,
+		};
+		let tx = H256::zero();
+
+		assert!(mint.matches(&mint_log(mint_topic(), 42, 1_500_000_000_000_000_000, tx)));
+		// Another event, or the mint of another deposit to the same recipient
+		let transfer = H256::from_slice(&Keccak256::digest(b"Transfer(address,address,uint256)"));
+		assert!(!mint.matches(&mint_log(transfer, 42, 1_500_000_000_000_000_000, tx)));
+		assert!(!mint.matches(&mint_log(mint_topic(), 41, 1_500_000_000_000_000_000, tx)));
+		assert!(!mint.matches(&mint_log(mint_topic(), 42, 1_500_000_000, tx)));
+		assert!(!mint.matches(&json!({ "topics": [], "data": "0x" })));
+	}
+
+	#[tokio::test]
+	async fn test_deposit_sends_transfer_to_bridge() {
+		let mut mock_provider = MockClient::new().await;
+		mock_provider
+			.mock_response_with_file_ignore_param(
+				"invokescript",
+				"invokescript_transfer_with_fixed_sysfee.json",
+			)
+			.await
+			.mock_response_with_file_ignore_param("calculatenetworkfee", "calculatenetworkfee.json")
+			.await
+			.mock_response_with_file_ignore_param("getblockcount", "getblockcount_1000.json")
+			.await
+			.mock_response_with_file_ignore_param("sendrawtransaction", "sendrawtransaction.json")
+			.await
+			.mount_mocks()
+			.await;
+		let client = mock_provider.into_client();
+		let account =
+			Account::from_wif("L1WMhxazScMhUrdv34JqQb1HFSQmWeN2Kpc1R9JGKwL7CDNP21uR").unwrap();
+		let bridge = bridge(&client, &client);
+
+		let hash = bridge
+			.deposit(&GAS_TOKEN_HASH, 1_50000000, H160::repeat_byte(0x0a), &account)
+			.await
+			.unwrap();
+		assert_eq!(
+			hash,
+			H256::from_str("830816f0c801bcabf919dfa1a90d7b9a4f867482cb4d18d0631a5aa6daefab6a")
+				.unwrap()
+		);
+
+		// The script is a GAS transfer to the bridge with the Neo X recipient as data
+		let expected = ScriptBuilder::new()
+			.contract_call(
+				&GAS_TOKEN_HASH,
+				"transfer",
+				&[
+					ContractParameter::h160(&account.get_script_hash()),
+					ContractParameter::h160(&bridge.script_hash()),
+					ContractParameter::integer(1_50000000),
+					ContractParameter::byte_array(vec![0x0a; 20]),
+				],
+				Some(CallFlags::All),
+			)
+			.unwrap()
+			.to_bytes();
+		let requests = mock_provider.server().received_requests().await.unwrap();
+		let scripts: Vec<Value> = requests
+			.iter()
+			.map(|request| serde_json::from_slice::<Value>(&request.body).unwrap())
+			.filter(|body| body["method"] == "invokescript")
+			.map(|body| body["params"][0].clone())
+			.collect();
+		assert_eq!(scripts, vec![json!(base64::encode(expected))]);
+
+		assert!(matches!(
+			bridge.deposit(&GAS_TOKEN_HASH, 0, H160::repeat_byte(0x0a), &account).await,
+			Err(ContractError::InvalidArgError(_))
+		));
+	}
+
+	#[tokio::test]
+	async fn test_configured_event_names() {
+		let mut neo_n3 = MockClient::new().await;
+		neo_n3
+			.mock_response_ignore_param(
+				"getapplicationlog",
+				json!({
+					"txid": format!("{:?}", H256::repeat_byte(0x01)),
+					"executions": [{
+						"trigger": "Application",
+						"vmstate": "HALT",
+						"exception": null,
+						"gasconsumed": "9999540",
+						"stack": [],
+						"notifications": [{
+							"contract": format!("{:?}", *GAS_TOKEN_HASH),
+							"eventname": "Transfer",
+							"state": {
+								"type": "Array",
+								"value": [
+									{ "type": "ByteString", "value": base64::encode([0x03; 20]) },
+									{ "type": "ByteString", "value": base64::encode([0xb1; 20]) },
+									{ "type": "Integer", "value": "150000000" }
+								]
+							}
+						}, {
+							"contract": format!("{:?}", H160::repeat_byte(0xb1)),
+							"eventname": "NativeDeposit",
+							"state": { "type": "Array", "value": [{ "type": "Integer", "value": "42" }] }
+						}]
+					}]
+				}),
+			)
+			.await;
+		neo_n3.mount_mocks().await;
+		let client = neo_n3.into_client();
+
+		// The deposit event of another deployment is not found
+		let result = bridge(&client, &client)
+			.deposit_event("Deposit")
+			.wait_for_bridge_completion(H256::repeat_byte(0x01), H160::repeat_byte(0x0a))
+			.await;
+		let message = match result {
+			Err(ContractError::InvalidStateError(message)) => message,
+			other => panic!("Unexpected result {other:?}"),
+		};
+		assert!(message.contains("has no Deposit event"));
+	}
+
+	#[cfg(feature = "sim")]
+	#[tokio::test]
+	async fn test_deposit() {
+		let sim = SimBlockchain::start().await;
+		let client = sim.client();
+		let account =
+			Account::from_wif("L1WMhxazScMhUrdv34JqQb1HFSQmWeN2Kpc1R9JGKwL7CDNP21uR").unwrap();
+		sim.set_balance(*GAS_TOKEN_HASH, account.get_script_hash(), 10_00000000);
+		let bridge = bridge(&client, &client);
+
+		let hash = bridge
+			.deposit(&GAS_TOKEN_HASH, 1_50000000, H160::repeat_byte(0x0a), &account)
+			.await
+			.unwrap();
+		assert_eq!(sim.transactions(), vec![hash]);
+		assert_eq!(sim.balance(&GAS_TOKEN_HASH, &bridge.script_hash()), 1_50000000);
+		assert_eq!(sim.balance(&GAS_TOKEN_HASH, &account.get_script_hash()), 8_50000000);
+
+		assert!(matches!(
+			bridge.deposit(&GAS_TOKEN_HASH, 0, H160::repeat_byte(0x0a), &account).await,
+			Err(ContractError::InvalidArgError(_))
+		));
+	}
+
+	#[tokio::test]
+	async fn test_wait_for_bridge_completion() {
+		let deposit_tx = H256::repeat_byte(0x01);
+		let neo_x_tx = H256::repeat_byte(0x02);
+		let recipient = H160::repeat_byte(0x0a);
+		// Script hashes are little-endian on the stack
+		let little_endian = |hash: H160| {
+			let mut bytes = hash.as_bytes().to_vec();
+			bytes.reverse();
+			base64::encode(bytes)
+		};
+		let (sender, bridge_hash) =
+			(little_endian(H160::repeat_byte(0x03)), little_endian(H160::repeat_byte(0xb1)));
+
+		let mut neo_n3 = MockClient::new().await;
+		neo_n3
+			.mock_response_ignore_param(
+				"getapplicationlog",
+				json!({
+					"txid": format!("{deposit_tx:?}"),
+					"executions": [{
+						"trigger": "Application",
+						"vmstate": "HALT",
+						"exception": null,
+						"gasconsumed": "9999540",
+						"stack": [],
+						"notifications": [{
+							"contract": format!("{:?}", *GAS_TOKEN_HASH),
+							"eventname": "Transfer",
+							"state": {
+								"type": "Array",
+								"value": [
+									{ "type": "ByteString", "value": sender },
+									{ "type": "ByteString", "value": bridge_hash },
+									{ "type": "Integer", "value": "150000000" }
+								]
+							}
+						}, {
+							"contract": format!("{:?}", H160::repeat_byte(0xb1)),
+							"eventname": "NativeDeposit",
+							"state": {
+								"type": "Array",
+								"value": [
+									{ "type": "Integer", "value": "42" },
+									{ "type": "ByteString", "value": sender },
+									{ "type": "ByteString", "value": base64::encode([0x0a; 20]) },
+									{ "type": "Integer", "value": "150000000" }
+								]
+							}
+						}]
+					}]
+				}),
+			)
+			.await;
+		neo_n3.mount_mocks().await;
+
+		// Only the last log is the mint of this deposit
+		let amount = 1_500_000_000_000_000_000;
+		let transfer = H256::from_slice(&Keccak256::digest(b"Transfer(address,address,uint256)"));
+		let mut neo_x = MockClient::new().await;
+		neo_x
+			.mock_response_ignore_param("eth_blockNumber", json!("0x2710"))
+			.await
+			.mock_response_ignore_param(
+				"eth_getLogs",
+				json!([
+					mint_log(transfer, 42, amount, H256::repeat_byte(0x03)),
+					mint_log(mint_topic(), 41, amount, H256::repeat_byte(0x04)),
+					mint_log(mint_topic(), 42, amount, neo_x_tx),
+				]),
+			)
+			.await;
+		neo_x.mount_mocks().await;
+
+		let (client, neo_x_client) = (neo_n3.into_client(), neo_x.into_client());
+		let receipt = bridge(&client, &neo_x_client)
+			.wait_for_bridge_completion(deposit_tx, recipient)
+			.await
+			.unwrap();
+		assert_eq!(receipt.nonce, 42);
+		assert_eq!(receipt.token, *GAS_TOKEN_HASH);
+		assert_eq!(receipt.amount, 1_50000000);
+		assert_eq!(receipt.neo_x_amount, amount);
+		assert_eq!(receipt.neo_x_tx, neo_x_tx);
+		assert_eq!(receipt.neo_x_block, 10001);
+
+		// Another recipient's deposit never shows up on Neo X
+		let result = bridge(&client, &neo_x_client)
+			.wait_for_bridge_completion(deposit_tx, H160::repeat_byte(0x0b))
+			.await;
+		assert!(matches!(result, Err(ContractError::Timeout(_))));
+	}
+}
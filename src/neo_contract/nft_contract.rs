@@ -74,3 +74,169 @@ impl<'a, P: JsonRpcProvider> SmartContractTrait<'a> for NftContract<'a, P> {
 
 #[async_trait]
 impl<'a, P: JsonRpcProvider> NonFungibleTokenTrait<'a, P> for NftContract<'a, P> {}
+
+#[cfg(test)]
+mod tests {
+	use futures::StreamExt;
+	use num_bigint::BigInt;
+	use primitive_types::H160;
+	use serde_json::json;
+
+	use neo::prelude::{ContractError, MockClient, NftContract, NonFungibleTokenTrait, StackItem};
+
+	fn invocation_result(stack: serde_json::Value) -> serde_json::Value {
+		let mut result = MockClient::halt_result(json!([stack]));
+		result["session"] = json!("a7b35b13-bdfc-4ab3-a398-88a9db9da4fe");
+		result
+	}
+
+	#[tokio::test]
+	async fn test_properties() {
+		let mut mock_provider = MockClient::new().await;
+		let entry = |key: &str, value: &str| {
+			json!({
+				"key": { "type": "ByteString", "value": key },
+				"value": { "type": "ByteString", "value": value }
+			})
+		};
+		mock_provider
+			.mock_response_ignore_param(
+				"invokefunction",
+				invocation_result(json!({
+					"type": "Map",
+					"value": [
+						entry("bmFtZQ==", "VG9rZW4gMQ=="),
+						entry("dG9rZW5VUkk=", "aHR0cHM6Ly9uZnQuZXhhbXBsZS8xLmpzb24=")
+					]
+				})),
+			)
+			.await;
+		mock_provider.mount_mocks().await;
+		let client = mock_provider.into_client();
+		let nft = NftContract::new(&H160::repeat_byte(1), Some(&client));
+
+		let properties = nft.properties(&[1]).await.unwrap();
+		assert_eq!(properties.len(), 2);
+		assert_eq!(properties["name"], StackItem::ByteString { value: "VG9rZW4gMQ==".to_string() });
+		assert_eq!(
+			nft.token_uri(&[1]).await.unwrap(),
+			Some("https://nft.example/1.json".to_string())
+		);
+		assert_eq!(nft.image(&[1]).await.unwrap(), None);
+	}
+
+	#[tokio::test]
+	async fn test_royalty_info() {
+		let mut mock_provider = MockClient::new().await;
+		let recipient = H160::from_slice(&(1..=20).collect::<Vec<u8>>());
+		let mut recipient_le = recipient.as_bytes().to_vec();
+		recipient_le.reverse();
+		mock_provider
+			.mock_response_ignore_param(
+				"invokefunction",
+				invocation_result(json!({
+					"type": "Array",
+					"value": [{
+						"type": "Struct",
+						"value": [
+							{ "type": "ByteString", "value": base64::encode(&recipient_le) },
+							{ "type": "Integer", "value": "250" }
+						]
+					}]
+				})),
+			)
+			.await;
+		mock_provider.mount_mocks().await;
+		let client = mock_provider.into_client();
+		let nft = NftContract::new(&H160::repeat_byte(1), Some(&client));
+
+		let royalties = nft.royalty_info(&[1], H160::repeat_byte(2), 10_000).await.unwrap();
+		assert_eq!(royalties, vec![(recipient, BigInt::from(250))]);
+	}
+
+	#[tokio::test]
+	async fn test_tokens_stream() {
+		let mut mock_provider = MockClient::new().await;
+		mock_provider
+			.mock_response_ignore_param(
+				"invokefunction",
+				invocation_result(json!({
+					"type": "InteropInterface",
+					"interface": "IIterator",
+					"id": "fcf7b800-192a-488e-8ee5-9ff3e3aa7c42"
+				})),
+			)
+			.await;
+		mock_provider
+			.mock_response_ignore_param(
+				"traverseiterator",
+				json!([
+					{ "type": "ByteString", "value": "MDE=" },
+					{ "type": "ByteString", "value": "MDI=" }
+				]),
+			)
+			.await;
+		mock_provider.mock_response_ignore_param("terminatesession", json!(true)).await;
+		mock_provider.mount_mocks().await;
+		let client = mock_provider.into_client();
+		let nft = NftContract::new(&H160::repeat_byte(1), Some(&client));
+
+		let tokens: Vec<_> = nft.tokens().await.unwrap().into_stream(10).collect().await;
+		let tokens: Vec<_> = tokens.into_iter().map(Result::unwrap).collect();
+		assert_eq!(tokens, vec![b"01".to_vec(), b"02".to_vec()]);
+
+		let requests = mock_provider.server().received_requests().await.unwrap();
+		assert!(requests
+			.iter()
+			.any(|request| String::from_utf8_lossy(&request.body).contains("terminatesession")));
+	}
+
+	#[tokio::test]
+	async fn test_tokens_with_undecodable_id_fails() {
+		let mut mock_provider = MockClient::new().await;
+		mock_provider
+			.mock_response_ignore_param(
+				"invokefunction",
+				invocation_result(json!({
+					"type": "InteropInterface",
+					"interface": "IIterator",
+					"id": "fcf7b800-192a-488e-8ee5-9ff3e3aa7c42"
+				})),
+			)
+			.await;
+		mock_provider
+			.mock_response_ignore_param(
+				"traverseiterator",
+				json!([{ "type": "ByteString", "value": "***" }]),
+			)
+			.await;
+		mock_provider.mock_response_ignore_param("terminatesession", json!(true)).await;
+		mock_provider.mount_mocks().await;
+		let client = mock_provider.into_client();
+		let nft = NftContract::new(&H160::repeat_byte(1), Some(&client));
+
+		let tokens: Vec<_> = nft.tokens().await.unwrap().into_stream(10).collect().await;
+		assert_eq!(tokens.len(), 1);
+		assert!(matches!(tokens[0], Err(ContractError::UnexpectedReturnType(_))));
+	}
+
+	#[tokio::test]
+	async fn test_tokens_without_session_fails() {
+		let mut mock_provider = MockClient::new().await;
+		mock_provider
+			.mock_halt_result(
+				"invokefunction",
+				json!([{
+					"type": "InteropInterface",
+					"interface": "IIterator",
+					"id": "fcf7b800-192a-488e-8ee5-9ff3e3aa7c42"
+				}]),
+			)
+			.await;
+		mock_provider.mount_mocks().await;
+		let client = mock_provider.into_client();
+		let nft = NftContract::new(&H160::repeat_byte(1), Some(&client));
+
+		assert!(matches!(nft.tokens().await, Err(ContractError::InvalidStateError(_))));
+	}
+}
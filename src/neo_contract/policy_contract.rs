@@ -15,6 +15,24 @@ pub struct PolicyContract<'a, P: JsonRpcProvider> {
 
 impl<'a, P: JsonRpcProvider + 'static> PolicyContract<'a, P> {
 	pub const NAME: &'static str = "PolicyContract";
+	/// The highest fee per byte the contract accepts, 1 GAS
+	pub const MAX_FEE_PER_BYTE: i32 = 100_000_000;
+	/// The highest execution fee factor the contract accepts
+	pub const MAX_EXEC_FEE_FACTOR: i32 = 100;
+	/// The highest storage price the contract accepts, 0.1 GAS
+	pub const MAX_STORAGE_PRICE: i32 = 10_000_000;
+	/// The native contracts, which the contract refuses to block
+	const NATIVE_CONTRACTS: [&'static str; 9] = [
+		"ContractManagement",
+		"StdLib",
+		"CryptoLib",
+		"LedgerContract",
+		"NeoToken",
+		"GasToken",
+		"PolicyContract",
+		"RoleManagement",
+		"OracleContract",
+	];
 	// pub const SCRIPT_HASH: H160 = Self::calc_native_contract_hash(Self::NAME).unwrap();
 
 	pub fn new(provider: Option<&'a RpcClient<P>>) -> Self {
@@ -38,29 +56,50 @@ impl<'a, P: JsonRpcProvider + 'static> PolicyContract<'a, P> {
 	}
 
 	// State modifying methods
+	//
+	// These build transactions that only succeed when witnessed by the committee, so the
+	// signers and witnesses are left to the caller.
 
+	/// Builds a transaction that sets the network fee per transaction byte, in GAS fractions.
 	pub async fn set_fee_per_byte(&self, fee: i32) -> Result<TransactionBuilder<P>, ContractError> {
+		Self::check_range("fee per byte", fee, 0, Self::MAX_FEE_PER_BYTE)?;
 		self.invoke_function("setFeePerByte", vec![fee.into()]).await
 	}
 
+	/// Builds a transaction that sets the factor applied to the execution fee of each opcode.
 	pub async fn set_exec_fee_factor(
 		&self,
 		fee: i32,
 	) -> Result<TransactionBuilder<P>, ContractError> {
+		Self::check_range("execution fee factor", fee, 1, Self::MAX_EXEC_FEE_FACTOR)?;
 		self.invoke_function("setExecFeeFactor", vec![fee.into()]).await
 	}
 
+	/// Builds a transaction that sets the price of one byte of contract storage, in GAS
+	/// fractions.
 	pub async fn set_storage_price(
 		&self,
 		price: i32,
 	) -> Result<TransactionBuilder<P>, ContractError> {
+		Self::check_range("storage price", price, 1, Self::MAX_STORAGE_PRICE)?;
 		self.invoke_function("setStoragePrice", vec![price.into()]).await
 	}
 
+	/// Builds a transaction that blocks `account` from sending transactions and being called.
+	/// Native contracts cannot be blocked.
 	pub async fn block_account(
 		&self,
 		account: &H160,
 	) -> Result<TransactionBuilder<P>, ContractError> {
+		let is_native = Self::NATIVE_CONTRACTS
+			.iter()
+			.any(|name| Self::calc_native_contract_hash(name).ok().as_ref() == Some(account));
+		if is_native {
+			return Err(ContractError::InvalidArgError(format!(
+				"{} is a native contract and cannot be blocked",
+				account.to_address()
+			)));
+		}
 		self.invoke_function("blockAccount", vec![account.into()]).await
 	}
 
@@ -68,10 +107,12 @@ impl<'a, P: JsonRpcProvider + 'static> PolicyContract<'a, P> {
 		&self,
 		address: &str,
 	) -> Result<TransactionBuilder<P>, ContractError> {
-		let account = ScriptHash::from_address(address).unwrap();
+		let account = ScriptHash::from_address(address)
+			.map_err(|e| ContractError::InvalidArgError(e.to_string()))?;
 		self.block_account(&account).await
 	}
 
+	/// Builds a transaction that removes `account` from the list of blocked accounts.
 	pub async fn unblock_account(
 		&self,
 		account: &H160,
@@ -83,9 +124,20 @@ impl<'a, P: JsonRpcProvider + 'static> PolicyContract<'a, P> {
 		&self,
 		address: &str,
 	) -> Result<TransactionBuilder<P>, ContractError> {
-		let account = ScriptHash::from_address(address).unwrap();
+		let account = ScriptHash::from_address(address)
+			.map_err(|e| ContractError::InvalidArgError(e.to_string()))?;
 		self.unblock_account(&account).await
 	}
+
+	fn check_range(name: &str, value: i32, min: i32, max: i32) -> Result<(), ContractError> {
+		if value < min || value > max {
+			return Err(ContractError::InvalidArgError(format!(
+				"The {} must be between {} and {}, got {}",
+				name, min, max, value
+			)));
+		}
+		Ok(())
+	}
 }
 
 #[async_trait]
@@ -104,3 +156,85 @@ impl<'a, P: JsonRpcProvider> SmartContractTrait<'a> for PolicyContract<'a, P> {
 		self.provider
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use neo::prelude::{
+		CallFlags, ContractError, HttpProvider, PolicyContract, ScriptBuilder, ScriptHashExtension,
+		SmartContractTrait, TestConstants,
+	};
+	use primitive_types::H160;
+
+	type Policy<'a> = PolicyContract<'a, HttpProvider>;
+
+	#[tokio::test]
+	async fn test_set_fee_per_byte() {
+		let policy = Policy::new(None);
+
+		let builder = policy.set_fee_per_byte(1000).await.unwrap();
+		let expected = ScriptBuilder::new()
+			.contract_call(
+				&policy.script_hash(),
+				"setFeePerByte",
+				&[1000i32.into()],
+				Some(CallFlags::All),
+			)
+			.unwrap()
+			.to_bytes();
+		assert_eq!(builder.script(), &Some(expected));
+		assert!(builder.signers().is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_setters_reject_out_of_range_values() {
+		let policy = Policy::new(None);
+
+		for fee in [-1, Policy::MAX_FEE_PER_BYTE + 1] {
+			assert!(matches!(
+				policy.set_fee_per_byte(fee).await,
+				Err(ContractError::InvalidArgError(_))
+			));
+		}
+		for factor in [0, Policy::MAX_EXEC_FEE_FACTOR + 1] {
+			assert!(matches!(
+				policy.set_exec_fee_factor(factor).await,
+				Err(ContractError::InvalidArgError(_))
+			));
+		}
+		for price in [0, Policy::MAX_STORAGE_PRICE + 1] {
+			assert!(matches!(
+				policy.set_storage_price(price).await,
+				Err(ContractError::InvalidArgError(_))
+			));
+		}
+		assert!(policy.set_exec_fee_factor(Policy::MAX_EXEC_FEE_FACTOR).await.is_ok());
+		assert!(policy.set_storage_price(Policy::MAX_STORAGE_PRICE).await.is_ok());
+	}
+
+	#[tokio::test]
+	async fn test_block_account() {
+		let policy = Policy::new(None);
+		let account = H160::from_address(TestConstants::DEFAULT_ACCOUNT_ADDRESS).unwrap();
+
+		let builder = policy.block_account(&account).await.unwrap();
+		let expected = ScriptBuilder::new()
+			.contract_call(
+				&policy.script_hash(),
+				"blockAccount",
+				&[account.into()],
+				Some(CallFlags::All),
+			)
+			.unwrap()
+			.to_bytes();
+		assert_eq!(builder.script(), &Some(expected));
+
+		assert!(matches!(
+			policy.block_account(&policy.script_hash()).await,
+			Err(ContractError::InvalidArgError(_))
+		));
+		assert!(matches!(
+			policy.block_account_address("not an address").await,
+			Err(ContractError::InvalidArgError(_))
+		));
+	}
+}
@@ -1,5 +1,4 @@
 use async_trait::async_trait;
-use num_enum::TryFromPrimitive;
 use primitive_types::H160;
 use serde::{Deserialize, Serialize};
 
@@ -16,18 +15,23 @@ pub struct RoleManagement<'a, P: JsonRpcProvider> {
 
 impl<'a, P: JsonRpcProvider + 'static> RoleManagement<'a, P> {
 	const NAME: &'static str = "RoleManagement";
+	/// The most nodes that can be designated for a role at once
+	pub const MAX_DESIGNATED_NODES: usize = 32;
 	// const SCRIPT_HASH: H160 = Self::calc_native_contract_hash(Self::NAME).unwrap(); // compute hash
 
 	pub fn new(provider: Option<&'a RpcClient<P>>) -> Self {
 		Self { script_hash: Self::calc_native_contract_hash(Self::NAME).unwrap(), provider }
 	}
 
+	/// Gets the public keys of the nodes designated for `role` as of block `block_index`.
+	///
+	/// `block_index` can be at most the current block count, i.e. the index of the next block.
 	pub async fn get_designated_by_role(
 		&self,
 		role: Role,
-		block_index: i32,
+		block_index: u32,
 	) -> Result<Vec<Secp256r1PublicKey>, ContractError> {
-		self.check_block_index_validity(block_index).await.unwrap();
+		self.check_block_index_validity(block_index).await?;
 
 		let invocation = self
 			.call_invoke_function(
@@ -35,30 +39,36 @@ impl<'a, P: JsonRpcProvider + 'static> RoleManagement<'a, P> {
 				vec![role.into(), block_index.into()],
 				vec![],
 			)
-			.await
-			.unwrap();
+			.await?;
+		self.throw_if_fault_state(&invocation)?;
 
-		let designated = invocation.stack[0]
-			.as_array()
-			.unwrap()
-			.into_iter()
+		let items = invocation.stack.first().and_then(|item| item.as_array()).ok_or_else(|| {
+			ContractError::UnexpectedReturnType("Expected an array of public keys".to_string())
+		})?;
+
+		items
+			.iter()
 			.map(|item| {
-				Secp256r1PublicKey::from_bytes(item.as_bytes().unwrap().as_slice()).unwrap()
+				item.as_bytes()
+					.and_then(|bytes| Secp256r1PublicKey::from_bytes(&bytes).ok())
+					.ok_or_else(|| {
+						ContractError::UnexpectedReturnType(format!(
+							"Expected a public key, got {:?}",
+							item
+						))
+					})
 			})
-			.collect();
-
-		Ok(designated)
+			.collect()
 	}
 
-	async fn check_block_index_validity(&self, block_index: i32) -> Result<(), ContractError> {
-		if block_index < 0 {
-			return Err(ContractError::InvalidNeoName("Block index must be positive".to_string()));
-		}
-
-		let current_block_count = self.provider.unwrap().get_block_count().await.unwrap();
+	async fn check_block_index_validity(&self, block_index: u32) -> Result<(), ContractError> {
+		let provider = self.provider.ok_or_else(|| {
+			ContractError::InvalidStateError("A provider is required to query designations".into())
+		})?;
+		let current_block_count = provider.get_block_count().await?;
 
-		if block_index > current_block_count as i32 {
-			return Err(ContractError::InvalidNeoName(format!(
+		if block_index > current_block_count {
+			return Err(ContractError::InvalidArgError(format!(
 				"Block index {} exceeds current block count {}",
 				block_index, current_block_count
 			)));
@@ -67,20 +77,24 @@ impl<'a, P: JsonRpcProvider + 'static> RoleManagement<'a, P> {
 		Ok(())
 	}
 
+	/// Builds a transaction that designates the nodes with `pub_keys` for `role`. The
+	/// transaction has to be witnessed by the committee, so the signers are left to the caller.
 	pub async fn designate_as_role(
 		&self,
 		role: Role,
 		pub_keys: Vec<Secp256r1PublicKey>,
 	) -> Result<TransactionBuilder<P>, ContractError> {
-		if pub_keys.is_empty() {
-			return Err(ContractError::InvalidNeoName(
-				"At least 1 public key is required".to_string(),
-			));
+		if pub_keys.is_empty() || pub_keys.len() > Self::MAX_DESIGNATED_NODES {
+			return Err(ContractError::InvalidArgError(format!(
+				"Between 1 and {} public keys are required, got {}",
+				Self::MAX_DESIGNATED_NODES,
+				pub_keys.len()
+			)));
 		}
 
-		let params: Vec<_> = pub_keys.into_iter().map(|key| key.to_value()).collect();
-
-		self.invoke_function("designateAsRole", vec![role.into(), params.into()]).await
+		let keys = pub_keys.iter().map(ContractParameter::public_key).collect();
+		self.invoke_function("designateAsRole", vec![role.into(), ContractParameter::array(keys)])
+			.await
 	}
 }
 
@@ -101,32 +115,103 @@ impl<'a, P: JsonRpcProvider> SmartContractTrait<'a> for RoleManagement<'a, P> {
 	}
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[repr(u8)]
-pub enum Role {
-	Oracle,
-	Policy,
-	Validator,
-	StateRootValidator,
-	PriceFeedOracle,
-	FeeCollector,
-	ComplianceOfficer,
-}
+#[cfg(test)]
+mod tests {
+	use serde_json::json;
+
+	use neo::prelude::{
+		CallFlags, ContractError, ContractParameter, HttpProvider, MockClient, Role,
+		RoleManagement, ScriptBuilder, Secp256r1PublicKey, SmartContractTrait, TestConstants,
+	};
 
-impl Role {
-	pub const fn byte(self) -> u8 {
-		self as u8
+	fn public_key() -> Secp256r1PublicKey {
+		Secp256r1PublicKey::from_encoded(TestConstants::DEFAULT_ACCOUNT_PUBLIC_KEY).unwrap()
 	}
-}
 
-impl From<Role> for StackItem {
-	fn from(role: Role) -> Self {
-		StackItem::Integer { value: role.byte() as i64 }
+	#[tokio::test]
+	async fn test_get_designated_by_role() {
+		let public_key = hex::decode(TestConstants::DEFAULT_ACCOUNT_PUBLIC_KEY).unwrap();
+		let mut mock_provider = MockClient::new().await;
+		mock_provider.mock_response_ignore_param("getblockcount", json!(1000)).await;
+		mock_provider
+			.mock_halt_result(
+				"invokefunction",
+				json!([{
+					"type": "Array",
+					"value": [{ "type": "ByteString", "value": base64::encode(&public_key) }]
+				}]),
+			)
+			.await;
+		mock_provider.mount_mocks().await;
+		let client = mock_provider.into_client();
+		let role_management = RoleManagement::new(Some(&client));
+
+		assert_eq!(
+			role_management.get_designated_by_role(Role::Oracle, 1000).await.unwrap(),
+			vec![Secp256r1PublicKey::from_bytes(&public_key).unwrap()]
+		);
+		assert!(matches!(
+			role_management.get_designated_by_role(Role::Oracle, 1001).await,
+			Err(ContractError::InvalidArgError(_))
+		));
 	}
-}
 
-impl Into<ContractParameter> for Role {
-	fn into(self) -> ContractParameter {
-		ContractParameter::integer(self.byte() as i64)
+	#[tokio::test]
+	async fn test_get_designated_by_role_fault() {
+		let mut mock_provider = MockClient::new().await;
+		mock_provider.mock_response_ignore_param("getblockcount", json!(1000)).await;
+		mock_provider
+			.mock_response_ignore_param(
+				"invokefunction",
+				json!({
+					"script": "",
+					"state": "FAULT",
+					"gasconsumed": "100000",
+					"exception": "The role is not supported",
+					"stack": []
+				}),
+			)
+			.await;
+		mock_provider.mount_mocks().await;
+		let client = mock_provider.into_client();
+		let role_management = RoleManagement::new(Some(&client));
+
+		match role_management.get_designated_by_role(Role::Oracle, 1000).await {
+			Err(ContractError::UnexpectedReturnType(exception)) => {
+				assert_eq!(exception, "The role is not supported")
+			},
+			other => panic!("expected the fault exception, got {other:?}"),
+		}
+	}
+
+	#[tokio::test]
+	async fn test_designate_as_role() {
+		let role_management = RoleManagement::<HttpProvider>::new(None);
+
+		let builder = role_management
+			.designate_as_role(Role::StateValidator, vec![public_key()])
+			.await
+			.unwrap();
+		let expected = ScriptBuilder::new()
+			.contract_call(
+				&role_management.script_hash(),
+				"designateAsRole",
+				&[
+					ContractParameter::integer(0x04),
+					ContractParameter::array(vec![ContractParameter::public_key(&public_key())]),
+				],
+				Some(CallFlags::All),
+			)
+			.unwrap()
+			.to_bytes();
+		assert_eq!(builder.script(), &Some(expected));
+
+		let too_many = RoleManagement::<HttpProvider>::MAX_DESIGNATED_NODES + 1;
+		for keys in [vec![], vec![public_key(); too_many]] {
+			assert!(matches!(
+				role_management.designate_as_role(Role::Oracle, keys).await,
+				Err(ContractError::InvalidArgError(_))
+			));
+		}
 	}
 }
@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+
+use primitive_types::H160;
+
+use neo::prelude::*;
+
+/// Remembers the number of decimals per token, so they only have to be fetched from the chain
+/// once. NEO and GAS are known up front.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenDecimals {
+	decimals: HashMap<H160, u8>,
+}
+
+impl TokenDecimals {
+	/// Creates a cache that knows the NEO and GAS tokens of clients using the provider `P`.
+	pub fn new<P: JsonRpcProvider + 'static>() -> Self {
+		let mut decimals = HashMap::new();
+		decimals.insert(
+			NeoToken::<P>::calc_native_contract_hash(NeoToken::<P>::NAME).unwrap(),
+			NeoToken::<P>::DECIMALS,
+		);
+		decimals.insert(
+			GasToken::<P>::calc_native_contract_hash(GasToken::<P>::NAME).unwrap(),
+			GasToken::<P>::DECIMALS,
+		);
+		Self { decimals }
+	}
+
+	/// The cached decimals of the token, if known.
+	pub fn get(&self, token: &H160) -> Option<u8> {
+		self.decimals.get(token).copied()
+	}
+
+	pub fn insert(&mut self, token: H160, decimals: u8) {
+		self.decimals.insert(token, decimals);
+	}
+
+	/// Returns the decimals of the token, invoking its `decimals` method on the first lookup.
+	pub async fn fetch<P: JsonRpcProvider>(
+		&mut self,
+		token: &H160,
+		client: &RpcClient<P>,
+	) -> Result<u8, ContractError> {
+		if let Some(decimals) = self.get(token) {
+			return Ok(decimals);
+		}
+
+		let decimals = FungibleTokenContract::new(token, Some(client)).get_decimals().await?;
+		self.insert(*token, decimals);
+		Ok(decimals)
+	}
+
+	/// Formats an amount in the smallest unit of the token, see [`format_token_amount`]. Returns
+	/// `None` if the decimals of the token are not cached yet.
+	pub fn format(&self, token: &H160, amount: u64) -> Option<String> {
+		self.get(token).map(|decimals| format_token_amount(amount, decimals))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::str::FromStr;
+
+	use primitive_types::H160;
+
+	use neo::prelude::{HttpProvider, GAS_TOKEN_HASH};
+
+	use super::TokenDecimals;
+
+	#[test]
+	fn test_knows_native_token_decimals() {
+		let decimals = TokenDecimals::new::<HttpProvider>();
+		let neo = H160::from_str("ef4073a0f2b305a38ec4050e4d3d28bc40ea63f5").unwrap();
+		assert_eq!(decimals.get(&neo), Some(0));
+		assert_eq!(decimals.get(&GAS_TOKEN_HASH), Some(8));
+	}
+
+	#[test]
+	fn test_format_with_cached_decimals() {
+		let mut decimals = TokenDecimals::new::<HttpProvider>();
+		assert_eq!(decimals.format(&GAS_TOKEN_HASH, 123456789), Some("1.23456789".to_string()));
+
+		let token = H160::repeat_byte(0x01);
+		assert_eq!(decimals.format(&token, 1234567), None);
+
+		decimals.insert(token, 6);
+		assert_eq!(decimals.get(&token), Some(6));
+		assert_eq!(decimals.format(&token, 1234567), Some("1.234567".to_string()));
+	}
+}
@@ -1,6 +1,7 @@
 use std::{collections::HashMap, sync::Arc};
 
 use async_trait::async_trait;
+use num_bigint::BigInt;
 use primitive_types::H160;
 
 use neo::prelude::{
@@ -17,6 +18,9 @@ pub trait NonFungibleTokenTrait<'a, P: JsonRpcProvider>: TokenTrait<'a, P> + Sen
 	const TRANSFER: &'static str = "transfer";
 	const TOKENS: &'static str = "tokens";
 	const PROPERTIES: &'static str = "properties";
+	const TOKEN_URI_PROPERTY: &'static str = "tokenURI";
+	const IMAGE_PROPERTY: &'static str = "image";
+	const ROYALTY_INFO: &'static str = "royaltyInfo";
 
 	// Token methods
 
@@ -31,14 +35,12 @@ pub trait NonFungibleTokenTrait<'a, P: JsonRpcProvider>: TokenTrait<'a, P> + Sen
 	// NFT methods
 
 	async fn tokens_of(&mut self, owner: H160) -> Result<NeoIterator<Bytes, P>, ContractError> {
-		let mapper_fn = Arc::new(|item: StackItem| item.as_bytes().unwrap());
-		Ok(self
-			.call_function_returning_iterator(
-				<NftContract<P> as NonFungibleTokenTrait<P>>::TOKENS_OF,
-				vec![owner.into()],
-				mapper_fn,
-			)
-			.await)
+		self.call_function_returning_iterator(
+			<NftContract<P> as NonFungibleTokenTrait<P>>::TOKENS_OF,
+			vec![owner.into()],
+			Arc::new(token_id),
+		)
+		.await
 	}
 
 	// Non-divisible NFT methods
@@ -254,13 +256,15 @@ pub trait NonFungibleTokenTrait<'a, P: JsonRpcProvider>: TokenTrait<'a, P> + Sen
 	) -> Result<NeoIterator<Address, P>, ContractError> {
 		self.throw_if_non_divisible_nft().await.unwrap();
 
-		Ok(self
-			.call_function_returning_iterator(
-				<NftContract<P> as NonFungibleTokenTrait<P>>::OWNER_OF,
-				vec![token_id.into()],
-				Arc::new(|item: StackItem| item.as_address().unwrap()),
-			)
-			.await)
+		self.call_function_returning_iterator(
+			<NftContract<P> as NonFungibleTokenTrait<P>>::OWNER_OF,
+			vec![token_id.into()],
+			Arc::new(|item: StackItem| {
+				item.as_address()
+					.ok_or_else(|| ContractError::UnexpectedReturnType("Address".to_string()))
+			}),
+		)
+		.await
 	}
 
 	async fn throw_if_non_divisible_nft(&mut self) -> Result<(), ContractError> {
@@ -289,41 +293,40 @@ pub trait NonFungibleTokenTrait<'a, P: JsonRpcProvider>: TokenTrait<'a, P> + Sen
 
 	// Optional methods
 
-	async fn tokens(&mut self) -> Result<NeoIterator<Bytes, P>, ContractError> {
-		Ok(self
-			.call_function_returning_iterator(
-				<NftContract<P> as NonFungibleTokenTrait<P>>::TOKENS,
-				vec![],
-				Arc::new(|item: StackItem| item.as_bytes().unwrap()),
-			)
-			.await)
+	/// Gets an iterator over the ids of all tokens of the contract. Use
+	/// [`NeoIterator::into_stream`] to enumerate large collections page by page.
+	async fn tokens(&self) -> Result<NeoIterator<Bytes, P>, ContractError> {
+		self.call_function_returning_iterator(
+			<NftContract<P> as NonFungibleTokenTrait<P>>::TOKENS,
+			vec![],
+			Arc::new(token_id),
+		)
+		.await
 	}
 
+	/// Gets the NEP-11 properties of the token `token_id`, keyed by property name.
 	async fn properties(
-		&mut self,
-		token_id: Bytes,
-	) -> Result<HashMap<String, String>, ContractError> {
-		let invocation_result = self
+		&self,
+		token_id: &[u8],
+	) -> Result<HashMap<String, StackItem>, ContractError> {
+		let output = self
 			.call_invoke_function(
 				<NftContract<P> as NonFungibleTokenTrait<P>>::PROPERTIES,
 				vec![token_id.into()],
 				vec![],
 			)
-			.await
-			.unwrap();
+			.await?;
+		self.throw_if_fault_state(&output)?;
 
-		let stack_item = invocation_result.get_first_stack_item().unwrap();
-		let map = stack_item
-			.as_map()
-			.ok_or(ContractError::UnexpectedReturnType(
-				stack_item.to_string() + &StackItem::MAP_VALUE.to_string(),
-			))
-			.unwrap();
-
-		map.iter()
-			.map(|(k, v)| {
-				let key = k.as_string().unwrap();
-				let value = v.as_string().unwrap();
+		let map =
+			output.stack.first().and_then(StackItem::as_map).ok_or_else(|| {
+				ContractError::UnexpectedReturnType(StackItem::MAP_VALUE.to_string())
+			})?;
+		map.into_iter()
+			.map(|(key, value)| {
+				let key = key.as_string().ok_or_else(|| {
+					ContractError::UnexpectedReturnType("String property name".to_string())
+				})?;
 				Ok((key, value))
 			})
 			.collect()
@@ -333,28 +336,61 @@ pub trait NonFungibleTokenTrait<'a, P: JsonRpcProvider>: TokenTrait<'a, P> + Sen
 		&mut self,
 		token_id: Bytes,
 	) -> Result<HashMap<String, StackItem>, ContractError> {
-		let invocation_result = self
+		self.properties(&token_id).await
+	}
+
+	/// Gets the `tokenURI` property of the token `token_id`, if it has one.
+	async fn token_uri(&self, token_id: &[u8]) -> Result<Option<String>, ContractError> {
+		let properties = self.properties(token_id).await?;
+		Ok(properties.get(Self::TOKEN_URI_PROPERTY).and_then(StackItem::as_string))
+	}
+
+	/// Gets the `image` property of the token `token_id`, if it has one.
+	async fn image(&self, token_id: &[u8]) -> Result<Option<String>, ContractError> {
+		let properties = self.properties(token_id).await?;
+		Ok(properties.get(Self::IMAGE_PROPERTY).and_then(StackItem::as_string))
+	}
+
+	/// Gets the NEP-24 royalties due when selling the token `token_id` for `sale_price` of
+	/// `royalty_token`, as the recipient and amount of each royalty.
+	async fn royalty_info(
+		&self,
+		token_id: &[u8],
+		royalty_token: H160,
+		sale_price: i64,
+	) -> Result<Vec<(H160, BigInt)>, ContractError> {
+		let output = self
 			.call_invoke_function(
-				<NftContract<P> as NonFungibleTokenTrait<P>>::PROPERTIES,
-				vec![token_id.into()],
+				Self::ROYALTY_INFO,
+				vec![token_id.into(), royalty_token.into(), ContractParameter::integer(sale_price)],
 				vec![],
 			)
-			.await
-			.unwrap();
-
-		let stack_item = invocation_result.get_first_stack_item().unwrap();
-		let map = stack_item
-			.as_map()
-			.ok_or(ContractError::UnexpectedReturnType(
-				stack_item.to_string() + &StackItem::MAP_VALUE.to_string(),
-			))
-			.unwrap();
-
-		map.into_iter()
-			.map(|(k, v)| {
-				let key = k.as_string().unwrap();
-				Ok((key, v.clone()))
+			.await?;
+		self.throw_if_fault_state(&output)?;
+
+		let unexpected = || ContractError::UnexpectedReturnType("Array of royalties".to_string());
+		let royalties =
+			output.stack.first().and_then(StackItem::as_array).ok_or_else(unexpected)?;
+		royalties
+			.iter()
+			.map(|royalty| {
+				let fields =
+					royalty.as_array().filter(|fields| fields.len() == 2).ok_or_else(unexpected)?;
+				// The contract returns the recipient's script hash in little-endian order
+				let mut recipient = fields[0]
+					.as_bytes()
+					.filter(|bytes| bytes.len() == 20)
+					.ok_or_else(unexpected)?;
+				recipient.reverse();
+				let amount = fields[1].as_i256().map_err(|_| unexpected())?;
+				Ok((H160::from_slice(&recipient), amount))
 			})
 			.collect()
 	}
 }
+
+/// Maps an iterator item to a token id, which the contract returns as a byte string.
+fn token_id(item: StackItem) -> Result<Bytes, ContractError> {
+	item.as_bytes()
+		.ok_or_else(|| ContractError::UnexpectedReturnType("ByteString token id".to_string()))
+}
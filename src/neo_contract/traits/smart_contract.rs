@@ -32,7 +32,7 @@ pub trait SmartContractTrait<'a>: Send + Sync {
 		function: &str,
 		params: Vec<ContractParameter>,
 	) -> Result<TransactionBuilder<Self::P>, ContractError> {
-		let script = self.build_invoke_function_script(function, params).await.unwrap();
+		let script = self.build_invoke_function_script(function, params).await?;
 		let mut builder = TransactionBuilder::new();
 		builder.set_script(Some(script));
 		Ok(builder)
@@ -47,9 +47,10 @@ pub trait SmartContractTrait<'a>: Send + Sync {
 			return Err(ContractError::InvalidNeoName("Function name cannot be empty".to_string()));
 		}
 
+		// Calls that change state need to be able to write, notify and call other contracts
 		let script = ScriptBuilder::new()
-			.contract_call(&self.script_hash(), function, params.as_slice(), Some(CallFlags::None))
-			.unwrap()
+			.contract_call(&self.script_hash(), function, params.as_slice(), Some(CallFlags::All))
+			.map_err(|e| ContractError::InvalidArgError(e.to_string()))?
 			.to_bytes();
 
 		Ok(script)
@@ -80,8 +81,8 @@ pub trait SmartContractTrait<'a>: Send + Sync {
 
 		let item = output.stack[0].clone();
 		match item.as_int() {
-			Some(i) => Ok(i as i32),
-			None => Err(ContractError::UnexpectedReturnType("Int".to_string())),
+			Ok(i) => Ok(i as i32),
+			Err(_) => Err(ContractError::UnexpectedReturnType("Int".to_string())),
 		}
 	}
 
@@ -138,37 +139,47 @@ pub trait SmartContractTrait<'a>: Send + Sync {
 		function: &str,
 		params: Vec<ContractParameter>,
 	) -> Result<H160, ContractError> {
-		let output = self.call_invoke_function(function, params, vec![]).await.unwrap();
-		self.throw_if_fault_state(&output).unwrap();
-
-		let item = &output.stack[0];
-		item.as_bytes()
-			.as_deref()
-			.map(|b| ScriptHash::from_script(b))
+		let output = self.call_invoke_function(function, params, vec![]).await?;
+		self.throw_if_fault_state(&output)?;
+
+		// The contract returns the script hash in little-endian order
+		output
+			.stack
+			.first()
+			.and_then(StackItem::as_bytes)
+			.filter(|bytes| bytes.len() == 20)
+			.map(|mut bytes| {
+				bytes.reverse();
+				ScriptHash::from_slice(&bytes)
+			})
 			.ok_or_else(|| ContractError::UnexpectedReturnType("Script hash".to_string()))
 	}
 
+	/// Invokes `function`, which returns an iterator, and gets the iterator. Its items are mapped
+	/// with `mapper` as they are traversed. Fails if the node did not open a session for it,
+	/// e.g. because sessions are disabled.
 	async fn call_function_returning_iterator<U>(
 		&self,
 		function: &str,
 		params: Vec<ContractParameter>,
-		mapper: Arc<dyn Fn(StackItem) -> U + Send + Sync>,
-	) -> NeoIterator<U, Self::P>
+		mapper: Arc<dyn Fn(StackItem) -> Result<U, ContractError> + Send + Sync>,
+	) -> Result<NeoIterator<U, Self::P>, ContractError>
 	where
 		U: Send + Sync, // Adding this bound if necessary
 	{
-		let output = self.call_invoke_function(function, params, vec![]).await.unwrap();
-		self.throw_if_fault_state(&output).unwrap();
-
-		let item = &output.stack[0];
-		let StackItem::InteropInterface { id, interface: _ } = item else { panic!("") };
-
+		let output = self.call_invoke_function(function, params, vec![]).await?;
+		self.throw_if_fault_state(&output)?;
+
+		let Some(StackItem::InteropInterface { id, .. }) = output.stack.first() else {
+			return Err(ContractError::UnexpectedReturnType(
+				StackItem::INTEROP_INTERFACE_VALUE.to_string(),
+			));
+		};
 		let session_id = output
 			.session_id
-			.ok_or(ContractError::InvalidNeoNameServiceRoot("No session ID".to_string()))
-			.unwrap();
+			.ok_or_else(|| ContractError::InvalidStateError("No session ID".to_string()))?;
 
-		NeoIterator::new(session_id, id.clone(), mapper, None)
+		Ok(NeoIterator::new(session_id, id.clone(), mapper, self.provider()))
 	}
 
 	async fn call_function_and_unwrap_iterator<U>(
@@ -193,7 +204,7 @@ pub trait SmartContractTrait<'a>: Send + Sync {
 
 		self.throw_if_fault_state(&output).unwrap();
 
-		let items = output.stack[0].as_array().unwrap().into_iter().map(mapper).collect();
+		let items = output.stack[0].as_array().unwrap().iter().cloned().map(mapper).collect();
 
 		Ok(items)
 	}
@@ -207,14 +218,17 @@ pub trait SmartContractTrait<'a>: Send + Sync {
 		nef_checksum: u32,
 		contract_name: &str,
 	) -> Result<H160, ContractError> {
+		// The sender is pushed as the network serializes it, i.e. in little-endian order
+		let mut sender = sender.to_vec();
+		sender.reverse();
 		let mut script = ScriptBuilder::new();
 		script
 			.op_code(&[OpCode::Abort])
-			.push_data(sender.to_vec())
+			.push_data(sender)
 			.push_integer(BigInt::from(nef_checksum))
 			.push_data(contract_name.as_bytes().to_vec());
 
-		Ok(H160::from_slice(&script.to_bytes()))
+		Ok(H160::from_script(&script.to_bytes()))
 	}
 
 	async fn get_manifest(&self) -> ContractManifest {
@@ -1,5 +1,10 @@
 use sha2::{Digest, Sha256};
 
+use neo::prelude::Base58Error;
+
+/// Length of the checksum appended to the data
+const CHECKSUM_LENGTH: usize = 4;
+
 /// Encodes a byte slice into a base58check string.
 ///
 /// # Arguments
@@ -19,17 +24,37 @@ pub fn base58check_encode(bytes: &[u8]) -> String {
 		return "".to_string();
 	}
 
-	let checksum = &calculate_checksum(bytes)[..4];
-	let bytes_with_checksum = [bytes, checksum].concat();
+	let checksum = calculate_checksum(bytes);
+	let bytes_with_checksum = [bytes, &checksum].concat();
 	bs58::encode(bytes_with_checksum).into_string()
 }
 
+/// Encodes `payload` prefixed with the `version` byte into a base58check string, as done for
+/// addresses.
+///
+/// # Example
+///
+/// ```
+///
+/// use NeoRust::prelude::base58check_encode_with_version;
+/// let script_hash = [0u8; 20];
+/// let address = base58check_encode_with_version(&script_hash, 0x35);
+/// ```
+pub fn base58check_encode_with_version(payload: &[u8], version: u8) -> String {
+	base58check_encode(&[&[version], payload].concat())
+}
+
 /// Decodes a base58check string into a byte vector.
 ///
 /// # Arguments
 ///
 /// * `input` - A base58check string to be decoded.
 ///
+/// # Errors
+///
+/// Returns a [`Base58Error`] telling whether the input has a character outside the Base58
+/// alphabet, is too short to hold a checksum, or has a checksum that does not match.
+///
 /// # Example
 ///
 /// ```
@@ -38,24 +63,29 @@ pub fn base58check_encode(bytes: &[u8]) -> String {
 /// let input = "Abc123";
 /// let decoded = base58check_decode(input);
 /// ```
-pub fn base58check_decode(input: &str) -> Option<Vec<u8>> {
-	let bytes_with_checksum = match bs58::decode(input).into_vec() {
-		Ok(bytes) => bytes,
-		Err(_) => return None,
-	};
-
-	if bytes_with_checksum.len() < 4 {
-		return None;
+pub fn base58check_decode(input: &str) -> Result<Vec<u8>, Base58Error> {
+	let bytes_with_checksum = bs58::decode(input).into_vec().map_err(|err| {
+		let index = match err {
+			bs58::decode::Error::InvalidCharacter { index, .. }
+			| bs58::decode::Error::NonAsciiCharacter { index } => index,
+			_ => 0,
+		};
+		let character = input[index..].chars().next().unwrap_or_default();
+		Base58Error::InvalidCharacter { character, index }
+	})?;
+
+	if bytes_with_checksum.len() <= CHECKSUM_LENGTH {
+		return Err(Base58Error::InvalidLength(bytes_with_checksum.len()));
 	}
 
-	let (bytes, checksum) = bytes_with_checksum.split_at(bytes_with_checksum.len() - 4);
-	let expected_checksum = calculate_checksum(bytes);
-
-	if checksum != &expected_checksum[..4] {
-		return None;
+	let (bytes, checksum) =
+		bytes_with_checksum.split_at(bytes_with_checksum.len() - CHECKSUM_LENGTH);
+	let expected = calculate_checksum(bytes);
+	if checksum != expected {
+		return Err(Base58Error::InvalidChecksum { expected, actual: checksum.try_into().unwrap() });
 	}
 
-	Some(bytes.to_vec())
+	Ok(bytes.to_vec())
 }
 
 /// Calculates the checksum of a byte slice.
@@ -127,7 +157,7 @@ mod base58_tests {
 	fn test_base58_decoding_for_invalid_strings() {
 		for invalid_string in INVALID_STRINGS {
 			let result = base58check_decode(invalid_string);
-			assert!(result.is_none());
+			assert!(matches!(result, Err(Base58Error::InvalidCharacter { .. })));
 		}
 	}
 
@@ -150,16 +180,66 @@ mod base58_tests {
 			44, 61, 157, 254,
 		];
 		let actual_output = base58check_decode(input_string);
-		assert_eq!(actual_output, Some(expected_output_data));
+		assert_eq!(actual_output, Ok(expected_output_data));
 	}
 
 	#[test]
 	fn test_base58check_decoding_with_invalid_characters() {
-		assert!(base58check_decode("0oO1lL").is_none());
+		assert_eq!(
+			base58check_decode("0oO1lL"),
+			Err(Base58Error::InvalidCharacter { character: '0', index: 0 })
+		);
+		assert_eq!(
+			base58check_decode("tz1Y3qqTg9Hdrz€GbEjiCPmwuZ7fWVxpPtRw"),
+			Err(Base58Error::InvalidCharacter { character: '€', index: 14 })
+		);
 	}
 
 	#[test]
 	fn test_base58check_decoding_with_invalid_checksum() {
-		assert!(base58check_decode("tz1Y3qqTg9HdrzZGbEjiCPmwuZ7fWVxpPtrW").is_none());
+		assert!(matches!(
+			base58check_decode("tz1Y3qqTg9HdrzZGbEjiCPmwuZ7fWVxpPtrW"),
+			Err(Base58Error::InvalidChecksum { .. })
+		));
+	}
+
+	#[test]
+	fn test_base58check_decoding_with_invalid_length() {
+		assert_eq!(base58check_decode(""), Err(Base58Error::InvalidLength(0)));
+		assert_eq!(base58check_decode("2g"), Err(Base58Error::InvalidLength(1)));
+	}
+
+	#[test]
+	fn test_base58check_decoding_truncated_and_corrupted() {
+		let valid = base58check_encode_with_version(&[0x11; 20], 0x35);
+		assert_eq!(base58check_decode(&valid).unwrap()[0], 0x35);
+
+		for end in 0..valid.len() {
+			assert!(base58check_decode(&valid[..end]).is_err(), "truncated to {}", end);
+		}
+
+		let alphabet = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+		for index in 0..valid.len() {
+			for replacement in alphabet.chars().filter(|c| valid.chars().nth(index) != Some(*c)) {
+				let mut corrupted = valid.clone();
+				corrupted.replace_range(index..=index, &replacement.to_string());
+				assert!(base58check_decode(&corrupted).is_err(), "corrupted {}", corrupted);
+			}
+
+			let mut corrupted = valid.clone();
+			corrupted.replace_range(index..=index, "0");
+			assert_eq!(
+				base58check_decode(&corrupted),
+				Err(Base58Error::InvalidCharacter { character: '0', index })
+			);
+		}
+	}
+
+	#[test]
+	fn test_base58check_encoding_with_version() {
+		assert_eq!(
+			base58check_encode_with_version(&[0x01, 0x02, 0x03], 0x35),
+			base58check_encode(&[0x35, 0x01, 0x02, 0x03])
+		);
 	}
 }
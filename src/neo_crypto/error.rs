@@ -6,6 +6,8 @@ pub enum CryptoError {
 	InvalidPassphrase(String),
 	#[error("Invalid format: {0}")]
 	InvalidFormat(String),
+	#[error("Invalid WIF: {0}")]
+	InvalidWif(String),
 	#[error("invalid signature length, got {0}, expected 65")]
 	HeaderOutOfRange(u8),
 	#[error("Could not recover public key from signature")]
@@ -20,10 +22,22 @@ pub enum CryptoError {
 	SigningError,
 	#[error("Signature verification error")]
 	SignatureVerificationError,
+	#[error("Operation cancelled")]
+	Cancelled,
 	#[error(transparent)]
 	FromHexError(#[from] hex::FromHexError),
 }
 
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum Base58Error {
+	#[error("Invalid Base58 character {character:?} at index {index}")]
+	InvalidCharacter { character: char, index: usize },
+	#[error("Invalid Base58Check checksum, expected {expected:02x?} but got {actual:02x?}")]
+	InvalidChecksum { expected: [u8; 4], actual: [u8; 4] },
+	#[error("Base58Check data of {0} bytes is too short to hold a payload and its checksum")]
+	InvalidLength(usize),
+}
+
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum Nep2Error {
 	#[error("Invalid passphrase: {0}")]
@@ -38,4 +52,6 @@ pub enum SignError {
 	HeaderOutOfRange(u8),
 	#[error("Could not recover public key from signature")]
 	RecoverFailed,
+	#[error("Signing failed")]
+	SigningFailed,
 }
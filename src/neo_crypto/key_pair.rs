@@ -6,7 +6,8 @@
 //! including generating new pairs, importing them from raw bytes,
 //! and converting them to various formats.
 
-use rand::rngs::OsRng;
+use elliptic_curve::zeroize::Zeroizing;
+use rand::{rngs::OsRng, CryptoRng, RngCore};
 
 use neo::prelude::{
 	private_key_from_wif, wif_from_private_key, CryptoError, PublicKeyExtension,
@@ -59,10 +60,19 @@ impl KeyPair {
 	}
 
 	/// Returns the 32-byte representation of the private key.
+	///
+	/// Prefer [`KeyPair::expose_secret`], which zeroizes the copy when it is dropped.
 	pub fn private_key_bytes(&self) -> [u8; 32] {
 		self.private_key.to_raw_bytes()
 	}
 
+	/// Returns a copy of the 32-byte private key that is zeroized when dropped.
+	///
+	/// This is the way to read the secret; the key pair's `Debug` output does not show it.
+	pub fn expose_secret(&self) -> Zeroizing<[u8; 32]> {
+		Zeroizing::new(self.private_key.to_raw_bytes())
+	}
+
 	/// Returns the 64-byte uncompressed representation of the public key.
 	pub fn public_key_bytes(&self) -> [u8; 64] {
 		let mut buf = [0u8; 64];
@@ -78,7 +88,12 @@ impl KeyPair {
 	/// Generates a new random `KeyPair`.
 	pub fn new_random() -> Self {
 		let mut rng = OsRng; // A cryptographically secure random number generator
-		let secret_key = Secp256r1PrivateKey::random(&mut rng);
+		Self::new_random_with_rng(&mut rng)
+	}
+
+	/// Generates a new random `KeyPair` from the given cryptographically secure RNG.
+	pub fn new_random_with_rng(rng: &mut (impl RngCore + CryptoRng)) -> Self {
+		let secret_key = Secp256r1PrivateKey::random(rng);
 		Self::from_secret_key(&secret_key)
 	}
 
@@ -119,11 +134,17 @@ impl KeyPair {
 		Ok(Self::new(secret_key, public_key))
 	}
 
+	/// Encodes the private key of the key pair as a Wallet Import Format (WIF) string, which
+	/// [`KeyPair::from_wif`] turns back into this key pair.
+	pub fn to_wif(&self) -> String {
+		wif_from_private_key(&self.private_key())
+	}
+
 	/// Exports the key pair as a Wallet Import Format (WIF) string
 	///
 	/// Returns: The WIF encoding of this key pair
 	pub fn export_as_wif(&self) -> String {
-		wif_from_private_key(&self.private_key())
+		self.to_wif()
 	}
 
 	pub fn get_script_hash(&self) -> ScriptHash {
@@ -257,4 +278,16 @@ mod tests {
 	//         enc_point.from_hex().unwrap()
 	//     );
 	// }
+
+	#[test]
+	fn test_expose_secret() {
+		let key_pair = KeyPair::from_wif(TestConstants::DEFAULT_ACCOUNT_WIF).unwrap();
+
+		assert_eq!(*key_pair.expose_secret(), key_pair.private_key.to_raw_bytes());
+		assert_eq!(
+			hex::encode(*key_pair.expose_secret()),
+			TestConstants::DEFAULT_ACCOUNT_PRIVATE_KEY
+		);
+		assert!(!format!("{:?}", key_pair).contains(TestConstants::DEFAULT_ACCOUNT_PRIVATE_KEY));
+	}
 }
@@ -59,8 +59,10 @@ use std::{
 	hash::{Hash, Hasher},
 };
 
-// use zeroize::Zeroize;
-use elliptic_curve::zeroize::Zeroize;
+use elliptic_curve::{
+	subtle::ConstantTimeEq,
+	zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing},
+};
 use neo::prelude::{CryptoError, Decoder, Encoder, NeoConstants, NeoSerializable};
 use p256::{
 	ecdsa::{signature::Signer, Signature, SigningKey, VerifyingKey},
@@ -71,7 +73,7 @@ use p256::{
 	EncodedPoint, FieldBytes, PublicKey, SecretKey,
 };
 use primitive_types::U256;
-use rand_core::OsRng;
+use rand_core::{CryptoRng, RngCore};
 use rustc_serialize::hex::{FromHex, ToHex};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use signature::{hazmat::PrehashSigner, SignerMut, Verifier};
@@ -82,6 +84,10 @@ pub struct Secp256r1PublicKey {
 	inner: PublicKey,
 }
 
+/// A private key on the secp256r1 curve.
+///
+/// The key is zeroized when dropped and compared in constant time. Its `Debug` and `Display`
+/// output does not contain the key.
 #[derive(Debug, Clone)]
 pub struct Secp256r1PrivateKey {
 	inner: SecretKey,
@@ -236,10 +242,10 @@ impl Secp256r1PublicKey {
 impl Secp256r1PrivateKey {
 	/// Generates a new private key using the provided random number generator (RNG).
 	///
-	/// - Parameter rng: A mutable reference to an `OsRng` instance.
+	/// - Parameter rng: A mutable reference to a cryptographically secure RNG, e.g. `OsRng`.
 	///
 	/// - Returns: A new instance of the private key.
-	pub fn random(rng: &mut OsRng) -> Self {
+	pub fn random(rng: &mut (impl RngCore + CryptoRng)) -> Self {
 		Self { inner: SecretKey::random(rng) }
 	}
 
@@ -264,7 +270,10 @@ impl Secp256r1PrivateKey {
 	///
 	/// - Returns: A 32-byte array representing the private key.
 	pub fn to_raw_bytes(&self) -> [u8; 32] {
-		self.inner.clone().to_bytes().as_slice().try_into().unwrap()
+		let mut bytes = self.inner.to_bytes();
+		let raw = bytes.as_slice().try_into().unwrap();
+		bytes.as_mut_slice().zeroize();
+		raw
 	}
 
 	/// Converts the private key to its corresponding public key.
@@ -274,11 +283,12 @@ impl Secp256r1PrivateKey {
 		Secp256r1PublicKey::from_public_key(self.inner.public_key())
 	}
 
+	/// Replaces the key with a fixed, non-secret key. The replaced key is zeroized.
+	///
+	/// A key of all zero bytes is not a valid private key, so the key bytes are all ones
+	/// afterwards.
 	pub fn erase(&mut self) {
-		// let mut bytes = self.inner.to_bytes();
-		// bytes.zeroize();
 		let bytes = [1u8; 32];
-		// Recreate the SecretKey from zeroized bytes
 		self.inner = SecretKey::from_bytes(&bytes.into()).unwrap();
 	}
 
@@ -291,8 +301,7 @@ impl Secp256r1PrivateKey {
 	///
 	/// - Returns: A `Result` with the `Secp256r1Signature` or a `CryptoError`.
 	pub fn sign_tx(&self, message: &[u8]) -> Result<Secp256r1Signature, CryptoError> {
-		let signing_key = SigningKey::from_slice(&self.inner.to_bytes().as_slice())
-			.map_err(|_| CryptoError::InvalidPrivateKey)?;
+		let signing_key = SigningKey::from(&self.inner);
 		let (signature, _) =
 			signing_key.try_sign(message).map_err(|_| CryptoError::SigningError)?;
 
@@ -307,8 +316,7 @@ impl Secp256r1PrivateKey {
 	/// - Note: The message should be prehashed using a secure hash function before calling this method.
 	///  The signature is generated using the ECDSA algorithm.
 	pub fn sign_prehash(&self, message: &[u8]) -> Result<Secp256r1Signature, CryptoError> {
-		let signing_key = SigningKey::from_slice(&self.inner.to_bytes().as_slice())
-			.map_err(|_| CryptoError::InvalidPrivateKey)?;
+		let signing_key = SigningKey::from(&self.inner);
 		let (signature, _) =
 			signing_key.sign_prehash(message).map_err(|_| CryptoError::SigningError)?;
 
@@ -390,7 +398,7 @@ impl Secp256r1Signature {
 
 impl fmt::Display for Secp256r1PrivateKey {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "Secp256r1PrivateKey: {}\n", hex::encode(self.inner.to_bytes().as_slice()))
+		write!(f, "Secp256r1PrivateKey: <redacted>")
 	}
 }
 
@@ -425,7 +433,7 @@ impl Serialize for Secp256r1PrivateKey {
 	where
 		S: Serializer,
 	{
-		serializer.serialize_bytes(&self.to_raw_bytes())
+		serializer.serialize_bytes(Zeroizing::new(self.to_raw_bytes()).as_slice())
 	}
 }
 
@@ -454,7 +462,7 @@ impl<'de> Deserialize<'de> for Secp256r1PrivateKey {
 	where
 		D: Deserializer<'de>,
 	{
-		let bytes = <Vec<u8>>::deserialize(deserializer)?;
+		let bytes = Zeroizing::new(<Vec<u8>>::deserialize(deserializer)?);
 		Secp256r1PrivateKey::from_bytes(&bytes)
 			.map_err(|_| serde::de::Error::custom("Invalid private key"))
 	}
@@ -503,7 +511,7 @@ impl Hash for Secp256r1PublicKey {
 
 impl Hash for Secp256r1PrivateKey {
 	fn hash<H: Hasher>(&self, state: &mut H) {
-		self.to_raw_bytes().hash(state);
+		Zeroizing::new(self.to_raw_bytes()).hash(state);
 	}
 }
 
@@ -515,10 +523,15 @@ impl Hash for Secp256r1Signature {
 
 impl PartialEq for Secp256r1PrivateKey {
 	fn eq(&self, other: &Self) -> bool {
-		self.to_raw_bytes() == other.to_raw_bytes()
+		self.inner.ct_eq(&other.inner).into()
 	}
 }
 
+impl Eq for Secp256r1PrivateKey {}
+
+// The wrapped `SecretKey` zeroizes its scalar when dropped.
+impl ZeroizeOnDrop for Secp256r1PrivateKey {}
+
 impl PartialEq for Secp256r1Signature {
 	fn eq(&self, other: &Self) -> bool {
 		self.to_bytes() == other.to_bytes()
@@ -592,7 +605,9 @@ impl NeoSerializable for Secp256r1PublicKey {
 	}
 
 	fn decode(reader: &mut Decoder) -> Result<Self, Self::Error> {
-		let bytes = reader.read_bytes(NeoConstants::PUBLIC_KEY_SIZE_COMPRESSED as usize).unwrap();
+		let bytes = reader
+			.read_bytes(NeoConstants::PUBLIC_KEY_SIZE_COMPRESSED as usize)
+			.map_err(|_| CryptoError::InvalidPublicKey)?;
 		Secp256r1PublicKey::from_bytes(&bytes).map_err(|_| CryptoError::InvalidPublicKey)
 	}
 
@@ -606,6 +621,9 @@ impl NeoSerializable for Secp256r1PublicKey {
 
 #[cfg(test)]
 mod tests {
+	use std::{mem, mem::ManuallyDrop, slice};
+
+	use elliptic_curve::zeroize::ZeroizeOnDrop;
 	use hex_literal::hex;
 	use p256::EncodedPoint;
 	use rustc_serialize::hex::{FromHex, ToHex};
@@ -744,4 +762,64 @@ mod tests {
 		// TODO: check this verification
 		// assert!(public_key.verify(&hashed_msg, &expected_signature).is_ok());
 	}
+
+	#[test]
+	fn test_private_key_equality() {
+		let key = Secp256r1PrivateKey::from_bytes(&hex!(
+			"a7038726c5a127989d78593c423e3dad93b2d74db90a16c0a58468c9e6617a87"
+		))
+		.unwrap();
+		let other = Secp256r1PrivateKey::from_bytes(&hex!(
+			"a7038726c5a127989d78593c423e3dad93b2d74db90a16c0a58468c9e6617a88"
+		))
+		.unwrap();
+
+		assert_eq!(key, key.clone());
+		assert_ne!(key, other);
+	}
+
+	#[test]
+	fn test_private_key_is_not_printed() {
+		let key = Secp256r1PrivateKey::from_bytes(&hex!(
+			"a7038726c5a127989d78593c423e3dad93b2d74db90a16c0a58468c9e6617a87"
+		))
+		.unwrap();
+
+		for output in [format!("{}", key), format!("{:?}", key)] {
+			assert!(!output.contains("a7038726c5a127989d78593c423e3dad"));
+		}
+	}
+
+	#[test]
+	fn test_private_key_zeroizes_on_drop() {
+		fn assert_zeroize_on_drop<T: ZeroizeOnDrop>() {}
+		assert_zeroize_on_drop::<Secp256r1PrivateKey>();
+
+		let mut key = ManuallyDrop::new(
+			Secp256r1PrivateKey::from_bytes(&hex!(
+				"a7038726c5a127989d78593c423e3dad93b2d74db90a16c0a58468c9e6617a87"
+			))
+			.unwrap(),
+		);
+		let ptr = &*key as *const Secp256r1PrivateKey as *const u8;
+		// SAFETY: the key's storage outlives the reads, dropping it only runs its destructor
+		let memory = || {
+			unsafe { slice::from_raw_parts(ptr, mem::size_of::<Secp256r1PrivateKey>()) }.to_vec()
+		};
+		assert!(memory().iter().any(|byte| *byte != 0));
+
+		unsafe { ManuallyDrop::drop(&mut key) };
+		assert!(memory().iter().all(|byte| *byte == 0));
+	}
+
+	#[test]
+	fn test_erase_private_key() {
+		let mut key = Secp256r1PrivateKey::from_bytes(&hex!(
+			"a7038726c5a127989d78593c423e3dad93b2d74db90a16c0a58468c9e6617a87"
+		))
+		.unwrap();
+
+		key.erase();
+		assert_eq!(key.to_raw_bytes(), [1u8; 32]);
+	}
 }
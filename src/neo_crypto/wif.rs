@@ -2,6 +2,14 @@ use sha2::{Digest, Sha256};
 
 use neo::prelude::*;
 
+/// Version byte that prefixes the private key
+const WIF_VERSION: u8 = 0x80;
+/// Flag after the private key marking that its public key is used in compressed form, which
+/// is the only form Neo uses
+const WIF_COMPRESSED_FLAG: u8 = 0x01;
+/// Version byte, private key, compression flag and 4 byte checksum
+const WIF_LENGTH: usize = 38;
+
 /// Converts a WIF (Wallet Import Format) string into a `Secp256r1PrivateKey`.
 ///
 /// This function decodes a WIF string, verifies its format and checksum,
@@ -15,24 +23,44 @@ use neo::prelude::*;
 /// or an `Err` with `CryptoError` if the WIF string is invalid.
 ///
 /// # Errors
-/// This function returns an error if:
+/// This function returns a [`CryptoError::InvalidWif`] describing the problem if:
 /// * The WIF string is not properly base58 encoded.
-/// * The decoded data does not have the correct length, prefix, or suffix expected for a WIF.
 /// * The checksum of the WIF does not match the expected value.
+/// * The decoded data does not have the correct length, prefix, or suffix expected for a WIF.
 pub fn private_key_from_wif(wif: &str) -> Result<Secp256r1PrivateKey, CryptoError> {
 	let data = bs58::decode(wif)
 		.into_vec()
-		.map_err(|_| CryptoError::InvalidFormat("Incorrect WIF format.".to_string()))?;
-	if data.len() != 38 || data[0] != 0x80 || data[33] != 0x01 {
-		return Err(CryptoError::InvalidFormat("Incorrect WIF format.".to_string()));
+		.map_err(|e| CryptoError::InvalidWif(format!("not valid Base58: {}", e)))?;
+	if data.len() != WIF_LENGTH {
+		return Err(CryptoError::InvalidWif(format!(
+			"expected {} bytes, got {}",
+			WIF_LENGTH,
+			data.len()
+		)));
 	}
 
 	let checksum_calculated = Sha256::digest(&Sha256::digest(&data[..34]));
 	if checksum_calculated[..4] != data[34..] {
-		return Err(CryptoError::InvalidFormat("Incorrect WIF checksum.".to_string()));
+		return Err(CryptoError::InvalidWif(format!(
+			"checksum mismatch, expected {} but the WIF has {}",
+			hex::encode(&checksum_calculated[..4]),
+			hex::encode(&data[34..])
+		)));
+	}
+	if data[0] != WIF_VERSION {
+		return Err(CryptoError::InvalidWif(format!(
+			"expected version byte {:#04x}, got {:#04x}",
+			WIF_VERSION, data[0]
+		)));
+	}
+	if data[33] != WIF_COMPRESSED_FLAG {
+		return Err(CryptoError::InvalidWif(format!(
+			"expected compressed public key flag {:#04x}, got {:#04x}",
+			WIF_COMPRESSED_FLAG, data[33]
+		)));
 	}
 
-	Secp256r1PrivateKey::from_bytes(&data[1..33].to_vec())
+	Secp256r1PrivateKey::from_bytes(&data[1..33])
 }
 
 /// Converts a `Secp256r1PrivateKey` into a WIF (Wallet Import Format) string.
@@ -46,9 +74,9 @@ pub fn private_key_from_wif(wif: &str) -> Result<Secp256r1PrivateKey, CryptoErro
 /// # Returns
 /// A `String` containing the WIF representation of the provided private key.
 pub fn wif_from_private_key(private_key: &Secp256r1PrivateKey) -> String {
-	let mut extended_key: Vec<u8> = vec![0x80];
+	let mut extended_key: Vec<u8> = vec![WIF_VERSION];
 	extended_key.extend(private_key.to_raw_bytes());
-	extended_key.push(0x01);
+	extended_key.push(WIF_COMPRESSED_FLAG);
 
 	let hash = Sha256::digest(&Sha256::digest(&extended_key));
 	let checksum = &hash[0..4];
@@ -59,10 +87,19 @@ pub fn wif_from_private_key(private_key: &Secp256r1PrivateKey) -> String {
 
 #[cfg(test)]
 mod tests {
+	use sha2::{Digest, Sha256};
+
 	use neo::prelude::{
-		private_key_from_wif, wif_from_private_key, PrivateKeyExtension, Secp256r1PrivateKey,
+		private_key_from_wif, wif_from_private_key, CryptoError, KeyPair, PrivateKeyExtension,
+		Secp256r1PrivateKey,
 	};
 
+	fn encode_with_checksum(mut data: Vec<u8>) -> String {
+		let hash = Sha256::digest(&Sha256::digest(&data));
+		data.extend_from_slice(&hash[..4]);
+		bs58::encode(data).into_string()
+	}
+
 	#[test]
 	fn test_valid_wif_to_private_key() {
 		let wif = "L25kgAQJXNHnhc7Sx9bomxxwVSMsZdkaNQ3m2VfHrnLzKWMLP13A";
@@ -121,4 +158,58 @@ mod tests {
 		// wif_from_private_key(&
 		assert!(Secp256r1PrivateKey::from_slice(&invalid_len).is_err());
 	}
+
+	#[test]
+	fn test_wif_round_trip_random_keys() {
+		for _ in 0..256 {
+			let key_pair = KeyPair::new_random();
+			let wif = key_pair.to_wif();
+
+			assert!(wif.starts_with('K') || wif.starts_with('L'));
+			assert_eq!(KeyPair::from_wif(&wif).unwrap(), key_pair);
+			assert_eq!(wif_from_private_key(&private_key_from_wif(&wif).unwrap()), wif);
+		}
+	}
+
+	#[test]
+	fn test_wif_round_trip_key_with_leading_zeros() {
+		let mut key = [0u8; 32];
+		key[31] = 1;
+		let key_pair = KeyPair::from_private_key(&key).unwrap();
+
+		assert_eq!(KeyPair::from_wif(&key_pair.to_wif()).unwrap(), key_pair);
+	}
+
+	#[test]
+	fn test_wif_checksum_mismatch() {
+		let wif = "L25kgAQJXNHnhc7Sx9bomxxwVSMsZdkaNQ3m2VfHrnLzKWMLP13A";
+		let mut decoded = bs58::decode(wif).into_vec().unwrap();
+		decoded[37] ^= 0x01;
+
+		let err = private_key_from_wif(&bs58::encode(&decoded).into_string()).unwrap_err();
+		assert!(matches!(err, CryptoError::InvalidWif(_)));
+		assert!(err.to_string().contains("checksum"));
+	}
+
+	#[test]
+	fn test_wif_flags_with_valid_checksum() {
+		let key = hex::decode("9117f4bf9be717c9a90994326897f4243503accd06712162267e77f18b49c3a3")
+			.unwrap();
+
+		// Uncompressed WIFs lack the flag byte, which Neo requires
+		let mut uncompressed = vec![0x80];
+		uncompressed.extend_from_slice(&key);
+		let err = private_key_from_wif(&encode_with_checksum(uncompressed.clone())).unwrap_err();
+		assert!(matches!(err, CryptoError::InvalidWif(ref message) if message.contains("37")));
+
+		let mut testnet = vec![0xef];
+		testnet.extend_from_slice(&key);
+		testnet.push(0x01);
+		let err = private_key_from_wif(&encode_with_checksum(testnet)).unwrap_err();
+		assert!(matches!(err, CryptoError::InvalidWif(ref message) if message.contains("0xef")));
+
+		uncompressed.push(0x00);
+		let err = private_key_from_wif(&encode_with_checksum(uncompressed)).unwrap_err();
+		assert!(matches!(err, CryptoError::InvalidWif(ref message) if message.contains("flag")));
+	}
 }
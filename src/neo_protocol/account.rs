@@ -7,6 +7,7 @@ use std::{
 };
 
 use primitive_types::H160;
+use rand::{rngs::OsRng, CryptoRng, RngCore};
 use rustc_serialize::hex::ToHex;
 use serde_derive::{Deserialize, Serialize};
 use signature::{hazmat::PrehashSigner, Error, SignerMut};
@@ -118,6 +119,12 @@ pub struct Account {
 	pub nr_of_participants: Option<u32>,
 	#[serde(skip)]
 	pub wallet: Option<Weak<Wallet>>,
+	/// The `extra` data of the NEP-6 account this account was loaded from.
+	#[serde(skip)]
+	pub(crate) nep6_extra: Option<HashMap<String, serde_json::Value>>,
+	/// Fields of the NEP-6 account this account was loaded from that NEP-6 does not define.
+	#[serde(skip)]
+	pub(crate) nep6_unknown_fields: HashMap<String, serde_json::Value>,
 }
 
 impl Account {
@@ -135,6 +142,47 @@ impl Account {
 	pub fn get_public_key(&self) -> Option<Secp256r1PublicKey> {
 		self.key_pair.as_ref().map(|k| k.public_key.clone())
 	}
+
+	/// Decrypts the NEP-2 encrypted private key, deriving the key with the given scrypt parameters.
+	pub fn decrypt_private_key_with_params(
+		&mut self,
+		password: &str,
+		params: &ScryptParamsDef,
+	) -> Result<(), ProviderError> {
+		if self.key_pair.is_some() {
+			return Ok(());
+		}
+
+		let encrypted_private_key = self
+			.encrypted_private_key
+			.as_ref()
+			.ok_or(ProviderError::IllegalState("No encrypted private key present".to_string()))?;
+		let key_pair =
+			get_private_key_from_nep2_with_params(encrypted_private_key, password, params)?;
+		self.key_pair = Some(KeyPair::from_private_key(&vec_to_array32(key_pair)?)?);
+		Ok(())
+	}
+
+	/// Encrypts the private key to a NEP-2 string, deriving the key with the given scrypt
+	/// parameters, and removes the decrypted key pair from the account.
+	pub fn encrypt_private_key_with_params(
+		&mut self,
+		password: &str,
+		params: &ScryptParamsDef,
+	) -> Result<(), ProviderError> {
+		let key_pair = self.key_pair.as_ref().ok_or(ProviderError::IllegalState(
+			"The account does not hold a decrypted private key.".to_string(),
+		))?;
+
+		let encrypted_private_key = get_nep2_from_private_key_with_params(
+			key_pair.private_key.to_raw_bytes().to_hex().as_str(),
+			password,
+			params,
+		)?;
+		self.encrypted_private_key = Some(encrypted_private_key);
+		self.key_pair = None;
+		Ok(())
+	}
 }
 
 impl From<H160> for Account {
@@ -460,8 +508,7 @@ impl AccountTrait for Account {
 	}
 
 	fn create() -> Result<Self, Self::Error> {
-		let key_pair = KeyPair::new_random();
-		Self::from_key_pair(key_pair, None, None)
+		Self::create_with_rng(&mut OsRng).map_err(|e| ProviderError::IllegalState(e.to_string()))
 	}
 
 	fn is_multi_sig(&self) -> bool {
@@ -471,17 +518,55 @@ impl AccountTrait for Account {
 
 impl PrehashSigner<Secp256r1Signature> for Account {
 	fn sign_prehash(&self, _prehash: &[u8]) -> Result<Secp256r1Signature, Error> {
-		if self.key_pair.is_none() {
-			return Err(Error::new());
-		}
-
-		let key_pair = self.key_pair.as_ref().unwrap();
-		let signature = key_pair.private_key.sign_prehash(_prehash).unwrap();
-		Ok(signature)
+		self.sign_hash(_prehash).map_err(|_| Error::new())
 	}
 }
 
 impl Account {
+	/// Creates a new account with a key generated from `rng`, without any network access.
+	///
+	/// [`AccountTrait::create`] uses the OS RNG; this lets tests inject a seeded RNG and
+	/// constrained environments supply their own entropy source.
+	pub fn create_with_rng(rng: &mut (impl RngCore + CryptoRng)) -> Result<Self, ProtocolError> {
+		Self::from_key_pair(KeyPair::new_random_with_rng(rng), None, None)
+			.map_err(|e| ProtocolError::IllegalState { message: e.to_string() })
+	}
+
+	/// Creates a watch-only account for the given script hash.
+	///
+	/// The account has no private key, so it can be used to track an address but not to sign.
+	/// Use [`AccountTrait::from_public_key`] to also keep the verification script.
+	pub fn watch_only(script_hash: ScriptHash) -> Self {
+		Self {
+			address_or_scripthash: AddressOrScriptHash::ScriptHash(script_hash),
+			label: Some(script_hash.to_address()),
+			..Default::default()
+		}
+	}
+
+	/// Returns `true` if the account holds neither a private key nor an encrypted one.
+	pub fn is_watch_only(&self) -> bool {
+		self.key_pair.is_none() && self.encrypted_private_key.is_none()
+	}
+
+	/// Signs the given hash with the account's private key.
+	///
+	/// Returns `ProtocolError::WatchOnlyAccount` for watch-only accounts and
+	/// `ProtocolError::IllegalState` if the private key is still encrypted.
+	pub fn sign_hash(&self, hash: &[u8]) -> Result<Secp256r1Signature, ProtocolError> {
+		if self.is_watch_only() {
+			return Err(ProtocolError::WatchOnlyAccount { address: self.get_address() });
+		}
+		let key_pair = self.key_pair.as_ref().ok_or(ProtocolError::IllegalState {
+			message: "The account's private key is encrypted. Decrypt it before signing."
+				.to_string(),
+		})?;
+		key_pair
+			.private_key
+			.sign_prehash(hash)
+			.map_err(|e| ProtocolError::IllegalState { message: e.to_string() })
+	}
+
 	pub fn to_nep6_account(&self) -> Result<NEP6Account, ProviderError> {
 		if self.key_pair.is_some() && self.encrypted_private_key.is_none() {
 			return Err(ProviderError::IllegalState(
@@ -490,15 +575,17 @@ impl Account {
 		}
 
 		if self.verification_script.is_none() {
-			return Ok(NEP6Account::new(
+			let mut account = NEP6Account::new(
 				self.address_or_scripthash.address().clone(),
 				self.label.clone(),
 				self.is_default,
 				self.is_locked,
 				self.encrypted_private_key.clone(),
 				None,
-				None,
-			));
+				self.nep6_extra.clone(),
+			);
+			account.unknown_fields = self.nep6_unknown_fields.clone();
+			return Ok(account);
 		}
 
 		let mut parameters = Vec::new();
@@ -525,15 +612,17 @@ impl Account {
 			nep6_parameters: parameters,
 		};
 
-		Ok(NEP6Account::new(
+		let mut account = NEP6Account::new(
 			self.address_or_scripthash.address().clone(),
 			self.label.clone(),
 			self.is_default,
 			self.is_locked,
 			self.encrypted_private_key.clone(),
 			Some(contract),
-			None,
-		))
+			self.nep6_extra.clone(),
+		);
+		account.unknown_fields = self.nep6_unknown_fields.clone();
+		Ok(account)
 	}
 
 	pub async fn get_nep17_balances<P>(
@@ -558,6 +647,7 @@ impl Account {
 #[cfg(test)]
 mod tests {
 	use primitive_types::H160;
+	use rand::{rngs::StdRng, SeedableRng};
 	use rustc_serialize::hex::FromHex;
 	use serde_json::Value;
 	use url::Url;
@@ -568,12 +658,55 @@ mod tests {
 
 	use neo::prelude::{
 		Account, AccountTrait, BodyRegexMatcher, HttpProvider, KeyPair, NeoSerializable,
-		ProviderError, RpcClient, ScriptHashExtension, Secp256r1PublicKey, TestConstants,
-		ToArray32, VerificationScript, Wallet, WalletTrait,
+		ProtocolError, ProviderError, RpcClient, ScriptHash, ScriptHashExtension,
+		Secp256r1PublicKey, StringExt, TestConstants, ToArray32, VerificationScript, Wallet,
+		WalletTrait,
 	};
 
 	use super::APITrait;
 
+	#[test]
+	fn test_watch_only_account() {
+		let script_hash = ScriptHash::from_address(TestConstants::DEFAULT_ACCOUNT_ADDRESS).unwrap();
+		let account = Account::watch_only(script_hash);
+
+		assert!(account.is_watch_only());
+		assert_eq!(account.get_address(), TestConstants::DEFAULT_ACCOUNT_ADDRESS);
+		assert!(matches!(
+			account.sign_hash(&[0u8; 32]),
+			Err(ProtocolError::WatchOnlyAccount { address })
+				if address == TestConstants::DEFAULT_ACCOUNT_ADDRESS
+		));
+
+		let public_key =
+			Secp256r1PublicKey::from_encoded(TestConstants::DEFAULT_ACCOUNT_PUBLIC_KEY).unwrap();
+		let account = Account::from_public_key(&public_key).unwrap();
+		assert!(account.is_watch_only());
+		assert_eq!(account.get_address(), TestConstants::DEFAULT_ACCOUNT_ADDRESS);
+		assert!(account.sign_hash(&[0u8; 32]).is_err());
+
+		let signer = Account::create().unwrap();
+		assert!(!signer.is_watch_only());
+		assert!(signer.sign_hash(&[0u8; 32]).is_ok());
+
+		let wallet = Wallet::from_accounts(vec![signer.clone(), account.clone()]).unwrap();
+		assert!(wallet.get_account(&account.get_script_hash()).unwrap().is_watch_only());
+		assert!(!wallet.get_account(&signer.get_script_hash()).unwrap().is_watch_only());
+	}
+
+	#[test]
+	fn test_create_with_seeded_rng() {
+		let account = Account::create_with_rng(&mut StdRng::seed_from_u64(42)).unwrap();
+		let same_seed = Account::create_with_rng(&mut StdRng::seed_from_u64(42)).unwrap();
+		let other_seed = Account::create_with_rng(&mut StdRng::seed_from_u64(43)).unwrap();
+
+		assert_eq!(account.get_address(), same_seed.get_address());
+		assert_eq!(account.key_pair, same_seed.key_pair);
+		assert_ne!(account.get_address(), other_seed.get_address());
+		assert!(account.get_address().is_valid_address());
+		assert!(!account.is_watch_only());
+	}
+
 	#[test]
 	fn test_create_generic_account() {
 		let account = Account::create().unwrap();
@@ -853,7 +986,7 @@ mod tests {
 		let mut account = Account::from_address(TestConstants::DEFAULT_ACCOUNT_ADDRESS).unwrap();
 		let mut wallet = Wallet::new();
 		let script_hash: H160 = account.get_script_hash();
-		wallet.add_account(account);
+		wallet.add_account(account).unwrap();
 		{
 			let account = wallet.get_account(&script_hash).unwrap();
 			assert!(!account.is_default);
@@ -0,0 +1,241 @@
+use std::collections::HashSet;
+
+use primitive_types::{H160, H256};
+
+use neo::prelude::{
+	Decoder, Encoder, HashableForVec, JsonRpcProvider, NeoSerializable, Transaction,
+	TransactionError, Witness,
+};
+
+/// A block decoded from its serialized form, e.g. the result of `getblock` when not verbose.
+///
+/// Block and transaction hashes are held in the order they are displayed in, while the
+/// serialized block has them in little-endian order.
+#[derive(Debug, Clone)]
+pub struct Block<'a, P: JsonRpcProvider + 'static> {
+	pub version: u32,
+	pub prev_hash: H256,
+	pub merkle_root: H256,
+	/// Milliseconds since the Unix epoch.
+	pub timestamp: u64,
+	pub nonce: u64,
+	pub index: u32,
+	/// The index of the consensus node that proposed the block.
+	pub primary_index: u8,
+	/// The script hash of the multi-sig account of the validators of the next block.
+	pub next_consensus: H160,
+	pub witness: Witness,
+	pub transactions: Vec<Transaction<'a, P>>,
+}
+
+impl<'a, P: JsonRpcProvider + 'static> Block<'a, P> {
+	/// The size of the header without its witness.
+	const UNSIGNED_HEADER_SIZE: usize = 109;
+	const MAX_TRANSACTIONS: usize = u16::MAX as usize;
+
+	/// Decodes a block, checking that it is complete, that every transaction has a witness for
+	/// each of its signers and that the transactions match the merkle root of the header.
+	pub fn decode_raw(bytes: &[u8]) -> Result<Self, TransactionError> {
+		let mut reader = Decoder::new(bytes);
+		if reader.available() < Self::UNSIGNED_HEADER_SIZE {
+			return Err(TransactionError::InvalidBlock);
+		}
+		let version = reader.read_u32();
+		if version != 0 {
+			return Err(TransactionError::InvalidBlock);
+		}
+		let prev_hash = read_hash256(&mut reader)?;
+		let merkle_root = read_hash256(&mut reader)?;
+		let timestamp = reader.read_u64();
+		let nonce = reader.read_u64();
+		let index = reader.read_u32();
+		let primary_index = reader.read_u8();
+		let next_consensus = reader.read_serializable::<H160>()?;
+
+		let mut witnesses = reader.read_serializable_list::<Witness>()?;
+		if witnesses.len() != 1 {
+			return Err(TransactionError::InvalidBlock);
+		}
+		let witness = witnesses.remove(0);
+
+		let count = reader.read_var_count()?;
+		if count > Self::MAX_TRANSACTIONS {
+			return Err(TransactionError::InvalidBlock);
+		}
+		let mut transactions = Vec::with_capacity(count);
+		for _ in 0..count {
+			let transaction = Transaction::decode(&mut reader)?;
+			if transaction.witnesses.len() != transaction.signers.len() {
+				return Err(TransactionError::InvalidBlock);
+			}
+			transactions.push(transaction);
+		}
+		if reader.available() > 0 {
+			return Err(TransactionError::InvalidBlock);
+		}
+
+		let hashes = transactions
+			.iter()
+			.map(|transaction| transaction.get_tx_id())
+			.collect::<Result<Vec<_>, _>>()?;
+		if hashes.iter().collect::<HashSet<_>>().len() != hashes.len()
+			|| merkle_root_of(&hashes) != merkle_root
+		{
+			return Err(TransactionError::InvalidBlock);
+		}
+
+		Ok(Self {
+			version,
+			prev_hash,
+			merkle_root,
+			timestamp,
+			nonce,
+			index,
+			primary_index,
+			next_consensus,
+			witness,
+			transactions,
+		})
+	}
+
+	/// The hash of the block, computed from its header without the witness.
+	pub fn hash(&self) -> H256 {
+		let mut writer = Encoder::new();
+		writer.write_u32(self.version);
+		writer.write_bytes(&reversed(&self.prev_hash));
+		writer.write_bytes(&reversed(&self.merkle_root));
+		writer.write_bytes(&self.timestamp.to_le_bytes());
+		writer.write_bytes(&self.nonce.to_le_bytes());
+		writer.write_u32(self.index);
+		writer.write_u8(self.primary_index);
+		writer.write_serializable_fixed(&self.next_consensus);
+		H256::from_slice(&reversed(&H256::from_slice(&writer.to_bytes().hash256())))
+	}
+}
+
+fn read_hash256(reader: &mut Decoder) -> Result<H256, TransactionError> {
+	let mut bytes = reader.read_bytes(32)?;
+	bytes.reverse();
+	Ok(H256::from_slice(&bytes))
+}
+
+fn reversed(hash: &H256) -> Vec<u8> {
+	hash.as_bytes().iter().rev().copied().collect()
+}
+
+/// Computes the merkle root of the transaction hashes as Neo does: pairs of little-endian hashes
+/// are hashed with double SHA-256, and a level with an odd number of hashes pairs the last one
+/// with itself.
+fn merkle_root_of(hashes: &[H256]) -> H256 {
+	if hashes.is_empty() {
+		return H256::zero();
+	}
+	let mut level: Vec<Vec<u8>> = hashes.iter().map(reversed).collect();
+	while level.len() > 1 {
+		level = level
+			.chunks(2)
+			.map(|pair| {
+				let right = pair.get(1).unwrap_or(&pair[0]);
+				[pair[0].as_slice(), right.as_slice()].concat().hash256().hash256()
+			})
+			.collect();
+	}
+	H256::from_slice(&reversed(&H256::from_slice(&level[0])))
+}
+
+#[cfg(test)]
+mod tests {
+	use std::str::FromStr;
+
+	use primitive_types::{H160, H256};
+
+	use neo::prelude::{
+		APITrait, Block, HttpProvider, MockClient, TransactionAttribute, TransactionError,
+	};
+
+	// A block with two transactions, the first with every kind of attribute
+	const BLOCK: &str = "00000000aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa3bd30bd543294b3739828117ff1da132cbc004f8a14806483bdbcbd0d2a7ca9f0068e5cf8b01000088776655443322112a0000000369ecca587293047be4c59159bf8bc399985c160d01420c4000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000011102000100000040420f0000000000b0c4120000000000881300000169ecca587293047be4c59159bf8bc399985c160d01040120d2040000211f1e1d1c1b1a191817161514131211100f0e0d0c0b0a09080706050403020100110700000000000000000361626302114001420c40000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f202122232425262728292a2b2c2d2e2f303132333435363738393a3b3c3d3e3f280c2102000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f4156e7b327000200000040420f0000000000b0c4120000000000881300000169ecca587293047be4c59159bf8bc399985c160d010002124001420c40000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f202122232425262728292a2b2c2d2e2f303132333435363738393a3b3c3d3e3f280c2102000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f4156e7b327";
+
+	#[test]
+	fn test_decode_raw() {
+		let bytes = hex::decode(BLOCK).unwrap();
+		let block = Block::<HttpProvider>::decode_raw(&bytes).unwrap();
+
+		assert_eq!(block.prev_hash, H256::repeat_byte(0xaa));
+		assert_eq!(
+			block.merkle_root,
+			H256::from_str("9fcaa7d2d0cbdb3b480648a1f804c0cb32a11dff17818239374b2943d50bd33b")
+				.unwrap()
+		);
+		assert_eq!(block.timestamp, 1_700_000_000_000);
+		assert_eq!(block.nonce, 0x1122334455667788);
+		assert_eq!(block.index, 42);
+		assert_eq!(block.primary_index, 3);
+		assert_eq!(
+			block.next_consensus,
+			H160::from_str("69ecca587293047be4c59159bf8bc399985c160d").unwrap()
+		);
+		assert_eq!(
+			block.hash(),
+			H256::from_str("a85b4c3d914953fdfa7002f972d9edec1f6f046ed3d38efaff11d8e568a34c22")
+				.unwrap()
+		);
+
+		assert_eq!(block.transactions.len(), 2);
+		assert_eq!(block.transactions[0].attributes.len(), 4);
+		assert_eq!(
+			block.transactions[0].attributes[1],
+			TransactionAttribute::NotValidBefore { height: 1234 }
+		);
+		assert_eq!(
+			block.transactions[1].get_tx_id().unwrap(),
+			H256::from_str("0da67e3266707846440c76853303a096eb590083207350f87c1bc61a77727a82")
+				.unwrap()
+		);
+	}
+
+	#[tokio::test]
+	async fn test_decode_genesis_blocks() {
+		// Block 0 as returned by `getblock` when not verbose, with the hashes the chains report
+		for (response_file, hash) in [
+			(
+				"getblock_mainnet_0.json",
+				"1f4d1defa46faa5e7b9b8d3f79a06bec777d7c26c4aa5f6f5899a291daa87c15",
+			),
+			(
+				"getblock_testnet_0.json",
+				"9d3276785e7306daf59a3f3b9e31912c095598bbfb8a4476b821b0e59be4c57a",
+			),
+		] {
+			let mut mock_provider = MockClient::new().await;
+			mock_provider
+				.mock_response_with_file_ignore_param("getblock", response_file)
+				.await;
+			mock_provider.mount_mocks().await;
+			let raw = mock_provider.into_client().get_raw_block_by_index(0).await.unwrap();
+
+			let block = Block::<HttpProvider>::decode_raw(&base64::decode(raw).unwrap()).unwrap();
+			assert_eq!(block.index, 0);
+			assert!(block.transactions.is_empty());
+			assert_eq!(block.hash(), H256::from_str(hash).unwrap());
+		}
+	}
+
+	#[test]
+	fn test_decode_raw_rejects_invalid_blocks() {
+		let bytes = hex::decode(BLOCK).unwrap();
+		let decode = |bytes: &[u8]| Block::<HttpProvider>::decode_raw(bytes).map(|_| ());
+
+		// A merkle root that does not match the transactions
+		let mut tampered = bytes.clone();
+		tampered[36] ^= 1;
+		assert_eq!(decode(&tampered), Err(TransactionError::InvalidBlock));
+
+		let mut trailing = bytes.clone();
+		trailing.push(0);
+		assert_eq!(decode(&trailing), Err(TransactionError::InvalidBlock));
+
+		assert!(decode(&bytes[..bytes.len() - 1]).is_err());
+		assert_eq!(decode(&bytes[..100]), Err(TransactionError::InvalidBlock));
+	}
+}
@@ -1,9 +1,12 @@
 pub use account::*;
+pub use block::*;
 pub use nep2::*;
 pub use protocol_error::*;
 pub use responses::*;
+pub use role::*;
 
 mod account;
+mod block;
 mod nep2;
 mod protocol_error;
 mod responses;
@@ -48,8 +48,9 @@ use rustc_serialize::hex::FromHex;
 use scrypt::{scrypt, Params};
 
 use neo::prelude::{
-	base58check_decode, base58check_encode, public_key_to_address, vec_to_array32, HashableForVec,
-	KeyPair, NeoConstants, ProviderError, Secp256r1PublicKey, ToBase58,
+	base58check_decode, base58check_encode, public_key_to_address, vec_to_array32, CryptoError,
+	HashableForVec, KeyPair, NeoConstants, ProviderError, ScryptParamsDef, Secp256r1PublicKey,
+	ToBase58,
 };
 
 type Aes256EcbEnc = ecb::Encryptor<aes::Aes256>;
@@ -57,6 +58,18 @@ type Aes256EcbDec = ecb::Decryptor<aes::Aes256>;
 
 pub struct NEP2;
 
+/// A stage of NEP-2 encryption or decryption, reported to the hook of
+/// [`get_nep2_from_private_key_with_hook`] and [`get_private_key_from_nep2_with_hook`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Nep2Stage {
+	/// The scrypt key derivation, which takes nearly all of the time, is about to start.
+	DerivingKey,
+	/// The key has been derived and the private key is about to be encrypted or decrypted.
+	Cipher,
+	/// The operation has completed. The hook's return value is ignored for this stage.
+	Done,
+}
+
 impl NEP2 {
 	const DKLEN: usize = 64;
 	const NEP2_PRIVATE_KEY_LENGTH: usize = 39;
@@ -91,15 +104,75 @@ fn decrypt_aes256_ecb(encrypted_data: &[u8], key: &[u8]) -> Result<Vec<u8>, Prov
 	Ok(pt.to_vec())
 }
 
+/// Reports `stage` to `hook` and fails with [`CryptoError::Cancelled`] if the hook returns false.
+fn report(hook: &mut dyn FnMut(Nep2Stage) -> bool, stage: Nep2Stage) -> Result<(), ProviderError> {
+	if hook(stage) {
+		Ok(())
+	} else {
+		Err(ProviderError::CryptoError(CryptoError::Cancelled))
+	}
+}
+
+/// Derives the NEP-2 key from the passphrase and the address hash.
+fn derive_key(
+	passphrase: &str,
+	address_hash: &[u8],
+	scrypt_params_def: &ScryptParamsDef,
+) -> Result<Vec<u8>, ProviderError> {
+	let mut result = vec![0u8; NeoConstants::SCRYPT_DK_LEN];
+	let params = scrypt_params(scrypt_params_def)?;
+	scrypt(passphrase.as_bytes(), address_hash, &params, &mut result).map_err(|e| {
+		ProviderError::CryptoError(CryptoError::InvalidFormat(format!(
+			"Invalid scrypt output length: {e}"
+		)))
+	})?;
+	Ok(result)
+}
+
+fn scrypt_params(params: &ScryptParamsDef) -> Result<Params, ProviderError> {
+	Params::new(params.log_n, params.r, params.p, 32).map_err(|e| {
+		ProviderError::CryptoError(CryptoError::InvalidFormat(format!(
+			"Invalid scrypt parameters: {e}"
+		)))
+	})
+}
+
 pub fn get_nep2_from_private_key(pri_key: &str, passphrase: &str) -> Result<String, ProviderError> {
-	let private_key = pri_key.from_hex().unwrap();
-	let key_pair = KeyPair::from_private_key(&vec_to_array32(private_key.to_vec()).unwrap())?;
+	get_nep2_from_private_key_with_params(pri_key, passphrase, &ScryptParamsDef::default())
+}
+
+/// Encrypts a hex encoded private key to a NEP-2 string, deriving the key with the given scrypt
+/// parameters instead of the standard ones.
+pub fn get_nep2_from_private_key_with_params(
+	pri_key: &str,
+	passphrase: &str,
+	scrypt_params_def: &ScryptParamsDef,
+) -> Result<String, ProviderError> {
+	get_nep2_from_private_key_with_hook(pri_key, passphrase, scrypt_params_def, &mut |_| true)
+}
+
+/// Like [`get_nep2_from_private_key_with_params`], but reports each [`Nep2Stage`] to `hook`
+/// before entering it. Returning false from the hook cancels the encryption with
+/// [`CryptoError::Cancelled`], except at [`Nep2Stage::Done`].
+///
+/// The scrypt derivation cannot be interrupted once started, so a UI should show progress for
+/// [`Nep2Stage::DerivingKey`] and check for cancellation when the hook is called.
+pub fn get_nep2_from_private_key_with_hook(
+	pri_key: &str,
+	passphrase: &str,
+	scrypt_params_def: &ScryptParamsDef,
+	hook: &mut dyn FnMut(Nep2Stage) -> bool,
+) -> Result<String, ProviderError> {
+	let private_key = pri_key.from_hex().map_err(|e| {
+		ProviderError::CryptoError(CryptoError::InvalidFormat(format!(
+			"Invalid private key hex: {e}"
+		)))
+	})?;
+	let key_pair = KeyPair::from_private_key(&vec_to_array32(private_key.to_vec())?)?;
 	let addresshash: [u8; 4] = address_hash_from_pubkey(&key_pair.public_key.get_encoded(true));
-	let mut result = vec![0u8; NeoConstants::SCRYPT_DK_LEN];
-	let params =
-		Params::new(NeoConstants::SCRYPT_LOG_N, NeoConstants::SCRYPT_R, NeoConstants::SCRYPT_P, 32)
-			.unwrap();
-	scrypt(passphrase.as_bytes(), addresshash.to_vec().as_slice(), &params, &mut result).unwrap();
+	report(hook, Nep2Stage::DerivingKey)?;
+	let result = derive_key(passphrase, &addresshash, scrypt_params_def)?;
+	report(hook, Nep2Stage::Cipher)?;
 	let half_1 = &result[0..32];
 	let _half_2 = &result[32..64];
 	let mut u8xor = [0u8; 32];
@@ -123,15 +196,40 @@ pub fn get_nep2_from_private_key(pri_key: &str, passphrase: &str) -> Result<Stri
 
 	// # Finally, encode with Base58Check
 	//Ok(assembled.to_base58())
-	Ok(base58check_encode(&assembled))
+	let nep2 = base58check_encode(&assembled);
+	// The result is ready, so the hook can no longer cancel
+	hook(Nep2Stage::Done);
+	Ok(nep2)
 }
 
 pub fn get_private_key_from_nep2(nep2: &str, passphrase: &str) -> Result<Vec<u8>, ProviderError> {
-	if nep2.len() != 58 {
-		println!("Wrong Nep2");
-		()
-	}
-	let decoded_key: [u8; 39] = base58check_decode(nep2).unwrap().try_into().unwrap();
+	get_private_key_from_nep2_with_params(nep2, passphrase, &ScryptParamsDef::default())
+}
+
+/// Decrypts a NEP-2 string that was encrypted with the given scrypt parameters.
+pub fn get_private_key_from_nep2_with_params(
+	nep2: &str,
+	passphrase: &str,
+	scrypt_params_def: &ScryptParamsDef,
+) -> Result<Vec<u8>, ProviderError> {
+	get_private_key_from_nep2_with_hook(nep2, passphrase, scrypt_params_def, &mut |_| true)
+}
+
+/// Like [`get_private_key_from_nep2_with_params`], but reports each [`Nep2Stage`] to `hook`
+/// before entering it. Returning false from the hook cancels the decryption with
+/// [`CryptoError::Cancelled`], except at [`Nep2Stage::Done`].
+pub fn get_private_key_from_nep2_with_hook(
+	nep2: &str,
+	passphrase: &str,
+	scrypt_params_def: &ScryptParamsDef,
+	hook: &mut dyn FnMut(Nep2Stage) -> bool,
+) -> Result<Vec<u8>, ProviderError> {
+	let decoded_key: [u8; 39] = base58check_decode(nep2)
+		.ok()
+		.and_then(|decoded| decoded.try_into().ok())
+		.ok_or(ProviderError::CryptoError(CryptoError::InvalidFormat(
+			"Not a valid NEP-2 encrypted private key.".to_string(),
+		)))?;
 	if (decoded_key[0] != 0x01 || decoded_key[1] != 0x42 || decoded_key[2] != 0xe0) {
 		return Err(ProviderError::InvalidAddress);
 	}
@@ -140,12 +238,9 @@ pub fn get_private_key_from_nep2(nep2: &str, passphrase: &str) -> Result<Vec<u8>
 	let encrypted: &[u8] = &decoded_key[7..39];
 
 	// pwd_normalized = bytes(unicodedata.normalize('NFC', passphrase), 'utf-8')
-	let mut result = vec![0u8; NeoConstants::SCRYPT_DK_LEN];
-	let params =
-		Params::new(NeoConstants::SCRYPT_LOG_N, NeoConstants::SCRYPT_R, NeoConstants::SCRYPT_P, 32)
-			.unwrap();
-
-	scrypt(passphrase.as_bytes(), &address_hash, &params, &mut result).unwrap();
+	report(hook, Nep2Stage::DerivingKey)?;
+	let result = derive_key(passphrase, address_hash, scrypt_params_def)?;
+	report(hook, Nep2Stage::Cipher)?;
 
 	// derived = scrypt.hash(pwd_normalized, address_hash,
 	//                       N=SCRYPT_ITERATIONS,
@@ -179,14 +274,15 @@ pub fn get_private_key_from_nep2(nep2: &str, passphrase: &str) -> Result<Vec<u8>
 	// kp_new_address_hash_tmp = hashlib.sha256(kp_new_address.encode("utf-8")).digest()
 	// kp_new_address_hash_tmp2 = hashlib.sha256(kp_new_address_hash_tmp).digest()
 	// kp_new_address_hash = kp_new_address_hash_tmp2[:4]
-	assert_eq!(kp_addresshash, address_hash);
 	if kp_addresshash != address_hash {
-		println!(
+		return Err(ProviderError::CryptoError(CryptoError::InvalidPassphrase(
 			"Calculated address hash does not match the one in the provided encrypted address."
-		);
-		//return Err(ProviderError::CustomError("Calculated address hash does not match the one in the provided encrypted address.".to_string()));
+				.to_string(),
+		)));
 	}
 
+	// The result is ready, so the hook can no longer cancel
+	hook(Nep2Stage::Done);
 	Ok(pri_key.to_vec())
 }
 
@@ -237,6 +333,107 @@ mod tests {
 		assert_eq!(encrypted, TestConstants::DEFAULT_ACCOUNT_ENCRYPTED_PRIVATE_KEY);
 	}
 
+	#[test]
+	fn test_encrypt_decrypt_with_custom_scrypt_params() {
+		let params = ScryptParamsDef { log_n: 4, r: 1, p: 1 };
+		let encrypted = get_nep2_from_private_key_with_params(
+			TestConstants::DEFAULT_ACCOUNT_PRIVATE_KEY,
+			TestConstants::DEFAULT_ACCOUNT_PASSWORD,
+			&params,
+		)
+		.unwrap();
+		assert_ne!(encrypted, TestConstants::DEFAULT_ACCOUNT_ENCRYPTED_PRIVATE_KEY);
+
+		let decrypted = get_private_key_from_nep2_with_params(
+			&encrypted,
+			TestConstants::DEFAULT_ACCOUNT_PASSWORD,
+			&params,
+		)
+		.unwrap();
+		assert_eq!(decrypted, hex::decode(TestConstants::DEFAULT_ACCOUNT_PRIVATE_KEY).unwrap());
+
+		// The standard parameters derive a different key, so the address hash check fails
+		assert!(matches!(
+			get_private_key_from_nep2(&encrypted, TestConstants::DEFAULT_ACCOUNT_PASSWORD),
+			Err(ProviderError::CryptoError(CryptoError::InvalidPassphrase(_)))
+		));
+	}
+
+	#[test]
+	fn test_hook_reports_stages() {
+		let params = ScryptParamsDef { log_n: 4, r: 1, p: 1 };
+		let mut stages = Vec::new();
+		let encrypted = get_nep2_from_private_key_with_hook(
+			TestConstants::DEFAULT_ACCOUNT_PRIVATE_KEY,
+			TestConstants::DEFAULT_ACCOUNT_PASSWORD,
+			&params,
+			&mut |stage| {
+				stages.push(stage);
+				true
+			},
+		)
+		.unwrap();
+		assert_eq!(stages, vec![Nep2Stage::DerivingKey, Nep2Stage::Cipher, Nep2Stage::Done]);
+
+		stages.clear();
+		get_private_key_from_nep2_with_hook(
+			&encrypted,
+			TestConstants::DEFAULT_ACCOUNT_PASSWORD,
+			&params,
+			&mut |stage| {
+				stages.push(stage);
+				true
+			},
+		)
+		.unwrap();
+		assert_eq!(stages, vec![Nep2Stage::DerivingKey, Nep2Stage::Cipher, Nep2Stage::Done]);
+	}
+
+	#[test]
+	fn test_hook_cancels() {
+		let mut stages = Vec::new();
+		let result = get_private_key_from_nep2_with_hook(
+			TestConstants::DEFAULT_ACCOUNT_ENCRYPTED_PRIVATE_KEY,
+			TestConstants::DEFAULT_ACCOUNT_PASSWORD,
+			&ScryptParamsDef::default(),
+			&mut |stage| {
+				stages.push(stage);
+				false
+			},
+		);
+		assert_eq!(result, Err(ProviderError::CryptoError(CryptoError::Cancelled)));
+		// Cancelled before the expensive key derivation ran
+		assert_eq!(stages, vec![Nep2Stage::DerivingKey]);
+
+		// Too late to cancel once the result is ready
+		let params = ScryptParamsDef { log_n: 4, r: 1, p: 1 };
+		let encrypted = get_nep2_from_private_key_with_hook(
+			TestConstants::DEFAULT_ACCOUNT_PRIVATE_KEY,
+			TestConstants::DEFAULT_ACCOUNT_PASSWORD,
+			&params,
+			&mut |stage| stage != Nep2Stage::Done,
+		)
+		.unwrap();
+		let result = get_private_key_from_nep2_with_hook(
+			&encrypted,
+			TestConstants::DEFAULT_ACCOUNT_PASSWORD,
+			&params,
+			&mut |stage| stage != Nep2Stage::Done,
+		);
+		assert_eq!(result, Ok(hex::decode(TestConstants::DEFAULT_ACCOUNT_PRIVATE_KEY).unwrap()));
+	}
+
+	#[test]
+	fn test_encrypt_invalid_private_key_fails() {
+		let result = get_nep2_from_private_key_with_hook(
+			"not hex",
+			TestConstants::DEFAULT_ACCOUNT_PASSWORD,
+			&ScryptParamsDef::default(),
+			&mut |_| true,
+		);
+		assert!(matches!(result, Err(ProviderError::CryptoError(CryptoError::InvalidFormat(_)))));
+	}
+
 	#[test]
 	fn test_encrypt_decrypt_aes256_ecb() {
 		let key = &[0u8; 32];
@@ -12,6 +12,8 @@ pub enum ProtocolError {
 	StackItemCast { item: String, target: String },
 	#[error("Illegal state: {message}")]
 	IllegalState { message: String },
+	#[error("Account {address} is watch-only and cannot sign")]
+	WatchOnlyAccount { address: String },
 	#[error("HTTP error: {0}")]
 	HttpError(#[from] reqwest::Error),
 }
@@ -11,6 +11,7 @@ pub use neo_get_claimable::*;
 pub use neo_get_mem_pool::*;
 pub use neo_get_next_block_validators::*;
 pub use neo_get_peers::*;
+pub use neo_get_raw_transaction::*;
 pub use neo_get_state_height::*;
 pub use neo_get_state_root::*;
 pub use neo_get_token_balances::*;
@@ -33,6 +34,7 @@ pub use populated_blocks::*;
 pub use reponse_transaction::*;
 pub use response_transaction_attribute::*;
 pub use response_transaction_signer::*;
+pub use transaction_confirmation::*;
 
 mod diagnostics;
 mod express_contract_state;
@@ -49,6 +51,7 @@ mod neo_transfers;
 mod neo_block;
 mod neo_get_next_block_validators;
 mod neo_get_peers;
+mod neo_get_raw_transaction;
 mod neo_get_state_height;
 mod neo_get_state_root;
 mod neo_get_token_balances;
@@ -70,3 +73,4 @@ mod populated_blocks;
 mod reponse_transaction;
 mod response_transaction_attribute;
 mod response_transaction_signer;
+mod transaction_confirmation;
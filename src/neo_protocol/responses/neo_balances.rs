@@ -1,7 +1,10 @@
 use getset::Getters;
+use num_bigint::BigInt;
 use serde::{Deserialize, Serialize};
 
-use neo::prelude::{deserialize_script_hash, serialize_script_hash, ScriptHash};
+use neo::prelude::{
+	deserialize_script_hash, format_token_amount_bigint, serialize_script_hash, ScriptHash,
+};
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Debug)]
 pub struct Nep11Balances {
@@ -33,6 +36,26 @@ impl Nep11Balance {
 	) -> Self {
 		Nep11Balance { name, symbol, decimals, tokens, asset_hash }
 	}
+
+	/// Whether the NFTs of this contract are divisible, i.e. an account can own a fraction of a
+	/// token id. Returns `false` if the decimals are not a valid number.
+	pub fn is_divisible(&self) -> bool {
+		self.decimals.parse::<u8>().map_or(false, |decimals| decimals > 0)
+	}
+
+	/// The token with the given id, if the account owns (a fraction of) it.
+	pub fn token(&self, token_id: &str) -> Option<&Nep11Token> {
+		self.tokens.iter().find(|token| token.token_id == token_id)
+	}
+
+	/// The amount of the token with the given id the account owns, formatted with the
+	/// contract's decimals, e.g. `"0.5"` for half of a divisible NFT. Returns `None` if the
+	/// account does not own the token or the amount or decimals are not valid numbers.
+	pub fn formatted_amount(&self, token_id: &str) -> Option<String> {
+		let decimals = self.decimals.parse::<u8>().ok()?;
+		let amount = self.token(token_id)?.raw_amount()?;
+		Some(format_token_amount_bigint(&amount, decimals))
+	}
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Debug)]
@@ -49,6 +72,11 @@ impl Nep11Token {
 	pub fn new(token_id: String, amount: String, last_updated_block: u32) -> Self {
 		Nep11Token { token_id, amount, last_updated_block }
 	}
+
+	/// The owned amount in the smallest unit, which is `1` for a whole non-divisible NFT.
+	pub fn raw_amount(&self) -> Option<BigInt> {
+		self.amount.parse().ok()
+	}
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Debug)]
@@ -85,3 +113,56 @@ impl Nep17Balance {
 		Self { name, symbol, decimals, amount, last_updated_block, asset_hash }
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use num_bigint::BigInt;
+	use serde_json::json;
+
+	use super::Nep11Balances;
+
+	#[test]
+	fn test_divisible_nep11_balances() {
+		let balances: Nep11Balances = serde_json::from_value(json!({
+			"address": "NXXazKH39yNFWWZF5MJ8tEN98VYHwzn7g3",
+			"balance": [
+				{
+					"assethash": "0x1a1ea6d49cd20a8e2cc8b3d8bd4ec6c4ffbd4a4b",
+					"name": "Shares",
+					"symbol": "SHR",
+					"decimals": "2",
+					"tokens": [
+						{ "tokenid": "01", "amount": "50", "lastupdatedblock": 1234 },
+						{ "tokenid": "02", "amount": "100", "lastupdatedblock": 1240 },
+						{
+							"tokenid": "03",
+							"amount": "100000000000000000000000",
+							"lastupdatedblock": 1250
+						}
+					]
+				},
+				{
+					"assethash": "0x4c1a0c1bd2c8f6e5b06fa30ad6f1fd4c3e0a6b23",
+					"name": "Collectibles",
+					"symbol": "COL",
+					"decimals": "0",
+					"tokens": [{ "tokenid": "0a", "amount": "1", "lastupdatedblock": 99 }]
+				}
+			]
+		}))
+		.unwrap();
+
+		let shares = &balances.balances[0];
+		assert!(shares.is_divisible());
+		assert_eq!(shares.formatted_amount("01"), Some("0.5".to_string()));
+		assert_eq!(shares.formatted_amount("02"), Some("1".to_string()));
+		// More than a u64 holds
+		assert_eq!(shares.formatted_amount("03"), Some("1000000000000000000000".to_string()));
+		assert_eq!(shares.formatted_amount("04"), None);
+
+		let collectibles = &balances.balances[1];
+		assert!(!collectibles.is_divisible());
+		assert_eq!(collectibles.token("0a").unwrap().raw_amount(), Some(BigInt::from(1)));
+		assert_eq!(collectibles.formatted_amount("0a"), Some("1".to_string()));
+	}
+}
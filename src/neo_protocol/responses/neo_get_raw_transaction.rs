@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+use neo::prelude::{JsonRpcProvider, RTransaction, Transaction, TransactionError};
+
+/// The result of `getrawtransaction`. The verbose form is the full transaction together with
+/// its block hash, block time and number of confirmations, the non-verbose form is the
+/// serialized transaction as a base64 string.
+#[derive(Serialize, Deserialize, Clone, Debug, Hash)]
+#[serde(untagged)]
+pub enum RawTransactionInfo {
+	Verbose(Box<RTransaction>),
+	Base64(String),
+}
+
+impl RawTransactionInfo {
+	pub fn transaction(&self) -> Option<&RTransaction> {
+		match self {
+			RawTransactionInfo::Verbose(transaction) => Some(transaction),
+			RawTransactionInfo::Base64(_) => None,
+		}
+	}
+
+	pub fn base64(&self) -> Option<&str> {
+		match self {
+			RawTransactionInfo::Verbose(_) => None,
+			RawTransactionInfo::Base64(base64) => Some(base64),
+		}
+	}
+
+	/// Decodes the serialized transaction of the non-verbose form. See
+	/// [`Transaction::from_base64`].
+	pub fn decode<'a, P: JsonRpcProvider + 'static>(
+		&self,
+	) -> Option<Result<Transaction<'a, P>, TransactionError>> {
+		self.base64().map(Transaction::from_base64)
+	}
+
+	/// The number of confirmations, only available in the verbose form.
+	pub fn confirmations(&self) -> Option<i32> {
+		self.transaction().map(|transaction| transaction.confirmations)
+	}
+}
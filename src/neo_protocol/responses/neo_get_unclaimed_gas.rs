@@ -1,7 +1,38 @@
 use serde::{Deserialize, Serialize};
 
+use neo::prelude::{deserialize_u64, serialize_u64};
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Debug)]
 pub struct UnclaimedGas {
-	pub unclaimed: String,
+	/// The unclaimed GAS in fractions, i.e. 10^-8 GAS
+	#[serde(deserialize_with = "deserialize_u64")]
+	#[serde(serialize_with = "serialize_u64")]
+	pub unclaimed: u64,
 	pub address: String,
 }
+
+#[cfg(test)]
+mod tests {
+	use serde_json::json;
+
+	use super::UnclaimedGas;
+
+	#[test]
+	fn test_unclaimed_gas() {
+		let response = json!({
+			"unclaimed": "79199824176",
+			"address": "NaQ6Kj6qYinh1frv1wrn53wbPFe5BH5T7g"
+		});
+		let unclaimed_gas: UnclaimedGas = serde_json::from_value(response.clone()).unwrap();
+
+		assert_eq!(unclaimed_gas.unclaimed, 79_199_824_176);
+		assert_eq!(unclaimed_gas.address, "NaQ6Kj6qYinh1frv1wrn53wbPFe5BH5T7g");
+		assert_eq!(serde_json::to_value(&unclaimed_gas).unwrap(), response);
+
+		assert!(serde_json::from_value::<UnclaimedGas>(json!({
+			"unclaimed": "1.5",
+			"address": "NaQ6Kj6qYinh1frv1wrn53wbPFe5BH5T7g"
+		}))
+		.is_err());
+	}
+}
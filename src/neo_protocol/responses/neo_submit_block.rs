@@ -2,17 +2,31 @@ use neo::prelude::{deserialize_h256, serialize_h256};
 use primitive_types::H256;
 use serde_derive::{Deserialize, Serialize};
 
-// #[derive(Serialize, Deserialize, Debug, Clone, Hash)]
-// pub struct SubmitBlock {
-// 	#[serde(serialize_with = "serialize_h256")]
-// 	#[serde(deserialize_with = "deserialize_h256")]
-// 	pub hash: H256,
-// }
-
+/// The result of `submitblock`. Neo N3 nodes reply with the hash of the relayed block, while
+/// legacy nodes reply with a plain boolean.
 #[derive(Serialize, Deserialize, Debug, Clone, Hash)]
-pub struct SubmitBlock(bool);
+#[serde(untagged)]
+pub enum SubmitBlock {
+	Hash {
+		#[serde(serialize_with = "serialize_h256")]
+		#[serde(deserialize_with = "deserialize_h256")]
+		hash: H256,
+	},
+	Accepted(bool),
+}
+
 impl SubmitBlock {
 	pub fn get_submit_block(&self) -> bool {
-		self.0
+		match self {
+			SubmitBlock::Hash { .. } => true,
+			SubmitBlock::Accepted(accepted) => *accepted,
+		}
+	}
+
+	pub fn hash(&self) -> Option<H256> {
+		match self {
+			SubmitBlock::Hash { hash } => Some(*hash),
+			SubmitBlock::Accepted(_) => None,
+		}
 	}
 }
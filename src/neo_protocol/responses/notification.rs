@@ -1,5 +1,6 @@
 use std::hash::{Hash, Hasher};
 
+use num_bigint::BigInt;
 use primitive_types::H160;
 use serde::{Deserialize, Serialize};
 
@@ -19,4 +20,111 @@ impl LogNotification {
 	pub fn new(contract: H160, event_name: String, state: StackItem) -> Self {
 		Self { contract, event_name, state }
 	}
+
+	/// Parses the notification as a NEP-17 `Transfer` event. A null sender or recipient, as
+	/// emitted for mints and burns, is returned as the zero script hash.
+	pub fn as_nep17_transfer(&self) -> Option<Nep17TransferEvent> {
+		if self.event_name != "Transfer" {
+			return None;
+		}
+
+		let state = self.state.as_array()?;
+		if state.len() != 3 {
+			return None;
+		}
+
+		Some(Nep17TransferEvent {
+			token: self.contract,
+			from: Self::transfer_party(&state[0])?,
+			to: Self::transfer_party(&state[1])?,
+			amount: state[2].as_i256().ok()?,
+		})
+	}
+
+	fn transfer_party(item: &StackItem) -> Option<ScriptHash> {
+		if let StackItem::Any = item {
+			return Some(ScriptHash::zero());
+		}
+
+		let mut bytes = item.as_bytes()?;
+		if bytes.len() != 20 {
+			return None;
+		}
+		bytes.reverse();
+		Some(ScriptHash::from_slice(&bytes))
+	}
+}
+
+/// A NEP-17 `Transfer` event, see [`LogNotification::as_nep17_transfer`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Nep17TransferEvent {
+	pub token: ScriptHash,
+	pub from: ScriptHash,
+	pub to: ScriptHash,
+	pub amount: BigInt,
+}
+
+impl Nep17TransferEvent {
+	/// Whether the tokens were newly minted, i.e. sent from the zero address.
+	pub fn is_mint(&self) -> bool {
+		self.from.is_zero()
+	}
+
+	/// Whether the tokens were burned, i.e. sent to the zero address.
+	pub fn is_burn(&self) -> bool {
+		self.to.is_zero()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use num_bigint::BigInt;
+	use primitive_types::H160;
+
+	use neo::prelude::StackItem;
+
+	use super::LogNotification;
+
+	fn transfer(from: StackItem, to: StackItem) -> LogNotification {
+		transfer_of(from, to, 100.into())
+	}
+
+	fn transfer_of(from: StackItem, to: StackItem, amount: BigInt) -> LogNotification {
+		LogNotification::new(
+			H160::repeat_byte(0xcf),
+			"Transfer".to_string(),
+			StackItem::Array { value: vec![from, to, StackItem::Integer { value: amount }] },
+		)
+	}
+
+	#[test]
+	fn test_as_nep17_transfer_detects_mint_and_burn() {
+		let account = StackItem::new_byte_string(vec![0x01; 20]);
+
+		let mint = transfer(StackItem::Any, account.clone()).as_nep17_transfer().unwrap();
+		assert!(mint.is_mint());
+		assert!(!mint.is_burn());
+		assert_eq!(mint.to, H160::repeat_byte(0x01));
+		assert_eq!(mint.amount, BigInt::from(100));
+
+		let burn = transfer(account.clone(), StackItem::new_byte_string(vec![0; 20]))
+			.as_nep17_transfer()
+			.unwrap();
+		assert!(burn.is_burn());
+		assert!(!burn.is_mint());
+
+		let mut other = transfer(account.clone(), account);
+		assert!(other.as_nep17_transfer().is_some());
+		other.event_name = "Approval".to_string();
+		assert!(other.as_nep17_transfer().is_none());
+	}
+
+	#[test]
+	fn test_as_nep17_transfer_keeps_large_amounts() {
+		let amount = BigInt::from(u64::MAX) * 1000;
+		let account = StackItem::new_byte_string(vec![0x01; 20]);
+
+		let transfer = transfer_of(account.clone(), account, amount.clone());
+		assert_eq!(transfer.as_nep17_transfer().unwrap().amount, amount);
+	}
 }
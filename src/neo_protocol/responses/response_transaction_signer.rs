@@ -128,6 +128,18 @@ impl RTransactionSigner {
 		Ok(&self.allowed_groups[index])
 	}
 
+	/// Decodes the allowed groups into public keys.
+	pub fn get_allowed_group_keys(&self) -> Result<Vec<Secp256r1PublicKey>, TypeError> {
+		self.allowed_groups
+			.iter()
+			.map(|group| {
+				Secp256r1PublicKey::from_encoded(group).ok_or_else(|| {
+					TypeError::InvalidData(format!("Invalid allowed group public key: {}", group))
+				})
+			})
+			.collect()
+	}
+
 	pub fn get_first_rule(&self) -> Result<&WitnessRule, TypeError> {
 		if self.rules.is_empty() {
 			return Err(TypeError::IndexOutOfBounds(
@@ -259,3 +271,75 @@ impl SignerTrait for RTransactionSigner {
 // 		writer.to_bytes()
 // 	}
 // }
+
+#[cfg(test)]
+mod tests {
+	use std::str::FromStr;
+
+	use primitive_types::H160;
+
+	use neo::prelude::{WitnessAction, WitnessCondition, WitnessScope};
+
+	use super::RTransactionSigner;
+
+	#[test]
+	fn test_deserialize_custom_contracts_signer() {
+		let json = r#"{
+			"account": "0x69ecca587293047be4c59159bf8bc399985c160d",
+			"scopes": "CalledByEntry, CustomContracts, CustomGroups, WitnessRules",
+			"allowedcontracts": ["0xd2a4cff31913016155e38e474a2c06d08be276cf"],
+			"allowedgroups": ["033a4d051b04b7fc0230d2b1aaedfd5a84be279a5361a7358db665ad7857787f1b"],
+			"rules": [
+				{
+					"action": "Deny",
+					"condition": {
+						"type": "CalledByContract",
+						"hash": "0xef4073a0f2b305a38ec4050e4d3d28bc40ea63f5"
+					}
+				}
+			]
+		}"#;
+		let signer: RTransactionSigner = serde_json::from_str(json).unwrap();
+
+		assert_eq!(
+			signer.account,
+			H160::from_str("69ecca587293047be4c59159bf8bc399985c160d").unwrap()
+		);
+		assert_eq!(
+			signer.scopes,
+			vec![
+				WitnessScope::CalledByEntry,
+				WitnessScope::CustomContracts,
+				WitnessScope::CustomGroups,
+				WitnessScope::WitnessRules
+			]
+		);
+		assert_eq!(
+			signer.allowed_contracts,
+			vec![H160::from_str("d2a4cff31913016155e38e474a2c06d08be276cf").unwrap()]
+		);
+
+		let groups = signer.get_allowed_group_keys().unwrap();
+		assert_eq!(groups.len(), 1);
+		assert_eq!(
+			hex::encode(groups[0].get_encoded(true)),
+			"033a4d051b04b7fc0230d2b1aaedfd5a84be279a5361a7358db665ad7857787f1b"
+		);
+
+		let rule = signer.get_first_rule().unwrap();
+		assert_eq!(rule.action, WitnessAction::Deny);
+		assert_eq!(
+			rule.condition,
+			WitnessCondition::CalledByContract(
+				H160::from_str("ef4073a0f2b305a38ec4050e4d3d28bc40ea63f5").unwrap()
+			)
+		);
+	}
+
+	#[test]
+	fn test_get_allowed_group_keys_rejects_invalid_key() {
+		let mut signer = RTransactionSigner::default();
+		signer.allowed_groups = vec!["0xdeadbeef".to_string()];
+		assert!(signer.get_allowed_group_keys().is_err());
+	}
+}
@@ -0,0 +1,181 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use neo::prelude::{ApplicationLog, RTransaction, TypeError};
+
+/// A transaction as returned by the node once it has been included in a block, optionally
+/// paired with its application log and the fees estimated by the `TransactionBuilder`.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct TransactionConfirmation {
+	pub transaction: RTransaction,
+	pub application_log: Option<ApplicationLog>,
+	pub estimated_system_fee: Option<i64>,
+	pub estimated_network_fee: Option<i64>,
+}
+
+impl TransactionConfirmation {
+	pub fn new(transaction: RTransaction, application_log: Option<ApplicationLog>) -> Self {
+		Self {
+			transaction,
+			application_log,
+			estimated_system_fee: None,
+			estimated_network_fee: None,
+		}
+	}
+
+	/// Attaches the fees that were estimated when building the transaction, i.e. the
+	/// `sys_fee` and `net_fee` of the unsigned `Transaction`.
+	pub fn with_estimates(mut self, system_fee: i64, network_fee: i64) -> Self {
+		self.estimated_system_fee = Some(system_fee);
+		self.estimated_network_fee = Some(network_fee);
+		self
+	}
+
+	/// Summarizes the fees actually paid by the confirmed transaction and compares them against
+	/// the estimates, if any were attached. Fails if a fee or the consumed GAS is not an integer.
+	pub fn fee_summary(&self) -> Result<FeeSummary, TypeError> {
+		let gas_consumed =
+			match self.application_log.as_ref().and_then(|log| log.get_first_execution().ok()) {
+				Some(execution) => Some(parse_fee("gasconsumed", &execution.gas_consumed)?),
+				None => None,
+			};
+
+		Ok(FeeSummary {
+			system_fee: parse_fee("sysfee", &self.transaction.sys_fee)?,
+			network_fee: parse_fee("netfee", &self.transaction.net_fee)?,
+			gas_consumed,
+			estimated_system_fee: self.estimated_system_fee,
+			estimated_network_fee: self.estimated_network_fee,
+		})
+	}
+}
+
+fn parse_fee(field: &str, value: &str) -> Result<i64, TypeError> {
+	i64::from_str(value)
+		.map_err(|err| TypeError::InvalidData(format!("Invalid {field} '{value}': {err}")))
+}
+
+/// Actual and estimated fees of a confirmed transaction, in GAS fractions (10^-8 GAS).
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct FeeSummary {
+	pub system_fee: i64,
+	pub network_fee: i64,
+	pub gas_consumed: Option<i64>,
+	pub estimated_system_fee: Option<i64>,
+	pub estimated_network_fee: Option<i64>,
+}
+
+impl FeeSummary {
+	pub fn total_fee(&self) -> i64 {
+		self.system_fee + self.network_fee
+	}
+
+	pub fn estimated_total_fee(&self) -> Option<i64> {
+		Some(self.estimated_system_fee? + self.estimated_network_fee?)
+	}
+
+	/// The difference between the paid and the estimated system fee. Positive values mean more
+	/// was paid than estimated.
+	pub fn system_fee_delta(&self) -> Option<i64> {
+		self.estimated_system_fee.map(|estimate| self.system_fee - estimate)
+	}
+
+	/// The difference between the paid and the estimated network fee. Positive values mean more
+	/// was paid than estimated.
+	pub fn network_fee_delta(&self) -> Option<i64> {
+		self.estimated_network_fee.map(|estimate| self.network_fee - estimate)
+	}
+
+	pub fn total_fee_delta(&self) -> Option<i64> {
+		self.estimated_total_fee().map(|estimate| self.total_fee() - estimate)
+	}
+
+	/// The part of the system fee that was paid but not consumed by the VM execution.
+	pub fn unused_system_fee(&self) -> Option<i64> {
+		self.gas_consumed.map(|consumed| self.system_fee - consumed)
+	}
+
+	/// Whether the transaction paid more in total than was estimated.
+	pub fn is_overpaid(&self) -> bool {
+		self.total_fee_delta().map_or(false, |delta| delta > 0)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use primitive_types::H256;
+
+	use neo::prelude::{ApplicationLog, Execution, RTransaction, TypeError};
+
+	use super::TransactionConfirmation;
+
+	fn confirmed_transaction(sys_fee: &str, net_fee: &str) -> RTransaction {
+		RTransaction::new(
+			H256::zero(),
+			252,
+			0,
+			1,
+			"NZNovBUJ6Pp4fTuq2ALj5J6vZrjMz2gyV2".to_string(),
+			sys_fee.to_string(),
+			net_fee.to_string(),
+			100,
+			vec![],
+			vec![],
+			"EMAfDAB+2fA=".to_string(),
+			vec![],
+		)
+	}
+
+	#[test]
+	fn test_fee_summary_against_estimate() {
+		let log = ApplicationLog {
+			transaction_id: H256::zero(),
+			executions: vec![Execution {
+				gas_consumed: "997778".to_string(),
+				..Default::default()
+			}],
+		};
+		let confirmation =
+			TransactionConfirmation::new(confirmed_transaction("1997778", "1230610"), Some(log))
+				.with_estimates(1997778, 1200000);
+
+		let summary = confirmation.fee_summary().unwrap();
+		assert_eq!(summary.system_fee, 1997778);
+		assert_eq!(summary.network_fee, 1230610);
+		assert_eq!(summary.total_fee(), 3228388);
+		assert_eq!(summary.estimated_total_fee(), Some(3197778));
+		assert_eq!(summary.system_fee_delta(), Some(0));
+		assert_eq!(summary.network_fee_delta(), Some(30610));
+		assert_eq!(summary.total_fee_delta(), Some(30610));
+		assert_eq!(summary.unused_system_fee(), Some(1000000));
+		assert!(summary.is_overpaid());
+	}
+
+	#[test]
+	fn test_fee_summary_without_estimate() {
+		let confirmation =
+			TransactionConfirmation::new(confirmed_transaction("1997778", "1230610"), None);
+
+		let summary = confirmation.fee_summary().unwrap();
+		assert_eq!(summary.gas_consumed, None);
+		assert_eq!(summary.estimated_total_fee(), None);
+		assert_eq!(summary.total_fee_delta(), None);
+		assert!(!summary.is_overpaid());
+	}
+
+	#[test]
+	fn test_fee_summary_rejects_malformed_fees() {
+		let confirmation =
+			TransactionConfirmation::new(confirmed_transaction("1997778", "1.5"), None);
+		assert!(matches!(confirmation.fee_summary(), Err(TypeError::InvalidData(_))));
+
+		let log = ApplicationLog {
+			transaction_id: H256::zero(),
+			executions: vec![Execution { gas_consumed: "".to_string(), ..Default::default() }],
+		};
+		let confirmation =
+			TransactionConfirmation::new(confirmed_transaction("1997778", "1230610"), Some(log));
+		assert!(matches!(confirmation.fee_summary(), Err(TypeError::InvalidData(_))));
+	}
+}
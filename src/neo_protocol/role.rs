@@ -1,19 +1,27 @@
 use num_enum::TryFromPrimitive;
 use strum_macros::{Display, EnumString};
 
+/// A role that the committee can designate nodes for through the `RoleManagement` native
+/// contract.
 #[derive(Display, EnumString, Copy, Clone, Debug, PartialEq, Eq, TryFromPrimitive)]
 #[repr(u8)]
-pub(crate) enum Role {
+pub enum Role {
+	/// Nodes that sign state roots
 	#[strum(serialize = "StateValidator")]
 	StateValidator = 0x04,
+	/// Nodes that answer oracle requests
 	#[strum(serialize = "Oracle")]
 	Oracle = 0x08,
+	/// Alphabet nodes of NeoFS
 	#[strum(serialize = "NeoFSAlphabetNode")]
 	NeoFsAlphabetNode = 0x10,
+	/// Nodes of the notary service
+	#[strum(serialize = "P2PNotary")]
+	P2PNotary = 0x20,
 }
 
 impl Role {
-	pub(crate) fn byte_repr(self) -> u8 {
+	pub fn byte_repr(self) -> u8 {
 		self as u8
 	}
 }
@@ -141,6 +141,7 @@ impl Hash for ContractManifest {
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Hash, Debug, Clone)]
 pub struct ContractGroup {
+	#[serde(rename = "pubkey")]
 	pub pub_key: String,
 	pub signature: String,
 }
@@ -5,6 +5,7 @@ use std::{
 };
 
 use getset::Getters;
+use num_bigint::BigInt;
 use primitive_types::{H160, H256};
 use rustc_serialize::{
 	base64::FromBase64,
@@ -22,7 +23,8 @@ use strum_macros::{Display, EnumString};
 
 use neo::prelude::{
 	deserialize_map, serialize_map, Base64Encode, ContractParameterType, NNSName, NefFile,
-	NeoSerializable, Role, ScriptHashExtension, Secp256r1PublicKey, ValueExtension,
+	NeoSerializable, Role, ScriptHashExtension, Secp256r1PublicKey, TypeError, ValueExtension,
+	DEFAULT_ADDRESS_VERSION,
 };
 
 #[derive(Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Clone)]
@@ -110,8 +112,11 @@ impl<'de> Deserialize<'de> for ContractParameter {
 				let value: Option<ParameterValue> = match typ {
 					ContractParameterType::Boolean =>
 						value.map(|v| ParameterValue::Boolean(serde_json::from_value(v).unwrap())),
-					ContractParameterType::Integer =>
-						value.map(|v| ParameterValue::Integer(serde_json::from_value(v).unwrap())),
+					ContractParameterType::Integer => value.map(|v| match v {
+						Value::String(s) =>
+							ContractParameter::big_integer(&s.parse().unwrap()).value.unwrap(),
+						v => ParameterValue::Integer(serde_json::from_value(v).unwrap()),
+					}),
 					ContractParameterType::ByteArray =>
 						value.map(|v| ParameterValue::ByteArray(serde_json::from_value(v).unwrap())),
 					ContractParameterType::String =>
@@ -173,13 +178,19 @@ impl From<u32> for ContractParameter {
 
 impl From<u64> for ContractParameter {
 	fn from(value: u64) -> Self {
-		Self::integer(value as i64)
+		Self::big_integer(&BigInt::from(value))
+	}
+}
+
+impl From<Role> for ContractParameter {
+	fn from(value: Role) -> Self {
+		Self::integer(value.byte_repr() as i64)
 	}
 }
 
 impl From<&Role> for ContractParameter {
 	fn from(value: &Role) -> Self {
-		Self::integer(value.clone() as i64)
+		Self::from(*value)
 	}
 }
 
@@ -307,6 +318,7 @@ impl Into<Value> for ContractParameter {
 		match self.value.unwrap() {
 			ParameterValue::Boolean(b) => Value::Bool(b),
 			ParameterValue::Integer(i) => Value::Number(serde_json::Number::from(i)),
+			ParameterValue::BigInteger(i) => Value::String(i),
 			ParameterValue::ByteArray(b) => Value::String(b),
 			ParameterValue::String(s) => Value::String(s),
 			ParameterValue::H160(h) => Value::String(h),
@@ -358,6 +370,8 @@ impl ValueExtension for Vec<ContractParameter> {
 pub enum ParameterValue {
 	Boolean(bool),
 	Integer(i64),
+	/// An integer outside the `i64` range, as a decimal string.
+	BigInteger(String),
 	ByteArray(String),
 	String(String),
 	H160(String),
@@ -374,6 +388,7 @@ impl Hash for ParameterValue {
 		match self {
 			ParameterValue::Boolean(b) => b.hash(state),
 			ParameterValue::Integer(i) => i.hash(state),
+			ParameterValue::BigInteger(i) => i.hash(state),
 			ParameterValue::ByteArray(b) => b.hash(state),
 			ParameterValue::String(s) => s.hash(state),
 			ParameterValue::H160(h) => h.hash(state),
@@ -420,6 +435,18 @@ impl ContractParameter {
 		Self::with_value(ContractParameterType::Integer, ParameterValue::Integer(value))
 	}
 
+	/// Builds an `Integer` parameter of any size. Values outside the `i64` range are kept as a
+	/// decimal string, which is also how the RPC expects integer values.
+	pub fn big_integer(value: &BigInt) -> Self {
+		match i64::try_from(value) {
+			Ok(i) => Self::integer(i),
+			Err(_) => Self::with_value(
+				ContractParameterType::Integer,
+				ParameterValue::BigInteger(value.to_string()),
+			),
+		}
+	}
+
 	pub fn to_integer(&self) -> i64 {
 		match self.value.as_ref().unwrap() {
 			ParameterValue::Integer(i) => *i,
@@ -543,6 +570,61 @@ impl ContractParameter {
 		Hash::hash(&self, &mut hasher);
 		hasher.finish().to_be_bytes().to_vec()
 	}
+
+	/// Builds a parameter from a JSON value, inferring its type:
+	///
+	/// - `null` is `Any`, booleans are `Boolean` and integers are `Integer`.
+	/// - Arrays are `Array` and objects are `Map` with `String` keys, converted recursively.
+	/// - Neo addresses and `0x` followed by 40 hex digits are `Hash160`, the latter in the
+	///   big-endian order script hashes are displayed in. `0x` followed by 64 hex digits is
+	///   `Hash256`.
+	/// - Any other `0x` hex string, and a `base64:` prefixed string, is a `ByteArray`.
+	/// - All other strings are `String`.
+	///
+	/// Unlike the `From<Value>` conversion, this fails instead of panicking on fractional numbers
+	/// and on malformed hex or base64.
+	pub fn from_json(value: &Value) -> Result<Self, TypeError> {
+		match value {
+			Value::Null => Ok(Self::any()),
+			Value::Bool(b) => Ok(Self::bool(*b)),
+			Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+				(Some(i), _) => Ok(Self::integer(i)),
+				(None, Some(u)) => Ok(Self::from(u)),
+				_ => Err(TypeError::InvalidData(format!("{n} is not an integer"))),
+			},
+			Value::String(s) => Self::from_json_string(s),
+			Value::Array(a) =>
+				Ok(Self::array(a.iter().map(Self::from_json).collect::<Result<_, _>>()?)),
+			Value::Object(o) => {
+				let map = o
+					.iter()
+					.map(|(k, v)| Self::from_json(v).map(|v| (Self::string(k.clone()), v)))
+					.collect::<Result<_, _>>()?;
+				Ok(Self::map(ContractParameterMap::from_map(map)))
+			},
+		}
+	}
+
+	fn from_json_string(s: &str) -> Result<Self, TypeError> {
+		if let Some(digits) = s.strip_prefix("0x") {
+			let bytes =
+				hex::decode(digits).map_err(|e| TypeError::InvalidEncoding(format!("{s}: {e}")))?;
+			return Ok(match bytes.len() {
+				20 => Self::h160(&H160::from_slice(&bytes)),
+				32 => Self::h256(&H256::from_slice(&bytes)),
+				_ => Self::byte_array(bytes),
+			});
+		}
+		if let Some(encoded) = s.strip_prefix("base64:") {
+			let bytes = base64::decode(encoded)
+				.map_err(|e| TypeError::InvalidEncoding(format!("{s}: {e}")))?;
+			return Ok(Self::byte_array(bytes));
+		}
+		match H160::from_address_checked(s, DEFAULT_ADDRESS_VERSION) {
+			Ok(script_hash) => Ok(Self::h160(&script_hash)),
+			Err(_) => Ok(Self::string(s.to_string())),
+		}
+	}
 }
 
 #[derive(Default, Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
@@ -651,11 +733,14 @@ impl ParameterValue {
 
 #[cfg(test)]
 mod tests {
+	use num_bigint::BigInt;
 	use primitive_types::{H160, H256};
 	use rustc_serialize::hex::FromHex;
+	use serde_json::json;
 
 	use neo::prelude::{
-		ContractParameter, ContractParameterMap, ContractParameterType, Secp256r1PublicKey,
+		ContractParameter, ContractParameterMap, ContractParameterType, ScriptBuilder,
+		ScriptHashExtension, Secp256r1PublicKey, TypeError,
 	};
 
 	#[test]
@@ -947,6 +1032,73 @@ mod tests {
 		assert_ne!(p1, p3);
 	}
 
+	#[test]
+	fn test_from_json() {
+		let hash = H160::repeat_byte(0xab);
+		let json = json!({
+			"amount": 100,
+			"enabled": true,
+			"recipients": [hash.to_address(), format!("0x{}", "ab".repeat(20))],
+			"block": format!("0x{}", "01".repeat(32)),
+			"data": "0xa602",
+			"payload": "base64:AQI=",
+			"memo": "hello",
+			"extra": null,
+		});
+
+		let param = ContractParameter::from_json(&json).unwrap();
+		assert_eq!(param.typ, ContractParameterType::Map);
+		let map = param.to_map().0;
+		let get = |key: &str| map.get(&ContractParameter::string(key.to_string())).unwrap();
+		assert_eq!(*get("amount"), ContractParameter::integer(100));
+		assert_eq!(*get("enabled"), ContractParameter::bool(true));
+		assert_eq!(
+			*get("recipients"),
+			ContractParameter::array(vec![
+				ContractParameter::h160(&hash),
+				ContractParameter::h160(&hash),
+			])
+		);
+		assert_eq!(*get("block"), ContractParameter::h256(&H256::repeat_byte(1)));
+		assert_eq!(*get("data"), ContractParameter::byte_array(vec![0xa6, 0x02]));
+		assert_eq!(*get("payload"), ContractParameter::byte_array(vec![1, 2]));
+		assert_eq!(*get("memo"), ContractParameter::string("hello".to_string()));
+		assert_eq!(*get("extra"), ContractParameter::any());
+	}
+
+	#[test]
+	fn test_from_json_accepts_integers_above_i64_max() {
+		let param = ContractParameter::from_json(&json!(u64::MAX)).unwrap();
+		assert_eq!(param.typ, ContractParameterType::Integer);
+		assert_eq!(param, ContractParameter::big_integer(&BigInt::from(u64::MAX)));
+
+		let mut builder = ScriptBuilder::new();
+		builder.push_param(&param).unwrap();
+		let mut expected = ScriptBuilder::new();
+		expected.push_integer(BigInt::from(u64::MAX));
+		assert_eq!(builder.to_bytes(), expected.to_bytes());
+
+		let json = serde_json::to_value(&param).unwrap();
+		assert_eq!(json["value"], json!(u64::MAX.to_string()));
+		assert_eq!(serde_json::from_value::<ContractParameter>(json).unwrap(), param);
+	}
+
+	#[test]
+	fn test_from_json_rejects_invalid_values() {
+		assert!(matches!(
+			ContractParameter::from_json(&json!(1.5)),
+			Err(TypeError::InvalidData(_))
+		));
+		assert!(matches!(
+			ContractParameter::from_json(&json!(["0xzz"])),
+			Err(TypeError::InvalidEncoding(_))
+		));
+		assert!(matches!(
+			ContractParameter::from_json(&json!({ "data": "base64:***" })),
+			Err(TypeError::InvalidEncoding(_))
+		));
+	}
+
 	// #[test]
 	// fn invalid_type_errors() {
 	// 	let result = ContractParameter::from(MyStruct);
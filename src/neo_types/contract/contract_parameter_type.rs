@@ -1,7 +1,11 @@
+use std::str::FromStr;
+
 use num_enum::TryFromPrimitive;
 use serde_derive::{Deserialize, Serialize};
 use strum_macros::{Display, EnumString};
 
+use neo::prelude::TypeError;
+
 #[derive(
 	Display,
 	EnumString,
@@ -48,6 +52,35 @@ pub enum ContractParameterType {
 	Void = 0xff,
 }
 
+impl ContractParameterType {
+	/// Parses a parameter type as it is named in contract manifests, e.g. `"Hash160"`. The name
+	/// of a type is its `Display` representation.
+	pub fn from_abi_name(name: &str) -> Result<Self, TypeError> {
+		Self::from_str(name).map_err(|_| {
+			TypeError::IllegalArgument(format!("Unknown contract parameter type: {}", name))
+		})
+	}
+
+	/// A representative Rust type for values of this parameter type, e.g. for code generation.
+	pub fn rust_type_name(&self) -> &'static str {
+		match self {
+			ContractParameterType::Any => "StackItem",
+			ContractParameterType::Boolean => "bool",
+			ContractParameterType::Integer => "i64",
+			ContractParameterType::ByteArray => "Vec<u8>",
+			ContractParameterType::String => "String",
+			ContractParameterType::H160 => "H160",
+			ContractParameterType::H256 => "H256",
+			ContractParameterType::PublicKey => "Secp256r1PublicKey",
+			ContractParameterType::Signature => "Secp256r1Signature",
+			ContractParameterType::Array => "Vec<ContractParameter>",
+			ContractParameterType::Map => "ContractParameterMap",
+			ContractParameterType::InteropInterface => "StackItem",
+			ContractParameterType::Void => "()",
+		}
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -89,4 +122,35 @@ mod tests {
 		let json = serde_json::to_string(&test).unwrap();
 		assert_eq!(json, r#"{"type":"Array"}"#);
 	}
+
+	#[test]
+	fn test_from_abi_name_round_trip() {
+		let types = [
+			(ContractParameterType::Any, "Any", "StackItem"),
+			(ContractParameterType::Boolean, "Boolean", "bool"),
+			(ContractParameterType::Integer, "Integer", "i64"),
+			(ContractParameterType::ByteArray, "ByteArray", "Vec<u8>"),
+			(ContractParameterType::String, "String", "String"),
+			(ContractParameterType::H160, "Hash160", "H160"),
+			(ContractParameterType::H256, "Hash256", "H256"),
+			(ContractParameterType::PublicKey, "PublicKey", "Secp256r1PublicKey"),
+			(ContractParameterType::Signature, "Signature", "Secp256r1Signature"),
+			(ContractParameterType::Array, "Array", "Vec<ContractParameter>"),
+			(ContractParameterType::Map, "Map", "ContractParameterMap"),
+			(ContractParameterType::InteropInterface, "InteropInterface", "StackItem"),
+			(ContractParameterType::Void, "Void", "()"),
+		];
+
+		for (param_type, abi_name, rust_type) in types {
+			assert_eq!(ContractParameterType::from_abi_name(abi_name), Ok(param_type));
+			assert_eq!(
+				ContractParameterType::from_abi_name(&param_type.to_string()),
+				Ok(param_type)
+			);
+			assert_eq!(param_type.rust_type_name(), rust_type);
+		}
+
+		assert!(ContractParameterType::from_abi_name("H160").is_err());
+		assert!(ContractParameterType::from_abi_name("Unknown").is_err());
+	}
 }
@@ -12,7 +12,9 @@ use serde::{
 use strum;
 use strum_macros::{AsRefStr, Display, EnumString};
 
-use neo::prelude::{deserialize_script_hash, serialize_script_hash, ContractParameter, StackItem};
+use neo::prelude::{
+	deserialize_script_hash, serialize_script_hash, ContractError, ContractParameter, StackItem,
+};
 
 use crate::prelude::TypeError;
 
@@ -126,6 +128,16 @@ impl InvocationResult {
 		matches!(self.state, NeoVMStateType::Fault)
 	}
 
+	/// Returns the result if the invocation did not fault, or a [`ContractError::VmFault`] with
+	/// the state and exception message otherwise, so callers can use `?` on invocations.
+	pub fn into_result(self) -> Result<Self, ContractError> {
+		if self.has_state_fault() {
+			Err(ContractError::VmFault { state: self.state, exception: self.exception })
+		} else {
+			Ok(self)
+		}
+	}
+
 	pub fn get_first_stack_item(&self) -> Result<&StackItem, TypeError> {
 		if self.stack.is_empty() {
 			return Err(TypeError::IndexOutOfBounds(
@@ -290,3 +302,38 @@ pub enum NotificationState {
 	StepOver,
 	Break,
 }
+
+#[cfg(test)]
+mod tests {
+	use serde_json::json;
+
+	use neo::prelude::{ContractError, InvocationResult, NeoVMStateType};
+
+	#[test]
+	fn test_into_result() {
+		let halted = InvocationResult::default();
+		assert_eq!(halted.clone().into_result().unwrap(), halted);
+
+		let faulted: InvocationResult = serde_json::from_value(json!({
+			"script": "0001",
+			"state": "FAULT",
+			"gasconsumed": "1000",
+			"exception": "ABORT is executed.",
+			"stack": []
+		}))
+		.unwrap();
+		let err = faulted.into_result().unwrap_err();
+		assert_eq!(err.to_string(), "Invocation ended in state FAULT: ABORT is executed.");
+		assert!(matches!(
+			err,
+			ContractError::VmFault { state: NeoVMStateType::Fault, exception: Some(_) }
+		));
+
+		let without_message =
+			InvocationResult { state: NeoVMStateType::Fault, ..InvocationResult::default() };
+		assert!(matches!(
+			without_message.into_result(),
+			Err(ContractError::VmFault { exception: None, .. })
+		));
+	}
+}
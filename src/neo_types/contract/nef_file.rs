@@ -51,6 +51,18 @@ impl NefFile {
 	const CHECKSUM_SIZE: usize = 4;
 	pub const HEADER_SIZE: usize = Self::MAGIC_SIZE + Self::COMPILER_SIZE;
 
+	/// The checksum as the integer the network uses, e.g. to compute the hash of a contract, or
+	/// `None` if the file has no checksum.
+	pub fn checksum_integer(&self) -> Option<u32> {
+		let checksum: [u8; Self::CHECKSUM_SIZE] = self.checksum.as_slice().try_into().ok()?;
+		Some(u32::from_le_bytes(checksum))
+	}
+
+	/// Whether the checksum matches the rest of the file.
+	pub fn has_valid_checksum(&self) -> bool {
+		self.checksum.len() == Self::CHECKSUM_SIZE && Self::compute_checksum(self) == self.checksum
+	}
+
 	fn get_checksum_as_integer(bytes: &Bytes) -> i32 {
 		let mut bytes = bytes.clone();
 		bytes.reverse();
@@ -147,13 +159,15 @@ impl NeoSerializable for NefFile {
 			return Err(TypeError::InvalidEncoding("Invalid script".to_string()));
 		}
 
-		let file =
+		let mut file =
 			Self { compiler: Some(compiler), source_url, method_tokens, script, checksum: vec![] };
 
+		// Without a checksum the file serializes to exactly the bytes the checksum covers
 		let checksum = reader.read_bytes(Self::CHECKSUM_SIZE)?;
-		if checksum != Self::compute_checksum(&file) {
+		if checksum[..] != file.to_array().hash256()[..Self::CHECKSUM_SIZE] {
 			return Err(TypeError::InvalidEncoding("Invalid checksum".to_string()));
 		}
+		file.checksum = checksum;
 
 		Ok(file)
 	}
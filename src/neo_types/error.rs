@@ -38,6 +38,8 @@ pub enum TypeError {
 	InvalidPublicKey,
 	#[error("Invalid address")]
 	InvalidAddress,
+	#[error("Address version mismatch: expected {expected}, got {actual}")]
+	AddressVersionMismatch { expected: u8, actual: u8 },
 	#[error("Invalid signature")]
 	InvalidSignature,
 	#[error("Invalid encoding {0}")]
@@ -55,3 +57,11 @@ pub enum TypeError {
 	#[error(transparent)]
 	CodecError(#[from] CodecError),
 }
+
+#[derive(Error, Debug, PartialEq, Eq, Hash, Clone)]
+pub enum ConversionError {
+	#[error("Amount {amount} has {dust} dust below the target precision")]
+	Dust { amount: u128, dust: u128 },
+	#[error("Amount {0} overflows the target type")]
+	Overflow(u128),
+}
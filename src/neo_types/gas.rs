@@ -0,0 +1,73 @@
+use neo::prelude::ConversionError;
+
+/// The number of decimals of GAS on Neo N3.
+pub const GAS_N3_DECIMALS: u8 = 8;
+
+/// The number of decimals of GAS on Neo X.
+pub const GAS_NEOX_DECIMALS: u8 = 18;
+
+/// The factor between the smallest GAS units on Neo X and on Neo N3, i.e. 10^(18 - 8).
+const GAS_N3_TO_NEOX_FACTOR: u128 = 10_000_000_000;
+
+/// Converts a GAS amount with 8 decimals (Neo N3) to the same amount with 18 decimals (Neo X).
+///
+/// The conversion is always exact, `u64::MAX` GAS fractions fit comfortably in a `u128`.
+pub fn gas_n3_to_neox(amount_8dp: u64) -> u128 {
+	amount_8dp as u128 * GAS_N3_TO_NEOX_FACTOR
+}
+
+/// Converts a GAS amount with 18 decimals (Neo X) to the same amount with 8 decimals (Neo N3).
+///
+/// Fails with [`ConversionError::Dust`] if the amount has a non-zero part below 10^-8 GAS that
+/// cannot be represented on Neo N3. Use [`gas_neox_to_n3_truncated`] to drop it instead.
+pub fn gas_neox_to_n3(amount_18dp: u128) -> Result<u64, ConversionError> {
+	let (amount, dust) = gas_neox_to_n3_truncated(amount_18dp)?;
+	if dust != 0 {
+		return Err(ConversionError::Dust { amount: amount_18dp, dust });
+	}
+	Ok(amount)
+}
+
+/// Converts a GAS amount with 18 decimals (Neo X) to 8 decimals (Neo N3), truncating the part
+/// below 10^-8 GAS. Returns the converted amount and the truncated dust in 18-decimal units.
+pub fn gas_neox_to_n3_truncated(amount_18dp: u128) -> Result<(u64, u128), ConversionError> {
+	let amount = u64::try_from(amount_18dp / GAS_N3_TO_NEOX_FACTOR)
+		.map_err(|_| ConversionError::Overflow(amount_18dp))?;
+	Ok((amount, amount_18dp % GAS_N3_TO_NEOX_FACTOR))
+}
+
+#[cfg(test)]
+mod tests {
+	use neo::prelude::ConversionError;
+
+	use super::*;
+
+	#[test]
+	fn test_gas_n3_to_neox() {
+		assert_eq!(gas_n3_to_neox(0), 0);
+		assert_eq!(gas_n3_to_neox(1_00000000), 1_000_000_000_000_000_000);
+		assert_eq!(gas_n3_to_neox(1), 10_000_000_000);
+		assert_eq!(gas_n3_to_neox(u64::MAX), u64::MAX as u128 * 10_000_000_000);
+	}
+
+	#[test]
+	fn test_gas_neox_to_n3_exact() {
+		assert_eq!(gas_neox_to_n3(1_000_000_000_000_000_000), Ok(1_00000000));
+		assert_eq!(gas_neox_to_n3(12_345_678_900_000_000_000), Ok(12_34567890));
+		assert_eq!(gas_neox_to_n3(gas_n3_to_neox(u64::MAX)), Ok(u64::MAX));
+	}
+
+	#[test]
+	fn test_gas_neox_to_n3_dust() {
+		let amount = 1_000_000_000_000_000_001;
+		assert_eq!(gas_neox_to_n3(amount), Err(ConversionError::Dust { amount, dust: 1 }));
+		assert_eq!(gas_neox_to_n3_truncated(amount), Ok((1_00000000, 1)));
+		assert_eq!(gas_neox_to_n3_truncated(9_999_999_999), Ok((0, 9_999_999_999)));
+	}
+
+	#[test]
+	fn test_gas_neox_to_n3_overflow() {
+		let amount = gas_n3_to_neox(u64::MAX) + GAS_N3_TO_NEOX_FACTOR;
+		assert_eq!(gas_neox_to_n3(amount), Err(ConversionError::Overflow(amount)));
+	}
+}
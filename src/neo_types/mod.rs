@@ -9,6 +9,8 @@ pub use block::*;
 pub use bytes::*;
 pub use contract::*;
 pub use error::*;
+pub use gas::*;
+pub use neox_address::*;
 pub use nns::*;
 pub use numeric::*;
 pub use op_code::*;
@@ -20,6 +22,7 @@ pub use serde_with_utils::*;
 pub use stack_item::*;
 pub use string::*;
 pub use syncing::*;
+pub use token_amount::*;
 pub use tx_pool::*;
 pub use url_session::*;
 pub use util::*;
@@ -33,6 +36,8 @@ mod address_or_scripthash;
 mod block;
 mod bytes;
 mod error;
+mod gas;
+mod neox_address;
 mod numeric;
 mod op_code;
 mod path_or_string;
@@ -43,6 +48,7 @@ mod serde_with_utils;
 mod stack_item;
 mod string;
 mod syncing;
+mod token_amount;
 mod tx_pool;
 mod url_session;
 mod util;
@@ -65,7 +71,13 @@ impl ExternBase64 for String {
 // ScryptParams
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ScryptParamsDef {
-	#[serde(rename = "n")]
+	/// The base 2 logarithm of the scrypt cost N. NEP-6 stores N itself, e.g. 16384, which is
+	/// read as its logarithm and written back as N.
+	#[serde(
+		rename = "n",
+		serialize_with = "serialize_scrypt_n",
+		deserialize_with = "deserialize_scrypt_log_n"
+	)]
 	pub log_n: u8,
 	#[serde(rename = "r")]
 	pub r: u32,
@@ -79,6 +91,34 @@ impl Default for ScryptParamsDef {
 	}
 }
 
+/// Writes the scrypt `n` of a wallet as N, as NEP-6 defines it.
+fn serialize_scrypt_n<S>(log_n: &u8, serializer: S) -> Result<S::Ok, S::Error>
+where
+	S: serde::Serializer,
+{
+	let n = 1u64
+		.checked_shl(u32::from(*log_n))
+		.ok_or_else(|| serde::ser::Error::custom(format!("scrypt log2(N) {log_n} is too large")))?;
+	serializer.serialize_u64(n)
+}
+
+/// Reads the scrypt `n` of a wallet. Values up to 64 are taken as log2(N), as written by older
+/// versions of this crate, so an actual N that small is misread. Larger values must be a power
+/// of two N.
+fn deserialize_scrypt_log_n<'de, D>(deserializer: D) -> Result<u8, D::Error>
+where
+	D: serde::Deserializer<'de>,
+{
+	let n: u64 = serde::Deserialize::deserialize(deserializer)?;
+	if n <= 64 {
+		Ok(n as u8)
+	} else if n.is_power_of_two() {
+		Ok(n.trailing_zeros() as u8)
+	} else {
+		Err(serde::de::Error::custom(format!("scrypt n must be a power of two, got {n}")))
+	}
+}
+
 // Extend Vec<u8> with a to_base64 method
 pub trait Base64Encode {
 	fn to_base64(&self) -> String;
@@ -0,0 +1,142 @@
+//! Conversion between Neo N3 script hashes and Neo X (EVM) addresses.
+//!
+//! Both are 20 bytes. A Neo N3 address encodes the script hash in little-endian order, while the
+//! script hash is displayed, and held by [`ScriptHash`], in big-endian order. The Neo X address
+//! of a script hash is those big-endian bytes as an EIP-55 checksummed hex string, so
+//! `0xd2a4cff31913016155e38e474a2c06d08be276cf` maps to
+//! `0xd2a4cfF31913016155e38e474a2C06d08bE276cf`.
+//!
+//! The mapping only converts formats: a Neo N3 account and the Neo X account with the same bytes
+//! are not controlled by the same key, as the chains derive addresses from different curves.
+use primitive_types::H160;
+use sha3::{Digest, Keccak256};
+
+use neo::prelude::{ScriptHash, ScriptHashExtension, TypeError, DEFAULT_ADDRESS_VERSION};
+
+/// Returns the EIP-55 checksummed Neo X address with the bytes of `script_hash`.
+pub fn neo_n3_to_neo_x(script_hash: &H160) -> String {
+	let hex = hex::encode(script_hash.as_bytes());
+	let hash = Keccak256::digest(hex.as_bytes());
+	let checksummed: String = hex
+		.chars()
+		.enumerate()
+		.map(|(i, c)| {
+			// The nibble of the hash at the same position decides the case of a letter
+			let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0x0f;
+			if nibble >= 8 {
+				c.to_ascii_uppercase()
+			} else {
+				c
+			}
+		})
+		.collect();
+	format!("0x{checksummed}")
+}
+
+/// Returns the script hash with the bytes of the Neo X address.
+///
+/// Addresses in a single case are accepted as is; mixed-case addresses must carry a valid EIP-55
+/// checksum. The zero address is rejected, as anything sent to it is lost.
+pub fn neo_x_to_neo_n3(address: &str) -> Result<ScriptHash, TypeError> {
+	let invalid = |reason: &str| TypeError::InvalidEncoding(format!("{address}: {reason}"));
+	let hex =
+		address
+			.strip_prefix("0x")
+			.or_else(|| address.strip_prefix("0X"))
+			.ok_or_else(|| {
+				if address.starts_with('N') {
+					invalid("this is a Neo N3 address, not a Neo X address")
+				} else {
+					invalid("a Neo X address starts with 0x")
+				}
+			})?;
+	if hex.len() != 40 {
+		return Err(invalid("a Neo X address has 40 hex digits"));
+	}
+	let bytes = hex::decode(hex).map_err(|_| invalid("not a hex string"))?;
+	let script_hash = H160::from_slice(&bytes);
+
+	let has_lower = hex.chars().any(|c| c.is_ascii_lowercase());
+	let has_upper = hex.chars().any(|c| c.is_ascii_uppercase());
+	if has_lower && has_upper && neo_n3_to_neo_x(&script_hash)[2..] != *hex {
+		return Err(invalid("invalid EIP-55 checksum"));
+	}
+	if script_hash.is_zero() {
+		return Err(invalid("the zero address has no Neo N3 counterpart"));
+	}
+	Ok(script_hash)
+}
+
+/// Converts a Neo N3 address to the Neo X address with the same script hash, see
+/// [`neo_n3_to_neo_x`].
+pub fn neo_n3_address_to_neo_x(address: &str) -> Result<String, TypeError> {
+	if address.starts_with("0x") || address.starts_with("0X") {
+		return Err(TypeError::InvalidEncoding(format!(
+			"{address}: this is a Neo X address, not a Neo N3 address"
+		)));
+	}
+	let script_hash = ScriptHash::from_address_checked(address, DEFAULT_ADDRESS_VERSION)?;
+	Ok(neo_n3_to_neo_x(&script_hash))
+}
+
+/// Converts a Neo X address to the Neo N3 address with the same script hash, see
+/// [`neo_x_to_neo_n3`].
+pub fn neo_x_to_neo_n3_address(address: &str) -> Result<String, TypeError> {
+	neo_x_to_neo_n3(address).map(|script_hash| script_hash.to_address())
+}
+
+#[cfg(test)]
+mod tests {
+	use std::str::FromStr;
+
+	use primitive_types::H160;
+
+	use neo::prelude::{ScriptHashExtension, TypeError};
+
+	use super::*;
+
+	#[test]
+	fn test_eip55_checksum() {
+		// Test vectors of EIP-55
+		for address in [
+			"0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+			"0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+			"0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+			"0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+		] {
+			let script_hash = neo_x_to_neo_n3(address).unwrap();
+			assert_eq!(neo_n3_to_neo_x(&script_hash), address);
+		}
+	}
+
+	#[test]
+	fn test_round_trip_with_neo_n3_address() {
+		let script_hash = H160::from_str("d2a4cff31913016155e38e474a2c06d08be276cf").unwrap();
+		let neo_x = neo_n3_to_neo_x(&script_hash);
+		assert_eq!(neo_x, "0xd2a4cfF31913016155e38e474a2C06d08bE276cf");
+		assert_eq!(neo_x_to_neo_n3(&neo_x).unwrap(), script_hash);
+
+		let address = script_hash.to_address();
+		assert_eq!(neo_n3_address_to_neo_x(&address).unwrap(), neo_x);
+		assert_eq!(neo_x_to_neo_n3_address(&neo_x).unwrap(), address);
+		// Single-case addresses carry no checksum
+		assert_eq!(neo_x_to_neo_n3(&neo_x.to_lowercase()).unwrap(), script_hash);
+	}
+
+	#[test]
+	fn test_rejects_addresses_without_mapping() {
+		let invalid =
+			|result: Result<H160, TypeError>| matches!(result, Err(TypeError::InvalidEncoding(_)));
+		assert!(invalid(neo_x_to_neo_n3("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD")));
+		assert!(invalid(neo_x_to_neo_n3("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beae")));
+		assert!(invalid(neo_x_to_neo_n3("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beazz")));
+		assert!(invalid(neo_x_to_neo_n3(&format!("0x{}", "0".repeat(40)))));
+		assert!(invalid(neo_x_to_neo_n3("NM7Aky765FG8NhhwtxjXRx7jEL1cnw7PBP")));
+
+		assert!(matches!(
+			neo_n3_address_to_neo_x("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"),
+			Err(TypeError::InvalidEncoding(_))
+		));
+		assert!(neo_n3_address_to_neo_x("NM7Aky765FG8NhhwtxjXRx7jEL1cnw7PBQ").is_err());
+	}
+}
@@ -31,7 +31,7 @@ impl NNSName {
 		}
 
 		for fragment in &fragments {
-			Self::validate_fragment(fragment, fragment == fragments.last().unwrap()).unwrap();
+			Self::validate_fragment(fragment, fragment == fragments.last().unwrap())?;
 		}
 
 		Ok(())
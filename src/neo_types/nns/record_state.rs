@@ -1,9 +1,8 @@
 use std::hash::{Hash, Hasher};
 
-use num_enum::TryFromPrimitive;
 use serde::{Deserialize, Serialize};
 
-use neo::prelude::StackItem;
+use neo::prelude::{RecordType, StackItem};
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct RecordState {
@@ -12,15 +11,6 @@ pub struct RecordState {
 	pub data: String,
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Debug, TryFromPrimitive)]
-#[repr(u8)]
-pub enum RecordType {
-	A = 0x01,
-	AAAA = 0x02,
-	CNAME = 0x04,
-	Delete = 0x08,
-}
-
 impl RecordState {
 	pub fn new(name: String, record_type: RecordType, data: String) -> Self {
 		Self { name, record_type, data }
@@ -30,7 +20,7 @@ impl RecordState {
 		match item {
 			StackItem::Array { value: vec } if vec.len() == 3 => {
 				if let Some(name) = vec[0].as_string() {
-					if let Some(byte) = vec[1].as_int() {
+					if let Ok(byte) = vec[1].as_int() {
 						if let Some(record_type) = RecordType::try_from(byte as u8).ok() {
 							if let Some(data) = vec[2].as_string() {
 								return Ok(Self::new(name, record_type, data));
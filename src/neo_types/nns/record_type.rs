@@ -1,6 +1,8 @@
 use num_enum::{IntoPrimitive, TryFromPrimitive};
+use serde::{Deserialize, Serialize};
 use strum_macros::{AsRefStr, Display, EnumCount, EnumIter, EnumString, IntoStaticStr};
 
+/// The record types of the NNS contract, with the contract's type constants as values.
 #[derive(
 	EnumString,
 	IntoStaticStr,
@@ -13,6 +15,9 @@ use strum_macros::{AsRefStr, Display, EnumCount, EnumIter, EnumString, IntoStati
 	Debug,
 	PartialEq,
 	Eq,
+	Hash,
+	Serialize,
+	Deserialize,
 	TryFromPrimitive,
 	IntoPrimitive,
 )]
@@ -4,7 +4,7 @@ use primitive_types::H160;
 use rustc_serialize::hex::ToHex;
 
 use neo::prelude::{
-	public_key_to_script_hash, HashableForVec, Secp256r1PublicKey, TypeError,
+	base58check_decode, public_key_to_script_hash, HashableForVec, Secp256r1PublicKey, TypeError,
 	DEFAULT_ADDRESS_VERSION,
 };
 
@@ -43,9 +43,24 @@ where
 	/// Returns an error if the address is invalid.
 	fn from_address(address: &str) -> Result<Self, TypeError>;
 
-	/// Converts the object into its address string representation.
+	/// Creates an instance from an address string representation, checking that the address's
+	/// version byte is `expected_version`.
+	///
+	/// # Errors
+	///
+	/// Returns [`TypeError::AddressVersionMismatch`] if the address was encoded with another
+	/// version, e.g. a Neo X or private-net address where a mainnet one is expected.
+	fn from_address_checked(address: &str, expected_version: u8) -> Result<Self, TypeError>;
+
+	/// Converts the object into its address string representation, using the default Neo N3
+	/// address version.
 	fn to_address(&self) -> String;
 
+	/// Converts the object into the address string representation of a network whose addresses
+	/// use the given version byte. [`from_address_checked`](Self::from_address_checked) parses
+	/// such addresses back.
+	fn to_address_with_version(&self, version: u8) -> String;
+
 	/// Converts the object into its hex string representation.
 	fn to_hex(&self) -> String;
 
@@ -97,28 +112,26 @@ impl ScriptHashExtension for H160 {
 	}
 
 	fn from_address(address: &str) -> Result<Self, TypeError> {
-		let bytes = match bs58::decode(address).into_vec() {
-			Ok(bytes) => bytes,
-			Err(_) => return Err(TypeError::InvalidAddress),
-		};
-
-		let _salt = bytes[0];
-		let hash = &bytes[1..21];
-		let checksum = &bytes[21..25];
-		let sha = &bytes[..21].hash256().hash256();
-		let check = &sha[..4];
-		if checksum != check {
-			return Err(TypeError::InvalidAddress);
-		}
+		decode_address(address).map(|(_version, script_hash)| script_hash)
+	}
 
-		let mut rev = [0u8; 20];
-		rev.clone_from_slice(hash);
-		rev.reverse();
-		Ok(Self::from_slice(&rev))
+	fn from_address_checked(address: &str, expected_version: u8) -> Result<Self, TypeError> {
+		let (version, script_hash) = decode_address(address)?;
+		if version != expected_version {
+			return Err(TypeError::AddressVersionMismatch {
+				expected: expected_version,
+				actual: version,
+			});
+		}
+		Ok(script_hash)
 	}
 
 	fn to_address(&self) -> String {
-		let mut data = vec![DEFAULT_ADDRESS_VERSION];
+		self.to_address_with_version(DEFAULT_ADDRESS_VERSION)
+	}
+
+	fn to_address_with_version(&self, version: u8) -> String {
+		let mut data = vec![version];
 		let mut reversed_bytes = self.as_bytes().to_vec();
 		reversed_bytes.reverse();
 		//data.extend_from_slice(&self.as_bytes());
@@ -164,6 +177,22 @@ impl ScriptHashExtension for H160 {
 	}
 }
 
+/// Decodes an address into its version byte and script hash, verifying its checksum.
+fn decode_address(address: &str) -> Result<(u8, H160), TypeError> {
+	let bytes = base58check_decode(address).map_err(|_| TypeError::InvalidAddress)?;
+	if bytes.len() != 21 {
+		return Err(TypeError::InvalidAddress);
+	}
+
+	let version = bytes[0];
+	let hash = &bytes[1..21];
+
+	let mut rev = [0u8; 20];
+	rev.clone_from_slice(hash);
+	rev.reverse();
+	Ok((version, H160::from_slice(&rev)))
+}
+
 #[cfg(test)]
 mod tests {
 	use std::str::FromStr;
@@ -202,6 +231,16 @@ mod tests {
 		H160::from_hex("c56f33fc6ecfcd0c225c4ab356fee59390af8560be0e930faebe74a6daff7c9b").unwrap();
 	}
 
+	#[test]
+	fn test_zero() {
+		let zero = <H160 as ScriptHashExtension>::zero();
+		assert_eq!(zero.to_vec(), vec![0u8; 20]);
+		assert!(ScriptHashExtension::is_zero(&zero));
+
+		let hash = H160::from_str("23ba2703c53263e8d6e522dc32203339dcd8eee9").unwrap();
+		assert!(!ScriptHashExtension::is_zero(&hash));
+	}
+
 	#[test]
 	fn test_to_array() {
 		let hash = H160::from_str("23ba2703c53263e8d6e522dc32203339dcd8eee9").unwrap();
@@ -277,6 +316,33 @@ mod tests {
 	// 	assert_eq!(hash.to_hex(), "0898ea2197378f623a7670974454448576d0aeaf");
 	// }
 
+	#[test]
+	fn test_from_address_checked() {
+		let address = "NeE8xcV4ohHi9rjyj4nPdCYTGyXnWZ79UU";
+		assert_eq!(
+			H160::from_address_checked(address, DEFAULT_ADDRESS_VERSION).unwrap(),
+			H160::from_address(address).unwrap()
+		);
+
+		// The same script hash encoded with the legacy Neo 2 version byte
+		let script_hash = H160::from_address(address).unwrap();
+		let legacy_address = "Aa63RMYRWHPRcrZNzUnq5SNrPqoV866Spu";
+		assert_eq!(script_hash.to_address_with_version(0x17), legacy_address);
+
+		assert_eq!(H160::from_address(legacy_address).unwrap(), script_hash);
+		assert_eq!(
+			H160::from_address_checked(legacy_address, DEFAULT_ADDRESS_VERSION),
+			Err(TypeError::AddressVersionMismatch {
+				expected: DEFAULT_ADDRESS_VERSION,
+				actual: 0x17
+			})
+		);
+		assert_eq!(
+			H160::from_address_checked("NeE8xcV4ohHi9rjy", DEFAULT_ADDRESS_VERSION),
+			Err(TypeError::InvalidAddress)
+		);
+	}
+
 	#[test]
 	fn test_to_address() {
 		let mut script_hash = hex::decode(
@@ -288,5 +354,12 @@ mod tests {
 		let hash = H160::from_hex(&script_hash.to_hex()).unwrap();
 		let address = hash.to_address();
 		assert_eq!(address, "NLnyLtep7jwyq1qhNPkwXbJpurC4jUT8ke".to_string());
+		assert_eq!(hash.to_address_with_version(DEFAULT_ADDRESS_VERSION), address);
+
+		// A private network with its own version byte
+		let private_address = hash.to_address_with_version(0x42);
+		assert_ne!(private_address, address);
+		assert_eq!(H160::from_address_checked(&private_address, 0x42).unwrap(), hash);
+		assert!(H160::from_address_checked(&private_address, DEFAULT_ADDRESS_VERSION).is_err());
 	}
 }
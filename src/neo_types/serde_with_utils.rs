@@ -19,8 +19,8 @@ use serde::{
 
 use neo::prelude::{
 	encode_string_h160, encode_string_h256, encode_string_u256, parse_address, parse_string_h256,
-	parse_string_u256, parse_string_u64, Address, AddressOrScriptHash, ContractParameter,
-	ScriptHash, ScriptHashExtension, Secp256r1PrivateKey, Secp256r1PublicKey, WitnessScope,
+	parse_string_u256, Address, AddressOrScriptHash, ContractParameter, ScriptHash,
+	ScriptHashExtension, Secp256r1PrivateKey, Secp256r1PublicKey, WitnessScope,
 };
 #[cfg(feature = "substrate")]
 use serde_big_array_substrate::big_array;
@@ -70,17 +70,13 @@ where
 	D: Deserializer<'de>,
 {
 	let s: String = Deserialize::deserialize(deserializer)?;
-	// let scopes = s.split(",").map(|x| x.parse().unwrap()).collect::<Vec<WitnessScope>>();
-	let scopes = s
-		.split(",")
+	s.split(",")
 		.map(|x| {
-			x.trim()
-				.parse()
-				.unwrap_or_else(|e| panic!("Failed to parse scope: {}, Error: {}", x, e))
+			x.trim().parse().map_err(|e| {
+				serde::de::Error::custom(format!("Failed to parse scope: {}, Error: {}", x, e))
+			})
 		})
-		.collect::<Vec<WitnessScope>>();
-
-	Ok(scopes)
+		.collect::<Result<Vec<WitnessScope>, _>>()
 }
 
 pub fn serialize_boolean_expression<S>(value: &bool, serializer: S) -> Result<S::Ok, S::Error>
@@ -278,7 +274,11 @@ where
 	D: Deserializer<'de>,
 {
 	let s: String = Deserialize::deserialize(deserializer)?;
-	Ok(parse_string_u64(&s))
+	match s.strip_prefix("0x") {
+		Some(hex) => u64::from_str_radix(hex, 16),
+		None => s.parse(),
+	}
+	.map_err(serde::de::Error::custom)
 }
 
 pub fn deserialize_script_hash<'de, D>(deserializer: D) -> Result<ScriptHash, D::Error>
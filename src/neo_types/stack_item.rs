@@ -4,13 +4,15 @@ use std::{collections::HashMap, fmt};
 /// `StackItem` is a recursive enum that can represent any type of value that can be stored on the stack, including arrays, maps, and custom types.
 /// `MapEntry` is a simple struct that represents a key-value pair in a `StackItem::Map`.
 /// The `StackItem` enum also provides several utility methods for converting between different types and formats.
-use primitive_types::{H160, H256};
+use num_bigint::{BigInt, Sign};
+use num_traits::{ToPrimitive, Zero};
+use primitive_types::{H160, H256, U256};
 use serde::{
 	de::{Unexpected, Visitor},
-	Deserialize, Deserializer, Serialize,
+	Deserialize, Deserializer, Serialize, Serializer,
 };
 
-use neo::prelude::{Address, ScriptHashExtension, Secp256r1PublicKey};
+use neo::prelude::{Address, ScriptHashExtension, Secp256r1PublicKey, TypeError};
 
 /// The `StackItem` enum represents an item on the Neo virtual machine stack.
 #[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
@@ -31,11 +33,12 @@ pub enum StackItem {
 	#[serde(rename = "Boolean")]
 	Boolean { value: bool },
 
-	/// Represents an integer value.
+	/// Represents an integer value, which the NeoVM allows to be up to 256 bits wide.
 	#[serde(rename = "Integer")]
 	Integer {
-		#[serde(deserialize_with = "deserialize_integer_from_string")]
-		value: i64,
+		#[serde(deserialize_with = "deserialize_big_int")]
+		#[serde(serialize_with = "serialize_big_int")]
+		value: BigInt,
 	},
 
 	/// Represents a byte string value.
@@ -115,6 +118,51 @@ where
 	deserializer.deserialize_any(StringOrIntVisitor)
 }
 
+fn deserialize_big_int<'de, D>(deserializer: D) -> Result<BigInt, D::Error>
+where
+	D: Deserializer<'de>,
+{
+	struct BigIntVisitor;
+
+	impl<'de> Visitor<'de> for BigIntVisitor {
+		type Value = BigInt;
+
+		fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+			formatter.write_str("a string or integer")
+		}
+
+		fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+		where
+			E: serde::de::Error,
+		{
+			value.parse::<BigInt>().map_err(serde::de::Error::custom)
+		}
+
+		fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+		where
+			E: serde::de::Error,
+		{
+			Ok(value.into())
+		}
+
+		fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+		where
+			E: serde::de::Error,
+		{
+			Ok(value.into())
+		}
+	}
+
+	deserializer.deserialize_any(BigIntVisitor)
+}
+
+fn serialize_big_int<S>(value: &BigInt, serializer: S) -> Result<S::Ok, S::Error>
+where
+	S: Serializer,
+{
+	serializer.collect_str(value)
+}
+
 /// The `MapEntry` struct represents a key-value pair in a `StackItem::Map`.
 #[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
 pub struct MapEntry {
@@ -192,7 +240,7 @@ impl StackItem {
 	pub fn as_bool(&self) -> Option<bool> {
 		match self {
 			StackItem::Boolean { value } => Some(*value),
-			StackItem::Integer { value } => Some(value != &0),
+			StackItem::Integer { value } => Some(!value.is_zero()),
 			_ => None,
 		}
 	}
@@ -200,8 +248,9 @@ impl StackItem {
 	/// Returns the string value of a `StackItem::ByteString`, `StackItem::Buffer`, `StackItem::Integer`, or `StackItem::Boolean`.
 	pub fn as_string(&self) -> Option<String> {
 		match self {
-			StackItem::ByteString { value } | StackItem::Buffer { value } =>
-				Some(String::from_utf8_lossy(&base64::decode(value).unwrap()).to_string()),
+			StackItem::ByteString { value } | StackItem::Buffer { value } => base64::decode(value)
+				.ok()
+				.map(|bytes| String::from_utf8_lossy(&bytes).to_string()),
 			StackItem::Integer { value } => Some(value.to_string()),
 			StackItem::Boolean { value } => Some(value.to_string()),
 			_ => None,
@@ -249,39 +298,70 @@ impl StackItem {
 	pub fn as_bytes(&self) -> Option<Vec<u8>> {
 		match self {
 			StackItem::ByteString { value } | StackItem::Buffer { value } =>
-			// Some(hex::decode(value).unwrap()),
-				Some(
-					base64::decode(value.trim_end())
-						.expect(&format!("Failed to decode the string: {}", value)),
-				),
-			//Some(value.trim_end().as_bytes().to_vec()),
-			StackItem::Integer { value } => {
-				let mut bytes = value.to_be_bytes().to_vec();
-				bytes.reverse();
-				Some(bytes)
-			},
+				base64::decode(value.trim_end()).ok(),
+			// The NeoVM encodes integers in minimal little-endian two's complement, and zero as
+			// no bytes at all
+			StackItem::Integer { value } if value.is_zero() => Some(vec![]),
+			StackItem::Integer { value } => Some(value.to_signed_bytes_le()),
 			_ => None,
 		}
 	}
 
-	/// Returns the array value of a `StackItem::Array` or `StackItem::Struct`.
-	pub fn as_array(&self) -> Option<Vec<StackItem>> {
+	/// Returns the items of a `StackItem::Array` or `StackItem::Struct`.
+	///
+	/// Nested items can be reached by chaining, e.g. `item.as_array()?[1].as_array()?[0]` for the
+	/// first field of the second struct in an array of structs.
+	pub fn as_array(&self) -> Option<&[StackItem]> {
 		match self {
-			StackItem::Array { value } | StackItem::Struct { value } => Some(value.clone()),
+			StackItem::Array { value } | StackItem::Struct { value } => Some(value),
 			_ => None,
 		}
 	}
 
-	/// Returns the integer value of a `StackItem::Integer` or `StackItem::Boolean`.
-	pub fn as_int(&self) -> Option<i64> {
+	/// Converts the items of a `StackItem::Array` or `StackItem::Struct` to `T`, failing if the
+	/// item is not an array or any of its items cannot be converted.
+	pub fn try_as_vec<T>(&self) -> Result<Vec<T>, TypeError>
+	where
+		T: for<'a> TryFrom<&'a StackItem, Error = TypeError>,
+	{
+		self.as_array()
+			.ok_or_else(|| self.unexpected("an array"))?
+			.iter()
+			.map(T::try_from)
+			.collect()
+	}
+
+	/// Returns the integer value of a `StackItem::Integer` or `StackItem::Boolean`, failing with
+	/// [`TypeError::NumericOverflow`] if the integer does not fit into an `i64`. Use
+	/// [`Self::as_i256`] for wider integers.
+	pub fn as_int(&self) -> Result<i64, TypeError> {
 		match self {
-			StackItem::Integer { value } => Some(*value),
-			StackItem::Boolean { value } => Some(if *value { 1 } else { 0 }),
-			StackItem::Pointer { value } => Some(*value),
-			_ => None,
+			StackItem::Integer { value } => value.to_i64().ok_or(TypeError::NumericOverflow),
+			StackItem::Boolean { value } => Ok(if *value { 1 } else { 0 }),
+			StackItem::Pointer { value } => Ok(*value),
+			_ => Err(self.unexpected("an integer")),
+		}
+	}
+
+	/// Returns the full integer value of a `StackItem::Integer` or `StackItem::Boolean`.
+	pub fn as_i256(&self) -> Result<BigInt, TypeError> {
+		match self {
+			StackItem::Integer { value } => Ok(value.clone()),
+			StackItem::Boolean { value } => Ok(BigInt::from(*value as u8)),
+			_ => Err(self.unexpected("an integer")),
 		}
 	}
 
+	/// Returns the integer value of a `StackItem::Integer` or `StackItem::Boolean` as a `U256`,
+	/// failing for negative integers.
+	pub fn as_u256(&self) -> Result<U256, TypeError> {
+		let (sign, bytes) = self.as_i256()?.to_bytes_be();
+		if sign == Sign::Minus || bytes.len() > 32 {
+			return Err(TypeError::NumericOverflow);
+		}
+		Ok(U256::from_big_endian(&bytes))
+	}
+
 	/// Returns the map value of a `StackItem::Map`.
 	pub fn as_map(&self) -> Option<HashMap<StackItem, StackItem>> {
 		match self {
@@ -296,11 +376,23 @@ impl StackItem {
 		}
 	}
 
-	/// Returns the `Address` value of a `StackItem::ByteString` or `StackItem::Buffer`.
+	/// Returns the value of the `StackItem::Map` entry whose key is the given string.
+	pub fn get_map_value(&self, key: &str) -> Option<&StackItem> {
+		match self {
+			StackItem::Map { value } => value
+				.iter()
+				.find(|entry| entry.key.as_bytes().as_deref() == Some(key.as_bytes()))
+				.map(|entry| &entry.value),
+			_ => None,
+		}
+	}
+
+	/// Returns the Neo address of the little-endian script hash in a `StackItem::ByteString` or
+	/// `StackItem::Buffer`, or `None` if it does not hold exactly 20 bytes.
 	pub fn as_address(&self) -> Option<Address> {
-		self.as_bytes().and_then(|mut bytes| {
+		self.as_bytes().filter(|bytes| bytes.len() == 20).map(|mut bytes| {
 			bytes.reverse();
-			Some(H160::from_slice(&bytes).to_address())
+			H160::from_slice(&bytes).to_address()
 		})
 	}
 
@@ -309,14 +401,18 @@ impl StackItem {
 		self.as_bytes().and_then(|bytes| Secp256r1PublicKey::from_bytes(&bytes).ok())
 	}
 
-	/// Returns the `H160` value of a `StackItem::ByteString` or `StackItem::Buffer`.
+	/// Returns the `H160` value of a `StackItem::ByteString` or `StackItem::Buffer` of 20 bytes.
 	pub fn as_hash160(&self) -> Option<H160> {
-		self.as_bytes().and_then(|bytes| Some(H160::from_slice(&bytes)))
+		self.as_bytes()
+			.filter(|bytes| bytes.len() == 20)
+			.map(|bytes| H160::from_slice(&bytes))
 	}
 
-	/// Returns the `H256` value of a `StackItem::ByteString` or `StackItem::Buffer`.
+	/// Returns the `H256` value of a `StackItem::ByteString` or `StackItem::Buffer` of 32 bytes.
 	pub fn as_hash256(&self) -> Option<H256> {
-		self.as_bytes().and_then(|bytes| Some(H256::from_slice(&bytes)))
+		self.as_bytes()
+			.filter(|bytes| bytes.len() == 32)
+			.map(|bytes| H256::from_slice(&bytes))
 	}
 
 	pub fn as_interop(&self, interface_name: &str) -> Option<StackItem> {
@@ -371,6 +467,10 @@ impl StackItem {
 			None
 		}
 	}
+
+	fn unexpected(&self, expected: &str) -> TypeError {
+		TypeError::UnexpectedReturnType(format!("Expected {expected}, got {}", self.to_string()))
+	}
 }
 
 impl From<String> for StackItem {
@@ -387,47 +487,226 @@ impl From<H160> for StackItem {
 
 impl From<u8> for StackItem {
 	fn from(value: u8) -> Self {
-		StackItem::Integer { value: value as i64 }
+		StackItem::Integer { value: value.into() }
 	}
 }
 
 impl From<i8> for StackItem {
 	fn from(value: i8) -> Self {
-		StackItem::Integer { value: value as i64 }
+		StackItem::Integer { value: value.into() }
 	}
 }
 
 impl From<u16> for StackItem {
 	fn from(value: u16) -> Self {
-		StackItem::Integer { value: value as i64 }
+		StackItem::Integer { value: value.into() }
 	}
 }
 
 impl From<i16> for StackItem {
 	fn from(value: i16) -> Self {
-		StackItem::Integer { value: value as i64 }
+		StackItem::Integer { value: value.into() }
 	}
 }
 
 impl From<u32> for StackItem {
 	fn from(value: u32) -> Self {
-		StackItem::Integer { value: value as i64 }
+		StackItem::Integer { value: value.into() }
 	}
 }
 
 impl From<i32> for StackItem {
 	fn from(value: i32) -> Self {
-		StackItem::Integer { value: value as i64 }
+		StackItem::Integer { value: value.into() }
 	}
 }
 
 impl From<u64> for StackItem {
 	fn from(value: u64) -> Self {
-		StackItem::Integer { value: value as i64 }
+		StackItem::Integer { value: value.into() }
+	}
+}
+
+impl From<BigInt> for StackItem {
+	fn from(value: BigInt) -> Self {
+		StackItem::Integer { value }
 	}
 }
+
 impl From<&str> for StackItem {
 	fn from(value: &str) -> Self {
 		StackItem::ByteString { value: value.to_string() }
 	}
 }
+
+impl TryFrom<&StackItem> for bool {
+	type Error = TypeError;
+
+	fn try_from(item: &StackItem) -> Result<Self, Self::Error> {
+		item.as_bool().ok_or_else(|| item.unexpected("a boolean"))
+	}
+}
+
+impl TryFrom<&StackItem> for BigInt {
+	type Error = TypeError;
+
+	fn try_from(item: &StackItem) -> Result<Self, Self::Error> {
+		item.as_i256()
+	}
+}
+
+impl TryFrom<&StackItem> for U256 {
+	type Error = TypeError;
+
+	fn try_from(item: &StackItem) -> Result<Self, Self::Error> {
+		item.as_u256()
+	}
+}
+
+/// Implements `TryFrom<&StackItem>` for primitive integers, failing if the value does not fit.
+macro_rules! try_from_stack_item_for_int {
+	($($int:ty),*) => {
+		$(
+			impl TryFrom<&StackItem> for $int {
+				type Error = TypeError;
+
+				fn try_from(item: &StackItem) -> Result<Self, Self::Error> {
+					<$int>::try_from(&item.as_i256()?).map_err(|_| TypeError::NumericOverflow)
+				}
+			}
+		)*
+	};
+}
+
+try_from_stack_item_for_int!(u8, i32, u32, i64, u64, i128, u128);
+
+impl TryFrom<&StackItem> for String {
+	type Error = TypeError;
+
+	fn try_from(item: &StackItem) -> Result<Self, Self::Error> {
+		item.as_string().ok_or_else(|| item.unexpected("a string"))
+	}
+}
+
+impl TryFrom<&StackItem> for Vec<u8> {
+	type Error = TypeError;
+
+	fn try_from(item: &StackItem) -> Result<Self, Self::Error> {
+		item.as_bytes().ok_or_else(|| item.unexpected("a byte string"))
+	}
+}
+
+/// Reads the 20 bytes as they are, like [`StackItem::as_hash160`].
+impl TryFrom<&StackItem> for H160 {
+	type Error = TypeError;
+
+	fn try_from(item: &StackItem) -> Result<Self, Self::Error> {
+		item.as_hash160().ok_or_else(|| item.unexpected("a 20-byte hash"))
+	}
+}
+
+impl TryFrom<&StackItem> for H256 {
+	type Error = TypeError;
+
+	fn try_from(item: &StackItem) -> Result<Self, Self::Error> {
+		item.as_hash256().ok_or_else(|| item.unexpected("a 32-byte hash"))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use num_bigint::BigInt;
+	use primitive_types::{H160, H256, U256};
+	use serde_json::json;
+
+	use neo::prelude::{StackItem, TypeError};
+
+	#[test]
+	fn test_integers_wider_than_i64() {
+		// 2^70, e.g. the total supply of a token with 18 decimals
+		let item: StackItem =
+			serde_json::from_value(json!({ "type": "Integer", "value": "1180591620717411303424" }))
+				.unwrap();
+
+		assert_eq!(item.as_int(), Err(TypeError::NumericOverflow));
+		assert_eq!(item.as_i256().unwrap(), BigInt::from(1u128 << 70));
+		assert_eq!(item.as_u256().unwrap(), U256::from(1u128 << 70));
+		assert_eq!(
+			serde_json::to_value(&item).unwrap(),
+			json!({ "type": "Integer", "value": "1180591620717411303424" })
+		);
+	}
+
+	#[test]
+	fn test_integer_accessors() {
+		let item = StackItem::from(-5i32);
+		assert_eq!(item.as_int(), Ok(-5));
+		assert_eq!(item.as_i256().unwrap(), BigInt::from(-5));
+		assert_eq!(item.as_u256(), Err(TypeError::NumericOverflow));
+		assert_eq!(item.as_bytes(), Some(vec![0xfb]));
+
+		assert_eq!(StackItem::from(u64::MAX).as_int(), Err(TypeError::NumericOverflow));
+		assert!(matches!(StackItem::Any.as_int(), Err(TypeError::UnexpectedReturnType(_))));
+		assert_eq!(StackItem::from(u64::MAX).as_u256().unwrap(), U256::from(u64::MAX));
+		assert_eq!(StackItem::from(0u8).as_bytes(), Some(vec![]));
+		assert!(StackItem::Any.as_i256().is_err());
+	}
+
+	#[test]
+	fn test_try_from() {
+		assert_eq!(u8::try_from(&StackItem::from(200u8)), Ok(200));
+		assert_eq!(u8::try_from(&StackItem::from(300u32)), Err(TypeError::NumericOverflow));
+		assert_eq!(u64::try_from(&StackItem::from(-1i32)), Err(TypeError::NumericOverflow));
+		assert_eq!(i64::try_from(&StackItem::Boolean { value: true }), Ok(1));
+		assert_eq!(bool::try_from(&StackItem::from(0u8)), Ok(false));
+		assert!(matches!(bool::try_from(&StackItem::Any), Err(TypeError::UnexpectedReturnType(_))));
+
+		let item = StackItem::new_byte_string(b"neo".to_vec());
+		assert_eq!(String::try_from(&item).unwrap(), "neo");
+		assert_eq!(Vec::<u8>::try_from(&item).unwrap(), b"neo".to_vec());
+		// Hashes need the exact number of bytes
+		assert!(H160::try_from(&item).is_err());
+		assert_eq!(item.as_address(), None);
+
+		let item = StackItem::new_byte_string(vec![1; 20]);
+		assert_eq!(H160::try_from(&item).unwrap(), H160::repeat_byte(1));
+		assert!(H256::try_from(&item).is_err());
+
+		// Invalid base64 is not a byte string
+		assert_eq!(StackItem::ByteString { value: "!".to_string() }.as_bytes(), None);
+	}
+
+	#[test]
+	fn test_nested_items() {
+		let item: StackItem = serde_json::from_value(json!({
+			"type": "Array",
+			"value": [
+				{ "type": "Struct", "value": [
+					{ "type": "Integer", "value": "7" },
+					{ "type": "Map", "value": [{
+						"key": { "type": "ByteString", "value": "bmFtZQ==" },
+						"value": { "type": "ByteString", "value": "bmVv" }
+					}] }
+				] },
+				{ "type": "Struct", "value": [{ "type": "Integer", "value": "8" }] }
+			]
+		}))
+		.unwrap();
+
+		let structs = item.as_array().unwrap();
+		assert_eq!(structs.len(), 2);
+		assert_eq!(structs[0].as_array().unwrap()[0].as_int(), Ok(7));
+		let map = &structs[0].as_array().unwrap()[1];
+		assert_eq!(map.get_map_value("name").and_then(StackItem::as_string), Some("neo".into()));
+		assert_eq!(map.get_map_value("symbol"), None);
+
+		let first_fields =
+			structs.iter().map(|s| s.as_array().unwrap()[0].clone()).collect::<Vec<_>>();
+		assert_eq!(
+			StackItem::Array { value: first_fields }.try_as_vec::<u32>().unwrap(),
+			vec![7, 8]
+		);
+		assert!(item.try_as_vec::<u32>().is_err());
+		assert!(StackItem::from(1u8).try_as_vec::<u32>().is_err());
+	}
+}
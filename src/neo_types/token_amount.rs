@@ -0,0 +1,95 @@
+use num_bigint::{BigInt, Sign};
+
+/// Formats an amount given in the smallest unit of a token as a decimal string, e.g. `123456789`
+/// with 8 decimals becomes `"1.23456789"`. Trailing zeros of the fractional part are dropped.
+///
+/// The decimals differ per token (GAS has 8, NEO has 0), so always pass the value returned by the
+/// token's `decimals` method rather than assuming 8.
+pub fn format_token_amount(amount: u64, decimals: u8) -> String {
+	format_digits(amount.to_string(), decimals)
+}
+
+/// Like [`format_token_amount`], but for amounts that do not fit in a `u64`, such as GAS on
+/// Neo X with 18 decimals. Works for any number of decimals without overflowing.
+pub fn format_token_amount_u128(amount: u128, decimals: u8) -> String {
+	format_digits(amount.to_string(), decimals)
+}
+
+/// Like [`format_token_amount`], for token amounts read from the chain, which are
+/// arbitrary-precision integers.
+pub fn format_token_amount_bigint(amount: &BigInt, decimals: u8) -> String {
+	let formatted = format_digits(amount.magnitude().to_string(), decimals);
+	if amount.sign() == Sign::Minus {
+		format!("-{}", formatted)
+	} else {
+		formatted
+	}
+}
+
+/// Inserts the decimal point into the decimal digits of an amount, so no power of ten has to be
+/// computed.
+fn format_digits(digits: String, decimals: u8) -> String {
+	let decimals = decimals as usize;
+	if decimals == 0 {
+		return digits;
+	}
+
+	let padded = format!("{:0>width$}", digits, width = decimals + 1);
+	let (integer, fraction) = padded.split_at(padded.len() - decimals);
+	let fraction = fraction.trim_end_matches('0');
+	if fraction.is_empty() {
+		integer.to_string()
+	} else {
+		format!("{}.{}", integer, fraction)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_format_token_amount() {
+		assert_eq!(format_token_amount(123456789, 8), "1.23456789");
+		assert_eq!(format_token_amount(100000000, 8), "1");
+		assert_eq!(format_token_amount(1, 8), "0.00000001");
+		assert_eq!(format_token_amount(0, 8), "0");
+		assert_eq!(format_token_amount(42, 0), "42");
+	}
+
+	#[test]
+	fn test_format_token_amount_six_decimals() {
+		assert_eq!(format_token_amount(1234567, 6), "1.234567");
+		assert_eq!(format_token_amount(2500000, 6), "2.5");
+		assert_ne!(format_token_amount(1234567, 6), format_token_amount(1234567, 8));
+	}
+
+	#[test]
+	fn test_format_token_amount_u128_eighteen_decimals() {
+		assert_eq!(format_token_amount_u128(1_500_000_000_000_000_000, 18), "1.5");
+		assert_eq!(format_token_amount_u128(1, 18), "0.000000000000000001");
+		assert_eq!(
+			format_token_amount_u128(u128::MAX, 18),
+			"340282366920938463463.374607431768211455"
+		);
+	}
+
+	#[test]
+	fn test_format_token_amount_high_decimals() {
+		// 10^20 does not fit in a u64.
+		assert_eq!(format_token_amount_u128(123, 20), "0.00000000000000000123");
+		assert_eq!(format_token_amount(u64::MAX, 24), "0.000018446744073709551615");
+		assert_eq!(format_token_amount_u128(10u128.pow(30), 30), "1");
+	}
+
+	#[test]
+	fn test_format_token_amount_bigint() {
+		let amount = BigInt::from(u128::MAX) * 10;
+		assert_eq!(
+			format_token_amount_bigint(&amount, 2),
+			"34028236692093846346337460743176821145.5"
+		);
+		assert_eq!(format_token_amount_bigint(&BigInt::from(-150), 2), "-1.5");
+		assert_eq!(format_token_amount_bigint(&BigInt::from(0), 8), "0");
+	}
+}
@@ -145,6 +145,40 @@ pub fn encode_string_h256(h256: &H256) -> String {
 	format!("{:?}", h256).to_owned()
 }
 
+/// Parses a block or transaction hash as returned by the node, e.g. by `getblockhash`.
+///
+/// Hashes are kept as `H256` in the order the node displays them, which is the reverse of the
+/// little-endian order they are serialized in. Unlike [`parse_string_h256`], the hash must be
+/// exactly 32 bytes long and is not padded.
+///
+/// # Examples
+///
+/// ```
+/// use NeoRust::prelude::{h256_from_rpc_string, h256_to_rpc_string};
+/// let rpc = "0x1f4d1defa46faa5e7b9b8d3f79a06bec777d7c26c4aa5f6f5899a291daa87c15";
+/// let hash = h256_from_rpc_string(rpc).unwrap();
+/// assert_eq!(hash.as_bytes()[0], 0x1f);
+/// assert_eq!(h256_to_rpc_string(&hash), rpc);
+/// ```
+pub fn h256_from_rpc_string(hash: &str) -> Result<H256, TypeError> {
+	let bytes = hex::decode(hash.trim_start_matches("0x"))
+		.map_err(|e| TypeError::InvalidEncoding(e.to_string()))?;
+	if bytes.len() != H256::len_bytes() {
+		return Err(TypeError::InvalidData(format!(
+			"A hash must be {} bytes long, got {}",
+			H256::len_bytes(),
+			bytes.len()
+		)));
+	}
+	Ok(H256::from_slice(&bytes))
+}
+
+/// Formats a block or transaction hash the way the node expects it in RPC parameters, i.e. as a
+/// "0x" prefixed hex string in display order. The inverse of [`h256_from_rpc_string`].
+pub fn h256_to_rpc_string(hash: &H256) -> String {
+	format!("0x{}", hex::encode(hash.as_bytes()))
+}
+
 /// Encodes a `U256` value into a hexadecimal string prefixed with "0x".
 ///
 /// # Examples
@@ -373,6 +407,8 @@ impl ToBase64 for [u8] {
 
 #[cfg(test)]
 mod test {
+	use hex_literal::hex;
+
 	use super::*;
 
 	// #[test]
@@ -397,4 +433,28 @@ mod test {
 		let ok_mybytes = string_to_bytes(orig_bytestring).unwrap();
 		assert_eq!(&mybytes[..], &ok_mybytes[..]);
 	}
+
+	#[test]
+	pub fn test_h256_rpc_string_round_trip() {
+		// Hash of the Neo N3 MainNet genesis block
+		let rpc = "0x1f4d1defa46faa5e7b9b8d3f79a06bec777d7c26c4aa5f6f5899a291daa87c15";
+		let hash = h256_from_rpc_string(rpc).unwrap();
+
+		assert_eq!(h256_to_rpc_string(&hash), rpc);
+		assert_eq!(hash, parse_string_h256(rpc));
+		assert_eq!(h256_from_rpc_string(&rpc[2..]).unwrap(), hash);
+
+		// The hash is kept in display order, not in the little-endian order it is serialized in
+		assert_eq!(
+			hash.as_bytes(),
+			hex!("1f4d1defa46faa5e7b9b8d3f79a06bec777d7c26c4aa5f6f5899a291daa87c15")
+		);
+		assert_ne!(
+			hash.as_bytes(),
+			hex!("157ca8da91a299586f5faac4267c7d77ec6ba0793f8d9b7b5eaa6fa4ef1d4d1f")
+		);
+
+		assert!(h256_from_rpc_string("0x1f4d").is_err());
+		assert!(h256_from_rpc_string("0xzz").is_err());
+	}
 }
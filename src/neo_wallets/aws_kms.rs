@@ -0,0 +1,239 @@
+//! Signing with secp256r1 keys held by AWS KMS
+use std::fmt;
+
+use once_cell::sync::OnceCell;
+
+use p256::{
+	ecdsa::Signature, elliptic_curve::sec1::ToEncodedPoint, pkcs8::DecodePublicKey, PublicKey,
+};
+use primitive_types::{H160, H256};
+use rusoto_kms::{GetPublicKeyRequest, Kms, KmsClient, SignRequest};
+use signature::hazmat::PrehashSigner;
+use tokio::runtime::Runtime;
+
+use neo::prelude::{
+	public_key_to_address, Address, HashableForVec, InvocationScript, JsonRpcProvider,
+	Secp256r1PublicKey, Secp256r1Signature, Transaction, VerificationScript, WalletError,
+	WalletSigner, Witness,
+};
+
+/// A signer whose private key never leaves AWS KMS.
+///
+/// The key must be an asymmetric `ECC_NIST_P256` key with the `SIGN_VERIFY` usage. Signing calls
+/// `kms:Sign` with `ECDSA_SHA_256`, and the DER signature KMS returns is converted to Neo's
+/// 64-byte format. The public key is fetched once, when the signer is created.
+///
+/// It implements [`PrehashSigner`], so it can back a [`WalletSigner`] like a local key.
+pub struct AwsKmsSigner {
+	kms: KmsClient,
+	key_id: String,
+	public_key: Secp256r1PublicKey,
+	address: Address,
+}
+
+impl AwsKmsSigner {
+	const SIGNING_ALGORITHM: &'static str = "ECDSA_SHA_256";
+
+	/// Creates a signer for the KMS key `key_id`, which may be a key id, key ARN, alias name or
+	/// alias ARN, and fetches its public key.
+	pub async fn new(kms: KmsClient, key_id: impl Into<String>) -> Result<Self, WalletError> {
+		let key_id = key_id.into();
+		let response = kms
+			.get_public_key(GetPublicKeyRequest { key_id: key_id.clone(), ..Default::default() })
+			.await
+			.map_err(|e| WalletError::KmsError(e.to_string()))?;
+		let public_key = response
+			.public_key
+			.ok_or(WalletError::KmsError("KMS returned no public key".to_string()))?;
+		let public_key = public_key_from_der(&public_key)?;
+		let address = public_key_to_address(&public_key);
+		Ok(Self { kms, key_id, public_key, address })
+	}
+
+	/// Signs a given hash directly, without performing any additional hashing.
+	pub async fn sign_hash(&self, hash: H256) -> Result<Signature, WalletError> {
+		self.sign(hash.as_bytes().to_vec(), "DIGEST").await
+	}
+
+	/// Signs a given message. The message is hashed the same way as by
+	/// [`WalletSigner::sign_message`](crate::prelude::WalletSigner) before being signed.
+	pub async fn sign_message(&self, message: &[u8]) -> Result<Signature, WalletError> {
+		self.sign_hash(H256::from_slice(&message.hash256())).await
+	}
+
+	/// Signs the hash data of `tx`, i.e. its network magic and hash, as a Neo witness signs it.
+	pub async fn sign_transaction<'a, P: JsonRpcProvider + 'static>(
+		&self,
+		tx: &Transaction<'a, P>,
+	) -> Result<Signature, WalletError> {
+		// KMS hashes the raw message with SHA-256, as a local key pair does before signing
+		self.sign(tx.get_hash_data().await?, "RAW").await
+	}
+
+	/// Signs `tx` and returns the witness of the key's single-signature account.
+	pub async fn get_witness<'a, P: JsonRpcProvider + 'static>(
+		&self,
+		tx: &Transaction<'a, P>,
+	) -> Result<Witness, WalletError> {
+		let signature = self.sign_transaction(tx).await?;
+		let signature = Secp256r1Signature::from_bytes(&signature.to_bytes())?;
+		Ok(Witness::from_scripts_obj(
+			InvocationScript::from_signature(signature),
+			VerificationScript::from_public_key(&self.public_key),
+		))
+	}
+
+	/// Returns the public key of the KMS key.
+	pub fn public_key(&self) -> &Secp256r1PublicKey {
+		&self.public_key
+	}
+
+	/// Returns the address of the key's single-signature account.
+	pub fn address(&self) -> Address {
+		self.address.clone()
+	}
+
+	/// Returns the script hash of the key's single-signature account.
+	pub fn script_hash(&self) -> H160 {
+		VerificationScript::from_public_key(&self.public_key).hash()
+	}
+
+	async fn sign(&self, message: Vec<u8>, message_type: &str) -> Result<Signature, WalletError> {
+		kms_sign(&self.kms, self.sign_request(message, message_type)).await
+	}
+
+	fn sign_request(&self, message: Vec<u8>, message_type: &str) -> SignRequest {
+		SignRequest {
+			key_id: self.key_id.clone(),
+			message: message.into(),
+			message_type: Some(message_type.to_string()),
+			signing_algorithm: Self::SIGNING_ALGORITHM.to_string(),
+			..Default::default()
+		}
+	}
+}
+
+impl PrehashSigner<Signature> for AwsKmsSigner {
+	/// Signs the SHA-256 digest `prehash` with `kms:Sign`, blocking until KMS answers. The request
+	/// runs on a runtime shared by all signers, so this can also be called from async code.
+	fn sign_prehash(&self, prehash: &[u8]) -> Result<Signature, signature::Error> {
+		let kms = self.kms.clone();
+		let request = self.sign_request(prehash.to_vec(), "DIGEST");
+		let runtime = kms_runtime().map_err(|_| signature::Error::new())?;
+		let sign = runtime.spawn(async move { kms_sign(&kms, request).await });
+		futures_executor::block_on(sign)
+			.map_err(|_| signature::Error::new())?
+			.map_err(|_| signature::Error::new())
+	}
+}
+
+impl From<AwsKmsSigner> for WalletSigner<AwsKmsSigner> {
+	fn from(signer: AwsKmsSigner) -> Self {
+		let address = signer.address();
+		WalletSigner::new_with_signer(signer, address)
+	}
+}
+
+// do not log the client
+impl fmt::Debug for AwsKmsSigner {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("AwsKmsSigner")
+			.field("key_id", &self.key_id)
+			.field("address", &self.address)
+			.finish()
+	}
+}
+
+/// The runtime the blocking [`PrehashSigner::sign_prehash`] sends its requests on, created on
+/// first use.
+static KMS_RUNTIME: OnceCell<Runtime> = OnceCell::new();
+
+fn kms_runtime() -> Result<&'static Runtime, WalletError> {
+	KMS_RUNTIME.get_or_try_init(|| {
+		tokio::runtime::Builder::new_multi_thread()
+			.worker_threads(1)
+			.thread_name("aws-kms-signer")
+			.enable_all()
+			.build()
+			.map_err(|e| WalletError::KmsError(e.to_string()))
+	})
+}
+
+async fn kms_sign(kms: &KmsClient, request: SignRequest) -> Result<Signature, WalletError> {
+	let response = kms.sign(request).await.map_err(|e| WalletError::KmsError(e.to_string()))?;
+	let signature = response
+		.signature
+		.ok_or(WalletError::KmsError("KMS returned no signature".to_string()))?;
+	signature_from_der(&signature)
+}
+
+/// Reads the DER encoded `SubjectPublicKeyInfo` returned by `kms:GetPublicKey`.
+fn public_key_from_der(der: &[u8]) -> Result<Secp256r1PublicKey, WalletError> {
+	let public_key = PublicKey::from_public_key_der(der).map_err(|e| {
+		WalletError::KmsError(format!("The KMS key is not a secp256r1 public key: {e}"))
+	})?;
+	Ok(Secp256r1PublicKey::from_bytes(public_key.to_encoded_point(true).as_bytes())?)
+}
+
+/// Converts the DER signature returned by `kms:Sign` to its 64-byte `r || s` form. `s` is
+/// normalized to the lower half of the curve order.
+fn signature_from_der(der: &[u8]) -> Result<Signature, WalletError> {
+	let signature = Signature::from_der(der)
+		.map_err(|e| WalletError::KmsError(format!("Invalid signature from KMS: {e}")))?;
+	Ok(signature.normalize_s().unwrap_or(signature))
+}
+
+#[cfg(test)]
+mod tests {
+	use p256::{
+		ecdsa::{signature::hazmat::PrehashVerifier, SigningKey, VerifyingKey},
+		pkcs8::EncodePublicKey,
+	};
+	use rand::rngs::OsRng;
+
+	use super::*;
+
+	#[test]
+	fn test_backs_wallet_signer() {
+		fn assert_wallet_signer<D: PrehashSigner<Signature> + Send + Sync>()
+		where
+			WalletSigner<D>: From<D>,
+		{
+		}
+		assert_wallet_signer::<AwsKmsSigner>();
+	}
+
+	#[tokio::test]
+	async fn test_kms_runtime_is_shared() {
+		let runtime = kms_runtime().unwrap();
+		assert!(std::ptr::eq(runtime, kms_runtime().unwrap()));
+		assert_eq!(runtime.spawn(async { 1 + 1 }).await.unwrap(), 2);
+	}
+
+	#[test]
+	fn test_public_key_from_der() {
+		let signing_key = SigningKey::random(&mut OsRng);
+		let der = signing_key.verifying_key().to_public_key_der().unwrap();
+
+		let public_key = public_key_from_der(der.as_bytes()).unwrap();
+		assert_eq!(
+			public_key.get_encoded(true),
+			signing_key.verifying_key().to_encoded_point(true).as_bytes()
+		);
+		assert!(matches!(public_key_from_der(&[0x30, 0x00]), Err(WalletError::KmsError(_))));
+	}
+
+	#[test]
+	fn test_signature_from_der() {
+		let signing_key = SigningKey::random(&mut OsRng);
+		let hash = [7u8; 32];
+		let signature: Signature = signing_key.sign_prehash(&hash).unwrap();
+
+		let converted = signature_from_der(signature.to_der().as_bytes()).unwrap();
+		assert_eq!(converted.to_bytes().len(), 64);
+		assert!(converted.normalize_s().is_none());
+		VerifyingKey::from(&signing_key).verify_prehash(&hash, &converted).unwrap();
+
+		assert!(matches!(signature_from_der(&[0x30, 0x01, 0x00]), Err(WalletError::KmsError(_))));
+	}
+}
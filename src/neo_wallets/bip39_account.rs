@@ -1,6 +1,21 @@
+use std::fmt;
+
 use bip39::{Mnemonic, Language};
+use elliptic_curve::zeroize::Zeroizing;
+use p256::{
+    elliptic_curve::ff::{Field, PrimeField},
+    FieldBytes, Scalar,
+};
 use sha2::{Sha256, Digest};
-use crate::prelude::{Account, AccountTrait, KeyPair};
+use crate::prelude::{
+    Account, AccountTrait, HashableForVec, KeyPair, Secp256r1PrivateKey, WalletError,
+};
+
+/// HMAC key from which SLIP-10 derives the master key of a seed on the NIST P-256 curve
+const MASTER_KEY_SECRET: &[u8] = b"Nist256p1 seed";
+
+/// Child indices from this value up are hardened
+const HARDENED_OFFSET: u32 = 0x8000_0000;
 
 /// A BIP-39 compatible neo account that uses mnemonic phrases for key generation and recovery.
 ///
@@ -31,13 +46,26 @@ use crate::prelude::{Account, AccountTrait, KeyPair};
 /// let password = "my secure password";
 /// let recovered = Bip39Account::from_bip39_mnemonic(password, mnemonic).unwrap();
 /// ```
-#[derive(Debug)]
 pub struct Bip39Account {
     /// The underlying neo account
     account: Account,
     
     /// Generated BIP-39 mnemonic for the account
     mnemonic: String,
+
+    /// BIP-39 seed of the mnemonic and password, from which accounts are derived
+    seed: Zeroizing<Vec<u8>>,
+}
+
+/// Shows the account, but neither the mnemonic nor the seed it derives keys from.
+impl fmt::Debug for Bip39Account {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Bip39Account")
+            .field("account", &self.account)
+            .field("mnemonic", &"<redacted>")
+            .field("seed", &"<redacted>")
+            .finish()
+    }
 }
 
 impl Bip39Account {
@@ -78,6 +106,7 @@ impl Bip39Account {
         Ok(Self {
             account,
             mnemonic: mnemonic.to_string(),
+            seed: Zeroizing::new(seed.to_vec()),
         })
     }
 
@@ -115,10 +144,126 @@ impl Bip39Account {
         Ok(Self {
             account,
             mnemonic: mnemonic.to_string(),
+            seed: Zeroizing::new(seed.to_vec()),
+        })
+    }
+
+    /// Derives the account at the BIP-32 derivation `path` from the seed of this account, e.g.
+    /// `m/44'/888'/0'/0/0` for the first account of Neo's SLIP-44 coin type 888.
+    ///
+    /// Components ending in `'` or `h` are hardened. As Neo keys are on the NIST P-256 curve,
+    /// keys are derived as specified by SLIP-10 for that curve, like hardware wallets do.
+    /// The returned account shares the mnemonic and seed of this account.
+    ///
+    /// # Errors
+    /// Returns [`WalletError::InvalidDerivationPath`] if `path` is not a valid derivation path.
+    ///
+    /// # Example
+    /// ```
+    /// use neo_rust::prelude::Bip39Account;
+    ///
+    /// let account = Bip39Account::create("my secure password").unwrap();
+    /// let first = account.derive("m/44'/888'/0'/0/0").unwrap();
+    /// let second = account.derive("m/44'/888'/0'/0/1").unwrap();
+    /// ```
+    pub fn derive(&self, path: &str) -> Result<Self, WalletError> {
+        let private_key = derive_private_key(&self.seed, path)?;
+        let account = Account::from_key_pair(KeyPair::from_secret_key(&private_key), None, None)
+            .map_err(|e| WalletError::AccountState(e.to_string()))?;
+
+        Ok(Self {
+            account,
+            mnemonic: self.mnemonic.clone(),
+            seed: self.seed.clone(),
         })
     }
 }
 
+/// Derives the private key at the BIP-32 derivation `path` from `seed`, following SLIP-10 for
+/// the NIST P-256 curve.
+fn derive_private_key(seed: &[u8], path: &str) -> Result<Secp256r1PrivateKey, WalletError> {
+    let indices = parse_derivation_path(path)?;
+
+    // The digest is hashed again for the rare seeds that do not give a valid master key
+    let mut digest = seed.hmac_sha512(MASTER_KEY_SECRET);
+    let (mut key, mut chain_code) = loop {
+        if let Some(key) = to_nonzero_scalar(&digest[..32]) {
+            break (key, digest[32..].to_vec());
+        }
+        digest = digest.hmac_sha512(MASTER_KEY_SECRET);
+    };
+
+    for index in indices {
+        let mut data = if index >= HARDENED_OFFSET {
+            let mut data = vec![0u8];
+            data.extend_from_slice(&key.to_repr());
+            data
+        } else {
+            private_key_from_scalar(&key)?.to_public_key().get_encoded(true)
+        };
+        data.extend_from_slice(&index.to_be_bytes());
+
+        // If a child key is invalid, which is negligibly rare, the next candidate is derived
+        // from the right half of the digest
+        (key, chain_code) = loop {
+            let digest = data.hmac_sha512(&chain_code);
+            let child = to_scalar(&digest[..32])
+                .map(|tweak| tweak + key)
+                .filter(|child| !bool::from(child.is_zero()));
+            if let Some(child) = child {
+                break (child, digest[32..].to_vec());
+            }
+            data = vec![1u8];
+            data.extend_from_slice(&digest[32..]);
+            data.extend_from_slice(&index.to_be_bytes());
+        };
+    }
+
+    private_key_from_scalar(&key)
+}
+
+/// Parses a derivation path like `m/44'/888'/0'/0/0` into child indices.
+pub(crate) fn parse_derivation_path(path: &str) -> Result<Vec<u32>, WalletError> {
+    let invalid =
+        |reason: String| WalletError::InvalidDerivationPath(format!("{}: {}", path, reason));
+
+    let mut components = path.split('/');
+    if components.next() != Some("m") {
+        return Err(invalid("the path must start with m".to_string()));
+    }
+
+    components
+        .map(|component| {
+            let (number, hardened) = match component.strip_suffix(|c| c == '\'' || c == 'h') {
+                Some(number) => (number, true),
+                None => (component, false),
+            };
+            // Digits only, so that signs and whitespace are rejected
+            if number.is_empty() || !number.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(invalid(format!("invalid component {:?}", component)));
+            }
+            match number.parse::<u32>() {
+                Ok(index) if index < HARDENED_OFFSET =>
+                    Ok(if hardened { index + HARDENED_OFFSET } else { index }),
+                _ => Err(invalid(format!("index {} is too large", number))),
+            }
+        })
+        .collect()
+}
+
+/// Reads 32 big-endian bytes as a scalar, if they are below the curve order.
+fn to_scalar(bytes: &[u8]) -> Option<Scalar> {
+    Option::from(Scalar::from_repr(FieldBytes::clone_from_slice(bytes)))
+}
+
+fn to_nonzero_scalar(bytes: &[u8]) -> Option<Scalar> {
+    to_scalar(bytes).filter(|scalar| !bool::from(scalar.is_zero()))
+}
+
+fn private_key_from_scalar(scalar: &Scalar) -> Result<Secp256r1PrivateKey, WalletError> {
+    Ok(Secp256r1PrivateKey::from_bytes(&scalar.to_repr())?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -152,6 +297,17 @@ mod tests {
         assert_eq!(original.mnemonic, recovered.mnemonic);
     }
 
+    #[test]
+    fn test_debug_redacts_mnemonic_and_seed() {
+        let account = Bip39Account::create("test_password").unwrap();
+        let debug = format!("{:?}", account);
+
+        assert!(debug.contains("<redacted>"));
+        assert!(!debug.contains(&hex::encode(account.seed.as_slice())));
+        assert!(!debug.contains(&format!("{:?}", account.seed.as_slice())));
+        assert!(!debug.contains(account.mnemonic.as_str()));
+    }
+
     #[test]
     fn test_invalid_mnemonic() {
         let result = Bip39Account::from_bip39_mnemonic(
@@ -184,4 +340,65 @@ mod tests {
         assert_eq!(account1.mnemonic, account2.mnemonic);
         assert!(!account1.mnemonic.is_empty());
     }
+
+    #[test]
+    fn test_derive_slip10_vectors() {
+        // Test vector 1 for nist256p1 from SLIP-10
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let vectors = [
+            ("m", "612091aaa12e22dd2abef664f8a01a82cae99ad7441b7ef8110424915c268bc2"),
+            ("m/0'", "6939694369114c67917a182c59ddb8cafc3004e63ca5d3b84403ba8613debc0c"),
+            ("m/0'/1", "284e9d38d07d21e4e281b645089a94f4cf5a5a81369acf151a1c3a57f18b2129"),
+            ("m/0'/1/2'", "694596e8a54f252c960eb771a3c41e7e32496d03b954aeb90f61635b8e092aa7"),
+            ("m/0'/1/2'/2", "5996c37fd3dd2679039b23ed6f70b506c6b56b3cb5e424681fb0fa64caf82aaa"),
+            (
+                "m/0'/1/2'/2/1000000000",
+                "21c4f269ef0a5fd1badf47eeacebeeaa3de22eb8e5b0adcd0f27dd99d34d0119",
+            ),
+        ];
+
+        for (path, expected) in vectors {
+            let key = derive_private_key(&seed, path).unwrap();
+            assert_eq!(hex::encode(key.to_raw_bytes()), expected, "path {}", path);
+        }
+        assert_eq!(
+            hex::encode(derive_private_key(&seed, "m/0h/1").unwrap().to_raw_bytes()),
+            "284e9d38d07d21e4e281b645089a94f4cf5a5a81369acf151a1c3a57f18b2129"
+        );
+    }
+
+    #[test]
+    fn test_derive_accounts_from_mnemonic() {
+        let password = "test_password";
+        let account = Bip39Account::create(password).unwrap();
+        let recovered = Bip39Account::from_bip39_mnemonic(password, &account.mnemonic).unwrap();
+
+        let first = account.derive("m/44'/888'/0'/0/0").unwrap();
+        let second = account.derive("m/44'/888'/0'/0/1").unwrap();
+        let hardened = account.derive("m/44'/888'/0'/0/0'").unwrap();
+
+        assert_eq!(
+            first.account.get_address(),
+            recovered.derive("m/44'/888'/0'/0/0").unwrap().account.get_address()
+        );
+        assert_ne!(first.account.get_address(), second.account.get_address());
+        assert_ne!(first.account.get_address(), hardened.account.get_address());
+        assert_ne!(first.account.get_address(), account.account.get_address());
+        assert_eq!(first.mnemonic, account.mnemonic);
+    }
+
+    #[test]
+    fn test_invalid_derivation_paths() {
+        let account = Bip39Account::create("password").unwrap();
+
+        let paths =
+            ["", "44'/888'", "m/", "m//0", "m/abc", "m/-1", "m/+1", "m/0''", "m/2147483648"];
+        for path in paths {
+            assert!(
+                matches!(account.derive(path), Err(WalletError::InvalidDerivationPath(_))),
+                "path {:?}",
+                path
+            );
+        }
+    }
 }
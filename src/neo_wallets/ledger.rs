@@ -0,0 +1,223 @@
+//! Signing with the Neo N3 app on a Ledger device
+use std::fmt;
+
+use coins_ledger::{
+	common::{APDUCommand, APDUData},
+	transports::{Ledger, LedgerAsync},
+};
+use futures_util::lock::Mutex;
+
+use neo::prelude::{
+	public_key_to_address, APITrait, Address, InvocationScript, JsonRpcProvider,
+	Secp256r1PublicKey, Secp256r1Signature, Transaction, TransactionError, VerificationScript,
+	WalletError, Witness,
+};
+
+use super::bip39_account::parse_derivation_path;
+
+/// A BIP-44 derivation path of a key held by the Ledger device.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HDPath {
+	/// `m/44'/888'/0'/0/{index}`, the path of the accounts the Neo app shows.
+	Neo(u32),
+	/// Any other path of five components, e.g. `m/44'/888'/1'/0/0`.
+	Other(String),
+}
+
+impl HDPath {
+	/// Encodes the path as the device expects it: five big-endian child indices.
+	fn to_bytes(&self) -> Result<Vec<u8>, WalletError> {
+		let path = match self {
+			HDPath::Neo(index) => format!("m/44'/888'/0'/0/{index}"),
+			HDPath::Other(path) => path.clone(),
+		};
+		let indices = parse_derivation_path(&path)?;
+		if indices.len() != 5 {
+			return Err(WalletError::InvalidDerivationPath(format!(
+				"{path}: the Ledger device needs a BIP-44 path of 5 components"
+			)));
+		}
+		Ok(indices.iter().flat_map(|index| index.to_be_bytes()).collect())
+	}
+}
+
+/// A connection to the Neo N3 app on a Ledger device.
+///
+/// The device keeps the private keys: it shows the transaction to the user and returns a
+/// signature only if they approve it.
+pub struct LedgerWallet {
+	transport: Mutex<Ledger>,
+}
+
+impl LedgerWallet {
+	const CLA: u8 = 0x80;
+	const INS_SIGN_TX: u8 = 0x02;
+	const INS_GET_PUBLIC_KEY: u8 = 0x04;
+	/// P2 of a chunk that is followed by more chunks
+	const P2_MORE: u8 = 0x80;
+	const P2_LAST: u8 = 0x00;
+	/// The most data the device accepts in one APDU
+	const MAX_CHUNK_SIZE: usize = 255;
+	const SW_OK: u16 = 0x9000;
+	/// The user rejected the request on the device
+	const SW_DENY: u16 = 0x6985;
+
+	/// Connects to the first Ledger device found.
+	pub async fn new() -> Result<Self, WalletError> {
+		let transport =
+			Ledger::init().await.map_err(|e| WalletError::LedgerError(e.to_string()))?;
+		Ok(Self { transport: Mutex::new(transport) })
+	}
+
+	/// Returns the public key at `path`.
+	pub async fn get_public_key(&self, path: &HDPath) -> Result<Secp256r1PublicKey, WalletError> {
+		let command = APDUCommand {
+			cla: Self::CLA,
+			ins: Self::INS_GET_PUBLIC_KEY,
+			p1: 0x00,
+			p2: 0x00,
+			data: APDUData::new(&path.to_bytes()?),
+			response_len: None,
+		};
+		let public_key = self.exchange(&command).await?;
+		Ok(Secp256r1PublicKey::from_bytes(&public_key)?)
+	}
+
+	/// Returns the address of the single-signature account of the key at `path`.
+	pub async fn get_address(&self, path: &HDPath) -> Result<Address, WalletError> {
+		Ok(public_key_to_address(&self.get_public_key(path).await?))
+	}
+
+	/// Signs `tx` with the key at `bip44_path` and returns the witness of that key's
+	/// single-signature account.
+	///
+	/// The transaction is sent without witnesses, together with the network magic of its client,
+	/// in chunks of at most 255 bytes. The device shows it to the user, and
+	/// [`WalletError::LedgerRejected`] is returned if they reject it.
+	pub async fn sign_transaction<'a, P: JsonRpcProvider + 'static>(
+		&self,
+		tx: &Transaction<'a, P>,
+		bip44_path: &HDPath,
+	) -> Result<Witness, WalletError> {
+		let client = tx.network.ok_or(TransactionError::IllegalState(
+			"The transaction has no client to take the network magic from".to_string(),
+		))?;
+		let magic = client.network().await;
+		let public_key = self.get_public_key(bip44_path).await?;
+
+		let commands =
+			Self::sign_tx_commands(&bip44_path.to_bytes()?, magic, &tx.unsigned_bytes())?;
+		let mut signature = Vec::new();
+		for command in &commands {
+			signature = self.exchange(command).await?;
+		}
+
+		let signature = p256::ecdsa::Signature::from_der(&signature)
+			.map_err(|e| WalletError::LedgerError(format!("Invalid signature: {e}")))?;
+		let signature = Secp256r1Signature::from_bytes(&signature.to_bytes())?;
+		Ok(Witness::from_scripts_obj(
+			InvocationScript::from_signature(signature),
+			VerificationScript::from_public_key(&public_key),
+		))
+	}
+
+	/// Splits a signing request into APDUs. P1 numbers the chunks: the path comes first, then
+	/// the network magic, then the transaction in as many chunks as needed.
+	fn sign_tx_commands(
+		path: &[u8],
+		magic: u32,
+		tx: &[u8],
+	) -> Result<Vec<APDUCommand>, WalletError> {
+		let magic = magic.to_le_bytes();
+		let chunks = [path, &magic[..]]
+			.into_iter()
+			.chain(tx.chunks(Self::MAX_CHUNK_SIZE))
+			.collect::<Vec<_>>();
+		if chunks.len() > usize::from(u8::MAX) + 1 {
+			return Err(WalletError::LedgerError(format!(
+				"The transaction of {} bytes is too large for the device",
+				tx.len()
+			)));
+		}
+
+		let last = chunks.len() - 1;
+		Ok(chunks
+			.into_iter()
+			.enumerate()
+			.map(|(index, chunk)| APDUCommand {
+				cla: Self::CLA,
+				ins: Self::INS_SIGN_TX,
+				p1: index as u8,
+				p2: if index == last { Self::P2_LAST } else { Self::P2_MORE },
+				data: APDUData::new(chunk),
+				response_len: None,
+			})
+			.collect())
+	}
+
+	/// Sends one APDU and returns the response data, mapping the status word to an error.
+	async fn exchange(&self, command: &APDUCommand) -> Result<Vec<u8>, WalletError> {
+		let answer = self
+			.transport
+			.lock()
+			.await
+			.exchange(command)
+			.await
+			.map_err(|e| WalletError::LedgerError(e.to_string()))?;
+		match answer.retcode() {
+			Self::SW_OK => Ok(answer.data().unwrap_or_default().to_vec()),
+			Self::SW_DENY => Err(WalletError::LedgerRejected),
+			status => Err(WalletError::LedgerError(format!("The device returned {status:#06x}"))),
+		}
+	}
+}
+
+impl fmt::Debug for LedgerWallet {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("LedgerWallet").finish_non_exhaustive()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_hd_path_bytes() {
+		let bytes = HDPath::Neo(3).to_bytes().unwrap();
+		assert_eq!(hex::encode(bytes), "8000002c80000378800000000000000000000003");
+		assert_eq!(
+			HDPath::Other("m/44'/888'/0'/0/3".to_string()).to_bytes().unwrap(),
+			HDPath::Neo(3).to_bytes().unwrap()
+		);
+		assert!(matches!(
+			HDPath::Other("m/44'/888'/0'".to_string()).to_bytes(),
+			Err(WalletError::InvalidDerivationPath(_))
+		));
+	}
+
+	#[test]
+	fn test_sign_tx_commands() {
+		let path = HDPath::Neo(0).to_bytes().unwrap();
+		let tx = vec![0xAB; 600];
+		let commands = LedgerWallet::sign_tx_commands(&path, 860833102, &tx).unwrap();
+
+		assert_eq!(commands.len(), 5);
+		assert_eq!(commands[0].data.data(), path);
+		assert_eq!(commands[1].data.data(), 860833102u32.to_le_bytes());
+		let sizes = commands[2..].iter().map(|c| c.data.data().len()).collect::<Vec<_>>();
+		assert_eq!(sizes, vec![255, 255, 90]);
+		for (index, command) in commands.iter().enumerate() {
+			assert_eq!(command.cla, 0x80);
+			assert_eq!(command.ins, 0x02);
+			assert_eq!(command.p1, index as u8);
+			assert_eq!(command.p2, if index == 4 { 0x00 } else { 0x80 });
+		}
+
+		let too_large = vec![0; 255 * 255];
+		assert!(matches!(
+			LedgerWallet::sign_tx_commands(&path, 0, &too_large),
+			Err(WalletError::LedgerError(_))
+		));
+	}
+}
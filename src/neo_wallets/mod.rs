@@ -6,8 +6,15 @@ use p256::NistP256;
 #[cfg(all(feature = "yubihsm", not(target_arch = "wasm32")))]
 pub use yubihsm;
 
+#[cfg(feature = "aws")]
+pub use aws_kms::*;
 pub use error::*;
+#[cfg(feature = "ledger")]
+pub use ledger::*;
 use neo::prelude::Account;
+pub use neox_abi::*;
+pub use neox_signer::*;
+pub use neox_transaction::*;
 pub use wallet::*;
 pub use wallet_signer::WalletSigner;
 pub use wallet_trait::WalletTrait;
@@ -25,6 +32,13 @@ pub type YubiWallet = WalletSigner<yubihsm::ecdsa::Signer<NistP256>>;
 // #[cfg(all(feature = "yubihsm", not(target_arch = "wasm32")))]
 mod yubi;
 
+#[cfg(feature = "aws")]
+mod aws_kms;
 mod error;
+#[cfg(feature = "ledger")]
+mod ledger;
+mod neox_abi;
+mod neox_signer;
+mod neox_transaction;
 mod wallet_signer;
 mod bip39_account;
@@ -0,0 +1,438 @@
+use std::{fmt, str::FromStr};
+
+use primitive_types::{H160, U256};
+use sha3::{Digest, Keccak256};
+
+use neo::prelude::TypeError;
+
+/// A Solidity type supported by [`NeoXAbi`]. Tuples and fixed-size arrays are not supported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AbiType {
+	/// `uint<M>`, with the size in bits
+	Uint(usize),
+	Address,
+	Bool,
+	Bytes,
+	/// `bytes<M>`, with the size in bytes
+	FixedBytes(usize),
+	String,
+	/// `T[]`
+	Array(Box<AbiType>),
+}
+
+impl AbiType {
+	fn is_dynamic(&self) -> bool {
+		matches!(self, AbiType::Bytes | AbiType::String | AbiType::Array(_))
+	}
+}
+
+impl FromStr for AbiType {
+	type Err = TypeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let s = s.trim();
+		if let Some(element) = s.strip_suffix("[]") {
+			return Ok(AbiType::Array(Box::new(element.parse()?)));
+		}
+		let invalid = || TypeError::IllegalArgument(format!("Unsupported ABI type {s}"));
+		match s {
+			"address" => Ok(AbiType::Address),
+			"bool" => Ok(AbiType::Bool),
+			"bytes" => Ok(AbiType::Bytes),
+			"string" => Ok(AbiType::String),
+			"uint" => Ok(AbiType::Uint(256)),
+			_ =>
+				if let Some(bits) = s.strip_prefix("uint") {
+					let bits = bits.parse::<usize>().map_err(|_| invalid())?;
+					if bits == 0 || bits > 256 || bits % 8 != 0 {
+						return Err(invalid());
+					}
+					Ok(AbiType::Uint(bits))
+				} else if let Some(size) = s.strip_prefix("bytes") {
+					let size = size.parse::<usize>().map_err(|_| invalid())?;
+					if size == 0 || size > 32 {
+						return Err(invalid());
+					}
+					Ok(AbiType::FixedBytes(size))
+				} else {
+					Err(invalid())
+				},
+		}
+	}
+}
+
+impl fmt::Display for AbiType {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			AbiType::Uint(bits) => write!(f, "uint{bits}"),
+			AbiType::Address => write!(f, "address"),
+			AbiType::Bool => write!(f, "bool"),
+			AbiType::Bytes => write!(f, "bytes"),
+			AbiType::FixedBytes(size) => write!(f, "bytes{size}"),
+			AbiType::String => write!(f, "string"),
+			AbiType::Array(element) => write!(f, "{element}[]"),
+		}
+	}
+}
+
+/// A value of an [`AbiType`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AbiValue {
+	Uint(U256),
+	Address(H160),
+	Bool(bool),
+	Bytes(Vec<u8>),
+	FixedBytes(Vec<u8>),
+	String(String),
+	Array(Vec<AbiValue>),
+}
+
+impl From<u64> for AbiValue {
+	fn from(value: u64) -> Self {
+		AbiValue::Uint(value.into())
+	}
+}
+
+impl From<u128> for AbiValue {
+	fn from(value: u128) -> Self {
+		AbiValue::Uint(value.into())
+	}
+}
+
+impl From<U256> for AbiValue {
+	fn from(value: U256) -> Self {
+		AbiValue::Uint(value)
+	}
+}
+
+impl From<H160> for AbiValue {
+	fn from(value: H160) -> Self {
+		AbiValue::Address(value)
+	}
+}
+
+impl From<bool> for AbiValue {
+	fn from(value: bool) -> Self {
+		AbiValue::Bool(value)
+	}
+}
+
+impl From<&str> for AbiValue {
+	fn from(value: &str) -> Self {
+		AbiValue::String(value.to_string())
+	}
+}
+
+/// Encodes calls to and decodes results from Neo X (EVM) contracts, following the Solidity
+/// contract ABI.
+pub struct NeoXAbi;
+
+impl NeoXAbi {
+	const WORD: usize = 32;
+
+	/// The 4-byte selector of a function, e.g. `transfer(address,uint256)`. The signature is
+	/// normalized first, so `balanceOf( address )` and `f(uint)` are accepted.
+	pub fn selector(fn_signature: &str) -> Result<[u8; 4], TypeError> {
+		let (name, types) = Self::parse_signature(fn_signature)?;
+		Ok(Self::selector_of(&name, &types))
+	}
+
+	/// Encodes the calldata of a call: the selector of `fn_signature` followed by the encoded
+	/// `args`, which must match the signature's parameter types.
+	pub fn encode(fn_signature: &str, args: &[AbiValue]) -> Result<Vec<u8>, TypeError> {
+		let (name, types) = Self::parse_signature(fn_signature)?;
+		if types.len() != args.len() {
+			return Err(TypeError::IllegalArgument(format!(
+				"{fn_signature} takes {} arguments, got {}",
+				types.len(),
+				args.len()
+			)));
+		}
+		let mut data = Self::selector_of(&name, &types).to_vec();
+		data.extend(Self::encode_tuple(&types, args)?);
+		Ok(data)
+	}
+
+	/// Decodes return data, or any other ABI encoded values, of the given types, e.g.
+	/// `&["uint256", "address[]"]`.
+	pub fn decode(types: &[&str], data: &[u8]) -> Result<Vec<AbiValue>, TypeError> {
+		let types = types.iter().map(|t| t.parse()).collect::<Result<Vec<AbiType>, _>>()?;
+		Self::decode_tuple(&types, data)
+	}
+
+	fn parse_signature(fn_signature: &str) -> Result<(String, Vec<AbiType>), TypeError> {
+		let invalid =
+			|| TypeError::IllegalArgument(format!("Invalid function signature {fn_signature}"));
+		let (name, params) = fn_signature.trim().split_once('(').ok_or_else(invalid)?;
+		let params = params.strip_suffix(')').ok_or_else(invalid)?;
+		if name.is_empty() || params.contains('(') {
+			return Err(invalid());
+		}
+		let types = if params.trim().is_empty() {
+			Vec::new()
+		} else {
+			params.split(',').map(str::parse).collect::<Result<_, _>>()?
+		};
+		Ok((name.trim().to_string(), types))
+	}
+
+	fn selector_of(name: &str, types: &[AbiType]) -> [u8; 4] {
+		let types = types.iter().map(ToString::to_string).collect::<Vec<_>>().join(",");
+		let hash = Keccak256::digest(format!("{name}({types})").as_bytes());
+		[hash[0], hash[1], hash[2], hash[3]]
+	}
+
+	/// Encodes values as a tuple: the static values and the offsets of the dynamic ones first,
+	/// followed by the dynamic values.
+	fn encode_tuple(types: &[AbiType], values: &[AbiValue]) -> Result<Vec<u8>, TypeError> {
+		let mut head = Vec::new();
+		let mut tail = Vec::new();
+		let head_size = types.len() * Self::WORD;
+		for (abi_type, value) in types.iter().zip(values) {
+			let encoded = Self::encode_value(abi_type, value)?;
+			if abi_type.is_dynamic() {
+				head.extend(Self::uint_word(U256::from(head_size + tail.len())));
+				tail.extend(encoded);
+			} else {
+				head.extend(encoded);
+			}
+		}
+		head.extend(tail);
+		Ok(head)
+	}
+
+	fn encode_value(abi_type: &AbiType, value: &AbiValue) -> Result<Vec<u8>, TypeError> {
+		match (abi_type, value) {
+			(AbiType::Uint(bits), AbiValue::Uint(value)) => {
+				if value.bits() > *bits {
+					return Err(TypeError::IllegalArgument(format!(
+						"{value} does not fit into uint{bits}"
+					)));
+				}
+				Ok(Self::uint_word(*value).to_vec())
+			},
+			(AbiType::Address, AbiValue::Address(address)) => {
+				let mut word = vec![0u8; Self::WORD];
+				word[12..].copy_from_slice(address.as_bytes());
+				Ok(word)
+			},
+			(AbiType::Bool, AbiValue::Bool(value)) =>
+				Ok(Self::uint_word(U256::from(*value as u8)).to_vec()),
+			(AbiType::FixedBytes(size), AbiValue::FixedBytes(bytes)) => {
+				if bytes.len() != *size {
+					return Err(TypeError::IllegalArgument(format!(
+						"bytes{size} needs {size} bytes, got {}",
+						bytes.len()
+					)));
+				}
+				Ok(Self::padded(bytes))
+			},
+			(AbiType::Bytes, AbiValue::Bytes(bytes)) => Ok(Self::encode_bytes(bytes)),
+			(AbiType::String, AbiValue::String(value)) => Ok(Self::encode_bytes(value.as_bytes())),
+			(AbiType::Array(element), AbiValue::Array(values)) => {
+				let mut encoded = Self::uint_word(U256::from(values.len())).to_vec();
+				let types = vec![element.as_ref().clone(); values.len()];
+				encoded.extend(Self::encode_tuple(&types, values)?);
+				Ok(encoded)
+			},
+			(abi_type, value) => Err(TypeError::IllegalArgument(format!(
+				"{value:?} is not a value of type {abi_type}"
+			))),
+		}
+	}
+
+	fn encode_bytes(bytes: &[u8]) -> Vec<u8> {
+		let mut encoded = Self::uint_word(U256::from(bytes.len())).to_vec();
+		encoded.extend(Self::padded(bytes));
+		encoded
+	}
+
+	/// Right-pads the bytes with zeros to a multiple of the word size.
+	fn padded(bytes: &[u8]) -> Vec<u8> {
+		let mut padded = bytes.to_vec();
+		padded.resize(bytes.len().div_ceil(Self::WORD) * Self::WORD, 0);
+		padded
+	}
+
+	fn uint_word(value: U256) -> [u8; 32] {
+		value.to_big_endian()
+	}
+
+	fn decode_tuple(types: &[AbiType], data: &[u8]) -> Result<Vec<AbiValue>, TypeError> {
+		types
+			.iter()
+			.enumerate()
+			.map(|(index, abi_type)| {
+				let head = index * Self::WORD;
+				if abi_type.is_dynamic() {
+					let offset = Self::read_usize(data, head)?;
+					Self::decode_value(abi_type, data, offset)
+				} else {
+					Self::decode_value(abi_type, data, head)
+				}
+			})
+			.collect()
+	}
+
+	/// Decodes a value of the type at `position` of `data`, the tuple it is part of.
+	fn decode_value(
+		abi_type: &AbiType,
+		data: &[u8],
+		position: usize,
+	) -> Result<AbiValue, TypeError> {
+		match abi_type {
+			AbiType::Uint(bits) => {
+				let value = U256::from_big_endian(Self::word(data, position)?);
+				if value.bits() > *bits {
+					return Err(TypeError::InvalidData(format!("{value} overflows uint{bits}")));
+				}
+				Ok(AbiValue::Uint(value))
+			},
+			AbiType::Address => {
+				let word = Self::word(data, position)?;
+				if word[..12].iter().any(|byte| *byte != 0) {
+					return Err(TypeError::InvalidData("Invalid address padding".to_string()));
+				}
+				Ok(AbiValue::Address(H160::from_slice(&word[12..])))
+			},
+			AbiType::Bool => match Self::read_usize(data, position)? {
+				0 => Ok(AbiValue::Bool(false)),
+				1 => Ok(AbiValue::Bool(true)),
+				value => Err(TypeError::InvalidData(format!("Invalid bool {value}"))),
+			},
+			AbiType::FixedBytes(size) =>
+				Ok(AbiValue::FixedBytes(Self::word(data, position)?[..*size].to_vec())),
+			AbiType::Bytes => Ok(AbiValue::Bytes(Self::read_bytes(data, position)?.to_vec())),
+			AbiType::String => String::from_utf8(Self::read_bytes(data, position)?.to_vec())
+				.map(AbiValue::String)
+				.map_err(|_| TypeError::InvalidData("The string is not valid UTF-8".to_string())),
+			AbiType::Array(element) => {
+				let len = Self::read_usize(data, position)?;
+				let elements = data.get(position + Self::WORD..).unwrap_or_default();
+				// Every element takes at least one word, which bounds the allocation below
+				if len > elements.len() / Self::WORD {
+					return Err(TypeError::InvalidData(format!("Invalid array length {len}")));
+				}
+				let types = vec![element.as_ref().clone(); len];
+				Ok(AbiValue::Array(Self::decode_tuple(&types, elements)?))
+			},
+		}
+	}
+
+	fn word(data: &[u8], position: usize) -> Result<&[u8], TypeError> {
+		position
+			.checked_add(Self::WORD)
+			.and_then(|end| data.get(position..end))
+			.ok_or_else(|| TypeError::InvalidData("The ABI data is too short".to_string()))
+	}
+
+	fn read_usize(data: &[u8], position: usize) -> Result<usize, TypeError> {
+		let value = U256::from_big_endian(Self::word(data, position)?);
+		if value > U256::from(data.len()) {
+			return Err(TypeError::InvalidData(format!("Invalid offset or length {value}")));
+		}
+		Ok(value.as_usize())
+	}
+
+	fn read_bytes(data: &[u8], position: usize) -> Result<&[u8], TypeError> {
+		let len = Self::read_usize(data, position)?;
+		let start = position + Self::WORD;
+		data.get(start..start + len)
+			.ok_or_else(|| TypeError::InvalidData("The ABI data is too short".to_string()))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use primitive_types::{H160, U256};
+
+	use neo::prelude::TypeError;
+
+	use super::{AbiType, AbiValue, NeoXAbi};
+
+	#[test]
+	fn test_selector() {
+		let transfer = NeoXAbi::selector("transfer(address,uint256)").unwrap();
+		assert_eq!(hex::encode(transfer), "a9059cbb");
+		assert_eq!(hex::encode(NeoXAbi::selector("balanceOf( address )").unwrap()), "70a08231");
+		assert_eq!(NeoXAbi::selector("f(uint)").unwrap(), NeoXAbi::selector("f(uint256)").unwrap());
+		assert!(matches!(NeoXAbi::selector("transfer"), Err(TypeError::IllegalArgument(_))));
+		assert!(matches!(NeoXAbi::selector("f(uint7)"), Err(TypeError::IllegalArgument(_))));
+	}
+
+	#[test]
+	fn test_encode_dynamic_arguments() {
+		// The example of the Solidity ABI specification
+		let data = NeoXAbi::encode(
+			"sam(bytes,bool,uint256[])",
+			&[
+				AbiValue::Bytes(b"dave".to_vec()),
+				true.into(),
+				AbiValue::Array(vec![1u64.into(), 2u64.into(), 3u64.into()]),
+			],
+		)
+		.unwrap();
+		let expected = [
+			"a5643bf2",
+			"0000000000000000000000000000000000000000000000000000000000000060",
+			"0000000000000000000000000000000000000000000000000000000000000001",
+			"00000000000000000000000000000000000000000000000000000000000000a0",
+			"0000000000000000000000000000000000000000000000000000000000000004",
+			"6461766500000000000000000000000000000000000000000000000000000000",
+			"0000000000000000000000000000000000000000000000000000000000000003",
+			"0000000000000000000000000000000000000000000000000000000000000001",
+			"0000000000000000000000000000000000000000000000000000000000000002",
+			"0000000000000000000000000000000000000000000000000000000000000003",
+		]
+		.concat();
+		assert_eq!(hex::encode(data), expected);
+	}
+
+	#[test]
+	fn test_encode_checks_arguments() {
+		let transfer = "transfer(address,uint256)";
+		assert!(NeoXAbi::encode(transfer, &[1u64.into()]).is_err());
+		assert!(NeoXAbi::encode(transfer, &[1u64.into(), 1u64.into()]).is_err());
+		assert!(NeoXAbi::encode("f(uint8)", &[256u64.into()]).is_err());
+		assert!(NeoXAbi::encode("f(bytes2)", &[AbiValue::FixedBytes(vec![1])]).is_err());
+
+		let data = NeoXAbi::encode(transfer, &[H160::repeat_byte(0x35).into(), 10u64.into()]);
+		assert_eq!(data.unwrap().len(), 4 + 2 * 32);
+	}
+
+	#[test]
+	fn test_decode_round_trip() {
+		let long = "a string that takes more than one word";
+		let strings = vec!["Neo".into(), "".into(), long.into()];
+		let values = vec![
+			AbiValue::Uint(U256::MAX),
+			H160::repeat_byte(0x35).into(),
+			AbiValue::Array(strings),
+			AbiValue::FixedBytes(vec![0xab; 4]),
+			AbiValue::Bytes(vec![]),
+		];
+		let data = NeoXAbi::encode("f(uint256,address,string[],bytes4,bytes)", &values).unwrap();
+
+		let types = ["uint256", "address", "string[]", "bytes4", "bytes"];
+		assert_eq!(NeoXAbi::decode(&types, &data[4..]).unwrap(), values);
+		assert_eq!("string[]".parse::<AbiType>().unwrap().to_string(), "string[]");
+	}
+
+	#[test]
+	fn test_decode_rejects_malformed_data() {
+		let word = |value: u64| format!("{value:064x}");
+
+		assert!(matches!(NeoXAbi::decode(&["uint256"], &[0; 31]), Err(TypeError::InvalidData(_))));
+		let bool_data = hex::decode(word(2)).unwrap();
+		assert!(matches!(NeoXAbi::decode(&["bool"], &bool_data), Err(TypeError::InvalidData(_))));
+		let uint8_data = hex::decode(word(256)).unwrap();
+		assert!(matches!(NeoXAbi::decode(&["uint8"], &uint8_data), Err(TypeError::InvalidData(_))));
+
+		// An array claiming more elements than the data holds
+		let array = hex::decode([word(32), word(1_000_000)].concat()).unwrap();
+		assert!(matches!(NeoXAbi::decode(&["uint256[]"], &array), Err(TypeError::InvalidData(_))));
+		// Bytes running past the end of the data
+		let bytes = hex::decode([word(32), word(64), word(0)].concat()).unwrap();
+		assert!(matches!(NeoXAbi::decode(&["bytes"], &bytes), Err(TypeError::InvalidData(_))));
+	}
+}
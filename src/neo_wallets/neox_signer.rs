@@ -0,0 +1,221 @@
+use k256::ecdsa::{RecoveryId, Signature, SigningKey, VerifyingKey};
+use primitive_types::{H160, H256};
+use sha3::{Digest, Keccak256};
+
+use neo::prelude::{CryptoError, NeoXTransaction, SignError, SignedNeoXTransaction};
+
+/// The prefix EIP-191 prepends to messages signed with `personal_sign`.
+const PERSONAL_MESSAGE_PREFIX: &str = "\x19Ethereum Signed Message:\n";
+
+/// A recoverable secp256k1 signature as produced by Neo X (EVM) wallets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NeoXSignature {
+	pub r: H256,
+	pub s: H256,
+	/// The recovery id, offset by 27 as is customary for `personal_sign`.
+	pub v: u8,
+}
+
+impl NeoXSignature {
+	/// The signature in its 65 bytes `r || s || v` form.
+	pub fn to_bytes(&self) -> [u8; 65] {
+		let mut bytes = [0u8; 65];
+		bytes[..32].copy_from_slice(self.r.as_bytes());
+		bytes[32..64].copy_from_slice(self.s.as_bytes());
+		bytes[64] = self.v;
+		bytes
+	}
+
+	pub fn from_bytes(bytes: &[u8; 65]) -> Self {
+		Self {
+			r: H256::from_slice(&bytes[..32]),
+			s: H256::from_slice(&bytes[32..64]),
+			v: bytes[64],
+		}
+	}
+}
+
+/// Signs messages with a secp256k1 key the way Neo X wallets do, so NeoRust-based tools can
+/// authenticate against Neo X dApps.
+#[derive(Debug, Clone)]
+pub struct NeoXSigner {
+	key: SigningKey,
+}
+
+impl NeoXSigner {
+	pub fn new(key: SigningKey) -> Self {
+		Self { key }
+	}
+
+	pub fn from_slice(private_key: &[u8]) -> Result<Self, CryptoError> {
+		let key =
+			SigningKey::from_slice(private_key).map_err(|_| CryptoError::InvalidPrivateKey)?;
+		Ok(Self::new(key))
+	}
+
+	/// The Neo X address of the signer.
+	pub fn address(&self) -> H160 {
+		neox_address(self.key.verifying_key())
+	}
+
+	/// Signs the message as `personal_sign` (EIP-191) does, i.e. the Keccak-256 hash of
+	/// `"\x19Ethereum Signed Message:\n" + len(message) + message`.
+	pub fn personal_sign(&self, message: &[u8]) -> Result<NeoXSignature, SignError> {
+		let hash = personal_message_hash(message);
+		let (signature, recovery_id) = self
+			.key
+			.sign_prehash_recoverable(hash.as_bytes())
+			.map_err(|_| SignError::SigningFailed)?;
+
+		let (r, s) = signature.split_bytes();
+		Ok(NeoXSignature {
+			r: H256::from_slice(&r),
+			s: H256::from_slice(&s),
+			v: recovery_id.to_byte() + 27,
+		})
+	}
+
+	/// Signs the transaction, which includes its chain id in the signature for replay
+	/// protection.
+	pub fn sign_transaction(
+		&self,
+		transaction: &NeoXTransaction,
+	) -> Result<SignedNeoXTransaction, SignError> {
+		let hash = transaction.signing_hash();
+		let (signature, recovery_id) = self
+			.key
+			.sign_prehash_recoverable(hash.as_bytes())
+			.map_err(|_| SignError::SigningFailed)?;
+
+		let (r, s) = signature.split_bytes();
+		let signature = (recovery_id.to_byte(), H256::from_slice(&r), H256::from_slice(&s));
+		Ok(SignedNeoXTransaction::new(transaction.encode(Some(signature))))
+	}
+}
+
+/// The hash signed by [`NeoXSigner::personal_sign`] for the message.
+pub fn personal_message_hash(message: &[u8]) -> H256 {
+	let mut hasher = Keccak256::new();
+	hasher.update(PERSONAL_MESSAGE_PREFIX.as_bytes());
+	hasher.update(message.len().to_string().as_bytes());
+	hasher.update(message);
+	H256::from_slice(&hasher.finalize())
+}
+
+/// Recovers the Neo X address that produced a `personal_sign` signature over the message.
+pub fn recover_personal_sign(message: &[u8], signature: &NeoXSignature) -> Result<H160, SignError> {
+	let recovery_byte = match signature.v {
+		27 | 28 => signature.v - 27,
+		0 | 1 => signature.v,
+		v => return Err(SignError::HeaderOutOfRange(v)),
+	};
+	let recovery_id = RecoveryId::from_byte(recovery_byte).ok_or(SignError::RecoverFailed)?;
+	let signature =
+		Signature::from_scalars(signature.r.to_fixed_bytes(), signature.s.to_fixed_bytes())
+			.map_err(|_| SignError::RecoverFailed)?;
+
+	let hash = personal_message_hash(message);
+	let key = VerifyingKey::recover_from_prehash(hash.as_bytes(), &signature, recovery_id)
+		.map_err(|_| SignError::RecoverFailed)?;
+	Ok(neox_address(&key))
+}
+
+fn neox_address(key: &VerifyingKey) -> H160 {
+	let point = key.to_encoded_point(false);
+	let hash = Keccak256::digest(&point.as_bytes()[1..]);
+	H160::from_slice(&hash[12..])
+}
+
+#[cfg(test)]
+mod tests {
+	use std::str::FromStr;
+
+	use primitive_types::{H160, H256};
+
+	use super::*;
+
+	const PRIVATE_KEY: &str = "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+	#[test]
+	fn test_personal_sign_known_vector() {
+		let signer = NeoXSigner::from_slice(&hex::decode(PRIVATE_KEY).unwrap()).unwrap();
+		assert_eq!(
+			signer.address(),
+			H160::from_str("2c7536e3605d9c16a7a3d7b1898e529396a65c23").unwrap()
+		);
+		assert_eq!(
+			personal_message_hash(b"Some data"),
+			H256::from_str("1da44b586eb0729ff70a73c326926f6ed5a25f5b056e7f47fbc6e58d86871655")
+				.unwrap()
+		);
+
+		let signature = signer.personal_sign(b"Some data").unwrap();
+		assert_eq!(
+			hex::encode(signature.to_bytes()),
+			"b91467e570a6466aa9e9876cbcd013baba02900b8979d43fe208a4a4f339f5fd6007e74cd82e037b800186422fc2da167c747ef045e5d18a5f5d4300f8e1a0291c"
+		);
+	}
+
+	#[test]
+	fn test_recover_personal_sign_round_trip() {
+		let signer = NeoXSigner::from_slice(&hex::decode(PRIVATE_KEY).unwrap()).unwrap();
+		let signature = signer.personal_sign(b"Sign in to Neo X").unwrap();
+		let recovered = recover_personal_sign(b"Sign in to Neo X", &signature).unwrap();
+		assert_eq!(recovered, signer.address());
+
+		let signature = NeoXSignature::from_bytes(&signature.to_bytes());
+		assert_ne!(recover_personal_sign(b"Another message", &signature), Ok(signer.address()));
+
+		let invalid = NeoXSignature { v: 29, ..signature };
+		assert_eq!(
+			recover_personal_sign(b"Sign in to Neo X", &invalid),
+			Err(SignError::HeaderOutOfRange(29))
+		);
+	}
+
+	#[test]
+	fn test_sign_legacy_transaction() {
+		// The example of EIP-155
+		let signer = NeoXSigner::from_slice(&[0x46; 32]).unwrap();
+		let transaction = NeoXTransaction::legacy(1, 20_000_000_000)
+			.nonce(9)
+			.to(H160::repeat_byte(0x35))
+			.value(1_000_000_000_000_000_000);
+
+		let signed = signer.sign_transaction(&transaction).unwrap();
+		assert_eq!(
+			hex::encode(&signed.raw),
+			"f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83"
+		);
+		assert_eq!(signed.hash, H256::from_slice(&Keccak256::digest(&signed.raw)));
+	}
+
+	#[test]
+	fn test_sign_eip1559_transaction() {
+		let signer = NeoXSigner::from_slice(&hex::decode(PRIVATE_KEY).unwrap()).unwrap();
+		let transaction = NeoXTransaction::eip1559(47763, 50_000_000_000, 1_000_000_000)
+			.to(H160::repeat_byte(0x35))
+			.value(1);
+
+		let signed = signer.sign_transaction(&transaction).unwrap();
+		assert_eq!(signed.raw[0], 0x02);
+		let rlp = rlp::Rlp::new(&signed.raw[1..]);
+		assert_eq!(rlp.item_count().unwrap(), 12);
+		let recovery_id = RecoveryId::from_byte(rlp.val_at::<u8>(9).unwrap()).unwrap();
+		let mut r = [0u8; 32];
+		let mut s = [0u8; 32];
+		let r_bytes = rlp.val_at::<Vec<u8>>(10).unwrap();
+		let s_bytes = rlp.val_at::<Vec<u8>>(11).unwrap();
+		r[32 - r_bytes.len()..].copy_from_slice(&r_bytes);
+		s[32 - s_bytes.len()..].copy_from_slice(&s_bytes);
+		let signature = Signature::from_scalars(r, s).unwrap();
+
+		let key = VerifyingKey::recover_from_prehash(
+			transaction.signing_hash().as_bytes(),
+			&signature,
+			recovery_id,
+		)
+		.unwrap();
+		assert_eq!(neox_address(&key), signer.address());
+	}
+}
@@ -0,0 +1,234 @@
+use primitive_types::{H160, H256};
+use rlp::RlpStream;
+use sha3::{Digest, Keccak256};
+
+/// How a Neo X transaction pays for gas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NeoXFee {
+	/// A legacy transaction with a single gas price, replay protected by EIP-155.
+	Legacy { gas_price: u128 },
+	/// An EIP-1559 (type 2) transaction.
+	Eip1559 { max_fee_per_gas: u128, max_priority_fee_per_gas: u128 },
+}
+
+/// An unsigned Neo X (EVM) transaction. Sign it with [`NeoXSigner::sign_transaction`].
+///
+/// The chain id is part of the signed data, so a signed transaction is only valid on that chain.
+/// EIP-1559 transactions are encoded with an empty access list.
+///
+/// [`NeoXSigner::sign_transaction`]: crate::prelude::NeoXSigner::sign_transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NeoXTransaction {
+	pub chain_id: u64,
+	pub nonce: u64,
+	pub fee: NeoXFee,
+	pub gas_limit: u64,
+	/// The recipient, or `None` to deploy a contract.
+	pub to: Option<H160>,
+	/// The amount of GAS to transfer, in wei.
+	pub value: u128,
+	pub data: Vec<u8>,
+}
+
+impl NeoXTransaction {
+	/// The gas a plain transfer uses.
+	pub const TRANSFER_GAS_LIMIT: u64 = 21_000;
+	const EIP1559_TYPE: u8 = 0x02;
+
+	/// Creates a legacy transaction for the chain, paying `gas_price` wei per gas.
+	pub fn legacy(chain_id: u64, gas_price: u128) -> Self {
+		Self::new(chain_id, NeoXFee::Legacy { gas_price })
+	}
+
+	/// Creates an EIP-1559 transaction for the chain.
+	pub fn eip1559(chain_id: u64, max_fee_per_gas: u128, max_priority_fee_per_gas: u128) -> Self {
+		Self::new(chain_id, NeoXFee::Eip1559 { max_fee_per_gas, max_priority_fee_per_gas })
+	}
+
+	fn new(chain_id: u64, fee: NeoXFee) -> Self {
+		Self {
+			chain_id,
+			nonce: 0,
+			fee,
+			gas_limit: Self::TRANSFER_GAS_LIMIT,
+			to: None,
+			value: 0,
+			data: Vec::new(),
+		}
+	}
+
+	pub fn nonce(mut self, nonce: u64) -> Self {
+		self.nonce = nonce;
+		self
+	}
+
+	pub fn gas_limit(mut self, gas_limit: u64) -> Self {
+		self.gas_limit = gas_limit;
+		self
+	}
+
+	pub fn to(mut self, to: H160) -> Self {
+		self.to = Some(to);
+		self
+	}
+
+	pub fn value(mut self, value: u128) -> Self {
+		self.value = value;
+		self
+	}
+
+	pub fn data(mut self, data: Vec<u8>) -> Self {
+		self.data = data;
+		self
+	}
+
+	/// The hash the sender signs.
+	pub fn signing_hash(&self) -> H256 {
+		keccak256(&self.encode(None))
+	}
+
+	/// Encodes the transaction, with the signature `(y parity, r, s)` if given. Without a
+	/// signature, this is the data the signing hash is computed from.
+	pub(crate) fn encode(&self, signature: Option<(u8, H256, H256)>) -> Vec<u8> {
+		let fields = if signature.is_some() { 3 } else { 0 };
+		match self.fee {
+			NeoXFee::Legacy { gas_price } => {
+				let mut stream = RlpStream::new_list(6 + 3);
+				append_uint(&mut stream, self.nonce.into());
+				append_uint(&mut stream, gas_price);
+				self.append_call(&mut stream);
+				match signature {
+					// EIP-155: v = y parity + chain id * 2 + 35
+					Some((y_parity, r, s)) => {
+						let v = u128::from(self.chain_id) * 2 + 35 + u128::from(y_parity);
+						append_uint(&mut stream, v);
+						append_signature(&mut stream, r, s);
+					},
+					None => {
+						append_uint(&mut stream, self.chain_id.into());
+						append_uint(&mut stream, 0);
+						append_uint(&mut stream, 0);
+					},
+				}
+				stream.out().to_vec()
+			},
+			NeoXFee::Eip1559 { max_fee_per_gas, max_priority_fee_per_gas } => {
+				let mut stream = RlpStream::new_list(9 + fields);
+				append_uint(&mut stream, self.chain_id.into());
+				append_uint(&mut stream, self.nonce.into());
+				append_uint(&mut stream, max_priority_fee_per_gas);
+				append_uint(&mut stream, max_fee_per_gas);
+				self.append_call(&mut stream);
+				// access list
+				stream.begin_list(0);
+				if let Some((y_parity, r, s)) = signature {
+					append_uint(&mut stream, y_parity.into());
+					append_signature(&mut stream, r, s);
+				}
+				let mut encoded = vec![Self::EIP1559_TYPE];
+				encoded.extend_from_slice(&stream.out());
+				encoded
+			},
+		}
+	}
+
+	/// Appends gas limit, recipient, value and data, which both transaction types share.
+	fn append_call(&self, stream: &mut RlpStream) {
+		append_uint(stream, self.gas_limit.into());
+		stream.append(&self.to.map_or(Vec::new(), |to| to.as_bytes().to_vec()));
+		append_uint(stream, self.value);
+		stream.append(&self.data);
+	}
+}
+
+/// A signed Neo X transaction, ready to be sent with `eth_sendRawTransaction`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedNeoXTransaction {
+	/// The encoded transaction.
+	pub raw: Vec<u8>,
+	/// The transaction hash.
+	pub hash: H256,
+}
+
+impl SignedNeoXTransaction {
+	pub(crate) fn new(raw: Vec<u8>) -> Self {
+		let hash = keccak256(&raw);
+		Self { raw, hash }
+	}
+
+	/// The encoded transaction as a 0x-prefixed hex string.
+	pub fn raw_hex(&self) -> String {
+		format!("0x{}", hex::encode(&self.raw))
+	}
+
+	/// The transaction hash as a 0x-prefixed hex string.
+	pub fn hash_hex(&self) -> String {
+		format!("0x{}", hex::encode(self.hash.as_bytes()))
+	}
+}
+
+/// Appends an unsigned integer as RLP does: big-endian without leading zeros.
+fn append_uint(stream: &mut RlpStream, value: u128) {
+	let bytes = value.to_be_bytes();
+	let start = bytes.iter().position(|byte| *byte != 0).unwrap_or(bytes.len());
+	stream.append(&bytes[start..].to_vec());
+}
+
+fn append_signature(stream: &mut RlpStream, r: H256, s: H256) {
+	for value in [r, s] {
+		let bytes = value.as_bytes();
+		let start = bytes.iter().position(|byte| *byte != 0).unwrap_or(bytes.len());
+		stream.append(&bytes[start..].to_vec());
+	}
+}
+
+fn keccak256(data: &[u8]) -> H256 {
+	H256::from_slice(&Keccak256::digest(data))
+}
+
+#[cfg(test)]
+mod tests {
+	use std::str::FromStr;
+
+	use super::*;
+
+	/// The example transaction of EIP-155
+	fn eip155_transaction() -> NeoXTransaction {
+		NeoXTransaction::legacy(1, 20_000_000_000)
+			.nonce(9)
+			.to(H160::repeat_byte(0x35))
+			.value(1_000_000_000_000_000_000)
+	}
+
+	#[test]
+	fn test_legacy_signing_data() {
+		let transaction = eip155_transaction();
+		assert_eq!(
+			hex::encode(transaction.encode(None)),
+			"ec098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080018080"
+		);
+		assert_eq!(
+			transaction.signing_hash(),
+			H256::from_str("daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53")
+				.unwrap()
+		);
+	}
+
+	#[test]
+	fn test_eip1559_encoding() {
+		let transaction = NeoXTransaction::eip1559(47763, 50_000_000_000, 1_000_000_000)
+			.nonce(1)
+			.to(H160::repeat_byte(0x35))
+			.value(1);
+		let encoded = transaction.encode(None);
+
+		assert_eq!(encoded[0], 0x02);
+		let rlp = rlp::Rlp::new(&encoded[1..]);
+		assert_eq!(rlp.item_count().unwrap(), 9);
+		assert_eq!(rlp.val_at::<u64>(0).unwrap(), 47763);
+		assert_eq!(rlp.val_at::<u64>(2).unwrap(), 1_000_000_000);
+		assert_eq!(rlp.val_at::<u64>(3).unwrap(), 50_000_000_000);
+		assert_eq!(rlp.at(8).unwrap().item_count().unwrap(), 0);
+		assert_ne!(transaction.signing_hash(), transaction.clone().nonce(2).signing_hash());
+	}
+}
@@ -0,0 +1,151 @@
+use std::{
+	collections::HashMap,
+	fmt,
+	sync::{Arc, Mutex, MutexGuard, PoisonError, Weak},
+	thread,
+	time::Duration,
+};
+
+use elliptic_curve::zeroize::Zeroizing;
+use primitive_types::H160;
+use tokio::{runtime::Handle, time::Instant};
+
+use neo::prelude::{KeyPair, WalletError};
+
+/// The decrypted key pairs of a wallet with auto-lock enabled.
+///
+/// The keys are dropped, which zeroizes them, once `timeout` has passed without one being used.
+/// A background task drops them on time even if the wallet is idle. Clones of a wallet share
+/// one `AutoLock`, so the keys can be used and locked from several tasks.
+///
+/// The password the keys were unlocked with is kept and dropped along with them, to encrypt the
+/// keys of accounts added while the wallet is unlocked.
+pub(crate) struct AutoLock {
+	timeout: Duration,
+	state: Mutex<AutoLockState>,
+}
+
+struct AutoLockState {
+	keys: HashMap<H160, KeyPair>,
+	password: Option<Zeroizing<String>>,
+	last_used: Instant,
+	/// Whether a thread is waiting to drop the keys
+	watching: bool,
+}
+
+impl AutoLock {
+	pub(crate) fn new(timeout: Duration) -> Arc<Self> {
+		Arc::new(Self {
+			timeout,
+			state: Mutex::new(AutoLockState {
+				keys: HashMap::new(),
+				password: None,
+				last_used: Instant::now(),
+				watching: false,
+			}),
+		})
+	}
+
+	/// Adds decrypted key pairs, and the password they were decrypted with if given, and restarts
+	/// the inactivity timer.
+	pub(crate) fn unlock(self: &Arc<Self>, keys: HashMap<H160, KeyPair>, password: Option<&str>) {
+		let mut state = self.state();
+		state.keys.extend(keys);
+		if let Some(password) = password {
+			state.password = Some(Zeroizing::new(password.to_string()));
+		}
+		state.last_used = Instant::now();
+		if !state.watching && !state.keys.is_empty() {
+			state.watching = true;
+			let auto_lock = Arc::downgrade(self);
+			match Handle::try_current() {
+				Ok(handle) => {
+					handle.spawn(Self::watch(auto_lock));
+				},
+				Err(_) => {
+					thread::spawn(move || Self::watch_blocking(auto_lock));
+				},
+			}
+		}
+	}
+
+	/// Drops all decrypted key pairs and the password.
+	pub(crate) fn lock(&self) {
+		let mut state = self.state();
+		state.keys.clear();
+		state.password = None;
+	}
+
+	/// The password the wallet was unlocked with, unless the timeout has passed since.
+	pub(crate) fn password(&self) -> Option<Zeroizing<String>> {
+		let mut state = self.state();
+		self.expire(&mut state);
+		state.password.clone()
+	}
+
+	/// Whether no decrypted key pair is held, because none was unlocked or the timeout passed.
+	pub(crate) fn is_locked(&self) -> bool {
+		let mut state = self.state();
+		self.expire(&mut state);
+		state.keys.is_empty()
+	}
+
+	/// Returns the key pair of the account with the given script hash and restarts the
+	/// inactivity timer.
+	pub(crate) fn key_pair(&self, script_hash: &H160) -> Result<KeyPair, WalletError> {
+		let mut state = self.state();
+		self.expire(&mut state);
+		let key_pair = state.keys.get(script_hash).cloned().ok_or(WalletError::Locked)?;
+		state.last_used = Instant::now();
+		Ok(key_pair)
+	}
+
+	fn state(&self) -> MutexGuard<'_, AutoLockState> {
+		// The state stays consistent if a thread panicked while holding the lock
+		self.state.lock().unwrap_or_else(PoisonError::into_inner)
+	}
+
+	fn expire(&self, state: &mut AutoLockState) {
+		if state.last_used.elapsed() >= self.timeout {
+			state.keys.clear();
+			state.password = None;
+		}
+	}
+
+	/// Drops the keys once the timeout has passed, sleeping until the next possible deadline.
+	/// Returns when the keys are dropped or the wallet is.
+	async fn watch(auto_lock: Weak<Self>) {
+		while let Some(remaining) = Self::remaining(&auto_lock) {
+			tokio::time::sleep(remaining).await;
+		}
+	}
+
+	/// Like [`Self::watch`], for wallets unlocked outside of a Tokio runtime.
+	fn watch_blocking(auto_lock: Weak<Self>) {
+		while let Some(remaining) = Self::remaining(&auto_lock) {
+			thread::sleep(remaining);
+		}
+	}
+
+	/// Drops the keys if the timeout has passed, and returns the time until it can pass next, or
+	/// `None` once the keys are dropped or the wallet is.
+	fn remaining(auto_lock: &Weak<Self>) -> Option<Duration> {
+		let auto_lock = auto_lock.upgrade()?;
+		let mut state = auto_lock.state();
+		auto_lock.expire(&mut state);
+		if state.keys.is_empty() {
+			state.watching = false;
+			return None;
+		}
+		Some(auto_lock.timeout.saturating_sub(state.last_used.elapsed()))
+	}
+}
+
+// do not log the keys
+impl fmt::Debug for AutoLock {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("AutoLock")
+			.field("timeout", &self.timeout)
+			.finish_non_exhaustive()
+	}
+}
@@ -4,6 +4,7 @@ pub use nep6wallet::*;
 pub use wallet::*;
 pub use wallet_error::*;
 
+mod auto_lock;
 mod nep6account;
 mod nep6contract;
 mod nep6wallet;
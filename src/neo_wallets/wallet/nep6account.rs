@@ -2,6 +2,7 @@ use std::collections::HashMap;
 
 use getset::{Getters, Setters};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 use neo::prelude::{
 	Account, Address, AddressOrScriptHash, Base64Encode, ContractParameterType, NEP6Contract,
@@ -22,14 +23,16 @@ pub struct NEP6Account {
 	#[serde(rename = "label")]
 	pub label: Option<String>,
 
-	/// Indicates whether the account is set as default.
+	/// Indicates whether the account is set as default. Some wallets write the key as
+	/// `isdefault`.
 	#[getset(get = "pub")]
 	#[serde(default)]
-	#[serde(rename = "isDefault")]
+	#[serde(rename = "isDefault", alias = "isdefault")]
 	pub is_default: bool,
 
 	/// Indicates whether the account is locked.
 	#[getset(get = "pub")]
+	#[serde(default)]
 	#[serde(rename = "lock")]
 	pub lock: bool,
 
@@ -49,7 +52,13 @@ pub struct NEP6Account {
 	#[getset(get = "pub")]
 	#[serde(skip_serializing_if = "Option::is_none")]
 	#[serde(rename = "extra")]
-	pub extra: Option<HashMap<String, String>>,
+	pub extra: Option<HashMap<String, Value>>,
+
+	/// Fields not defined by NEP-6 that other wallet software wrote, kept so that they are
+	/// written back when the account is saved.
+	#[getset(get = "pub")]
+	#[serde(flatten)]
+	pub unknown_fields: HashMap<String, Value>,
 }
 
 impl NEP6Account {
@@ -88,9 +97,18 @@ impl NEP6Account {
 		lock: bool,
 		key: Option<String>,
 		contract: Option<NEP6Contract>,
-		extra: Option<HashMap<String, String>>,
+		extra: Option<HashMap<String, Value>>,
 	) -> Self {
-		Self { address, label, is_default, lock, key, contract, extra }
+		Self {
+			address,
+			label,
+			is_default,
+			lock,
+			key,
+			contract,
+			extra,
+			unknown_fields: HashMap::new(),
+		}
 	}
 
 	/// Converts an `Account` into a `NEP6Account`.
@@ -152,7 +170,8 @@ impl NEP6Account {
 			lock: account.is_locked,
 			key: account.encrypted_private_key.clone(),
 			contract,
-			extra: None,
+			extra: account.nep6_extra.clone(),
+			unknown_fields: account.nep6_unknown_fields.clone(),
 		})
 	}
 
@@ -175,10 +194,11 @@ impl NEP6Account {
 		let mut nr_of_participants: Option<u8> = None;
 
 		if let Some(contract) = &self.contract {
-			if contract.script.is_some() {
-				verification_script = Some(VerificationScript::from(
-					contract.script.clone().unwrap().base64_decoded().unwrap(),
-				));
+			if let Some(script) = &contract.script {
+				let script = script.base64_decoded().map_err(|e| {
+					WalletError::AccountState(format!("Invalid contract script: {e}"))
+				})?;
+				verification_script = Some(VerificationScript::from(script));
 
 				if verification_script.as_ref().unwrap().is_multi_sig() {
 					signing_threshold =
@@ -193,10 +213,13 @@ impl NEP6Account {
 			address_or_scripthash: AddressOrScriptHash::Address(self.clone().address),
 			label: self.clone().label,
 			verification_script,
+			is_default: self.is_default,
 			is_locked: self.clone().lock,
 			encrypted_private_key: self.clone().key,
 			signing_threshold: signing_threshold.map(|s| s as u32),
 			nr_of_participants: nr_of_participants.map(|s| s as u32),
+			nep6_extra: self.extra.clone(),
+			nep6_unknown_fields: self.unknown_fields.clone(),
 			..Default::default()
 		})
 	}
@@ -13,11 +13,13 @@ pub struct NEP6Contract {
 
 	/// Indicates whether the contract is deployed.
 	#[getset(get = "pub")]
+	#[serde(default)]
 	#[serde(rename = "deployed")]
 	pub is_deployed: bool,
 
 	/// The NEP-6 parameters associated with the contract.
 	#[getset(get = "pub")]
+	#[serde(default)]
 	#[serde(rename = "parameters")]
 	pub nep6_parameters: Vec<NEP6Parameter>,
 }
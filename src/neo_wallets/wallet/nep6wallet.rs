@@ -2,6 +2,7 @@ use std::collections::HashMap;
 
 use getset::{CopyGetters, Getters};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 use neo::prelude::{NEP6Account, ScryptParamsDef};
 
@@ -24,7 +25,11 @@ pub struct NEP6Wallet {
 	/// Additional data associated with the wallet.
 	#[serde(rename = "extra")]
 	#[serde(skip_serializing_if = "Option::is_none")]
-	pub(crate) extra: Option<HashMap<String, String>>,
+	pub(crate) extra: Option<HashMap<String, Value>>,
+	/// Fields not defined by NEP-6 that other wallet software wrote, kept so that they are
+	/// written back when the wallet is saved.
+	#[serde(flatten)]
+	pub(crate) unknown_fields: HashMap<String, Value>,
 }
 
 impl NEP6Wallet {
@@ -57,9 +62,9 @@ impl NEP6Wallet {
 		version: String,
 		scrypt: ScryptParamsDef,
 		accounts: Vec<NEP6Account>,
-		extra: Option<HashMap<String, String>>,
+		extra: Option<HashMap<String, Value>>,
 	) -> Self {
-		Self { name, version, scrypt, accounts, extra }
+		Self { name, version, scrypt, accounts, extra, unknown_fields: HashMap::new() }
 	}
 }
 
@@ -1,10 +1,12 @@
-use std::{collections::HashMap, fs::File, io::Write, path::PathBuf};
+use std::{collections::HashMap, fs::File, io::Write, path::PathBuf, sync::Arc, time::Duration};
 
 use primitive_types::H160;
 use serde_derive::{Deserialize, Serialize};
 
 use neo::prelude::*;
 
+use super::auto_lock::AutoLock;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Wallet {
 	pub name: String,
@@ -16,6 +18,15 @@ pub struct Wallet {
 	#[serde(deserialize_with = "deserialize_script_hash")]
 	#[serde(serialize_with = "serialize_script_hash")]
 	pub(crate) default_account: H160,
+	/// The `extra` data of the NEP-6 wallet this wallet was imported from.
+	#[serde(skip)]
+	pub(crate) nep6_extra: Option<HashMap<String, serde_json::Value>>,
+	/// Fields of the NEP-6 wallet this wallet was imported from that NEP-6 does not define.
+	#[serde(skip)]
+	pub(crate) nep6_unknown_fields: HashMap<String, serde_json::Value>,
+	/// Holds the decrypted keys instead of the accounts once auto-lock is enabled.
+	#[serde(skip)]
+	pub(crate) auto_lock: Option<Arc<AutoLock>>,
 }
 
 impl WalletTrait for Wallet {
@@ -53,8 +64,10 @@ impl WalletTrait for Wallet {
 		self.version = version;
 	}
 
-	fn set_scrypt_params(&mut self, params: ScryptParamsDef) {
+	fn set_scrypt_params(&mut self, params: ScryptParamsDef) -> Result<(), WalletError> {
+		Self::validate_scrypt_params(&params)?;
 		self.scrypt_params = params;
+		Ok(())
 	}
 
 	fn set_default_account(&mut self, default_account: H160) {
@@ -64,10 +77,16 @@ impl WalletTrait for Wallet {
 		}
 	}
 
-	fn add_account(&mut self, account: Self::Account) {
+	/// With auto-lock enabled, the decrypted private key of the account is encrypted with the
+	/// password the wallet is unlocked with and moved out of the account. If the wallet is locked
+	/// and the account does not hold the key encrypted, the account is not added and
+	/// [`WalletError::Locked`] is returned.
+	fn add_account(&mut self, mut account: Self::Account) -> Result<(), WalletError> {
 		// let weak_self = Arc::new(&self);
 		// account.set_wallet(Some(Arc::downgrade(weak_self)));
+		self.move_key_to_auto_lock(&mut account, None)?;
 		self.accounts.insert(account.get_script_hash().clone(), account);
+		Ok(())
 	}
 
 	fn remove_account(&mut self, hash: &H160) -> Option<Self::Account> {
@@ -80,6 +99,11 @@ impl Wallet {
 	pub const DEFAULT_WALLET_NAME: &'static str = "NeoWallet";
 	/// The current wallet version.
 	pub const CURRENT_VERSION: &'static str = "1.0";
+	/// The smallest accepted scrypt cost parameter, as the base 2 logarithm of N.
+	pub const MIN_SCRYPT_LOG_N: u8 = 1;
+	/// The largest accepted scrypt cost parameter, as the base 2 logarithm of N. Above this,
+	/// key derivation needs gigabytes of memory.
+	pub const MAX_SCRYPT_LOG_N: u8 = 20;
 
 	/// Creates a new wallet instance with a default account.
 	pub fn new() -> Self {
@@ -93,6 +117,9 @@ impl Wallet {
 			scrypt_params: ScryptParamsDef::default(),
 			accounts,
 			default_account: account.clone().address_or_scripthash.script_hash(),
+			nep6_extra: None,
+			nep6_unknown_fields: HashMap::new(),
+			auto_lock: None,
 		}
 	}
 
@@ -104,47 +131,125 @@ impl Wallet {
 			scrypt_params: ScryptParamsDef::default(),
 			accounts: HashMap::new(),
 			default_account: H160::default(),
+			nep6_extra: None,
+			nep6_unknown_fields: HashMap::new(),
+			auto_lock: None,
 		}
 	}
 
 	/// Converts the wallet to a NEP6Wallet format.
 	pub fn to_nep6(&self) -> Result<NEP6Wallet, WalletError> {
+		let accounts = self
+			.accounts
+			.values()
+			.map(|account| {
+				let mut nep6_account = NEP6Account::from_account(account)?;
+				nep6_account.is_default = account.get_script_hash() == self.default_account;
+				Ok(nep6_account)
+			})
+			.collect::<Result<Vec<NEP6Account>, WalletError>>()?;
+
 		Ok(NEP6Wallet {
 			name: self.name.clone(),
 			version: self.version.clone(),
 			scrypt: self.scrypt_params.clone(),
-			accounts: self
-				.accounts
-				.clone()
-				.into_iter()
-				.map(|(_, account)| NEP6Account::from_account(&account).unwrap())
-				.collect::<Vec<NEP6Account>>(),
-			extra: None,
+			accounts,
+			extra: self.nep6_extra.clone(),
+			unknown_fields: self.nep6_unknown_fields.clone(),
 		})
 	}
 
 	/// Creates a wallet from a NEP6Wallet format.
+	///
+	/// The first account marked `isDefault` becomes the default account. Wallets written by
+	/// software that does not mark one, e.g. neo-cli, default to their first account.
 	pub fn from_nep6(nep6: NEP6Wallet) -> Result<Self, WalletError> {
-		let accounts = nep6
-			.accounts()
-			.into_iter()
-			.filter_map(|v| v.to_account().ok())
-			.collect::<Vec<_>>();
-
-		let default_account = nep6
-			.accounts()
-			.iter()
-			.find(|a| a.is_default)
-			.map(|a| a.address())
-			.ok_or(WalletError::NoDefaultAccount)
-			.unwrap();
+		let mut accounts = HashMap::new();
+		let mut first_account = None;
+		let mut default_account = None;
+		for (index, nep6_account) in nep6.accounts().iter().enumerate() {
+			let account = Self::account_from_nep6(index, nep6_account)?;
+			let script_hash = account.get_script_hash();
+			first_account.get_or_insert(script_hash);
+			if default_account.is_none() && nep6_account.is_default {
+				default_account = Some(script_hash);
+			}
+			accounts.insert(script_hash, account);
+		}
+		let default_account = default_account.or(first_account).unwrap_or_default();
 
 		Ok(Self {
 			name: nep6.name().clone(),
 			version: nep6.version().clone(),
 			scrypt_params: nep6.scrypt().clone(),
-			accounts: accounts.into_iter().map(|a| (a.get_script_hash().clone(), a)).collect(),
-			default_account: default_account.address_to_script_hash().unwrap(),
+			accounts: accounts
+				.into_iter()
+				.map(|(script_hash, mut account)| {
+					account.is_default = script_hash == default_account;
+					(script_hash, account)
+				})
+				.collect(),
+			default_account,
+			nep6_extra: nep6.extra().clone(),
+			nep6_unknown_fields: nep6.unknown_fields().clone(),
+			auto_lock: None,
+		})
+	}
+
+	/// Imports a NEP-6 wallet from its JSON, as written by neo-cli, Neo GUI, neon-js and other
+	/// wallet software.
+	///
+	/// Fields that NEP-6 does not define are kept and written back by [`Self::save_to_file`].
+	/// On failure the returned [`WalletError::InvalidNep6`] names the account and, where known,
+	/// the field that could not be read.
+	pub fn import_nep6_json(json: &str) -> Result<Self, WalletError> {
+		let invalid = |location: &str, message: String| WalletError::InvalidNep6 {
+			location: location.to_string(),
+			message,
+		};
+
+		let mut value: serde_json::Value =
+			serde_json::from_str(json).map_err(|e| invalid("wallet", e.to_string()))?;
+		let object = value
+			.as_object_mut()
+			.ok_or_else(|| invalid("wallet", "expected a JSON object".to_string()))?;
+		let accounts = object.insert("accounts".to_string(), serde_json::Value::Array(vec![]));
+		let accounts = match accounts {
+			Some(serde_json::Value::Array(accounts)) => accounts,
+			Some(_) => return Err(invalid("accounts", "expected an array".to_string())),
+			None => return Err(invalid("accounts", "missing field".to_string())),
+		};
+
+		let mut nep6: NEP6Wallet =
+			serde_json::from_value(value).map_err(|e| invalid("wallet", e.to_string()))?;
+		for (index, account) in accounts.into_iter().enumerate() {
+			let account: NEP6Account = serde_json::from_value(account)
+				.map_err(|e| invalid(&format!("accounts[{index}]"), e.to_string()))?;
+			nep6.accounts.push(account);
+		}
+
+		Self::validate_scrypt_params(nep6.scrypt())?;
+		Self::from_nep6(nep6)
+	}
+
+	/// Reads a NEP-6 wallet file. See [`Self::import_nep6_json`].
+	pub fn load_from_file(path: PathBuf) -> Result<Self, WalletError> {
+		let json = std::fs::read_to_string(path)?;
+		Self::import_nep6_json(&json)
+	}
+
+	/// Converts the NEP-6 account at `index` of a wallet, naming the account in errors.
+	fn account_from_nep6(index: usize, nep6_account: &NEP6Account) -> Result<Account, WalletError> {
+		nep6_account
+			.address()
+			.address_to_script_hash()
+			.map_err(|e| WalletError::InvalidNep6 {
+				location: format!("accounts[{index}].address"),
+				message: e.to_string(),
+			})?;
+		nep6_account.to_account().map_err(|e| WalletError::InvalidNep6 {
+			location: format!("accounts[{index}] ({})", nep6_account.address()),
+			message: e.to_string(),
 		})
 	}
 
@@ -162,7 +267,7 @@ impl Wallet {
 
 	pub fn from_account(account: &Account) -> Result<Wallet, WalletError> {
 		let mut wallet: Wallet = Wallet::new();
-		wallet.add_account(account.clone());
+		wallet.add_account(account.clone())?;
 		wallet.set_default_account(account.get_script_hash());
 		Ok(wallet)
 	}
@@ -203,7 +308,7 @@ impl Wallet {
 
 		let mut wallet: Wallet = Wallet::default();
 		for account in &accounts {
-			wallet.add_account(account.clone());
+			wallet.add_account(account.clone())?;
 			// account.wallet = Some(self);
 		}
 		wallet.set_default_account(accounts.first().unwrap().get_script_hash());
@@ -212,14 +317,14 @@ impl Wallet {
 
 	pub fn save_to_file(&self, path: PathBuf) -> Result<(), WalletError> {
 		// Convert wallet to NEP6
-		let nep6 = self.to_nep6().unwrap();
+		let nep6 = self.to_nep6()?;
 
 		// Encode as JSON
-		let json = serde_json::to_string(&nep6).unwrap();
+		let json = serde_json::to_string(&nep6).map_err(std::io::Error::from)?;
 
 		// Write to file at path
-		let mut file = File::create(path).unwrap();
-		file.write_all(json.as_bytes()).unwrap();
+		let mut file = File::create(path)?;
+		file.write_all(json.as_bytes())?;
 
 		Ok(())
 	}
@@ -232,10 +337,240 @@ impl Wallet {
 		self.accounts.remove(script_hash).is_some()
 	}
 
-	pub fn encrypt_accounts(&mut self, password: &str) {
-		for account in self.accounts.values_mut() {
-			account.encrypt_private_key(password).expect("Failed to encrypt private key");
+	/// Encrypts the private keys of all accounts with the wallet's scrypt parameters.
+	///
+	/// Accounts without a decrypted private key, such as watch-only or already encrypted
+	/// accounts, are skipped. With auto-lock enabled, the accounts only hold their keys encrypted,
+	/// so they are all skipped. No account is changed if any key fails to encrypt.
+	pub fn encrypt_accounts(&mut self, password: &str) -> Result<(), WalletError> {
+		let encrypted = self
+			.accounts
+			.iter()
+			.filter(|(_, account)| account.key_pair.is_some())
+			.map(|(script_hash, account)| {
+				let mut account = account.clone();
+				account
+					.encrypt_private_key_with_params(password, &self.scrypt_params)
+					.map_err(|e| WalletError::AccountState(e.to_string()))?;
+				Ok((*script_hash, account))
+			})
+			.collect::<Result<Vec<_>, WalletError>>()?;
+		self.accounts.extend(encrypted);
+		Ok(())
+	}
+
+	/// Creates a new account, encrypts its private key with the wallet's scrypt parameters and
+	/// adds it to the wallet.
+	pub fn create_account(&mut self, password: &str) -> Result<&Account, WalletError> {
+		let mut account =
+			Account::create().map_err(|e| WalletError::AccountState(e.to_string()))?;
+		account
+			.encrypt_private_key_with_params(password, &self.scrypt_params)
+			.map_err(|e| WalletError::AccountState(e.to_string()))?;
+		let script_hash = account.get_script_hash();
+		self.add_account(account)?;
+		Ok(&self.accounts[&script_hash])
+	}
+
+	/// Decrypts the private key of the account with the given script hash, using the wallet's
+	/// scrypt parameters.
+	pub fn decrypt_account(
+		&mut self,
+		script_hash: &H160,
+		password: &str,
+	) -> Result<(), WalletError> {
+		let account = self
+			.accounts
+			.get_mut(script_hash)
+			.ok_or(WalletError::AccountState("Account not found in the wallet".to_string()))?;
+		if let Some(auto_lock) = &self.auto_lock {
+			let key_pair = Self::decrypt_key_pair(account, password, &self.scrypt_params)?;
+			auto_lock.unlock(HashMap::from([(*script_hash, key_pair)]), Some(password));
+			return Ok(());
 		}
+		account
+			.decrypt_private_key_with_params(password, &self.scrypt_params)
+			.map_err(|e| WalletError::AccountState(e.to_string()))
+	}
+
+	/// Enables auto-lock: the decrypted private keys are moved out of the accounts and dropped
+	/// once `timeout` has passed without signing. Signing then fails with
+	/// [`WalletError::Locked`] until [`Self::unlock`] is called with the password. Each signature
+	/// restarts the timer.
+	///
+	/// Clones of the wallet share the decrypted keys, so they can be used from several tasks.
+	/// Every account holding a decrypted key must also hold it encrypted, otherwise the key would
+	/// be lost when the wallet locks. Accounts added later are encrypted when they are added, with
+	/// the password the wallet is unlocked with.
+	pub fn with_auto_lock(mut self, timeout: Duration) -> Result<Self, WalletError> {
+		if let Some(account) = self
+			.accounts
+			.values()
+			.find(|a| a.key_pair.is_some() && a.encrypted_private_key.is_none())
+		{
+			return Err(WalletError::AccountState(format!(
+				"The private key of {} must be encrypted before auto-lock is enabled",
+				account.get_address()
+			)));
+		}
+
+		let keys = self
+			.accounts
+			.iter_mut()
+			.filter_map(|(script_hash, account)| Some((*script_hash, account.key_pair.take()?)))
+			.collect();
+		let auto_lock = AutoLock::new(timeout);
+		auto_lock.unlock(keys, None);
+		self.auto_lock = Some(auto_lock);
+		Ok(self)
+	}
+
+	/// Decrypts the private keys of all accounts for signing and restarts the auto-lock timer.
+	/// Accounts without an encrypted private key are skipped. Nothing is unlocked if any key
+	/// fails to decrypt.
+	pub fn unlock(&self, password: &str) -> Result<(), WalletError> {
+		let auto_lock = self.auto_lock.as_ref().ok_or(WalletError::AccountState(
+			"Auto-lock is not enabled for this wallet".to_string(),
+		))?;
+		let keys = self
+			.accounts
+			.iter()
+			.filter(|(_, account)| account.encrypted_private_key.is_some())
+			.map(|(script_hash, account)| {
+				Ok((*script_hash, Self::decrypt_key_pair(account, password, &self.scrypt_params)?))
+			})
+			.collect::<Result<HashMap<_, _>, WalletError>>()?;
+		auto_lock.unlock(keys, Some(password));
+		Ok(())
+	}
+
+	/// Drops the decrypted private keys of a wallet with auto-lock enabled right away.
+	pub fn lock(&self) {
+		if let Some(auto_lock) = &self.auto_lock {
+			auto_lock.lock();
+		}
+	}
+
+	/// Whether auto-lock is enabled and the decrypted private keys have been dropped.
+	pub fn is_locked(&self) -> bool {
+		self.auto_lock.as_ref().map_or(false, |auto_lock| auto_lock.is_locked())
+	}
+
+	/// With auto-lock enabled, moves the decrypted private key of `account` into the auto-lock.
+	/// If the account does not hold the key encrypted yet, it is encrypted first with `password`,
+	/// or else with the password the wallet is unlocked with.
+	fn move_key_to_auto_lock(
+		&self,
+		account: &mut Account,
+		password: Option<&str>,
+	) -> Result<(), WalletError> {
+		let (Some(auto_lock), Some(key_pair)) = (&self.auto_lock, account.key_pair.clone()) else {
+			return Ok(());
+		};
+		if account.encrypted_private_key.is_none() {
+			let unlocked = auto_lock.password();
+			let password = password
+				.or(unlocked.as_deref().map(String::as_str))
+				.ok_or(WalletError::Locked)?;
+			account
+				.encrypt_private_key_with_params(password, &self.scrypt_params)
+				.map_err(|e| WalletError::AccountState(e.to_string()))?;
+		}
+		account.key_pair = None;
+		auto_lock.unlock(HashMap::from([(account.get_script_hash(), key_pair)]), None);
+		Ok(())
+	}
+
+	fn decrypt_key_pair(
+		account: &Account,
+		password: &str,
+		params: &ScryptParamsDef,
+	) -> Result<KeyPair, WalletError> {
+		let mut account = account.clone();
+		account
+			.decrypt_private_key_with_params(password, params)
+			.map_err(|e| WalletError::AccountState(e.to_string()))?;
+		account.key_pair.take().ok_or(WalletError::NoKeyPair)
+	}
+
+	/// The key pair of the default account. With auto-lock enabled, it comes from the unlocked
+	/// keys and using it restarts the timer.
+	fn default_key_pair(&self) -> Result<KeyPair, WalletError> {
+		match &self.auto_lock {
+			Some(auto_lock) => auto_lock.key_pair(&self.default_account),
+			None => self.default_account().key_pair.clone().ok_or(WalletError::NoKeyPair),
+		}
+	}
+
+	/// Imports a batch of private keys, each given as a WIF or a NEP-2 string with its password.
+	///
+	/// Strings starting with `6P` are treated as NEP-2 and need a password. NEP-2 keys are
+	/// decrypted with the wallet's scrypt parameters to check the password and are then stored
+	/// encrypted as given. WIF keys are stored decrypted, unless auto-lock is enabled: they are
+	/// then encrypted with the given password, or else the one the wallet is unlocked with, and
+	/// fail with [`WalletError::Locked`] without either. Every key gets its own result, so one bad
+	/// key does not abort the batch. Keys of accounts that are already in the wallet are not
+	/// imported again and return an error.
+	pub fn import_keys(
+		&mut self,
+		keys: &[(String, Option<String>)],
+	) -> Vec<Result<H160, WalletError>> {
+		keys.iter()
+			.map(|(secret, password)| {
+				let mut account = self.account_from_secret(secret, password.as_deref())?;
+				let script_hash = account.get_script_hash();
+				if self.accounts.contains_key(&script_hash) {
+					return Err(WalletError::AccountState(format!(
+						"The account {} is already in the wallet",
+						account.get_address()
+					)));
+				}
+				self.move_key_to_auto_lock(&mut account, password.as_deref())?;
+				self.add_account(account)?;
+				Ok(script_hash)
+			})
+			.collect()
+	}
+
+	fn account_from_secret(
+		&self,
+		secret: &str,
+		password: Option<&str>,
+	) -> Result<Account, WalletError> {
+		if !secret.starts_with("6P") {
+			let key_pair = KeyPair::from_secret_key(&private_key_from_wif(secret)?);
+			return Account::from_key_pair(key_pair, None, None)
+				.map_err(|e| WalletError::AccountState(e.to_string()));
+		}
+
+		let password = password.ok_or(WalletError::CryptoError(CryptoError::InvalidPassphrase(
+			"A password is required to import a NEP-2 key".to_string(),
+		)))?;
+		let private_key =
+			get_private_key_from_nep2_with_params(secret, password, &self.scrypt_params)
+				.map_err(|e| WalletError::AccountState(e.to_string()))?;
+		let private_key =
+			vec_to_array32(private_key).map_err(|e| WalletError::AccountState(e.to_string()))?;
+		let key_pair = KeyPair::from_private_key(&private_key)?;
+		let mut account = Account::from_key_pair(key_pair, None, None)
+			.map_err(|e| WalletError::AccountState(e.to_string()))?;
+		account.encrypted_private_key = Some(secret.to_string());
+		account.key_pair = None;
+		Ok(account)
+	}
+
+	fn validate_scrypt_params(params: &ScryptParamsDef) -> Result<(), WalletError> {
+		if !(Self::MIN_SCRYPT_LOG_N..=Self::MAX_SCRYPT_LOG_N).contains(&params.log_n) {
+			return Err(WalletError::InvalidScryptParams(format!(
+				"log_n must be between {} and {}, got {}",
+				Self::MIN_SCRYPT_LOG_N,
+				Self::MAX_SCRYPT_LOG_N,
+				params.log_n
+			)));
+		}
+		scrypt::Params::new(params.log_n, params.r, params.p, 32)
+			.map_err(|e| WalletError::InvalidScryptParams(e.to_string()))?;
+		Ok(())
 	}
 }
 
@@ -275,11 +610,7 @@ impl Wallet {
 		let message = message.as_ref();
 		let binding = message.hash256();
 		let message_hash = binding.as_slice();
-		self.default_account()
-			.clone()
-			.key_pair()
-			.clone()
-			.unwrap()
+		self.default_key_pair()?
 			.private_key()
 			.sign_tx(message_hash)
 			.map_err(|_e| WalletError::NoKeyPair)
@@ -323,11 +654,8 @@ impl Wallet {
 			// tx_with_chain.set_network(Some(self.network()));
 		}
 
-		Witness::create(
-			tx.get_hash_data().await?,
-			&self.default_account().key_pair.clone().unwrap(),
-		)
-		.map_err(|_e| WalletError::NoKeyPair)
+		let hash_data = tx.get_hash_data().await?;
+		Witness::create(hash_data, &self.default_key_pair()?).map_err(|_e| WalletError::NoKeyPair)
 	}
 
 	/// Returns the address of the wallet's default account.
@@ -383,13 +711,21 @@ impl Wallet {
 
 #[cfg(test)]
 mod tests {
-	use neo::prelude::{Account, AccountTrait, TestConstants, Wallet, WalletTrait};
+	use std::{fs, path::PathBuf, process, time::Duration};
+
+	use primitive_types::H160;
+
+	use neo::prelude::{
+		get_nep2_from_private_key_with_params, get_private_key_from_nep2_with_params, Account,
+		AccountTrait, ScriptHashExtension, ScryptParamsDef, TestConstants, Wallet, WalletError,
+		WalletTrait,
+	};
 
 	#[test]
 	fn test_is_default() {
 		let account = Account::from_address(TestConstants::DEFAULT_ACCOUNT_ADDRESS).unwrap();
 		let mut wallet: Wallet = Wallet::new();
-		wallet.add_account(account.clone());
+		wallet.add_account(account.clone()).unwrap();
 
 		assert!(!account.is_default);
 
@@ -452,7 +788,7 @@ mod tests {
 		let account = Account::create().unwrap();
 		let mut wallet: Wallet = Wallet::new();
 
-		wallet.add_account(account.clone());
+		wallet.add_account(account.clone()).unwrap();
 
 		assert_eq!(wallet.accounts.len(), 2);
 		assert_eq!(
@@ -461,17 +797,393 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn test_create_account_with_wallet_scrypt_params() {
+		let mut wallet: Wallet = Wallet::default();
+		let params = ScryptParamsDef { log_n: 4, r: 1, p: 1 };
+		wallet.set_scrypt_params(params.clone()).unwrap();
+		assert_eq!(wallet.scrypt_params(), &params);
+
+		let script_hash = wallet.create_account("pw").unwrap().get_script_hash();
+		let account = wallet.get_account(&script_hash).unwrap().clone();
+		assert!(account.key_pair().is_none());
+
+		// The key only decrypts with the wallet's parameters
+		let encrypted = account.encrypted_private_key().clone().unwrap();
+		assert!(get_private_key_from_nep2_with_params(&encrypted, "pw", &params).is_ok());
+		wallet.decrypt_account(&script_hash, "pw").unwrap();
+		assert!(wallet.get_account(&script_hash).unwrap().key_pair().is_some());
+
+		assert_eq!(wallet.to_nep6().unwrap().scrypt(), &params);
+	}
+
+	#[test]
+	fn test_set_invalid_scrypt_params() {
+		let mut wallet: Wallet = Wallet::default();
+
+		assert!(matches!(
+			wallet.set_scrypt_params(ScryptParamsDef { log_n: 0, r: 8, p: 8 }),
+			Err(WalletError::InvalidScryptParams(_))
+		));
+		assert!(matches!(
+			wallet.set_scrypt_params(ScryptParamsDef { log_n: 32, r: 8, p: 8 }),
+			Err(WalletError::InvalidScryptParams(_))
+		));
+		assert_eq!(wallet.scrypt_params(), &ScryptParamsDef::default());
+	}
+
+	#[test]
+	fn test_import_keys() {
+		let mut wallet: Wallet = Wallet::default();
+
+		let results = wallet.import_keys(&[
+			(TestConstants::CLIENT1_ACCOUNT_WIF.to_string(), None),
+			(
+				TestConstants::DEFAULT_ACCOUNT_ENCRYPTED_PRIVATE_KEY.to_string(),
+				Some(TestConstants::DEFAULT_ACCOUNT_PASSWORD.to_string()),
+			),
+			("not a key".to_string(), None),
+			(TestConstants::DEFAULT_ACCOUNT_WIF.to_string(), None),
+		]);
+
+		assert_eq!(results.len(), 4);
+		let client1 = Account::from_wif(TestConstants::CLIENT1_ACCOUNT_WIF).unwrap();
+		assert_eq!(results[0].as_ref().unwrap(), &client1.get_script_hash());
+		let default_hash = H160::from_address(TestConstants::DEFAULT_ACCOUNT_ADDRESS).unwrap();
+		assert_eq!(results[1].as_ref().unwrap(), &default_hash);
+		assert!(results[2].is_err());
+		// The WIF belongs to the account imported from NEP-2 above
+		assert!(matches!(results[3], Err(WalletError::AccountState(_))));
+
+		assert_eq!(wallet.accounts.len(), 2);
+		let imported = wallet.get_account(&default_hash).unwrap();
+		assert!(imported.key_pair().is_none());
+		assert_eq!(
+			imported.encrypted_private_key().as_deref(),
+			Some(TestConstants::DEFAULT_ACCOUNT_ENCRYPTED_PRIVATE_KEY)
+		);
+	}
+
+	#[test]
+	fn test_import_keys_with_wallet_scrypt_params() {
+		let params = ScryptParamsDef { log_n: 4, r: 1, p: 1 };
+		let nep2 = get_nep2_from_private_key_with_params(
+			TestConstants::DEFAULT_ACCOUNT_PRIVATE_KEY,
+			TestConstants::DEFAULT_ACCOUNT_PASSWORD,
+			&params,
+		)
+		.unwrap();
+		let password = Some(TestConstants::DEFAULT_ACCOUNT_PASSWORD.to_string());
+
+		// Decrypting with the standard parameters derives another key, so the check fails
+		let mut standard = Wallet::default();
+		assert!(standard.import_keys(&[(nep2.clone(), password.clone())])[0].is_err());
+
+		let mut wallet = Wallet::default();
+		wallet.set_scrypt_params(params).unwrap();
+		let results = wallet.import_keys(&[(nep2.clone(), password)]);
+		let default_hash = H160::from_address(TestConstants::DEFAULT_ACCOUNT_ADDRESS).unwrap();
+		assert_eq!(results[0].as_ref().unwrap(), &default_hash);
+		assert_eq!(
+			wallet.get_account(&default_hash).unwrap().encrypted_private_key().as_deref(),
+			Some(nep2.as_str())
+		);
+	}
+
 	#[test]
 	fn test_encrypt_wallet() {
 		let mut wallet: Wallet = Wallet::new();
-		wallet.add_account(Account::create().unwrap());
+		wallet.add_account(Account::create().unwrap()).unwrap();
 
 		assert!(wallet.accounts()[0].key_pair().is_some());
 		assert!(wallet.accounts()[1].key_pair().is_some());
 
-		wallet.encrypt_accounts("pw");
+		wallet.encrypt_accounts("pw").unwrap();
 
 		assert!(wallet.accounts()[0].key_pair().is_none());
 		assert!(wallet.accounts()[1].key_pair().is_none());
 	}
+
+	#[test]
+	fn test_encrypt_mixed_wallet() {
+		let params = ScryptParamsDef { log_n: 4, r: 1, p: 1 };
+		let mut wallet: Wallet = Wallet::new();
+		wallet.set_scrypt_params(params.clone()).unwrap();
+		let plain = wallet.default_account;
+		let watch_only = Account::watch_only(H160::from([1u8; 20]));
+		let watch_only_hash = watch_only.get_script_hash();
+		wallet.add_account(watch_only).unwrap();
+		let mut encrypted = Account::create().unwrap();
+		encrypted.encrypt_private_key_with_params("other", &params).unwrap();
+		let encrypted_hash = encrypted.get_script_hash();
+		let nep2 = encrypted.encrypted_private_key().clone();
+		wallet.add_account(encrypted).unwrap();
+
+		wallet.encrypt_accounts("pw").unwrap();
+
+		let mut account = wallet.get_account(&plain).unwrap().clone();
+		assert!(account.key_pair().is_none());
+		account.decrypt_private_key_with_params("pw", &params).unwrap();
+		let account = wallet.get_account(&watch_only_hash).unwrap();
+		assert!(account.key_pair().is_none());
+		assert!(account.encrypted_private_key().is_none());
+		assert_eq!(wallet.get_account(&encrypted_hash).unwrap().encrypted_private_key(), &nep2);
+	}
+
+	#[test]
+	fn test_import_nep6_json_from_other_wallets() {
+		// Shaped like a neo-cli wallet: N rather than log2(N), no default account, extra data
+		// that is not a string map and fields NEP-6 does not define
+		let json = r#"{
+			"name": "neo-cli",
+			"version": "1.0",
+			"scrypt": {"n": 16384, "r": 8, "p": 8},
+			"accounts": [
+				{
+					"address": "NM7Aky765FG8NhhwtxjXRx7jEL1cnw7PBP",
+					"label": null,
+					"isdefault": false,
+					"key": "6PYM7jHL4GmS8Aw2iEFpuaHTCUKjhT4mwVqdoozGU6sUE25BjV4ePXDdLz",
+					"contract": {
+						"script": "DCEDOk0FGwS3/AIw0rGq7f1ahL4nmlNhpzWNtmWteFd4fxtBVuezJw==",
+						"parameters": [{"name": "signature", "type": "Signature"}]
+					},
+					"extra": {"color": 3, "tags": ["savings"]},
+					"tokens": ["0xd2a4cff31913016155e38e474a2c06d08be276cf"]
+				},
+				{
+					"address": "NXXazKH39yNFWWZF5MJ8tEN98VYHwzn7g3",
+					"label": "watch only",
+					"lock": true,
+					"contract": null,
+					"extra": null
+				}
+			],
+			"extra": {"network": 860833102},
+			"tokens": []
+		}"#;
+
+		let wallet = Wallet::import_nep6_json(json).unwrap();
+
+		assert_eq!(wallet.scrypt_params(), &ScryptParamsDef::default());
+		assert_eq!(wallet.accounts.len(), 2);
+		let default_hash = H160::from_address(TestConstants::DEFAULT_ACCOUNT_ADDRESS).unwrap();
+		assert_eq!(wallet.default_account().get_script_hash(), default_hash);
+		assert!(wallet.default_account().is_default);
+		let committee_hash = H160::from_address(TestConstants::COMMITTEE_ACCOUNT_ADDRESS).unwrap();
+		let watch_only = wallet.get_account(&committee_hash).unwrap();
+		assert!(watch_only.is_locked);
+		assert!(!watch_only.is_default);
+
+		// The extra data and unknown fields are written back
+		let saved = serde_json::to_value(wallet.to_nep6().unwrap()).unwrap();
+		assert_eq!(saved["extra"], serde_json::json!({"network": 860833102}));
+		assert_eq!(saved["tokens"], serde_json::json!([]));
+		let saved_default = saved["accounts"]
+			.as_array()
+			.unwrap()
+			.iter()
+			.find(|a| a["address"] == TestConstants::DEFAULT_ACCOUNT_ADDRESS)
+			.unwrap();
+		assert_eq!(saved_default["isDefault"], true);
+		assert_eq!(saved_default["extra"], serde_json::json!({"color": 3, "tags": ["savings"]}));
+		assert_eq!(
+			saved_default["tokens"],
+			serde_json::json!(["0xd2a4cff31913016155e38e474a2c06d08be276cf"])
+		);
+
+		let reloaded = Wallet::import_nep6_json(&saved.to_string()).unwrap();
+		assert_eq!(reloaded.default_account().get_script_hash(), default_hash);
+	}
+
+	#[test]
+	fn test_save_neo_cli_wallet() {
+		let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+			.join("test_resources/wallet/neo_cli_wallet.json");
+		let wallet = Wallet::load_from_file(path).unwrap();
+		assert_eq!(wallet.scrypt_params(), &ScryptParamsDef::default());
+
+		let saved = std::env::temp_dir().join(format!("neo_cli_wallet_{}.json", process::id()));
+		wallet.save_to_file(saved.clone()).unwrap();
+		let json: serde_json::Value =
+			serde_json::from_str(&fs::read_to_string(&saved).unwrap()).unwrap();
+		let reloaded = Wallet::load_from_file(saved.clone());
+		fs::remove_file(&saved).unwrap();
+
+		// neo-cli reads N, not its logarithm
+		assert_eq!(json["scrypt"], serde_json::json!({"n": 16384, "r": 8, "p": 8}));
+		let reloaded = reloaded.unwrap();
+		assert_eq!(reloaded.scrypt_params(), wallet.scrypt_params());
+		let default_hash = H160::from_address(TestConstants::DEFAULT_ACCOUNT_ADDRESS).unwrap();
+		let key = reloaded.get_account(&default_hash).unwrap().encrypted_private_key().clone();
+		let private_key = get_private_key_from_nep2_with_params(
+			&key.unwrap(),
+			TestConstants::DEFAULT_ACCOUNT_PASSWORD,
+			reloaded.scrypt_params(),
+		)
+		.unwrap();
+		assert_eq!(hex::encode(private_key), TestConstants::DEFAULT_ACCOUNT_PRIVATE_KEY);
+	}
+
+	#[test]
+	fn test_save_to_file_reports_io_errors() {
+		let missing = std::env::temp_dir().join("missing_directory").join("wallet.json");
+		assert!(matches!(Wallet::default().save_to_file(missing), Err(WalletError::IoError(_))));
+	}
+
+	#[test]
+	fn test_import_nep6_json_reports_failing_account() {
+		let wallet = |accounts: &str| {
+			let scrypt = r#""scrypt":{"n":16384,"r":8,"p":8}"#;
+			format!(r#"{{"name":"w","version":"1.0",{scrypt},"accounts":{accounts}}}"#)
+		};
+
+		let err = Wallet::import_nep6_json(&wallet(
+			r#"[{"address":"NM7Aky765FG8NhhwtxjXRx7jEL1cnw7PBP"},{"address":"not an address"}]"#,
+		))
+		.unwrap_err();
+		assert!(matches!(
+			&err,
+			WalletError::InvalidNep6 { location, .. } if location == "accounts[1].address"
+		));
+
+		let err = Wallet::import_nep6_json(&wallet(r#"[{"label":"no address"}]"#)).unwrap_err();
+		match err {
+			WalletError::InvalidNep6 { location, message } => {
+				assert_eq!(location, "accounts[0]");
+				assert!(message.contains("address"));
+			},
+			err => panic!("unexpected error {err}"),
+		}
+
+		let err = Wallet::import_nep6_json(&wallet(r#"{}"#)).unwrap_err();
+		assert!(
+			matches!(&err, WalletError::InvalidNep6 { location, .. } if location == "accounts")
+		);
+
+		let json = wallet("[]").replace("16384", "1000");
+		assert!(matches!(
+			Wallet::import_nep6_json(&json),
+			Err(WalletError::InvalidNep6 { location, .. }) if location == "wallet"
+		));
+	}
+
+	#[tokio::test]
+	async fn test_auto_lock() {
+		tokio::time::pause();
+		let mut wallet: Wallet = Wallet::default();
+		wallet.set_scrypt_params(ScryptParamsDef { log_n: 4, r: 1, p: 1 }).unwrap();
+		let script_hash = wallet.create_account("pw").unwrap().get_script_hash();
+		wallet.set_default_account(script_hash);
+		let wallet = wallet.with_auto_lock(Duration::from_millis(500)).unwrap();
+
+		assert!(wallet.is_locked());
+		assert!(matches!(wallet.sign_message("msg").await, Err(WalletError::Locked)));
+		assert!(wallet.unlock("wrong").is_err());
+		assert!(wallet.is_locked());
+
+		wallet.unlock("pw").unwrap();
+		assert!(!wallet.is_locked());
+		// Signing restarts the timer
+		tokio::time::advance(Duration::from_millis(300)).await;
+		wallet.sign_message("msg").await.unwrap();
+		tokio::time::advance(Duration::from_millis(300)).await;
+		wallet.sign_message("msg").await.unwrap();
+
+		tokio::time::advance(Duration::from_millis(500)).await;
+		assert!(wallet.is_locked());
+		assert!(matches!(wallet.sign_message("msg").await, Err(WalletError::Locked)));
+		// The decrypted key is never stored in the account
+		assert!(wallet.get_account(&script_hash).unwrap().key_pair().is_none());
+
+		// Clones share the decrypted keys
+		let clone = wallet.clone();
+		clone.unlock("pw").unwrap();
+		assert!(!wallet.is_locked());
+		clone.lock();
+		assert!(wallet.is_locked());
+
+		wallet.clone().decrypt_account(&script_hash, "pw").unwrap();
+		wallet.sign_message("msg").await.unwrap();
+	}
+
+	#[tokio::test]
+	async fn test_auto_lock_encrypts_added_accounts() {
+		tokio::time::pause();
+		let mut wallet: Wallet = Wallet::default();
+		wallet.set_scrypt_params(ScryptParamsDef { log_n: 4, r: 1, p: 1 }).unwrap();
+		wallet.create_account("pw").unwrap();
+		let mut wallet = wallet.with_auto_lock(Duration::from_millis(500)).unwrap();
+
+		// Locked, the wallet has no password to encrypt a WIF key with
+		let results = wallet.import_keys(&[(TestConstants::DEFAULT_ACCOUNT_WIF.to_string(), None)]);
+		assert!(matches!(results[0], Err(WalletError::Locked)));
+
+		wallet.unlock("pw").unwrap();
+		let account = Account::create().unwrap();
+		let added = account.get_script_hash();
+		wallet.add_account(account).unwrap();
+		let imported = wallet
+			.import_keys(&[(TestConstants::DEFAULT_ACCOUNT_WIF.to_string(), None)])
+			.remove(0)
+			.unwrap();
+
+		for script_hash in [added, imported] {
+			let account = wallet.get_account(&script_hash).unwrap();
+			assert!(account.key_pair().is_none());
+			let mut account = account.clone();
+			account.decrypt_private_key_with_params("pw", &wallet.scrypt_params).unwrap();
+		}
+		wallet.set_default_account(added);
+		wallet.sign_message("msg").await.unwrap();
+
+		tokio::time::advance(Duration::from_millis(500)).await;
+		assert!(matches!(wallet.sign_message("msg").await, Err(WalletError::Locked)));
+	}
+
+	#[tokio::test]
+	async fn test_auto_lock_rejects_plaintext_keys_while_locked() {
+		tokio::time::pause();
+		let mut wallet: Wallet = Wallet::default();
+		wallet.set_scrypt_params(ScryptParamsDef { log_n: 4, r: 1, p: 1 }).unwrap();
+		let script_hash = wallet.create_account("pw").unwrap().get_script_hash();
+		wallet.set_default_account(script_hash);
+		let mut wallet = wallet.with_auto_lock(Duration::from_millis(500)).unwrap();
+
+		let account = Account::create().unwrap();
+		let rejected = account.get_script_hash();
+		assert!(matches!(wallet.add_account(account), Err(WalletError::Locked)));
+		assert!(wallet.get_account(&rejected).is_none());
+		// Watch-only accounts hold no key and can still be added
+		let watch_only = Account::watch_only(H160::from([1u8; 20]));
+		wallet.add_account(watch_only).unwrap();
+
+		wallet.encrypt_accounts("pw").unwrap();
+		wallet.unlock("pw").unwrap();
+		wallet.add_account(Account::create().unwrap()).unwrap();
+		wallet.encrypt_accounts("pw").unwrap();
+		assert!(wallet.accounts.values().all(|account| account.key_pair().is_none()));
+		wallet.sign_message("msg").await.unwrap();
+
+		wallet.lock();
+		wallet.unlock("pw").unwrap();
+		wallet.sign_message("msg").await.unwrap();
+	}
+
+	#[test]
+	fn test_auto_lock_requires_encrypted_keys() {
+		let wallet: Wallet = Wallet::new();
+		assert!(matches!(
+			wallet.with_auto_lock(Duration::from_secs(60)),
+			Err(WalletError::AccountState(_))
+		));
+
+		let mut wallet: Wallet = Wallet::new();
+		wallet.set_scrypt_params(ScryptParamsDef { log_n: 4, r: 1, p: 1 }).unwrap();
+		wallet.encrypt_accounts("pw").unwrap();
+		let wallet = wallet.with_auto_lock(Duration::from_secs(60)).unwrap();
+		assert!(wallet.is_locked());
+		wallet.unlock("pw").unwrap();
+		assert!(!wallet.is_locked());
+	}
 }
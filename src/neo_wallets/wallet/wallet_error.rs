@@ -24,6 +24,13 @@ use neo::prelude::{BuilderError, CryptoError, TransactionError};
 /// - `CryptoError`: Wraps cryptographic errors, potentially from operations like hashing or encryption.
 /// - `TransactionError`: Encapsulates errors that may occur during transaction creation or processing.
 /// - `BuilderError`: Wraps errors that occur during the construction of complex objects, possibly due to invalid parameters.
+/// - `InvalidScryptParams`: Signals scrypt parameters that are out of bounds.
+/// - `InvalidNep6`: Names the account or field of a NEP-6 wallet that could not be imported.
+/// - `Locked`: Signals that the wallet has to be unlocked before signing.
+/// - `LedgerError`: Wraps failures communicating with a Ledger device.
+/// - `LedgerRejected`: Signals that the user rejected a request on the Ledger device.
+/// - `KmsError`: Wraps failures of AWS KMS requests.
+/// - `YubiHsmError`: Wraps failures connecting to or signing with a YubiHSM2.
 ///
 /// # Examples
 ///
@@ -99,4 +106,41 @@ pub enum WalletError {
 	BuilderError(#[from] BuilderError),
 	#[error("Invalid signature")]
 	VerifyError,
+
+	/// Indicates that scrypt parameters are outside the supported bounds or rejected by the
+	/// scrypt implementation.
+	#[error("Invalid scrypt parameters: {0}")]
+	InvalidScryptParams(String),
+
+	/// Indicates that a BIP-32 derivation path is malformed, e.g. does not start with `m` or
+	/// has a component that is not a valid child index.
+	#[error("Invalid derivation path: {0}")]
+	InvalidDerivationPath(String),
+
+	/// Indicates that a NEP-6 wallet could not be imported. `location` names the part of the
+	/// wallet that failed, e.g. `accounts[1].address`.
+	#[error("Invalid NEP-6 wallet at {location}: {message}")]
+	InvalidNep6 { location: String, message: String },
+
+	/// Indicates that the wallet's auto-lock dropped the decrypted keys, or they were never
+	/// unlocked, so the wallet has to be unlocked with its password before signing.
+	#[error("The wallet is locked")]
+	Locked,
+
+	/// Wraps errors from communicating with a Ledger device, including unexpected status words
+	/// returned by the Neo app.
+	#[error("Ledger error: {0}")]
+	LedgerError(String),
+
+	/// Indicates that the user rejected the request on the Ledger device.
+	#[error("The request was rejected on the Ledger device")]
+	LedgerRejected,
+
+	/// Wraps errors from AWS KMS, e.g. a failed `kms:Sign` call or a key that is not secp256r1.
+	#[error("AWS KMS error: {0}")]
+	KmsError(String),
+
+	/// Wraps errors from connecting to or using a YubiHSM2.
+	#[error("YubiHSM error: {0}")]
+	YubiHsmError(String),
 }
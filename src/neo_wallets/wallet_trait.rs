@@ -1,6 +1,6 @@
 use primitive_types::H160;
 
-use neo::prelude::{AccountTrait, ScryptParamsDef};
+use neo::prelude::{AccountTrait, ScryptParamsDef, WalletError};
 
 /// Represents the core functionalities of a cryptocurrency wallet.
 ///
@@ -76,14 +76,18 @@ pub trait WalletTrait {
 	/// Sets the version of the wallet.
 	fn set_version(&mut self, version: String);
 
-	/// Sets the scrypt parameters for the wallet.
-	fn set_scrypt_params(&mut self, params: ScryptParamsDef);
+	/// Sets the scrypt parameters for the wallet, used when encrypting its accounts.
+	///
+	/// Returns an error and keeps the current parameters if the new ones are out of bounds.
+	fn set_scrypt_params(&mut self, params: ScryptParamsDef) -> Result<(), WalletError>;
 
 	/// Sets the default account of the wallet.
 	fn set_default_account(&mut self, default_account: H160);
 
 	/// Adds a new account to the wallet.
-	fn add_account(&mut self, account: Self::Account);
+	///
+	/// Returns an error and does not add the account if the wallet cannot store it safely.
+	fn add_account(&mut self, account: Self::Account) -> Result<(), WalletError>;
 
 	/// Removes an account from the wallet by its hash.
 	///
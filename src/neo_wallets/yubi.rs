@@ -1,15 +1,19 @@
 //! Helpers for creating wallets for YubiHSM2
 use elliptic_curve::sec1::{FromEncodedPoint, ToEncodedPoint};
 use p256::{NistP256, PublicKey};
+use primitive_types::H256;
 use signature::Verifier;
 use yubihsm::{
 	asymmetric::Algorithm::EcP256, ecdsa::Signer as YubiSigner, object, object::Label, Capability,
-	Client, Connector, Credentials, Domain,
+	Client, Connector, Credentials, Domain, HttpConfig, UsbConfig,
 };
 
 use neo::{
 	neo_clients::public_key_to_address,
-	prelude::{Secp256r1PublicKey, WalletSigner},
+	prelude::{
+		InvocationScript, Secp256r1PublicKey, Secp256r1Signature, VerificationScript, WalletError,
+		WalletSigner, Witness,
+	},
 };
 
 use crate::{crypto::HashableForVec, neo_types::Address};
@@ -22,6 +26,55 @@ impl WalletSigner<YubiSigner<NistP256>> {
 		signer.into()
 	}
 
+	/// Connects to the ECDSA key at the provided id of a YubiHSM2 plugged in over USB
+	pub fn connect_usb(
+		config: &UsbConfig,
+		credentials: Credentials,
+		id: object::Id,
+	) -> Result<Self, WalletError> {
+		Self::try_connect(Connector::usb(config), credentials, id)
+	}
+
+	/// Connects to the ECDSA key at the provided id through `yubihsm-connector` over HTTP
+	pub fn connect_http(
+		config: &HttpConfig,
+		credentials: Credentials,
+		id: object::Id,
+	) -> Result<Self, WalletError> {
+		Self::try_connect(Connector::http(config), credentials, id)
+	}
+
+	fn try_connect(
+		connector: Connector,
+		credentials: Credentials,
+		id: object::Id,
+	) -> Result<Self, WalletError> {
+		let client = Client::open(connector, credentials, true)
+			.map_err(|e| WalletError::YubiHsmError(e.to_string()))?;
+		let signer =
+			YubiSigner::create(client, id).map_err(|e| WalletError::YubiHsmError(e.to_string()))?;
+		Ok(signer.into())
+	}
+
+	/// Returns the public key of the HSM-held key
+	pub fn public_key(&self) -> Secp256r1PublicKey {
+		// the signer only exists for valid P-256 keys
+		let public_key = PublicKey::from_encoded_point(self.signer.public_key()).unwrap();
+		Secp256r1PublicKey::from_bytes(public_key.to_encoded_point(true).as_bytes()).unwrap()
+	}
+
+	/// Signs `message` on the HSM and returns the witness of the key's single-signature
+	/// account, e.g. for the hash data of a transaction. The message is hashed with SHA-256
+	/// before being signed, as for a local key pair.
+	pub fn create_witness(&self, message: &[u8]) -> Result<Witness, WalletError> {
+		let signature = self.sign_hash(H256::from_slice(&message.hash256()))?;
+		let signature = Secp256r1Signature::from_bytes(&signature.to_bytes())?;
+		Ok(Witness::from_scripts_obj(
+			InvocationScript::from_signature(signature),
+			VerificationScript::from_public_key(&self.public_key()),
+		))
+	}
+
 	/// Creates a new random ECDSA keypair on the yubi at the provided id
 	pub fn new(
 		connector: Connector,
@@ -85,7 +138,6 @@ impl From<YubiSigner<NistP256>> for WalletSigner<YubiSigner<NistP256>> {
 		let public_key = PublicKey::from_encoded_point(signer.public_key()).unwrap();
 		let public_key = public_key.to_encoded_point(true);
 		let public_key = public_key.as_bytes();
-		debug_assert_eq!(public_key[0], 0x02);
 		let address = public_key_to_address(&Secp256r1PublicKey::from_bytes(&public_key).unwrap());
 
 		Self { signer, address, network: None }
@@ -144,4 +196,30 @@ pub mod tests {
 			p256::ecdsa::VerifyingKey::from_encoded_point(wallet.signer.public_key()).unwrap();
 		assert!(verify_key.verify(msg.as_bytes(), &sig).is_ok());
 	}
+
+	#[test]
+	fn create_witness() {
+		let key = hex::decode("2d8c44dc2dd2f0bea410e342885379192381e82d855b1b112f9b55544f1e0900")
+			.unwrap();
+		let wallet = WalletSigner::from_key(
+			yubihsm::Connector::mockhsm(),
+			Credentials::default(),
+			0,
+			Label::from_bytes(&[]).unwrap(),
+			Domain::at(1).unwrap(),
+			key,
+		);
+
+		let msg = b"Some data";
+		let witness = wallet.create_witness(msg).unwrap();
+
+		assert_eq!(witness.verification, VerificationScript::from_public_key(&wallet.public_key()));
+		let signatures = witness.invocation.get_signatures();
+		assert_eq!(signatures.len(), 1);
+		assert!(wallet.public_key().verify(msg, &signatures[0]).is_ok());
+		assert_eq!(
+			public_key_to_address(&wallet.public_key()),
+			"NPZyWCdSCWghLM7hcxT5kgc7cC2V2RGeHZ"
+		);
+	}
 }